@@ -1,31 +1,143 @@
-use std::mem::MaybeUninit;
+use std::{any::TypeId, borrow::Cow, convert::TryInto, mem::MaybeUninit};
 
-pub trait AsBytes {
-    fn bytelen(&self) -> usize;
-    //SAFETY: The type T should contain any references
-    //and therefore it should be able to simply copy it as bytes
-    unsafe fn copy(&self, data: &mut [MaybeUninit<u8>]);
-    fn as_bytes(&self) -> &[u8];
-    fn from_bytes(data: &[u8]) -> Self;
-}
+use crate::RadixError;
 
-impl AsBytes for String {
+//Items of binary columns are stored as the bytes returned by as_bytes
+//#[derive(AsBytes)] encodes structs and enums field by field, see radix_derive
+pub trait AsBytes {
     fn bytelen(&self) -> usize {
         self.as_bytes().len()
     }
+    //SAFETY: The length of data has to be bytelen
     unsafe fn copy(&self, data: &mut [MaybeUninit<u8>]) {
-        let len = data.len();
-        assert_eq!(len, self.bytelen());
-        std::intrinsics::copy_nonoverlapping(
-            self.as_bytes().as_ptr(),
-            data.as_mut_ptr() as *mut u8,
-            len,
-        );
+        let bytes = self.as_bytes();
+        assert_eq!(data.len(), bytes.len());
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    //The bytes are borrowed if the value is stored as bytes, otherwise they are encoded
+    fn as_bytes(&self) -> Cow<'_, [u8]>;
+    //Fails if the bytes have not been written by as_bytes of the same type
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError>
+    where
+        Self: Sized;
+    fn from_bytes(data: &[u8]) -> Self
+    where
+        Self: Sized,
+    {
+        match Self::try_from_bytes(data) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e),
+        }
     }
-    fn as_bytes(&self) -> &[u8] {
-        self.as_bytes()
+}
+
+impl AsBytes for String {
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
     }
-    fn from_bytes(data: &[u8]) -> Self {
-        String::from_utf8(data.to_vec()).unwrap()
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+        String::from_utf8(data.to_vec())
+            .map_err(|_| RadixError::downcast_failed::<String>(TypeId::of::<Blob>()))
+    }
+}
+
+//Opaque bytes like hashes, serialized messages or UUIDs, stored without any encoding
+pub type Blob = Vec<u8>;
+
+impl AsBytes for Vec<u8> {
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+        Ok(data.to_vec())
+    }
+}
+
+impl AsBytes for Box<[u8]> {
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+        Ok(data.into())
+    }
+}
+
+impl<const N: usize> AsBytes for [u8; N] {
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+        data.try_into().map_err(|_| RadixError::LengthMismatch {
+            expected: N,
+            found: data.len(),
+        })
+    }
+}
+
+impl AsBytes for bool {
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(vec![*self as u8])
+    }
+    fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+        match data {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(RadixError::downcast_failed::<bool>(TypeId::of::<Blob>()))?,
+        }
+    }
+}
+
+//Numbers are encoded as little endian bytes, so that the encoding does not depend on the platform
+macro_rules! number_as_bytes_impl {
+    ($($tr:ty)+) => ($(
+        impl AsBytes for $tr {
+            fn as_bytes(&self) -> Cow<'_, [u8]> {
+                Cow::Owned(self.to_le_bytes().to_vec())
+            }
+            fn try_from_bytes(data: &[u8]) -> Result<Self, RadixError> {
+                let bytes = data.try_into().map_err(|_| RadixError::LengthMismatch {
+                    expected: std::mem::size_of::<$tr>(),
+                    found: data.len(),
+                })?;
+                Ok(<$tr>::from_le_bytes(bytes))
+            }
+        }
+    )+)
+}
+
+number_as_bytes_impl! {
+    u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64
+}
+
+//Splits the encoding of the next field off the front of data, fields are prefixed with their length as u32
+//Used by #[derive(AsBytes)]
+pub fn read_field<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], RadixError> {
+    let len = read_u32(data)? as usize;
+    if data.len() < len {
+        Err(RadixError::OutOfBounds {
+            index: len,
+            len: data.len(),
+        })?
+    }
+    let (field, rest) = data.split_at(len);
+    *data = rest;
+    Ok(field)
+}
+
+pub fn write_field(out: &mut Vec<u8>, field: &[u8]) {
+    out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    out.extend_from_slice(field);
+}
+
+//Enum variants are encoded by their position in the declaration as u32
+pub fn read_u32(data: &mut &[u8]) -> Result<u32, RadixError> {
+    if data.len() < 4 {
+        Err(RadixError::OutOfBounds {
+            index: 4,
+            len: data.len(),
+        })?
     }
+    let (bytes, rest) = data.split_at(4);
+    *data = rest;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
 }
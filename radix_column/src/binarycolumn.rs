@@ -1,14 +1,197 @@
-use std::any::TypeId;
+use std::{any::TypeId, ops::Range};
 
-use super::{asbytes::AsBytes, ErrorDesc};
+use super::{asbytes::AsBytes, ErrorDesc, RadixError};
 
-#[derive(Debug)]
+//Offsets are u32 as long as they fit into i32, so that they can be handed out as Arrow i32 offsets
+const MAX_U32_OFFSET: usize = i32::MAX as usize;
+
+//n+1 offsets of n items, item i being the bytes from offsets[i] to offsets[i + 1]
+//u32 offsets take half the memory and are used while the bytes of the column fit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryOffsets {
+    U32(Vec<u32>),
+    Usize(Vec<usize>),
+}
+
+impl BinaryOffsets {
+    //Offsets of number_of_items empty items
+    pub fn zeros(number_of_items: usize) -> Self {
+        BinaryOffsets::U32(vec![0; number_of_items + 1])
+    }
+
+    pub fn with_capacity(number_of_items: usize) -> Self {
+        let mut offsets = Vec::with_capacity(number_of_items + 1);
+        offsets.push(0);
+        BinaryOffsets::U32(offsets)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            BinaryOffsets::U32(o) => o.len(),
+            BinaryOffsets::Usize(o) => o.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_offsets_ref(&self) -> BinaryOffsetsRef<'_> {
+        match self {
+            BinaryOffsets::U32(o) => BinaryOffsetsRef::U32(o),
+            BinaryOffsets::Usize(o) => BinaryOffsetsRef::Usize(o),
+        }
+    }
+
+    //u32 offsets are widened when the offset does not fit
+    pub fn push(&mut self, offset: usize) {
+        match self {
+            BinaryOffsets::U32(o) if offset <= MAX_U32_OFFSET => o.push(offset as u32),
+            BinaryOffsets::U32(_) => self.widen().push(offset),
+            BinaryOffsets::Usize(o) => o.push(offset),
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        match self {
+            BinaryOffsets::U32(o) => o.truncate(len),
+            BinaryOffsets::Usize(o) => o.truncate(len),
+        }
+    }
+
+    //Converts u32 offsets to usize offsets, the offsets can then be written without checking their size
+    pub fn widen(&mut self) -> &mut Vec<usize> {
+        if let BinaryOffsets::U32(o) = self {
+            *self = BinaryOffsets::Usize(o.iter().map(|o| *o as usize).collect());
+        }
+        match self {
+            BinaryOffsets::Usize(o) => o,
+            BinaryOffsets::U32(_) => unreachable!(),
+        }
+    }
+
+    //Converts usize offsets to u32 offsets if the last offset fits
+    pub fn narrow(&mut self) {
+        if let BinaryOffsets::Usize(o) = self {
+            if o.last().is_none_or(|last| *last <= MAX_U32_OFFSET) {
+                *self = BinaryOffsets::U32(o.iter().map(|o| *o as u32).collect());
+            }
+        }
+    }
+}
+
+impl From<Vec<u32>> for BinaryOffsets {
+    fn from(offsets: Vec<u32>) -> Self {
+        BinaryOffsets::U32(offsets)
+    }
+}
+
+impl From<Vec<usize>> for BinaryOffsets {
+    fn from(offsets: Vec<usize>) -> Self {
+        BinaryOffsets::Usize(offsets)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BinaryOffsetsRef<'a> {
+    U32(&'a [u32]),
+    Usize(&'a [usize]),
+}
+
+impl<'a> BinaryOffsetsRef<'a> {
+    pub fn len(&self) -> usize {
+        match self {
+            BinaryOffsetsRef::U32(o) => o.len(),
+            BinaryOffsetsRef::Usize(o) => o.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn get(&self, i: usize) -> usize {
+        match self {
+            BinaryOffsetsRef::U32(o) => o[i] as usize,
+            BinaryOffsetsRef::Usize(o) => o[i],
+        }
+    }
+
+    pub fn first(&self) -> usize {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> usize {
+        self.get(self.len() - 1)
+    }
+
+    //The n+1 offsets of n rows, the offset ending a row is shared with the next row
+    pub fn slice(&self, rows: Range<usize>) -> BinaryOffsetsRef<'a> {
+        match self {
+            BinaryOffsetsRef::U32(o) => BinaryOffsetsRef::U32(&o[rows.start..rows.end + 1]),
+            BinaryOffsetsRef::Usize(o) => BinaryOffsetsRef::Usize(&o[rows.start..rows.end + 1]),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + 'a {
+        let offsets = *self;
+        (0..offsets.len()).map(move |i| offsets.get(i))
+    }
+
+    //Number of bytes of every item
+    pub fn item_lens(&self) -> impl Iterator<Item = usize> + 'a {
+        let offsets = *self;
+        (0..offsets.len() - 1).map(move |i| offsets.get(i + 1) - offsets.get(i))
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            BinaryOffsetsRef::U32(o) => std::mem::size_of_val(*o),
+            BinaryOffsetsRef::Usize(o) => std::mem::size_of_val(*o),
+        }
+    }
+}
+
+//Bytes of item i, the bytes of a column start at its first offset
+#[inline]
+pub fn binary_item<'b>(datau8: &'b [u8], offsets: BinaryOffsetsRef, i: usize) -> &'b [u8] {
+    let first = offsets.first();
+    &datau8[offsets.get(i) - first..offsets.get(i + 1) - first]
+}
+
+pub fn binary_items<'b>(
+    datau8: &'b [u8],
+    offsets: BinaryOffsetsRef<'b>,
+) -> impl Iterator<Item = &'b [u8]> + 'b {
+    let first = offsets.first();
+    (0..offsets.len() - 1).map(move |i| &datau8[offsets.get(i) - first..offsets.get(i + 1) - first])
+}
+
+fn check_offsets(data: &[u8], offsets: BinaryOffsetsRef) -> Result<(), ErrorDesc> {
+    if offsets.is_empty() {
+        Err("Binary columns need at least one offset")?
+    }
+    if offsets.last() < offsets.first() {
+        Err("The offsets of a binary column must not decrease")?
+    }
+    if offsets.last() - offsets.first() > data.len() {
+        Err(RadixError::OutOfBounds {
+            index: offsets.last() - offsets.first(),
+            len: data.len(),
+        })?
+    }
+    Ok(())
+}
+
+//Items are stored like the Apache Arrow variable-size binary layout: the bytes of all the items
+//one after the other, and n+1 offsets starting at 0
+//Slices share the offsets of the column, their bytes start at their first offset
+#[derive(Debug, Clone)]
 pub struct OnwedBinaryColumn {
     item_type_id: std::any::TypeId,
-    offset: usize,
     data: Vec<u8>,
-    start_pos: Vec<usize>,
-    len: Vec<usize>,
+    offsets: BinaryOffsets,
 }
 
 impl OnwedBinaryColumn {
@@ -17,14 +200,12 @@ impl OnwedBinaryColumn {
     }
 
     pub fn len(&self) -> usize {
-        self.len.len()
+        self.offsets.len() - 1
     }
 
     pub fn truncate(&mut self) {
-        self.offset = 0;
         self.data.truncate(0);
-        self.start_pos.truncate(0);
-        self.len.truncate(0);
+        self.offsets = BinaryOffsets::zeros(0);
     }
 
     pub fn new<T: 'static + AsBytes>(data: &[T]) -> Self {
@@ -35,44 +216,64 @@ impl OnwedBinaryColumn {
         _capacity: usize,
         binarycapacity: usize,
     ) -> Self {
-        let mut cur_pos = 0;
-        let data_len = data.len();
-
-        let mut start_pos: Vec<usize> = Vec::with_capacity(data_len);
-        let mut len: Vec<usize> = Vec::with_capacity(data_len);
+        let mut offsets = BinaryOffsets::with_capacity(data.len());
         let mut datau8: Vec<u8> = Vec::with_capacity(binarycapacity);
 
         data.iter().for_each(|t| {
             let t_as_u8 = <T as AsBytes>::as_bytes(t);
-            len.push(t_as_u8.len());
-            start_pos.push(cur_pos);
-            datau8.extend_from_slice(t_as_u8);
-            cur_pos += t_as_u8.len();
+            datau8.extend_from_slice(&t_as_u8);
+            offsets.push(datau8.len());
         });
 
         Self {
             item_type_id: std::any::TypeId::of::<T>(),
-            offset: 0,
             data: datau8,
-            start_pos,
-            len,
+            offsets,
+        }
+    }
+
+    //Takes the buffers of a column in the offsets layout without copying them
+    //The items are not decoded, reading items which cannot be decoded as T fails
+    pub fn new_from_offsets<T: 'static + AsBytes>(
+        data: Vec<u8>,
+        offsets: impl Into<BinaryOffsets>,
+    ) -> Result<Self, ErrorDesc> {
+        let offsets = offsets.into();
+        let offsets_ref = offsets.as_offsets_ref();
+        if offsets_ref.is_empty() {
+            Err("Binary columns need at least one offset")?
+        }
+        if offsets_ref.first() != 0 {
+            Err("The first offset of a binary column must be 0")?
+        }
+        if (1..offsets_ref.len()).any(|i| offsets_ref.get(i - 1) > offsets_ref.get(i)) {
+            Err("The offsets of a binary column must not decrease")?
+        }
+        if offsets_ref.last() > data.len() {
+            Err(RadixError::OutOfBounds {
+                index: offsets_ref.last(),
+                len: data.len(),
+            })?
         }
+        Ok(Self {
+            item_type_id: std::any::TypeId::of::<T>(),
+            data,
+            offsets,
+        })
     }
 
+    //The offsets are written by the repartition, they are usize until the size of the bytes is known
     pub fn new_uninit<T: 'static + AsBytes>(
         number_of_items: usize,
         binary_storage_size: usize,
     ) -> Self {
-        let start_pos: Vec<usize> = vec![0; number_of_items];
-        let len: Vec<usize> = vec![0; number_of_items];
+        let offsets = BinaryOffsets::Usize(vec![0; number_of_items + 1]);
         let datau8: Vec<u8> = vec![0; binary_storage_size];
 
         Self {
             item_type_id: std::any::TypeId::of::<T>(),
-            offset: 0,
             data: datau8,
-            start_pos,
-            len,
+            offsets,
         }
     }
 
@@ -86,124 +287,83 @@ impl OnwedBinaryColumn {
         // Compare both `TypeId`s on equality.
         t == concrete
     }
-    pub fn downcast_binary_owned<T>(
-        self,
-    ) -> Result<(Vec<u8>, Vec<usize>, Vec<usize>, usize), ErrorDesc>
+    pub fn downcast_binary_owned<T>(self) -> Result<(Vec<u8>, BinaryOffsets), ErrorDesc>
     where
         T: Send + Sync + 'static,
     {
         if self.is::<T>() {
-            Ok((self.data, self.start_pos, self.len, self.offset))
+            Ok((self.data, self.offsets))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 
     pub fn downcast_binary_vec<T>(
         &mut self,
-    ) -> Result<(&mut Vec<u8>, &mut Vec<usize>, &mut Vec<usize>, &mut usize), ErrorDesc>
+    ) -> Result<(&mut Vec<u8>, &mut BinaryOffsets), ErrorDesc>
     where
         T: Send + Sync + 'static,
     {
         if self.is::<T>() {
-            Ok((
-                &mut self.data,
-                &mut self.start_pos,
-                &mut self.len,
-                &mut self.offset,
-            ))
+            Ok((&mut self.data, &mut self.offsets))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
-    pub fn downcast_binary_mut<T>(
-        &mut self,
-    ) -> Result<(&mut [u8], &mut [usize], &mut [usize], &mut usize), ErrorDesc>
+    //The offsets can only be changed through downcast_binary_vec
+    pub fn downcast_binary_mut<T>(&mut self) -> Result<(&mut [u8], BinaryOffsetsRef<'_>), ErrorDesc>
     where
         T: 'static,
     {
         if self.is::<T>() {
-            Ok((
-                self.data.as_mut_slice(),
-                self.start_pos.as_mut_slice(),
-                self.len.as_mut_slice(),
-                &mut self.offset,
-            ))
+            Ok((self.data.as_mut_slice(), self.offsets.as_offsets_ref()))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
     pub fn downcast_binary_ref<'b, T>(
         &'b self,
-    ) -> Result<(&'b [u8], &'b [usize], &'b [usize], &'b usize), ErrorDesc>
+    ) -> Result<(&'b [u8], BinaryOffsetsRef<'b>), ErrorDesc>
     where
         T: 'static,
     {
         if self.is::<T>() {
-            Ok((
-                self.data.as_slice(),
-                self.start_pos.as_slice(),
-                self.len.as_slice(),
-                &self.offset,
-            ))
+            Ok((self.data.as_slice(), self.offsets.as_offsets_ref()))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
+
+    //The bytes of each item, whatever the item type
+    pub(crate) fn items(&self) -> impl Iterator<Item = &[u8]> {
+        binary_items(&self.data, self.offsets.as_offsets_ref())
+    }
 }
 
+//The positions of the items of a mutable slice are fixed, only their bytes can be written
 #[derive(Debug)]
 pub struct BinarySliceMut<'a> {
     item_type_id: std::any::TypeId,
-    offset: usize,
     data: &'a mut [u8],
-    start_pos: &'a mut [usize],
-    len: &'a mut [usize],
+    offsets: BinaryOffsetsRef<'a>,
 }
 
 impl<'a> BinarySliceMut<'a> {
     pub fn new<T: 'static>(
-        offset: usize,
         data: &'a mut [u8],
-        start_pos: &'a mut [usize],
-        len: &'a mut [usize],
-    ) -> Self {
-        assert_eq!(start_pos.len(), len.len());
-        if !start_pos.is_empty() {
-            let end_pos_last_element = start_pos[start_pos.len() - 1] + len[len.len() - 1];
-            assert!(data.len() >= end_pos_last_element);
-        }
-        Self {
+        offsets: BinaryOffsetsRef<'a>,
+    ) -> Result<Self, ErrorDesc> {
+        check_offsets(data, offsets)?;
+        Ok(Self {
             item_type_id: std::any::TypeId::of::<T>(),
-            offset,
             data,
-            start_pos,
-            len,
-        }
+            offsets,
+        })
     }
 
+    //Position of the first byte of the slice in the column it was taken from
     pub fn offset(&self) -> usize {
-        self.offset
+        self.offsets.first()
     }
     pub fn item_type_id(&self) -> std::any::TypeId {
         self.item_type_id
@@ -218,36 +378,24 @@ impl<'a> BinarySliceMut<'a> {
         // Compare both `TypeId`s on equality.
         t == concrete
     }
-    pub fn downcast_binary_mut<T>(
-        &mut self,
-    ) -> Result<(&mut [u8], &mut [usize], &mut [usize], &mut usize), ErrorDesc>
+    pub fn downcast_binary_mut<T>(&mut self) -> Result<(&mut [u8], BinaryOffsetsRef<'_>), ErrorDesc>
     where
         T: 'static,
     {
         if self.is::<T>() {
-            Ok((self.data, self.start_pos, self.len, &mut self.offset))
+            Ok((self.data, self.offsets))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
-    pub fn downcast_binary_ref<T>(&self) -> Result<(&[u8], &[usize], &[usize], &usize), ErrorDesc>
+    pub fn downcast_binary_ref<T>(&self) -> Result<(&[u8], BinaryOffsetsRef<'_>), ErrorDesc>
     where
         T: 'static,
     {
         if self.is::<T>() {
-            Ok((self.data, self.start_pos, self.len, &self.offset))
+            Ok((self.data, self.offsets))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 
@@ -255,47 +403,28 @@ impl<'a> BinarySliceMut<'a> {
     where
         T: 'static + Sync,
     {
-        if pos > self.start_pos.len() {
-            Err(format!(
-                "Attempt to split at slice at position {}, while the source slice has length {}",
-                pos,
-                self.start_pos.len(),
-            ))?
+        if pos >= self.offsets.len() {
+            Err(RadixError::OutOfBounds {
+                index: pos,
+                len: self.offsets.len() - 1,
+            })?
         } else if self.is::<T>() {
-            let tmp = std::mem::replace(&mut self.start_pos, &mut []);
-            let (s, tail) = tmp.split_at_mut(pos);
-            self.start_pos = tail;
-
-            let tmp = std::mem::replace(&mut self.len, &mut []);
-            let (l, tail) = tmp.split_at_mut(pos);
-            self.len = tail;
-
-            let mut data_len = 0;
-            if !s.is_empty() {
-                data_len = s[s.len() - 1] + l[l.len() - 1] - self.offset;
-            }
+            //The offset at pos ends the left part and starts the right one
+            let s = self.offsets.slice(0..pos);
+            self.offsets = self.offsets.slice(pos..self.offsets.len() - 1);
 
-            let tmp = std::mem::replace(&mut self.data, &mut []);
+            let data_len = s.last() - s.first();
+            let tmp = std::mem::take(&mut self.data);
             let (d, tail) = tmp.split_at_mut(data_len);
             self.data = tail;
 
-            let l = Self {
+            Ok(Self {
                 item_type_id: self.item_type_id,
-                offset: self.offset,
                 data: d,
-                start_pos: s,
-                len: l,
-            };
-
-            self.offset += data_len;
-            Ok(l)
+                offsets: s,
+            })
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 }
@@ -303,34 +432,25 @@ impl<'a> BinarySliceMut<'a> {
 #[derive(Debug)]
 pub struct BinarySlice<'a> {
     item_type_id: std::any::TypeId,
-    offset: usize,
     data: &'a [u8],
-    start_pos: &'a [usize],
-    len: &'a [usize],
+    offsets: BinaryOffsetsRef<'a>,
 }
 
 impl<'a> BinarySlice<'a> {
     pub fn new<T: 'static>(
-        offset: usize,
         data: &'a [u8],
-        start_pos: &'a [usize],
-        len: &'a [usize],
-    ) -> Self {
-        assert_eq!(start_pos.len(), len.len());
-        if !start_pos.is_empty() {
-            let end_pos_last_element = start_pos[start_pos.len() - 1] + len[len.len() - 1];
-            assert!(data.len() >= end_pos_last_element);
-        }
-        Self {
+        offsets: BinaryOffsetsRef<'a>,
+    ) -> Result<Self, ErrorDesc> {
+        check_offsets(data, offsets)?;
+        Ok(Self {
             item_type_id: std::any::TypeId::of::<T>(),
-            offset,
             data,
-            start_pos,
-            len,
-        }
+            offsets,
+        })
     }
+    //Position of the first byte of the slice in the column it was taken from
     pub fn offset(&self) -> usize {
-        self.offset
+        self.offsets.first()
     }
     pub fn item_type_id(&self) -> std::any::TypeId {
         self.item_type_id
@@ -345,19 +465,14 @@ impl<'a> BinarySlice<'a> {
         // Compare both `TypeId`s on equality.
         t == concrete
     }
-    pub fn downcast_binary_ref<T>(&self) -> Result<(&[u8], &[usize], &[usize], &usize), ErrorDesc>
+    pub fn downcast_binary_ref<T>(&self) -> Result<(&'a [u8], BinaryOffsetsRef<'a>), ErrorDesc>
     where
         T: 'static,
     {
         if self.is::<T>() {
-            Ok((self.data, self.start_pos, self.len, &self.offset))
+            Ok((self.data, self.offsets))
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 
@@ -365,43 +480,27 @@ impl<'a> BinarySlice<'a> {
     where
         T: 'static + Sync,
     {
-        if pos > self.start_pos.len() {
-            Err(format!(
-                "Attempt to split at slice at position {}, while the source slice has length {}",
-                pos,
-                self.start_pos.len(),
-            ))?
+        if pos >= self.offsets.len() {
+            Err(RadixError::OutOfBounds {
+                index: pos,
+                len: self.offsets.len() - 1,
+            })?
         } else if self.is::<T>() {
-            let (s, tmp) = self.start_pos.split_at(pos);
-            self.start_pos = tmp;
+            //The offset at pos ends the left part and starts the right one
+            let s = self.offsets.slice(0..pos);
+            self.offsets = self.offsets.slice(pos..self.offsets.len() - 1);
 
-            let (l, tmp) = self.len.split_at(pos);
-            self.len = tmp;
-
-            let mut data_len = 0;
-            if !s.is_empty() {
-                data_len = s[s.len() - 1] + l[l.len() - 1] - self.offset;
-            }
+            let data_len = s.last() - s.first();
             let (d, tmp) = self.data.split_at(data_len);
             self.data = tmp;
 
-            let l = Self {
+            Ok(Self {
                 item_type_id: self.item_type_id,
-                offset: self.offset,
                 data: d,
-                start_pos: s,
-                len: l,
-            };
-
-            self.offset += data_len;
-            Ok(l)
+                offsets: s,
+            })
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 }
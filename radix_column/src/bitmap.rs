@@ -0,0 +1,521 @@
+use std::{
+    iter::FromIterator,
+    ops::{Index, Range},
+};
+
+const WORD_BITS: usize = 64;
+
+fn number_of_words(len: usize) -> usize {
+    len.div_ceil(WORD_BITS)
+}
+
+//Mask keeping the first rows of a word
+fn low_mask(rows: usize) -> u64 {
+    if rows >= WORD_BITS {
+        u64::MAX
+    } else {
+        (1u64 << rows) - 1
+    }
+}
+
+//Indexing returns references to these constants, as single bits cannot be referenced
+static TRUE: bool = true;
+static FALSE: bool = false;
+
+fn bool_ref(b: bool) -> &'static bool {
+    if b {
+        &TRUE
+    } else {
+        &FALSE
+    }
+}
+
+//Word with all its bits set to b, used to evaluate boolean functions on whole words
+fn truth_mask(b: bool) -> u64 {
+    if b {
+        u64::MAX
+    } else {
+        0
+    }
+}
+
+//Validity bitmap, bit-packed with 64 rows per word: row i is stored in bit i % 64 of word i / 64,
+//a set bit marks a valid row. The bits after the last row are always zero
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    pub fn new() -> Self {
+        Bitmap::default()
+    }
+
+    pub fn with_capacity(rows: usize) -> Self {
+        Bitmap {
+            words: Vec::with_capacity(number_of_words(rows)),
+            len: 0,
+        }
+    }
+
+    //Bitmap of len rows, all of them valid or all of them null
+    pub fn filled(value: bool, len: usize) -> Self {
+        Bitmap::from_words(vec![truth_mask(value); number_of_words(len)], len)
+    }
+
+    //The bits after len are cleared
+    pub fn from_words(mut words: Vec<u64>, len: usize) -> Self {
+        assert!(
+            words.len() * WORD_BITS >= len,
+            "{} words cannot hold {} rows",
+            words.len(),
+            len
+        );
+        words.truncate(number_of_words(len));
+        let mut bitmap = Bitmap { words, len };
+        bitmap.clear_tail();
+        bitmap
+    }
+
+    fn clear_tail(&mut self) {
+        let rem = self.len % WORD_BITS;
+        if rem != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= low_mask(rem);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    //Capacity in rows
+    pub fn capacity(&self) -> usize {
+        self.words.capacity() * WORD_BITS
+    }
+
+    //Bytes used by the words, which is what is kept in memory for the bitmap
+    pub fn allocated_bytes(&self) -> usize {
+        self.words.capacity() * std::mem::size_of::<u64>()
+    }
+
+    pub fn reserve(&mut self, additional_rows: usize) {
+        let needed = number_of_words(self.len + additional_rows);
+        self.words.reserve(needed.saturating_sub(self.words.len()));
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        self.as_bitmap_ref().get(i)
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        self.as_bitmap_mut().set(i, value)
+    }
+
+    pub fn push(&mut self, value: bool) {
+        let bit = self.len % WORD_BITS;
+        if bit == 0 {
+            self.words.push(0);
+        }
+        if value {
+            //The word has just been pushed if needed
+            *self.words.last_mut().unwrap() |= 1 << bit;
+        }
+        self.len += 1;
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.words.truncate(number_of_words(len));
+            self.clear_tail();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    pub fn resize(&mut self, len: usize, value: bool) {
+        if len <= self.len {
+            self.truncate(len);
+        } else {
+            self.extend_from_bitmap(Bitmap::filled(value, len - self.len).as_bitmap_ref());
+        }
+    }
+
+    //Appends the rows of other a word at a time
+    pub fn extend_from_bitmap(&mut self, other: BitmapRef) {
+        let shift = self.len % WORD_BITS;
+        self.words
+            .reserve(number_of_words(self.len + other.len) - self.words.len());
+        for k in 0..other.number_of_words() {
+            let word = other.word(k);
+            if shift == 0 {
+                self.words.push(word);
+            } else {
+                //The tail of the last word is zero, so the low bits of the new word can be or-ed in
+                *self.words.last_mut().unwrap() |= word << shift;
+                self.words.push(word >> (WORD_BITS - shift));
+            }
+        }
+        self.len += other.len;
+        self.words.truncate(number_of_words(self.len));
+    }
+
+    pub fn as_bitmap_ref(&self) -> BitmapRef<'_> {
+        BitmapRef {
+            words: &self.words,
+            offset: 0,
+            len: self.len,
+        }
+    }
+
+    pub fn as_bitmap_mut(&mut self) -> BitmapMut<'_> {
+        BitmapMut {
+            words: &mut self.words,
+            len: self.len,
+        }
+    }
+
+    pub fn iter(&self) -> BitmapIter<'_> {
+        self.as_bitmap_ref().iter()
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    pub fn all_valid(&self) -> bool {
+        self.as_bitmap_ref().all_valid()
+    }
+
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+
+    //Applies f to every row, a word at a time: f is only evaluated for the two possible inputs
+    pub fn map(a: BitmapRef, f: impl Fn(bool) -> bool) -> Bitmap {
+        let (t, f) = (truth_mask(f(true)), truth_mask(f(false)));
+        let words = (0..a.number_of_words())
+            .map(|k| {
+                let a = a.word(k);
+                (a & t) | (!a & f)
+            })
+            .collect();
+        Bitmap::from_words(words, a.len)
+    }
+
+    //Combines two bitmaps of the same length row by row, a word at a time:
+    //f is only evaluated for the four possible pairs of inputs
+    pub fn zip_map(a: BitmapRef, b: BitmapRef, f: impl Fn(bool, bool) -> bool) -> Bitmap {
+        assert_eq!(
+            a.len, b.len,
+            "bitmaps of different lengths cannot be combined"
+        );
+        let tt = truth_mask(f(true, true));
+        let tf = truth_mask(f(true, false));
+        let ft = truth_mask(f(false, true));
+        let ff = truth_mask(f(false, false));
+        let words = (0..a.number_of_words())
+            .map(|k| {
+                let (a, b) = (a.word(k), b.word(k));
+                (a & b & tt) | (a & !b & tf) | (!a & b & ft) | (!a & !b & ff)
+            })
+            .collect();
+        Bitmap::from_words(words, a.len)
+    }
+
+    pub fn and(a: BitmapRef, b: BitmapRef) -> Bitmap {
+        Bitmap::zip_map(a, b, |a, b| a && b)
+    }
+
+    pub fn or(a: BitmapRef, b: BitmapRef) -> Bitmap {
+        Bitmap::zip_map(a, b, |a, b| a || b)
+    }
+}
+
+impl Index<usize> for Bitmap {
+    type Output = bool;
+    fn index(&self, i: usize) -> &bool {
+        bool_ref(self.get(i))
+    }
+}
+
+impl Extend<bool> for Bitmap {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|b| self.push(b));
+    }
+}
+
+impl<'a> Extend<&'a bool> for Bitmap {
+    fn extend<I: IntoIterator<Item = &'a bool>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+impl FromIterator<bool> for Bitmap {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bitmap = Bitmap::new();
+        bitmap.extend(iter);
+        bitmap
+    }
+}
+
+impl<'a> FromIterator<&'a bool> for Bitmap {
+    fn from_iter<I: IntoIterator<Item = &'a bool>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl From<&[bool]> for Bitmap {
+    fn from(v: &[bool]) -> Self {
+        v.iter().collect()
+    }
+}
+
+impl From<Vec<bool>> for Bitmap {
+    fn from(v: Vec<bool>) -> Self {
+        v.as_slice().into()
+    }
+}
+
+//Borrowed range of rows of a bitmap, the range does not need to start at a word boundary
+#[derive(Debug, Clone, Copy)]
+pub struct BitmapRef<'a> {
+    words: &'a [u64],
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> BitmapRef<'a> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(
+            i < self.len,
+            "index {} out of range for a bitmap of {} rows",
+            i,
+            self.len
+        );
+        let i = self.offset + i;
+        (self.words[i / WORD_BITS] >> (i % WORD_BITS)) & 1 == 1
+    }
+
+    pub fn slice(&self, rows: Range<usize>) -> BitmapRef<'a> {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.len,
+            "range {:?} out of range for a bitmap of {} rows",
+            rows,
+            self.len
+        );
+        let offset = self.offset + rows.start;
+        BitmapRef {
+            words: &self.words[offset / WORD_BITS..],
+            offset: offset % WORD_BITS,
+            len: rows.end - rows.start,
+        }
+    }
+
+    pub fn number_of_words(&self) -> usize {
+        number_of_words(self.len)
+    }
+
+    //Rows 64 * k to 64 * k + 63 of the range packed in one word, the bits after the last row are zero
+    pub fn word(&self, k: usize) -> u64 {
+        let start = self.offset + k * WORD_BITS;
+        let (w, shift) = (start / WORD_BITS, start % WORD_BITS);
+        let mut word = self.words[w] >> shift;
+        if shift != 0 && w + 1 < self.words.len() {
+            word |= self.words[w + 1] << (WORD_BITS - shift);
+        }
+        word & low_mask(self.len - k * WORD_BITS)
+    }
+
+    pub fn iter(&self) -> BitmapIter<'a> {
+        BitmapIter {
+            bitmap: *self,
+            pos: 0,
+            word: 0,
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        (0..self.number_of_words())
+            .map(|k| self.word(k).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    //Stops at the first word with a null row
+    pub fn all_valid(&self) -> bool {
+        (0..self.number_of_words()).all(|k| self.word(k) == low_mask(self.len - k * WORD_BITS))
+    }
+
+    pub fn to_bitmap(&self) -> Bitmap {
+        let words = (0..self.number_of_words()).map(|k| self.word(k)).collect();
+        Bitmap {
+            words,
+            len: self.len,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+}
+
+impl<'a> From<&'a Bitmap> for BitmapRef<'a> {
+    fn from(b: &'a Bitmap) -> Self {
+        b.as_bitmap_ref()
+    }
+}
+
+impl Index<usize> for BitmapRef<'_> {
+    type Output = bool;
+    fn index(&self, i: usize) -> &bool {
+        bool_ref(self.get(i))
+    }
+}
+
+impl PartialEq for BitmapRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && (0..self.number_of_words()).all(|k| self.word(k) == other.word(k))
+    }
+}
+impl Eq for BitmapRef<'_> {}
+
+impl<'a> IntoIterator for BitmapRef<'a> {
+    type Item = bool;
+    type IntoIter = BitmapIter<'a>;
+    fn into_iter(self) -> BitmapIter<'a> {
+        self.iter()
+    }
+}
+
+//Iterates over the rows of a bitmap, loading one word every 64 rows
+#[derive(Debug, Clone)]
+pub struct BitmapIter<'a> {
+    bitmap: BitmapRef<'a>,
+    pos: usize,
+    word: u64,
+}
+
+impl Iterator for BitmapIter<'_> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        if self.pos == self.bitmap.len {
+            return None;
+        }
+        if self.pos.is_multiple_of(WORD_BITS) {
+            self.word = self.bitmap.word(self.pos / WORD_BITS);
+        }
+        let b = self.word & 1 == 1;
+        self.word >>= 1;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rem = self.bitmap.len - self.pos;
+        (rem, Some(rem))
+    }
+}
+
+impl ExactSizeIterator for BitmapIter<'_> {}
+
+//Mutable access to the rows of an owned bitmap
+#[derive(Debug)]
+pub struct BitmapMut<'a> {
+    words: &'a mut [u64],
+    len: usize,
+}
+
+impl<'a> BitmapMut<'a> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        self.as_bitmap_ref().get(i)
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(
+            i < self.len,
+            "index {} out of range for a bitmap of {} rows",
+            i,
+            self.len
+        );
+        let (w, bit) = (i / WORD_BITS, i % WORD_BITS);
+        if value {
+            self.words[w] |= 1 << bit;
+        } else {
+            self.words[w] &= !(1 << bit);
+        }
+    }
+
+    //Overwrites the rows starting at row start with the rows of src, a word at a time
+    pub fn copy_from(&mut self, start: usize, src: BitmapRef) {
+        assert!(
+            start + src.len <= self.len,
+            "{} rows cannot be copied at row {} of a bitmap of {} rows",
+            src.len,
+            start,
+            self.len
+        );
+        let mut row = start;
+        for k in 0..src.number_of_words() {
+            let rows = (src.len - k * WORD_BITS).min(WORD_BITS);
+            let word = src.word(k);
+            let (w, shift) = (row / WORD_BITS, row % WORD_BITS);
+            let mask = low_mask(rows);
+            self.words[w] = (self.words[w] & !(mask << shift)) | (word << shift);
+            if shift != 0 && shift + rows > WORD_BITS {
+                let (mask, word) = (mask >> (WORD_BITS - shift), word >> (WORD_BITS - shift));
+                self.words[w + 1] = (self.words[w + 1] & !mask) | word;
+            }
+            row += rows;
+        }
+    }
+
+    pub fn as_bitmap_ref(&self) -> BitmapRef<'_> {
+        BitmapRef {
+            words: self.words,
+            offset: 0,
+            len: self.len,
+        }
+    }
+}
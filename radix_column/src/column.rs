@@ -1,10 +1,10 @@
-use crate::ColumnDataF;
+use crate::{BitmapRef, ColumnDataF, ErrorDesc};
 
 use super::columndata::ColumnData;
 #[derive(Debug)]
 pub struct ColumnWrapper<'a> {
     column: super::ColumnData<'a>,
-    bitmap: super::ColumnDataF<'a, bool>,
+    bitmap: super::ColumnDataF<'a>,
 }
 
 impl<'a> ColumnWrapper<'a> {
@@ -20,6 +20,33 @@ impl<'a> ColumnWrapper<'a> {
         self.column().is_sized()
     }
 
+    pub fn is_shared(&self) -> bool {
+        self.column().is_shared() || self.bitmap().is_shared()
+    }
+
+    //Moves an owned column (and its bitmap) behind an Arc, so that it can be shared between tables without copying
+    pub fn into_shared(self) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        Ok(ColumnWrapper {
+            column: self.column.into_shared()?,
+            bitmap: self.bitmap.into_shared()?,
+        })
+    }
+
+    pub fn into_static(self) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        Ok(ColumnWrapper {
+            column: self.column.into_static()?,
+            bitmap: self.bitmap.into_static()?,
+        })
+    }
+
+    //Returns a new reference to a shared column, the underlying buffers are not copied
+    pub fn share(&self) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        Ok(ColumnWrapper {
+            column: self.column.share()?,
+            bitmap: self.bitmap.share()?,
+        })
+    }
+
     pub fn new_from_columndata(data: ColumnData<'a>) -> Self {
         ColumnWrapper {
             column: data,
@@ -27,19 +54,19 @@ impl<'a> ColumnWrapper<'a> {
         }
     }
 
-    pub fn with_bitmap_slice(&mut self, bmap: &'a [bool]) {
+    pub fn with_bitmap_slice(&mut self, bmap: BitmapRef<'a>) {
         self.bitmap = ColumnDataF::Slice(bmap)
     }
 
-    pub fn get_inner(self) -> (ColumnData<'a>, ColumnDataF<'a, bool>) {
+    pub fn get_inner(self) -> (ColumnData<'a>, ColumnDataF<'a>) {
         (self.column, self.bitmap)
     }
 
-    pub fn get_inner_ref(&self) -> (&ColumnData<'a>, &ColumnDataF<'a, bool>) {
+    pub fn get_inner_ref(&self) -> (&ColumnData<'a>, &ColumnDataF<'a>) {
         (&self.column, &self.bitmap)
     }
 
-    pub fn get_inner_mut(&mut self) -> (&mut ColumnData<'a>, &mut ColumnDataF<'a, bool>) {
+    pub fn get_inner_mut(&mut self) -> (&mut ColumnData<'a>, &mut ColumnDataF<'a>) {
         (&mut self.column, &mut self.bitmap)
     }
 
@@ -54,18 +81,18 @@ impl<'a> ColumnWrapper<'a> {
         &mut self.column
     }
 
-    pub fn bitmap<'b>(&self) -> &ColumnDataF<'b, bool>
+    pub fn bitmap<'b>(&self) -> &ColumnDataF<'b>
     where
         'a: 'b,
     {
         &self.bitmap
     }
 
-    pub fn bitmap_mut(&mut self) -> &mut ColumnDataF<'a, bool> {
+    pub fn bitmap_mut(&mut self) -> &mut ColumnDataF<'a> {
         &mut self.bitmap
     }
 
-    pub fn bitmap_set(&mut self, b: ColumnDataF<'a, bool>) {
+    pub fn bitmap_set(&mut self, b: ColumnDataF<'a>) {
         self.bitmap = b;
     }
 }
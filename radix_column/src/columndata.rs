@@ -1,15 +1,31 @@
-use std::{any::TypeId, ops::AddAssign};
+use std::{any::TypeId, borrow::Cow, ops::AddAssign, sync::Arc};
 
-pub type ErrorDesc = Box<dyn std::error::Error>;
-
-use crate::OwnedColumn;
+use crate::{Bitmap, BitmapMut, BitmapRef, ErrorDesc, OwnedColumn, RadixError};
 
 use super::{
-    binarycolumn::BinarySlice,
+    binarycolumn::{binary_item, BinaryOffsets, BinaryOffsetsRef, BinarySlice},
     binarycolumn::{BinarySliceMut, OnwedBinaryColumn},
+    dictionarycolumn::{DictionaryColumn, DictionarySlice, DictionaryValues},
+    runlengthcolumn::RunLengthColumn,
     SliceRef, SliceRefMut,
 };
 
+//Bytes and offsets of the items of a binary column, see downcast_binary_decoded
+#[derive(Debug)]
+pub enum DecodedBinary<'b> {
+    Borrowed(&'b [u8], BinaryOffsetsRef<'b>),
+    Owned(Vec<u8>, BinaryOffsets),
+}
+
+impl<'b> DecodedBinary<'b> {
+    pub fn parts(&self) -> (&[u8], BinaryOffsetsRef<'_>) {
+        match self {
+            DecodedBinary::Borrowed(datau8, offsets) => (datau8, *offsets),
+            DecodedBinary::Owned(datau8, offsets) => (datau8, offsets.as_offsets_ref()),
+        }
+    }
+}
+
 /// Source code copied from std::boxed::into_boxed_slice()
 /// As of 13.09.2020, the feature is not stabilized. Tracking issue = "71582".
 /// Converts a `Box<T>` into a `Box<[T]>`
@@ -23,10 +39,15 @@ pub enum ColumnData<'a> {
     Slice(SliceRef<'a>),
     SliceMut(SliceRefMut<'a>),
     Const(OwnedColumn),
+    Shared(Arc<OwnedColumn>),
     BinaryOwned(OnwedBinaryColumn),
     BinarySlice(BinarySlice<'a>),
     BinarySliceMut(BinarySliceMut<'a>),
     BinaryConst(OnwedBinaryColumn),
+    BinaryShared(Arc<OnwedBinaryColumn>),
+    Dictionary(DictionaryColumn),
+    DictionarySlice(DictionarySlice<'a>),
+    RunLength(RunLengthColumn),
 }
 
 impl<'a> ColumnData<'a> {
@@ -37,10 +58,15 @@ impl<'a> ColumnData<'a> {
                 ColumnData::SliceMut(c) => c.item_type_id(),
                 ColumnData::Slice(c) => c.item_type_id(),
                 ColumnData::Const(c) => c.item_type_id(),
+                ColumnData::Shared(c) => c.item_type_id(),
                 ColumnData::BinaryOwned(c) => c.item_type_id(),
                 ColumnData::BinarySliceMut(c) => c.item_type_id(),
                 ColumnData::BinarySlice(c) => c.item_type_id(),
                 ColumnData::BinaryConst(c) => c.item_type_id(),
+                ColumnData::BinaryShared(c) => c.item_type_id(),
+                ColumnData::Dictionary(c) => c.item_type_id(),
+                ColumnData::DictionarySlice(c) => c.item_type_id(),
+                ColumnData::RunLength(c) => c.item_type_id(),
             }
         }
     }
@@ -52,10 +78,15 @@ impl<'a> ColumnData<'a> {
                 ColumnData::SliceMut(_) => false,
                 ColumnData::Slice(_) => false,
                 ColumnData::Const(_) => true,
+                ColumnData::Shared(_) => false,
                 ColumnData::BinaryOwned(_) => false,
                 ColumnData::BinarySliceMut(_) => false,
                 ColumnData::BinarySlice(_) => false,
                 ColumnData::BinaryConst(_) => true,
+                ColumnData::BinaryShared(_) => false,
+                ColumnData::Dictionary(_) => false,
+                ColumnData::DictionarySlice(_) => false,
+                ColumnData::RunLength(_) => false,
             }
         }
     }
@@ -67,10 +98,15 @@ impl<'a> ColumnData<'a> {
                 ColumnData::SliceMut(_) => false,
                 ColumnData::Slice(_) => false,
                 ColumnData::Const(_) => false,
+                ColumnData::Shared(_) => false,
                 ColumnData::BinaryOwned(_) => true,
                 ColumnData::BinarySliceMut(_) => false,
                 ColumnData::BinarySlice(_) => false,
                 ColumnData::BinaryConst(_) => false,
+                ColumnData::BinaryShared(_) => false,
+                ColumnData::Dictionary(_) => false,
+                ColumnData::DictionarySlice(_) => false,
+                ColumnData::RunLength(_) => false,
             }
         }
     }
@@ -82,10 +118,15 @@ impl<'a> ColumnData<'a> {
                 ColumnData::SliceMut(_) => false,
                 ColumnData::Slice(_) => false,
                 ColumnData::Const(_) => false,
+                ColumnData::Shared(_) => false,
                 ColumnData::BinaryOwned(_) => true,
                 ColumnData::BinarySliceMut(_) => true,
                 ColumnData::BinarySlice(_) => true,
                 ColumnData::BinaryConst(_) => true,
+                ColumnData::BinaryShared(_) => true,
+                ColumnData::Dictionary(_) => true,
+                ColumnData::DictionarySlice(_) => true,
+                ColumnData::RunLength(_) => false,
             }
         }
     }
@@ -97,10 +138,35 @@ impl<'a> ColumnData<'a> {
                 ColumnData::SliceMut(_) => true,
                 ColumnData::Slice(_) => true,
                 ColumnData::Const(_) => true,
+                ColumnData::Shared(_) => true,
                 ColumnData::BinaryOwned(_) => false,
                 ColumnData::BinarySliceMut(_) => false,
                 ColumnData::BinarySlice(_) => false,
                 ColumnData::BinaryConst(_) => false,
+                ColumnData::BinaryShared(_) => false,
+                ColumnData::Dictionary(_) => false,
+                ColumnData::DictionarySlice(_) => false,
+                ColumnData::RunLength(_) => true,
+            }
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        {
+            match self {
+                ColumnData::Owned(_) => false,
+                ColumnData::SliceMut(_) => false,
+                ColumnData::Slice(_) => false,
+                ColumnData::Const(_) => false,
+                ColumnData::Shared(_) => true,
+                ColumnData::BinaryOwned(_) => false,
+                ColumnData::BinarySliceMut(_) => false,
+                ColumnData::BinarySlice(_) => false,
+                ColumnData::BinaryConst(_) => false,
+                ColumnData::BinaryShared(_) => true,
+                ColumnData::Dictionary(_) => false,
+                ColumnData::DictionarySlice(_) => false,
+                ColumnData::RunLength(_) => false,
             }
         }
     }
@@ -115,25 +181,54 @@ impl<'a> ColumnData<'a> {
     {
         match self {
             ColumnData::Owned(c) => c.downcast_owned::<T>(),
-            ColumnData::SliceMut(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::Slice",
-            ))?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::Slice",
+            })?,
             ColumnData::Const(c) => c.downcast_owned::<T>(),
-            ColumnData::BinaryOwned(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::BinaryOwned",
-            ))?,
-            ColumnData::BinarySliceMut(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::BinarySliceMut",
-            ))?,
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::BinarySlice",
-            ))?,
-            ColumnData::BinaryConst(_) => Err(format!(
-                "Downcast failed. downcast_owned not possible for ColumnData::BinaryConst",
-            ))?,
+            ColumnData::Shared(c) => match Arc::try_unwrap(c) {
+                Ok(c) => c.downcast_owned::<T>(),
+                Err(_) => Err(RadixError::UnsupportedStorage {
+                    operation: "downcast_owned",
+                    storage: "ColumnData::Shared which is referenced by other columns",
+                })?,
+            },
+            ColumnData::BinaryOwned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::BinaryOwned",
+            })?,
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::BinarySlice",
+            })?,
+            ColumnData::BinaryConst(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::BinaryConst",
+            })?,
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_owned",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
     pub fn downcast_vec<T>(&mut self) -> Result<&mut Vec<T>, ErrorDesc>
@@ -142,25 +237,51 @@ impl<'a> ColumnData<'a> {
     {
         match self {
             ColumnData::Owned(c) => c.downcast_vec::<T>(),
-            ColumnData::SliceMut(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::Slice",
-            ))?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::Slice",
+            })?,
             ColumnData::Const(c) => c.downcast_vec::<T>(),
-            ColumnData::BinaryOwned(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::BinaryOwned",
-            ))?,
-            ColumnData::BinarySliceMut(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::BinarySliceMut",
-            ))?,
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::BinarySlice",
-            ))?,
-            ColumnData::BinaryConst(_) => Err(format!(
-                "Downcast failed. downcast_vec not possible for ColumnData::BinaryConst",
-            ))?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::Shared",
+            })?,
+            ColumnData::BinaryOwned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::BinaryOwned",
+            })?,
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::BinarySlice",
+            })?,
+            ColumnData::BinaryConst(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::BinaryConst",
+            })?,
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_vec",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
     pub fn downcast_mut<T>(&mut self) -> Result<&mut [T], ErrorDesc>
@@ -171,22 +292,47 @@ impl<'a> ColumnData<'a> {
             ColumnData::Owned(c) => c.downcast_mut::<T>(),
 
             ColumnData::SliceMut(c) => c.downcast_mut::<T>(),
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_mut not possible for ColumnData::Slice",
-            ))?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::Slice",
+            })?,
             ColumnData::Const(c) => c.downcast_mut::<T>(),
-            ColumnData::BinaryOwned(_) => Err(format!(
-                "Downcast failed. downcast_mut not possible for ColumnData::SliceMut",
-            ))?,
-            ColumnData::BinarySliceMut(_) => Err(format!(
-                "Downcast failed. downcast_mut not possible for ColumnData::BinarySliceMut",
-            ))?,
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_mut not possible for ColumnData::BinarySlice",
-            ))?,
-            ColumnData::BinaryConst(_) => Err(format!(
-                "Downcast failed. downcast_mut not possible for ColumnData::BinaryConst",
-            ))?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::Shared",
+            })?,
+            ColumnData::BinaryOwned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::SliceMut",
+            })?,
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::BinarySlice",
+            })?,
+            ColumnData::BinaryConst(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::BinaryConst",
+            })?,
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_mut",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
     pub fn downcast_ref<T>(&self) -> Result<&[T], ErrorDesc>
@@ -198,18 +344,39 @@ impl<'a> ColumnData<'a> {
             ColumnData::SliceMut(c) => c.downcast_ref::<T>(),
             ColumnData::Slice(c) => c.downcast_ref::<T>(),
             ColumnData::Const(c) => c.downcast_ref::<T>(),
-            ColumnData::BinaryOwned(_) => Err(format!(
-                "Downcast failed. downcast_ref not possible for ColumnData::BinaryOwned",
-            ))?,
-            ColumnData::BinarySliceMut(_) => Err(format!(
-                "Downcast failed. downcast_ref not possible for ColumnData::BinarySliceMut",
-            ))?,
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_ref not possible for ColumnData::BinarySlice",
-            ))?,
-            ColumnData::BinaryConst(_) => Err(format!(
-                "Downcast failed. downcast_ref not possible for ColumnData::BinaryConst",
-            ))?,
+            ColumnData::Shared(c) => c.downcast_ref::<T>(),
+            ColumnData::BinaryOwned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::BinaryOwned",
+            })?,
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::BinarySlice",
+            })?,
+            ColumnData::BinaryConst(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::BinaryConst",
+            })?,
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_ref",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
 
@@ -222,147 +389,349 @@ impl<'a> ColumnData<'a> {
             ColumnData::SliceMut(c) => c.downcast_ref::<T>()?.len(),
             ColumnData::Slice(c) => c.downcast_ref::<T>()?.len(),
             ColumnData::Const(c) => c.downcast_ref::<T>()?.len(),
-            ColumnData::BinaryOwned(c) => c.downcast_binary_ref::<T>()?.1.len(),
-            ColumnData::BinarySliceMut(c) => c.downcast_binary_ref::<T>()?.1.len(),
-            ColumnData::BinarySlice(c) => c.downcast_binary_ref::<T>()?.1.len(),
-            ColumnData::BinaryConst(c) => c.downcast_binary_ref::<T>()?.1.len(),
+            ColumnData::Shared(c) => c.downcast_ref::<T>()?.len(),
+            ColumnData::BinaryOwned(c) => c.downcast_binary_ref::<T>()?.1.len() - 1,
+            ColumnData::BinarySliceMut(c) => c.downcast_binary_ref::<T>()?.1.len() - 1,
+            ColumnData::BinarySlice(c) => c.downcast_binary_ref::<T>()?.1.len() - 1,
+            ColumnData::BinaryConst(c) => c.downcast_binary_ref::<T>()?.1.len() - 1,
+            ColumnData::BinaryShared(c) => c.downcast_binary_ref::<T>()?.1.len() - 1,
+            ColumnData::Dictionary(c) => c.downcast_dictionary_ref::<T>()?.0.len(),
+            ColumnData::DictionarySlice(c) => c.downcast_dictionary_ref::<T>()?.0.len(),
+            ColumnData::RunLength(c) => {
+                c.downcast_runs_ref::<T>()?;
+                c.len()
+            }
         };
         Ok(len)
     }
 
-    pub fn downcast_binary_owned<T>(
-        self,
-    ) -> Result<(Vec<u8>, Vec<usize>, Vec<usize>, usize), ErrorDesc>
+    pub fn downcast_binary_owned<T>(self) -> Result<(Vec<u8>, BinaryOffsets), ErrorDesc>
     where
         T: Send + Sync + 'static,
     {
-        match self{
-                ColumnData::Owned(_) => Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::Owned",
-                ))?,
-                ColumnData::SliceMut(_)=>Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::SliceRef",
-                ))?,
-                ColumnData::Slice(_)=>Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::Slice",
-                ))?,
-                ColumnData::Const(_) => Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::Const",
-                ))?,
-                ColumnData::BinaryOwned(c)=>c.downcast_binary_owned::<T>(),
-                ColumnData::BinarySliceMut(_)=>Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::BinarySliceMut",
-                ))?,
-                ColumnData::BinarySlice(_)=>Err(format!(
-                    "Downcast failed. downcast_binary_owned not possible for ColumnData::BinarySlice",
-                ))?,
-                ColumnData::BinaryConst(c)=>c.downcast_binary_owned::<T>(),
-            }
+        match self {
+            ColumnData::Owned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::Owned",
+            })?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::Slice",
+            })?,
+            ColumnData::Const(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::Const",
+            })?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::Shared",
+            })?,
+            ColumnData::BinaryOwned(c) => c.downcast_binary_owned::<T>(),
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::BinarySlice",
+            })?,
+            ColumnData::BinaryConst(c) => c.downcast_binary_owned::<T>(),
+            ColumnData::BinaryShared(c) => match Arc::try_unwrap(c) {
+                Ok(c) => c.downcast_binary_owned::<T>(),
+                Err(_) => Err(RadixError::UnsupportedStorage {
+                    operation: "downcast_binary_owned",
+                    storage: "ColumnData::BinaryShared which is referenced by other columns",
+                })?,
+            },
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_owned",
+                storage: "ColumnData::RunLength",
+            })?,
+        }
     }
 
     pub fn downcast_binary_vec<T>(
         &mut self,
-    ) -> Result<(&mut Vec<u8>, &mut Vec<usize>, &mut Vec<usize>, &mut usize), ErrorDesc>
+    ) -> Result<(&mut Vec<u8>, &mut BinaryOffsets), ErrorDesc>
     where
         T: Send + Sync + 'static,
     {
         match self {
-            ColumnData::Owned(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::Owned",
-            ))?,
-            ColumnData::SliceMut(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::Slice",
-            ))?,
-            ColumnData::Const(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::Const",
-            ))?,
+            ColumnData::Owned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::Owned",
+            })?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::Slice",
+            })?,
+            ColumnData::Const(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::Const",
+            })?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::Shared",
+            })?,
             ColumnData::BinaryOwned(c) => c.downcast_binary_vec::<T>(),
-            ColumnData::BinarySliceMut(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::BinarySliceMut",
-            ))?,
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_binary_vec not possible for ColumnData::BinarySlice",
-            ))?,
+            ColumnData::BinarySliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::BinarySliceMut",
+            })?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::BinarySlice",
+            })?,
             ColumnData::BinaryConst(c) => c.downcast_binary_vec::<T>(),
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_vec",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
 
-    pub fn downcast_binary_mut<T>(
-        &mut self,
-    ) -> Result<(&mut [u8], &mut [usize], &mut [usize], &mut usize), ErrorDesc>
+    pub fn downcast_binary_mut<T>(&mut self) -> Result<(&mut [u8], BinaryOffsetsRef<'_>), ErrorDesc>
     where
         T: Send + Sync + 'static,
     {
         match self {
-            ColumnData::Owned(_) => Err(format!(
-                "Downcast failed. downcast_binary_mut not possible for ColumnData::Owned",
-            ))?,
-            ColumnData::SliceMut(_) => Err(format!(
-                "Downcast failed. downcast_binary_mut not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_binary_mut not possible for ColumnData::Slice",
-            ))?,
-            ColumnData::Const(_) => Err(format!(
-                "Downcast failed. downcast_binary_mut not possible for ColumnData::Const",
-            ))?,
+            ColumnData::Owned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::Owned",
+            })?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::Slice",
+            })?,
+            ColumnData::Const(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::Const",
+            })?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::Shared",
+            })?,
             ColumnData::BinaryOwned(c) => c.downcast_binary_mut::<T>(),
             ColumnData::BinarySliceMut(c) => c.downcast_binary_mut::<T>(),
-            ColumnData::BinarySlice(_) => Err(format!(
-                "Downcast failed. downcast_binary_mut not possible for ColumnData::BinarySlice",
-            ))?,
+            ColumnData::BinarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::BinarySlice",
+            })?,
             ColumnData::BinaryConst(c) => c.downcast_binary_mut::<T>(),
+            ColumnData::BinaryShared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::BinaryShared",
+            })?,
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_mut",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
 
     pub fn downcast_binary_ref<'b, T>(
         &'b self,
-    ) -> Result<(&'b [u8], &'b [usize], &'b [usize], &'b usize), ErrorDesc>
+    ) -> Result<(&'b [u8], BinaryOffsetsRef<'b>), ErrorDesc>
     where
         T: Send + Sync + 'static,
         'a: 'b,
     {
         match self {
-            ColumnData::Owned(_) => Err(format!(
-                "Downcast failed. downcast_binary_ref not possible for ColumnData::Owned",
-            ))?,
-            ColumnData::SliceMut(_) => Err(format!(
-                "Downcast failed. downcast_binary_ref not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Downcast failed. downcast_binary_ref not possible for ColumnData::Slice",
-            ))?,
-            ColumnData::Const(_) => Err(format!(
-                "Downcast failed. downcast_binary_ref not possible for ColumnData::Const",
-            ))?,
+            ColumnData::Owned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::Owned",
+            })?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::Slice",
+            })?,
+            ColumnData::Const(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::Const",
+            })?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::Shared",
+            })?,
             ColumnData::BinaryOwned(c) => c.downcast_binary_ref::<T>(),
             ColumnData::BinarySliceMut(c) => c.downcast_binary_ref::<T>(),
             ColumnData::BinarySlice(c) => c.downcast_binary_ref::<T>(),
             ColumnData::BinaryConst(c) => c.downcast_binary_ref::<T>(),
+            ColumnData::BinaryShared(c) => c.downcast_binary_ref::<T>(),
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_binary_ref",
+                storage: "ColumnData::RunLength",
+            })?,
+        }
+    }
+
+    pub fn is_dictionary(&self) -> bool {
+        matches!(
+            self,
+            ColumnData::Dictionary(_) | ColumnData::DictionarySlice(_)
+        )
+    }
+
+    //The codes of a dictionary-encoded column and the values they refer to
+    pub fn downcast_dictionary_ref<'b, T>(
+        &'b self,
+    ) -> Result<(&'b [u32], &'b DictionaryValues), ErrorDesc>
+    where
+        T: Send + Sync + 'static,
+        'a: 'b,
+    {
+        match self {
+            ColumnData::Dictionary(c) => c.downcast_dictionary_ref::<T>(),
+            ColumnData::DictionarySlice(c) => c.downcast_dictionary_ref::<T>(),
+            _ => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_dictionary_ref",
+                storage: "columns which are not dictionary-encoded",
+            })?,
+        }
+    }
+
+    //Like downcast_binary_ref, dictionary-encoded columns are decoded into the offsets layout
+    pub fn downcast_binary_decoded<'b, T>(&'b self) -> Result<DecodedBinary<'b>, ErrorDesc>
+    where
+        T: Send + Sync + 'static,
+        'a: 'b,
+    {
+        if self.is_dictionary() {
+            let (codes, values) = self.downcast_dictionary_ref::<T>()?;
+            let (values_datau8, values_offsets) = values.downcast_binary_ref::<T>()?;
+            let mut datau8 = Vec::new();
+            let mut offsets = BinaryOffsets::with_capacity(codes.len());
+            codes.iter().for_each(|c| {
+                datau8.extend_from_slice(binary_item(values_datau8, values_offsets, *c as usize));
+                offsets.push(datau8.len());
+            });
+            Ok(DecodedBinary::Owned(datau8, offsets))
+        } else {
+            let (datau8, offsets) = self.downcast_binary_ref::<T>()?;
+            Ok(DecodedBinary::Borrowed(datau8, offsets))
+        }
+    }
+
+    pub fn is_run_length(&self) -> bool {
+        matches!(self, ColumnData::RunLength(_))
+    }
+
+    //The value and the length of every run of a run-length encoded column
+    pub fn downcast_runs_ref<T>(&self) -> Result<(&[T], &[usize]), ErrorDesc>
+    where
+        T: Send + Sync + 'static,
+    {
+        match self {
+            ColumnData::RunLength(c) => c.downcast_runs_ref::<T>(),
+            _ => Err(RadixError::UnsupportedStorage {
+                operation: "downcast_runs_ref",
+                storage: "columns which are not run-length encoded",
+            })?,
+        }
+    }
+
+    //Like downcast_ref, run-length encoded columns are expanded
+    pub fn downcast_decoded<T>(&self) -> Result<Cow<'_, [T]>, ErrorDesc>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        match self {
+            ColumnData::RunLength(c) => Ok(Cow::Owned(c.decode::<T>()?)),
+            _ => Ok(Cow::Borrowed(self.downcast_ref::<T>()?)),
         }
     }
 
     pub fn get_binary_offset(&self) -> Result<usize, ErrorDesc> {
         match self {
-            ColumnData::Owned(_) => Err(format!(
-                "Getting binary offset not possible for ColumnData::Owned",
-            ))?,
-            ColumnData::SliceMut(_) => Err(format!(
-                "Getting binary offset not possible for ColumnData::SliceRef",
-            ))?,
-            ColumnData::Slice(_) => Err(format!(
-                "Getting binary offset not possible for ColumnData::Slice",
-            ))?,
-            ColumnData::Const(_) => Err(format!(
-                "Getting binary offset not possible for ColumnData::Const",
-            ))?,
+            ColumnData::Owned(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::Owned",
+            })?,
+            ColumnData::SliceMut(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::SliceRef",
+            })?,
+            ColumnData::Slice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::Slice",
+            })?,
+            ColumnData::Const(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::Const",
+            })?,
+            ColumnData::Shared(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::Shared",
+            })?,
             ColumnData::BinaryOwned(_) => Ok(0),
             ColumnData::BinarySliceMut(c) => Ok(c.offset()),
             ColumnData::BinarySlice(c) => Ok(c.offset()),
             ColumnData::BinaryConst(_) => Ok(0),
+            ColumnData::BinaryShared(_) => Ok(0),
+            ColumnData::Dictionary(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::Dictionary",
+            })?,
+            ColumnData::DictionarySlice(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::DictionarySlice",
+            })?,
+            ColumnData::RunLength(_) => Err(RadixError::UnsupportedStorage {
+                operation: "binary offset",
+                storage: "ColumnData::RunLength",
+            })?,
         }
     }
 
@@ -386,6 +755,7 @@ impl<'a> ColumnData<'a> {
             ColumnData::SliceMut(c) => c.assume_init::<T>().map(|c| ColumnData::SliceMut(c)),
             ColumnData::Slice(_) => Err(format!("Assume init not possible for mutable columns",))?,
             ColumnData::Const(_) => Err(format!("Assume init not possible for mutable columns",))?,
+            ColumnData::Shared(_) => Err(format!("Assume init not possible for shared columns",))?,
             ColumnData::BinaryOwned(_) => Ok(self),
             ColumnData::BinarySliceMut(_) => Ok(self),
             ColumnData::BinarySlice(_) => {
@@ -394,20 +764,72 @@ impl<'a> ColumnData<'a> {
             ColumnData::BinaryConst(_) => {
                 Err(format!("Assume init not possible for mutable columns",))?
             }
+            ColumnData::BinaryShared(_) => {
+                Err(format!("Assume init not possible for shared columns",))?
+            }
+            ColumnData::Dictionary(_) | ColumnData::DictionarySlice(_) => {
+                Err("Assume init not possible for dictionary-encoded columns")?
+            }
+            ColumnData::RunLength(_) => {
+                Err("Assume init not possible for run-length encoded columns")?
+            }
+        }
+    }
+
+    pub fn into_shared(self) -> Result<ColumnData<'static>, ErrorDesc> {
+        match self {
+            ColumnData::Owned(c) => Ok(ColumnData::Shared(Arc::new(c))),
+            ColumnData::Shared(c) => Ok(ColumnData::Shared(c)),
+            ColumnData::BinaryOwned(c) => Ok(ColumnData::BinaryShared(Arc::new(c))),
+            ColumnData::BinaryShared(c) => Ok(ColumnData::BinaryShared(c)),
+            _ => Err("Only owned or shared columns can be transformed to shared columns")?,
+        }
+    }
+
+    //Columns which do not borrow their data can outlive the source they were created from
+    pub fn into_static(self) -> Result<ColumnData<'static>, ErrorDesc> {
+        match self {
+            ColumnData::Owned(c) => Ok(ColumnData::Owned(c)),
+            ColumnData::Const(c) => Ok(ColumnData::Const(c)),
+            ColumnData::Shared(c) => Ok(ColumnData::Shared(c)),
+            ColumnData::BinaryOwned(c) => Ok(ColumnData::BinaryOwned(c)),
+            ColumnData::BinaryConst(c) => Ok(ColumnData::BinaryConst(c)),
+            ColumnData::BinaryShared(c) => Ok(ColumnData::BinaryShared(c)),
+            ColumnData::Dictionary(c) => Ok(ColumnData::Dictionary(c)),
+            ColumnData::RunLength(c) => Ok(ColumnData::RunLength(c)),
+            _ => Err("Columns referencing borrowed data cannot be transformed to static columns")?,
+        }
+    }
+
+    pub fn share(&self) -> Result<ColumnData<'static>, ErrorDesc> {
+        match self {
+            ColumnData::Shared(c) => Ok(ColumnData::Shared(Arc::clone(c))),
+            ColumnData::BinaryShared(c) => Ok(ColumnData::BinaryShared(Arc::clone(c))),
+            _ => Err("Operation not supported for columns which are not Shared or BinaryShared")?,
+        }
+    }
+
+    //Number of columns referencing the same shared buffer, None if the column is not shared
+    pub fn shared_count(&self) -> Option<usize> {
+        match self {
+            ColumnData::Shared(c) => Some(Arc::strong_count(c)),
+            ColumnData::BinaryShared(c) => Some(Arc::strong_count(c)),
+            _ => None,
         }
     }
 }
 
+//Validity of the rows of a column, bit-packed
 #[derive(Debug, PartialEq, Eq)]
-pub enum ColumnDataF<'a, T> {
+pub enum ColumnDataF<'a> {
     //None,
-    Owned(Vec<T>),
-    Slice(&'a [T]),
-    SliceMut(&'a mut [T]),
+    Owned(Bitmap),
+    Slice(BitmapRef<'a>),
+    Shared(Arc<Bitmap>),
     None,
 }
 
-impl<'a, T> ColumnDataF<'a, T> {
+impl<'a> ColumnDataF<'a> {
     pub fn is_some(&self) -> bool {
         self.len().is_some()
     }
@@ -421,57 +843,99 @@ impl<'a, T> ColumnDataF<'a, T> {
             //ColumnDataF::None => None,
             ColumnDataF::Owned(v) => Some(v.len()),
             ColumnDataF::Slice(s) => Some(s.len()),
-            ColumnDataF::SliceMut(s) => Some(s.len()),
+            ColumnDataF::Shared(v) => Some(v.len()),
             ColumnDataF::None => None,
         }
     }
 
-    pub fn downcast_ref<'b>(&'b self) -> Result<&'b [T], ErrorDesc> {
+    pub fn downcast_ref<'b>(&'b self) -> Result<BitmapRef<'b>, ErrorDesc> {
         match &self {
-            ColumnDataF::Owned(v) => Ok(v.as_slice()),
-            ColumnDataF::Slice(s) => Ok(s),
-            ColumnDataF::SliceMut(s) => Ok(s),
+            ColumnDataF::Owned(v) => Ok(v.as_bitmap_ref()),
+            ColumnDataF::Slice(s) => Ok(*s),
+            ColumnDataF::Shared(v) => Ok(v.as_bitmap_ref()),
             ColumnDataF::None => Err("ColumnDataF is None and cannot be downcasted as a ref")?,
         }
     }
 
-    pub fn downcast_mut<'b>(&'b mut self) -> Result<&'b mut [T], ErrorDesc> {
+    pub fn downcast_mut<'b>(&'b mut self) -> Result<BitmapMut<'b>, ErrorDesc> {
         match self {
-            ColumnDataF::Owned(v) => Ok(v.as_mut_slice()),
+            ColumnDataF::Owned(v) => Ok(v.as_bitmap_mut()),
             ColumnDataF::Slice(_) => Err("")?,
-            ColumnDataF::SliceMut(s) => Ok(*s),
+            ColumnDataF::Shared(_) => {
+                Err("ColumnDataF is Shared and cannot be downcasted as a mut ref")?
+            }
             ColumnDataF::None => Err("ColumnDataF is None and cannot be downcasted as a mut ref")?,
         }
     }
 
-    pub fn downcast_vec<'b>(&'b mut self) -> Result<&'b mut Vec<T>, ErrorDesc>
+    pub fn downcast_vec<'b>(&'b mut self) -> Result<&'b mut Bitmap, ErrorDesc>
     where
         'a: 'b,
     {
         match self {
             ColumnDataF::Owned(v) => Ok(v),
             ColumnDataF::Slice(_) => Err("")?,
-            ColumnDataF::SliceMut(_) => Err("")?,
+            ColumnDataF::Shared(_) => {
+                Err("ColumnDataF is Shared and cannot be downcasted as a mut Vec")?
+            }
             ColumnDataF::None => Err("ColumnDataF is None and cannot be downcasted as a mut Vec")?,
         }
     }
-    pub fn new(data: Vec<T>) -> Self {
-        ColumnDataF::Owned(data)
+    pub fn new(data: impl Into<Bitmap>) -> Self {
+        ColumnDataF::Owned(data.into())
     }
-    pub fn new_from_slice(data: &'a [T]) -> Self {
+    pub fn new_from_slice(data: BitmapRef<'a>) -> Self {
         ColumnDataF::Slice(data)
     }
-    pub fn new_from_slice_mut(data: &'a mut [T]) -> Self {
-        ColumnDataF::SliceMut(data)
-    }
     pub fn is_owned(&self) -> bool {
         match self {
             ColumnDataF::Owned(_) => true,
             ColumnDataF::Slice(_) => false,
-            ColumnDataF::SliceMut(_) => false,
+            ColumnDataF::Shared(_) => false,
             ColumnDataF::None => false,
         }
     }
+    pub fn is_shared(&self) -> bool {
+        matches!(self, ColumnDataF::Shared(_))
+    }
+    //Popcount of the words, 0 if there is no bitmap
+    pub fn null_count(&self) -> usize {
+        self.downcast_ref().map(|b| b.null_count()).unwrap_or(0)
+    }
+    //True if there is no bitmap or none of the rows is null, in which case the bitmap can be ignored
+    pub fn all_valid(&self) -> bool {
+        self.downcast_ref().map(|b| b.all_valid()).unwrap_or(true)
+    }
+    pub fn into_shared(self) -> Result<ColumnDataF<'static>, ErrorDesc> {
+        match self {
+            ColumnDataF::Owned(v) => Ok(ColumnDataF::Shared(Arc::new(v))),
+            ColumnDataF::Shared(v) => Ok(ColumnDataF::Shared(v)),
+            ColumnDataF::None => Ok(ColumnDataF::None),
+            _ => Err("Only owned or shared bitmaps can be transformed to shared bitmaps")?,
+        }
+    }
+    pub fn into_static(self) -> Result<ColumnDataF<'static>, ErrorDesc> {
+        match self {
+            ColumnDataF::Owned(v) => Ok(ColumnDataF::Owned(v)),
+            ColumnDataF::Shared(v) => Ok(ColumnDataF::Shared(v)),
+            ColumnDataF::None => Ok(ColumnDataF::None),
+            _ => Err("Bitmaps referencing borrowed data cannot be transformed to static bitmaps")?,
+        }
+    }
+    pub fn share(&self) -> Result<ColumnDataF<'static>, ErrorDesc> {
+        match self {
+            ColumnDataF::Shared(v) => Ok(ColumnDataF::Shared(Arc::clone(v))),
+            ColumnDataF::None => Ok(ColumnDataF::None),
+            _ => Err("Operation not supported for bitmaps which are not Shared")?,
+        }
+    }
+    //Copy-on-write: the buffer is only copied if it is still referenced by other columns
+    pub fn unshare(&mut self) {
+        if let ColumnDataF::Shared(v) = self {
+            let v = std::mem::take(v);
+            *self = ColumnDataF::Owned(Arc::try_unwrap(v).unwrap_or_else(|v| (*v).clone()));
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -0,0 +1,721 @@
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::RadixError;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
+
+//Parts of dates and times which can be extracted or truncated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateTimeField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl FromStr for DateTimeField {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "YEAR" => Ok(Self::Year),
+            "MONTH" => Ok(Self::Month),
+            "DAY" => Ok(Self::Day),
+            "HOUR" => Ok(Self::Hour),
+            "MINUTE" => Ok(Self::Minute),
+            "SECOND" => Ok(Self::Second),
+            _ => Err(format!("Unknown date or time field {}", s))?,
+        }
+    }
+}
+
+impl fmt::Display for DateTimeField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Year => "YEAR",
+            Self::Month => "MONTH",
+            Self::Day => "DAY",
+            Self::Hour => "HOUR",
+            Self::Minute => "MINUTE",
+            Self::Second => "SECOND",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+//Types supporting EXTRACT and DATE_TRUNC
+pub trait Temporal: Sized {
+    fn has_field(field: DateTimeField) -> bool;
+    //Fields which are not part of the type are 0
+    fn extract(&self, field: DateTimeField) -> u32;
+    //Fields which are not part of the type leave the value unchanged
+    fn trunc(&self, field: DateTimeField) -> Self;
+}
+
+//Days since 1970-01-01
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Date(pub i32);
+
+impl Date {
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Result<Self, RadixError> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            Err(format!(
+                "{:04}-{:02}-{:02} is not a valid date",
+                year, month, day
+            ))?
+        }
+        Ok(Self(days_from_civil(year, month, day) as i32))
+    }
+
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        civil_from_days(self.0 as i64)
+    }
+
+    //Months are added first, the day is clamped to the end of the month
+    pub fn add_interval(&self, interval: &Interval) -> Result<Self, RadixError> {
+        let out_of_range = || RadixError::Other(format!("{} + {} is out of range", self, interval));
+        let (year, month, day) = self.ymd();
+        let months = year as i64 * 12 + month as i64 - 1 + interval.months as i64;
+        let (year, month) = (
+            i32::try_from(months.div_euclid(12)).map_err(|_| out_of_range())?,
+            months.rem_euclid(12) as u32 + 1,
+        );
+        let day = day.min(days_in_month(year, month));
+        let days = days_from_civil(year, month, day) + interval.days as i64;
+        Ok(Self(i32::try_from(days).map_err(|_| out_of_range())?))
+    }
+}
+
+impl Temporal for Date {
+    fn has_field(field: DateTimeField) -> bool {
+        matches!(
+            field,
+            DateTimeField::Year | DateTimeField::Month | DateTimeField::Day
+        )
+    }
+
+    fn extract(&self, field: DateTimeField) -> u32 {
+        let (year, month, day) = self.ymd();
+        match field {
+            DateTimeField::Year => year as u32,
+            DateTimeField::Month => month,
+            DateTimeField::Day => day,
+            _ => 0,
+        }
+    }
+
+    fn trunc(&self, field: DateTimeField) -> Self {
+        let (year, month, _) = self.ymd();
+        match field {
+            DateTimeField::Year => Self(days_from_civil(year, 1, 1) as i32),
+            DateTimeField::Month => Self(days_from_civil(year, month, 1) as i32),
+            _ => *self,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+//YYYY-MM-DD
+impl FromStr for Date {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Scanner::new(s, "date");
+        let date = p.date()?;
+        p.end()?;
+        Ok(date)
+    }
+}
+
+//Nanoseconds since midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Time(pub i64);
+
+impl Time {
+    pub fn from_hms_nano(
+        hour: u32,
+        minute: u32,
+        second: u32,
+        nano: u32,
+    ) -> Result<Self, RadixError> {
+        if hour > 23 || minute > 59 || second > 59 || nano as i64 >= NANOS_PER_SECOND {
+            Err(format!(
+                "{:02}:{:02}:{:02}.{:09} is not a valid time",
+                hour, minute, second, nano
+            ))?
+        }
+        Ok(Self(
+            hour as i64 * NANOS_PER_HOUR
+                + minute as i64 * NANOS_PER_MINUTE
+                + second as i64 * NANOS_PER_SECOND
+                + nano as i64,
+        ))
+    }
+}
+
+impl Temporal for Time {
+    fn has_field(field: DateTimeField) -> bool {
+        matches!(
+            field,
+            DateTimeField::Hour | DateTimeField::Minute | DateTimeField::Second
+        )
+    }
+
+    fn extract(&self, field: DateTimeField) -> u32 {
+        match field {
+            DateTimeField::Hour => (self.0 / NANOS_PER_HOUR) as u32,
+            DateTimeField::Minute => (self.0 % NANOS_PER_HOUR / NANOS_PER_MINUTE) as u32,
+            DateTimeField::Second => (self.0 % NANOS_PER_MINUTE / NANOS_PER_SECOND) as u32,
+            _ => 0,
+        }
+    }
+
+    fn trunc(&self, field: DateTimeField) -> Self {
+        match field {
+            DateTimeField::Hour => Self(self.0 - self.0 % NANOS_PER_HOUR),
+            DateTimeField::Minute => Self(self.0 - self.0 % NANOS_PER_MINUTE),
+            DateTimeField::Second => Self(self.0 - self.0 % NANOS_PER_SECOND),
+            _ => *self,
+        }
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.extract(DateTimeField::Hour),
+            self.extract(DateTimeField::Minute),
+            self.extract(DateTimeField::Second)
+        )?;
+        write_fraction(f, self.0 % NANOS_PER_SECOND)
+    }
+}
+
+//HH:MM[:SS[.fraction]]
+impl FromStr for Time {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Scanner::new(s, "time");
+        let time = p.time()?;
+        p.end()?;
+        Ok(time)
+    }
+}
+
+//Nanoseconds since 1970-01-01T00:00:00Z, timestamps without offset are local times of an unknown timezone
+//Timestamps are compared and hashed by their instant, the offset is only used for formatting and calendar fields
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timestamp {
+    nanos: i64,
+    //Minutes east of UTC
+    offset: Option<i16>,
+}
+
+impl Timestamp {
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self {
+            nanos,
+            offset: None,
+        }
+    }
+
+    //None if the instant is out of the range of the type
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        micros.checked_mul(1000).map(Self::from_nanos)
+    }
+
+    //The instant stays the same, only the local time changes
+    pub fn with_offset(mut self, offset_minutes: Option<i16>) -> Self {
+        self.offset = offset_minutes;
+        self
+    }
+
+    //None if the timestamp is out of the range of the type
+    pub fn from_date_time(date: Date, time: Time) -> Option<Self> {
+        (date.0 as i64)
+            .checked_mul(NANOS_PER_DAY)
+            .and_then(|n| n.checked_add(time.0))
+            .map(Self::from_nanos)
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.nanos.div_euclid(1000)
+    }
+
+    pub fn offset(&self) -> Option<i16> {
+        self.offset
+    }
+
+    fn local_nanos(&self) -> i64 {
+        self.nanos + self.offset.unwrap_or(0) as i64 * NANOS_PER_MINUTE
+    }
+
+    fn with_local_nanos(&self, local_nanos: i64) -> Self {
+        Self {
+            nanos: local_nanos - self.offset.unwrap_or(0) as i64 * NANOS_PER_MINUTE,
+            offset: self.offset,
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        Date(self.local_nanos().div_euclid(NANOS_PER_DAY) as i32)
+    }
+
+    pub fn time(&self) -> Time {
+        Time(self.local_nanos().rem_euclid(NANOS_PER_DAY))
+    }
+
+    //Months and days are added to the local date, so that the time of day does not change
+    pub fn add_interval(&self, interval: &Interval) -> Result<Self, RadixError> {
+        let date = self.date().add_interval(interval)?;
+        (date.0 as i64)
+            .checked_mul(NANOS_PER_DAY)
+            .and_then(|n| n.checked_add(self.time().0))
+            .and_then(|n| n.checked_add(interval.nanos))
+            .and_then(|n| n.checked_sub(self.offset.unwrap_or(0) as i64 * NANOS_PER_MINUTE))
+            .map(|nanos| Self {
+                nanos,
+                offset: self.offset,
+            })
+            .ok_or_else(|| RadixError::Other(format!("{} + {} is out of range", self, interval)))
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.nanos == other.nanos
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl Hash for Timestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nanos.hash(state)
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.nanos.cmp(&other.nanos)
+    }
+}
+
+impl Temporal for Timestamp {
+    fn has_field(_field: DateTimeField) -> bool {
+        true
+    }
+
+    fn extract(&self, field: DateTimeField) -> u32 {
+        if Date::has_field(field) {
+            self.date().extract(field)
+        } else {
+            self.time().extract(field)
+        }
+    }
+
+    fn trunc(&self, field: DateTimeField) -> Self {
+        let (date, time) = if Date::has_field(field) {
+            (self.date().trunc(field), Time(0))
+        } else {
+            (self.date(), self.time().trunc(field))
+        };
+        self.with_local_nanos(date.0 as i64 * NANOS_PER_DAY + time.0)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}", self.date(), self.time())?;
+        match self.offset {
+            None => Ok(()),
+            Some(0) => write!(f, "Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+            }
+        }
+    }
+}
+
+//YYYY-MM-DD[(T| )HH:MM[:SS[.fraction]]][Z|(+|-)HH[:MM]]
+impl FromStr for Timestamp {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut p = Scanner::new(s, "timestamp");
+        let date = p.date()?;
+        let time = if p.eat('T') || p.eat(' ') {
+            p.time()?
+        } else {
+            Time(0)
+        };
+        let offset = if p.eat('Z') {
+            Some(0)
+        } else if p.peek() == Some('+') || p.peek() == Some('-') {
+            let sign = if p.eat('-') {
+                -1
+            } else {
+                p.expect('+')?;
+                1
+            };
+            let hours = p.number(2, 2)? as i16;
+            p.eat(':');
+            let minutes = if p.is_end() {
+                0
+            } else {
+                p.number(2, 2)? as i16
+            };
+            if hours > 23 || minutes > 59 {
+                Err(p.error())?
+            }
+            Some(sign * (hours * 60 + minutes))
+        } else {
+            None
+        };
+        p.end()?;
+        Timestamp::from_date_time(date, time)
+            .and_then(|local| {
+                local
+                    .nanos
+                    .checked_sub(offset.unwrap_or(0) as i64 * NANOS_PER_MINUTE)
+            })
+            .map(|nanos| Self { nanos, offset })
+            .ok_or_else(|| RadixError::Other(format!("Timestamp '{}' is out of range", s)))
+    }
+}
+
+//Months and days are kept apart from the time, as their length depends on the calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, nanos: i64) -> Self {
+        Self {
+            months,
+            days,
+            nanos,
+        }
+    }
+
+    pub fn negate(&self) -> Result<Self, RadixError> {
+        match (
+            self.months.checked_neg(),
+            self.days.checked_neg(),
+            self.nanos.checked_neg(),
+        ) {
+            (Some(months), Some(days), Some(nanos)) => Ok(Self::new(months, days, nanos)),
+            _ => Err(format!("-({}) is out of range", self))?,
+        }
+    }
+
+    //The add functions add value times the size of a unit, None if the interval overflows
+    fn add_months(&mut self, value: i64, months_per_unit: i64) -> Option<()> {
+        let months = i32::try_from(value.checked_mul(months_per_unit)?).ok()?;
+        self.months = self.months.checked_add(months)?;
+        Some(())
+    }
+
+    fn add_days(&mut self, value: i64, days_per_unit: i64) -> Option<()> {
+        let days = i32::try_from(value.checked_mul(days_per_unit)?).ok()?;
+        self.days = self.days.checked_add(days)?;
+        Some(())
+    }
+
+    fn add_nanos(&mut self, value: i64, nanos_per_unit: i64) -> Option<()> {
+        self.nanos = self.nanos.checked_add(value.checked_mul(nanos_per_unit)?)?;
+        Some(())
+    }
+}
+
+//ISO-8601 duration like P1Y2M3DT4H5M6.5S, components can be negative
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Self::default() {
+            return write!(f, "PT0S");
+        }
+        write!(f, "P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        for (value, unit) in [(years, 'Y'), (months, 'M'), (self.days, 'D')] {
+            if value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        if self.nanos != 0 {
+            write!(f, "T")?;
+            let sign = if self.nanos < 0 { "-" } else { "" };
+            let nanos = self.nanos.unsigned_abs();
+            let (hours, minutes) = (
+                nanos / NANOS_PER_HOUR as u64,
+                nanos % NANOS_PER_HOUR as u64 / NANOS_PER_MINUTE as u64,
+            );
+            let (seconds, fraction) = (
+                nanos % NANOS_PER_MINUTE as u64 / NANOS_PER_SECOND as u64,
+                nanos % NANOS_PER_SECOND as u64,
+            );
+            if hours != 0 {
+                write!(f, "{}{}H", sign, hours)?;
+            }
+            if minutes != 0 {
+                write!(f, "{}{}M", sign, minutes)?;
+            }
+            if seconds != 0 || fraction != 0 {
+                write!(f, "{}{}", sign, seconds)?;
+                write_fraction(f, fraction as i64)?;
+                write!(f, "S")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+//Either an ISO-8601 duration or a list of quantities like '1 day' or '2 hours 30 minutes'
+impl FromStr for Interval {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RadixError::Other(format!("'{}' is not a valid interval", s));
+        let out_of_range = || RadixError::Other(format!("Interval '{}' is out of range", s));
+        let mut interval = Interval::default();
+        let mut p = Scanner::new(s.trim(), "interval");
+        if p.eat('P') {
+            let mut in_time = false;
+            while !p.is_end() {
+                if p.eat('T') {
+                    in_time = true;
+                    continue;
+                }
+                let negative = p.eat('-');
+                let value = p.number(1, 18)? as i64;
+                let value = if negative { -value } else { value };
+                let fraction = if p.eat('.') { p.fraction()? } else { 0 };
+                let added = match (p.next(), in_time) {
+                    (Some('Y'), false) => interval.add_months(value, 12),
+                    (Some('M'), false) => interval.add_months(value, 1),
+                    (Some('W'), false) => interval.add_days(value, 7),
+                    (Some('D'), false) => interval.add_days(value, 1),
+                    (Some('H'), true) => interval.add_nanos(value, NANOS_PER_HOUR),
+                    (Some('M'), true) => interval.add_nanos(value, NANOS_PER_MINUTE),
+                    (Some('S'), true) => {
+                        let fraction = if negative { -fraction } else { fraction };
+                        interval
+                            .add_nanos(value, NANOS_PER_SECOND)
+                            .and_then(|_| interval.add_nanos(fraction, 1))
+                    }
+                    _ => Err(error())?,
+                };
+                added.ok_or_else(out_of_range)?;
+            }
+            return Ok(interval);
+        }
+
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let pairs = words.chunks_exact(2);
+        if words.is_empty() || !pairs.remainder().is_empty() {
+            Err(error())?
+        }
+        for pair in pairs {
+            let value = pair[0].parse::<i64>().map_err(|_| error())?;
+            let unit = pair[1].to_ascii_lowercase();
+            let added = match unit.strip_suffix('s').unwrap_or(&unit) {
+                "year" => interval.add_months(value, 12),
+                "month" => interval.add_months(value, 1),
+                "week" => interval.add_days(value, 7),
+                "day" => interval.add_days(value, 1),
+                "hour" => interval.add_nanos(value, NANOS_PER_HOUR),
+                "minute" => interval.add_nanos(value, NANOS_PER_MINUTE),
+                "second" => interval.add_nanos(value, NANOS_PER_SECOND),
+                "millisecond" => interval.add_nanos(value, 1_000_000),
+                "microsecond" => interval.add_nanos(value, 1000),
+                _ => Err(error())?,
+            };
+            added.ok_or_else(out_of_range)?;
+        }
+        Ok(interval)
+    }
+}
+
+//Fractions of a second are written with 3, 6 or 9 digits and left out if they are 0
+fn write_fraction(f: &mut fmt::Formatter<'_>, nanos: i64) -> fmt::Result {
+    if nanos == 0 {
+        Ok(())
+    } else if nanos % 1_000_000 == 0 {
+        write!(f, ".{:03}", nanos / 1_000_000)
+    } else if nanos % 1000 == 0 {
+        write!(f, ".{:06}", nanos / 1000)
+    } else {
+        write!(f, ".{:09}", nanos)
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+//Days since 1970-01-01 of a date of the proleptic Gregorian calendar, see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year as i32, month, day)
+}
+
+//Reads the parts of ISO-8601 dates and times
+struct Scanner<'s> {
+    s: &'s str,
+    pos: usize,
+    what: &'static str,
+}
+
+impl<'s> Scanner<'s> {
+    fn new(s: &'s str, what: &'static str) -> Self {
+        Self { s, pos: 0, what }
+    }
+
+    fn error(&self) -> RadixError {
+        RadixError::Other(format!("'{}' is not a valid {}", self.s, self.what))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), RadixError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn end(&self) -> Result<(), RadixError> {
+        if self.is_end() {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn number(&mut self, min_digits: usize, max_digits: usize) -> Result<u64, RadixError> {
+        let digits = self.s[self.pos..]
+            .bytes()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits < min_digits || digits > max_digits {
+            Err(self.error())?
+        }
+        let value = self.s[self.pos..self.pos + digits].parse().unwrap();
+        self.pos += digits;
+        Ok(value)
+    }
+
+    //Digits after the decimal point as nanoseconds
+    fn fraction(&mut self) -> Result<i64, RadixError> {
+        let start = self.pos;
+        let value = self.number(1, 9)? as i64;
+        Ok(value * 10i64.pow(9 - (self.pos - start) as u32))
+    }
+
+    fn date(&mut self) -> Result<Date, RadixError> {
+        let year = self.number(4, 4)? as i32;
+        self.expect('-')?;
+        let month = self.number(2, 2)? as u32;
+        self.expect('-')?;
+        let day = self.number(2, 2)? as u32;
+        Date::from_ymd(year, month, day)
+    }
+
+    fn time(&mut self) -> Result<Time, RadixError> {
+        let hour = self.number(2, 2)? as u32;
+        self.expect(':')?;
+        let minute = self.number(2, 2)? as u32;
+        let second = if self.eat(':') {
+            self.number(2, 2)? as u32
+        } else {
+            0
+        };
+        let nano = if self.eat('.') { self.fraction()? } else { 0 };
+        Time::from_hms_nano(hour, minute, second, nano as u32)
+    }
+}
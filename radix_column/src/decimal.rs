@@ -0,0 +1,381 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::RadixError;
+
+//How digits beyond the scale of a result are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    //Ties are rounded away from zero, 2.5 -> 3, -2.5 -> -3
+    HalfUp,
+    //Ties are rounded to the even neighbour, 2.5 -> 2, 3.5 -> 4
+    HalfEven,
+    //Towards zero
+    Down,
+    //Towards negative infinity
+    Floor,
+    //Towards positive infinity
+    Ceiling,
+}
+
+//Fixed-point number value / 10^scale with at most precision digits, precision is at most 38
+//Every value carries its own precision and scale, values of different scales are compared by their numeric value
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    value: i128,
+    precision: u8,
+    scale: u8,
+}
+
+impl Decimal {
+    pub const MAX_PRECISION: u8 = 38;
+
+    //Result of an operation which does not fit into a decimal, operations on it are overflows as well
+    pub const OVERFLOW: Self = Self {
+        value: 0,
+        precision: 0,
+        scale: 0,
+    };
+
+    pub fn new(value: i128, precision: u8, scale: u8) -> Result<Self, RadixError> {
+        if precision == 0 || precision > Self::MAX_PRECISION || scale > precision {
+            Err(format!(
+                "DECIMAL({}, {}) is not a valid decimal type",
+                precision, scale
+            ))?
+        }
+        match Self::checked_new(value, precision, scale) {
+            Some(d) => Ok(d),
+            None => Err(format!(
+                "{} does not fit into DECIMAL({}, {})",
+                Self::unchecked(value, scale),
+                precision,
+                scale
+            ))?,
+        }
+    }
+
+    pub fn from_int(value: i128) -> Result<Self, RadixError> {
+        Self::new(value, digits(value).max(1), 0)
+    }
+
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    pub fn is_overflow(&self) -> bool {
+        self.precision == 0
+    }
+
+    //The scale is the larger scale of the operands, None on overflow
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        let precision =
+            (self.precision - self.scale).max(other.precision - other.scale) + scale + 1;
+        Self::checked_new(a.checked_add(b)?, precision.min(Self::MAX_PRECISION), scale)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        let precision =
+            (self.precision - self.scale).max(other.precision - other.scale) + scale + 1;
+        Self::checked_new(a.checked_sub(b)?, precision.min(Self::MAX_PRECISION), scale)
+    }
+
+    //The scale is the sum of the scales of the operands, rounded to at most 38 digits
+    pub fn checked_mul(&self, other: &Self, rounding: RoundingMode) -> Option<Self> {
+        if self.is_overflow() || other.is_overflow() {
+            return None;
+        }
+        let value = self.value.checked_mul(other.value)?;
+        let scale = self.scale as u32 + other.scale as u32;
+        let precision =
+            (self.precision as u32 + other.precision as u32).min(Self::MAX_PRECISION as u32) as u8;
+        if scale > precision as u32 {
+            let value = round_div(value, pow10(scale - precision as u32)?, rounding);
+            Self::checked_new(value, precision, precision)
+        } else {
+            Self::checked_new(value, precision, scale as u8)
+        }
+    }
+
+    //The scale of the quotient is the scale of the dividend plus 4, like 10.00 / 3 = 3.333333
+    //None on overflow and for division by zero
+    pub fn checked_div(&self, other: &Self, rounding: RoundingMode) -> Option<Self> {
+        self.checked_div_to_scale(other, (self.scale + 4).min(Self::MAX_PRECISION), rounding)
+    }
+
+    //The quotient rounded to the given scale
+    pub fn checked_div_to_scale(
+        &self,
+        other: &Self,
+        scale: u8,
+        rounding: RoundingMode,
+    ) -> Option<Self> {
+        if self.is_overflow() || other.is_overflow() || other.value == 0 || scale > Self::MAX_PRECISION {
+            return None;
+        }
+        //value / 10^s1 / (other / 10^s2) = value * 10^(scale + s2 - s1) / other / 10^scale
+        let exp = scale as i32 + other.scale as i32 - self.scale as i32;
+        let (dividend, divisor) = if exp >= 0 {
+            (self.value.checked_mul(pow10(exp as u32)?)?, other.value)
+        } else {
+            (self.value, other.value.checked_mul(pow10(-exp as u32)?)?)
+        };
+        let precision = (self.precision - self.scale) as u32 + other.scale as u32 + scale as u32;
+        Self::checked_new(
+            round_div(dividend, divisor, rounding),
+            precision.min(Self::MAX_PRECISION as u32) as u8,
+            scale,
+        )
+    }
+
+    //Rounds or extends the value to the given scale, the integer digits are kept
+    pub fn rescale(&self, scale: u8, rounding: RoundingMode) -> Option<Self> {
+        let integer_digits = self.precision - self.scale;
+        //Rounding can carry into a new integer digit, like 9.99 -> 10.0
+        let carry = (scale < self.scale) as u8;
+        let precision = (integer_digits + scale + carry).clamp(1, Self::MAX_PRECISION);
+        self.with_precision(precision, scale.min(precision), rounding)
+    }
+
+    //The value as DECIMAL(precision, scale), None if it has too many integer digits
+    pub fn with_precision(&self, precision: u8, scale: u8, rounding: RoundingMode) -> Option<Self> {
+        if self.is_overflow()
+            || precision == 0
+            || precision > Self::MAX_PRECISION
+            || scale > precision
+        {
+            return None;
+        }
+        let value = match scale.cmp(&self.scale) {
+            Ordering::Less => round_div(self.value, pow10((self.scale - scale) as u32)?, rounding),
+            Ordering::Equal => self.value,
+            Ordering::Greater => self
+                .value
+                .checked_mul(pow10((scale - self.scale) as u32)?)?,
+        };
+        Self::checked_new(value, precision, scale)
+    }
+
+    fn checked_new(value: i128, precision: u8, scale: u8) -> Option<Self> {
+        match value.unsigned_abs() < 10u128.pow(precision as u32) {
+            true => Some(Self {
+                value,
+                precision,
+                scale,
+            }),
+            false => None,
+        }
+    }
+
+    fn unchecked(value: i128, scale: u8) -> Self {
+        Self {
+            value,
+            precision: Self::MAX_PRECISION,
+            scale,
+        }
+    }
+
+    //Both values at the larger scale of the two
+    fn align(&self, other: &Self) -> Option<(i128, i128, u8)> {
+        if self.is_overflow() || other.is_overflow() {
+            return None;
+        }
+        let scale = self.scale.max(other.scale);
+        Some((
+            self.value
+                .checked_mul(pow10((scale - self.scale) as u32)?)?,
+            other
+                .value
+                .checked_mul(pow10((scale - other.scale) as u32)?)?,
+            scale,
+        ))
+    }
+
+    //Trailing zeros of the fraction removed, equal values have the same normalized form
+    fn normalized(&self) -> (i128, u8) {
+        let (mut value, mut scale) = (self.value, self.scale);
+        while scale > 0 && value % 10 == 0 {
+            value /= 10;
+            scale -= 1;
+        }
+        (value, scale)
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Self {
+            value: 0,
+            precision: 1,
+            scale: 0,
+        }
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(value: u64) -> Self {
+        Self {
+            value: value as i128,
+            precision: digits(value as i128).max(1),
+            scale: 0,
+        }
+    }
+}
+
+impl From<u32> for Decimal {
+    fn from(value: u32) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, a_scale) = self.normalized();
+        let (b, b_scale) = other.normalized();
+        let scale = a_scale.max(b_scale);
+        let a = pow10((scale - a_scale) as u32).and_then(|p| a.checked_mul(p));
+        let b = pow10((scale - b_scale) as u32).and_then(|p| b.checked_mul(p));
+        match (a, b) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            //A value which cannot be brought to the larger scale exceeds the other one
+            (None, _) if self.value < 0 => Ordering::Less,
+            (None, _) => Ordering::Greater,
+            (_, None) if other.value < 0 => Ordering::Greater,
+            (_, None) => Ordering::Less,
+        }
+    }
+}
+
+//All digits of the scale are printed, like 12.50
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_overflow() {
+            return write!(f, "OVERFLOW");
+        }
+        let sign = if self.value < 0 { "-" } else { "" };
+        let digits = self.value.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            write!(f, "{}{}", sign, digits)
+        } else if digits.len() > scale {
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{}{}.{}", sign, integer, fraction)
+        } else {
+            write!(f, "{}0.{:0>width$}", sign, digits, width = scale)
+        }
+    }
+}
+
+//The precision and scale are taken from the digits, like DECIMAL(4, 2) for 12.50
+impl FromStr for Decimal {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RadixError::Other(format!("'{}' is not a valid decimal", s));
+        let (negative, unsigned) = match s.trim().as_bytes().first() {
+            Some(b'-') => (true, &s.trim()[1..]),
+            Some(b'+') => (false, &s.trim()[1..]),
+            _ => (false, s.trim()),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (unsigned, ""),
+        };
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            Err(error())?
+        }
+        let integer = integer.trim_start_matches('0');
+        let precision = (integer.len() + fraction.len()).max(1);
+        if precision > Self::MAX_PRECISION as usize {
+            Err(format!(
+                "'{}' has more than {} digits",
+                s,
+                Self::MAX_PRECISION
+            ))?
+        }
+        let value: i128 = format!("{}{}", integer, fraction)
+            .parse::<i128>()
+            .unwrap_or(0);
+        Self::new(
+            if negative { -value } else { value },
+            precision as u8,
+            fraction.len() as u8,
+        )
+    }
+}
+
+fn digits(value: i128) -> u8 {
+    let mut value = value.unsigned_abs();
+    let mut digits = 0;
+    while value > 0 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+fn pow10(exp: u32) -> Option<i128> {
+    10i128.checked_pow(exp)
+}
+
+//value / divisor rounded to an integer
+fn round_div(value: i128, divisor: i128, rounding: RoundingMode) -> i128 {
+    let quotient = value / divisor;
+    let remainder = value % divisor;
+    if remainder == 0 {
+        return quotient;
+    }
+    let away = if (value < 0) != (divisor < 0) { -1 } else { 1 };
+    let remainder = remainder.unsigned_abs();
+    let rest = divisor.unsigned_abs() - remainder;
+    let round_away = match rounding {
+        RoundingMode::HalfUp => remainder >= rest,
+        RoundingMode::HalfEven => remainder > rest || remainder == rest && quotient % 2 != 0,
+        RoundingMode::Down => false,
+        RoundingMode::Floor => away < 0,
+        RoundingMode::Ceiling => away > 0,
+    };
+    match round_away {
+        true => quotient + away,
+        false => quotient,
+    }
+}
@@ -0,0 +1,115 @@
+use std::{any::TypeId, collections::HashSet, ops::Deref, sync::Arc};
+
+use super::{binarycolumn::OnwedBinaryColumn, ErrorDesc, RadixError};
+
+//The values of a dictionary, each one stored once so that equal items always get equal codes
+//Comparisons, IN and sorting work on the codes, so the values are checked when the dictionary is built
+//Columns encoded with the same dictionary share it by cloning the Arc
+#[derive(Debug, Clone)]
+pub struct DictionaryValues(Arc<OnwedBinaryColumn>);
+
+impl DictionaryValues {
+    pub fn new(values: OnwedBinaryColumn) -> Result<Self, ErrorDesc> {
+        let mut seen = HashSet::with_capacity(values.len());
+        if let Some(i) = values.items().position(|item| !seen.insert(item)) {
+            Err(format!("Dictionary value {} is a duplicate", i))?
+        }
+        Ok(Self(Arc::new(values)))
+    }
+
+    //Whether both columns are encoded with the same dictionary, and so can compare their codes
+    pub fn ptr_eq(&self, other: &DictionaryValues) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for DictionaryValues {
+    type Target = OnwedBinaryColumn;
+
+    fn deref(&self) -> &OnwedBinaryColumn {
+        &self.0
+    }
+}
+
+//Items stored as codes into a dictionary holding each distinct value once
+#[derive(Debug, Clone)]
+pub struct DictionaryColumn {
+    codes: Vec<u32>,
+    values: DictionaryValues,
+}
+
+impl DictionaryColumn {
+    pub fn new(codes: Vec<u32>, values: DictionaryValues) -> Result<Self, ErrorDesc> {
+        check_codes(&codes, &values)?;
+        Ok(Self { codes, values })
+    }
+
+    pub fn item_type_id(&self) -> TypeId {
+        self.values.item_type_id()
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn downcast_dictionary_ref<T>(&self) -> Result<(&[u32], &DictionaryValues), ErrorDesc>
+    where
+        T: 'static,
+    {
+        if self.values.is::<T>() {
+            Ok((&self.codes, &self.values))
+        } else {
+            Err(RadixError::downcast_failed::<T>(self.item_type_id()))?
+        }
+    }
+}
+
+//Borrows the codes of a dictionary-encoded column
+#[derive(Debug)]
+pub struct DictionarySlice<'a> {
+    codes: &'a [u32],
+    values: &'a DictionaryValues,
+}
+
+impl<'a> DictionarySlice<'a> {
+    pub fn new(codes: &'a [u32], values: &'a DictionaryValues) -> Self {
+        Self { codes, values }
+    }
+
+    pub fn item_type_id(&self) -> TypeId {
+        self.values.item_type_id()
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn downcast_dictionary_ref<T>(&self) -> Result<(&'a [u32], &'a DictionaryValues), ErrorDesc>
+    where
+        T: 'static,
+    {
+        if self.values.is::<T>() {
+            Ok((self.codes, self.values))
+        } else {
+            Err(RadixError::downcast_failed::<T>(self.item_type_id()))?
+        }
+    }
+}
+
+fn check_codes(codes: &[u32], values: &OnwedBinaryColumn) -> Result<(), ErrorDesc> {
+    match codes.iter().find(|c| **c as usize >= values.len()) {
+        Some(c) => Err(RadixError::OutOfBounds {
+            index: *c as usize,
+            len: values.len(),
+        })?,
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,152 @@
+use std::{any::TypeId, error::Error, fmt, io};
+
+use crate::{registered_type_name, MemoryLimitExceeded, TypeName};
+
+pub type ErrorDesc = RadixError;
+
+//Errors of the column operations, they are Send and Sync so that they can be returned from worker threads
+//The names of the types are the names registered in the dictionary
+#[derive(Debug)]
+pub enum RadixError {
+    //The column of an operation has another type than the operation expects
+    TypeMismatch {
+        expected: TypeId,
+        expected_name: String,
+        found: TypeId,
+        found_name: String,
+    },
+    //No operation or internal column operation is registered for the name and input types
+    SignatureNotFound {
+        op_name: String,
+        input: Vec<TypeId>,
+        input_names: Vec<String>,
+    },
+    //The data of a column is not of the requested type
+    DowncastFailed {
+        source: TypeId,
+        target: TypeId,
+        target_name: &'static str,
+    },
+    //The data of a column is stored in a way which does not support the access, e.g. a mutable access to a slice
+    UnsupportedStorage {
+        operation: &'static str,
+        storage: &'static str,
+    },
+    OutOfBounds {
+        index: usize,
+        len: usize,
+    },
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    MemoryLimitExceeded(MemoryLimitExceeded),
+    Cancelled,
+    Io(io::Error),
+    Other(String),
+}
+
+impl RadixError {
+    pub fn downcast_failed<T: 'static>(source: TypeId) -> Self {
+        RadixError::DowncastFailed {
+            source,
+            target: TypeId::of::<T>(),
+            target_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+impl fmt::Display for RadixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadixError::TypeMismatch {
+                expected_name,
+                found_name,
+                ..
+            } => write!(
+                f,
+                "Type mismatch. Expected type is {}, found type is {}",
+                expected_name, found_name
+            ),
+            RadixError::SignatureNotFound {
+                op_name,
+                input_names,
+                ..
+            } => write!(
+                f,
+                "The following operation not found in dictionary: {:?}({})",
+                op_name,
+                input_names.join(", ")
+            ),
+            RadixError::DowncastFailed {
+                source,
+                target,
+                target_name,
+            } => write!(
+                f,
+                "Downcast failed. Source type is {}, target type is {}",
+                TypeName(*source),
+                registered_type_name(*target).unwrap_or(target_name)
+            ),
+            RadixError::UnsupportedStorage { operation, storage } => write!(
+                f,
+                "Downcast failed. {} not possible for {}",
+                operation, storage
+            ),
+            RadixError::OutOfBounds { index, len } => write!(
+                f,
+                "Index out of bounds. Index is {}, while the length is {}",
+                index, len
+            ),
+            RadixError::LengthMismatch { expected, found } => write!(
+                f,
+                "Length mismatch. Expected length is {}, found length is {}",
+                expected, found
+            ),
+            RadixError::MemoryLimitExceeded(e) => e.fmt(f),
+            RadixError::Cancelled => write!(f, "The operation has been cancelled"),
+            RadixError::Io(e) => e.fmt(f),
+            RadixError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RadixError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RadixError::MemoryLimitExceeded(e) => Some(e),
+            RadixError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for RadixError {
+    fn from(e: String) -> Self {
+        RadixError::Other(e)
+    }
+}
+
+impl From<&str> for RadixError {
+    fn from(e: &str) -> Self {
+        RadixError::Other(e.to_string())
+    }
+}
+
+impl From<MemoryLimitExceeded> for RadixError {
+    fn from(e: MemoryLimitExceeded) -> Self {
+        RadixError::MemoryLimitExceeded(e)
+    }
+}
+
+impl From<io::Error> for RadixError {
+    fn from(e: io::Error) -> Self {
+        RadixError::Io(e)
+    }
+}
+
+impl From<fmt::Error> for RadixError {
+    fn from(e: fmt::Error) -> Self {
+        RadixError::Other(e.to_string())
+    }
+}
@@ -3,10 +3,13 @@ use std::{
     collections::{HashMap, VecDeque},
     hash::Hash,
     hash::Hasher,
+    sync::Arc,
 };
 
 use ahash::AHashMap;
 
+use crate::{MemoryLimitExceeded, MemoryReservation, MemoryTracker};
+
 pub struct NullableValue<T> {
     pub value: T,
     pub bitmap: bool,
@@ -29,24 +32,40 @@ impl<T: Hash> Hash for NullableValue<T> {
 }
 
 pub struct HashMapBuffer {
-    stored: VecDeque<(Box<dyn Any>, TypeId)>,
+    stored: VecDeque<(Box<dyn Any + Send>, TypeId)>,
+    //Covers the largest hash map built with the buffer, the maps keep their capacity when they are reused
+    reservation: MemoryReservation,
 }
 
 impl<'a> HashMapBuffer {
     pub fn new() -> Self {
+        Self::with_memory(Arc::new(MemoryTracker::unlimited()))
+    }
+    pub fn with_memory(tracker: Arc<MemoryTracker>) -> Self {
         Self {
             stored: VecDeque::new(),
+            reservation: MemoryReservation::new(tracker),
         }
     }
-    pub fn push<T: 'static>(
+    //Reserves the memory of a hash map with the given number of entries before it is filled
+    pub fn reserve<K, V>(&mut self, entries: usize) -> Result<(), MemoryLimitExceeded> {
+        self.reservation
+            .grow_to(entries.saturating_mul(std::mem::size_of::<(K, V)>()))
+    }
+    pub fn reserved(&self) -> usize {
+        self.reservation.size()
+    }
+    pub fn push<T: 'static + Send>(
         &mut self,
         mut h: Box<HashMap<(usize, NullableValue<T>), usize, ahash::RandomState>>,
     ) {
         h.clear();
-        self.stored
-            .push_back((Box::new(h) as Box<dyn Any + 'static>, TypeId::of::<T>()));
+        self.stored.push_back((
+            Box::new(h) as Box<dyn Any + Send + 'static>,
+            TypeId::of::<T>(),
+        ));
     }
-    pub fn pop<T: 'static>(
+    pub fn pop<T: 'static + Send>(
         &mut self,
     ) -> Box<HashMap<(usize, NullableValue<T>), usize, ahash::RandomState>> {
         let item_type_id = TypeId::of::<T>();
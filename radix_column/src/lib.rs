@@ -1,15 +1,33 @@
 pub mod asbytes;
 pub mod binarycolumn;
+pub mod bitmap;
 pub mod column;
 pub mod columndata;
+pub mod datetime;
+pub mod decimal;
+pub mod dictionarycolumn;
+pub mod error;
 pub mod hashmap_buffer;
+pub mod logicaltype;
+pub mod memory;
 pub mod owned;
+pub mod runlengthcolumn;
 pub mod sliceref;
 
 pub use asbytes::*;
 pub use binarycolumn::*;
+pub use bitmap::*;
 pub use column::*;
 pub use columndata::*;
+pub use datetime::*;
+pub use decimal::*;
+pub use dictionarycolumn::*;
+pub use error::*;
 pub use hashmap_buffer::*;
+pub use logicaltype::*;
+pub use memory::*;
 pub use owned::*;
+pub use runlengthcolumn::*;
 pub use sliceref::*;
+
+pub use radix_derive::AsBytes;
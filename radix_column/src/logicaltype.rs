@@ -0,0 +1,87 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, RwLock},
+};
+
+//Logical type of the items of a column, the name is stable and is used in errors, schemas and EXPLAIN output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalType {
+    pub name: &'static str,
+    pub item_type_id: TypeId,
+    //Binary items are stored as bytes with offsets and lengths, sized items as a slice
+    pub is_binary: bool,
+    pub is_numeric: bool,
+    pub is_orderable: bool,
+}
+
+impl LogicalType {
+    pub fn sized<T: 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            item_type_id: TypeId::of::<T>(),
+            is_binary: false,
+            is_numeric: false,
+            is_orderable: false,
+        }
+    }
+
+    pub fn binary<T: 'static>(name: &'static str) -> Self {
+        Self {
+            is_binary: true,
+            ..Self::sized::<T>(name)
+        }
+    }
+
+    pub fn numeric(mut self) -> Self {
+        self.is_numeric = true;
+        self
+    }
+
+    pub fn orderable(mut self) -> Self {
+        self.is_orderable = true;
+        self
+    }
+
+    pub fn is_sized(&self) -> bool {
+        !self.is_binary
+    }
+}
+
+static TYPE_NAMES: OnceLock<RwLock<HashMap<TypeId, &'static str>>> = OnceLock::new();
+
+//The names of the types registered in any dictionary are kept for the whole process,
+//so that errors and signatures can name a TypeId where no dictionary is at hand
+pub fn register_type_name(item_type_id: TypeId, name: &'static str) {
+    TYPE_NAMES
+        .get_or_init(Default::default)
+        .write()
+        .unwrap()
+        .insert(item_type_id, name);
+}
+
+pub fn registered_type_name(item_type_id: TypeId) -> Option<&'static str> {
+    TYPE_NAMES
+        .get()
+        .and_then(|names| names.read().unwrap().get(&item_type_id).copied())
+}
+
+//Prints the registered name of a type, or the TypeId if the type has not been registered
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TypeName(pub TypeId);
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match registered_type_name(self.0) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{:?}", self.0),
+        }
+    }
+}
+
+impl fmt::Debug for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
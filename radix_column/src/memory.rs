@@ -0,0 +1,149 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+//Returned when a reservation would take the tracker over its limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryLimitExceeded {
+    pub requested: usize,
+    pub reserved: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for MemoryLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Memory limit exceeded: {} bytes requested while {} of {} bytes are reserved",
+            self.requested, self.reserved, self.limit
+        )
+    }
+}
+
+impl Error for MemoryLimitExceeded {}
+
+//Counts the bytes reserved by a query, reservations fail once the limit would be exceeded
+//The bytes are reserved before an allocation is made, the tracker does not see the allocations themselves
+#[derive(Debug, Default)]
+pub struct MemoryTracker {
+    limit: Option<usize>,
+    reserved: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl MemoryTracker {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::Relaxed)
+    }
+
+    //Highest number of bytes reserved at the same time
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    pub fn try_reserve(&self, bytes: usize) -> Result<(), MemoryLimitExceeded> {
+        let mut reserved = self.reserved.load(Ordering::Relaxed);
+        loop {
+            let new_reserved = reserved.saturating_add(bytes);
+            if let Some(limit) = self.limit {
+                if new_reserved > limit {
+                    return Err(MemoryLimitExceeded {
+                        requested: bytes,
+                        reserved,
+                        limit,
+                    });
+                }
+            }
+            match self.reserved.compare_exchange_weak(
+                reserved,
+                new_reserved,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    self.peak.fetch_max(new_reserved, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(current) => reserved = current,
+            }
+        }
+    }
+
+    pub fn release(&self, bytes: usize) {
+        self.reserved.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+//Bytes reserved in a tracker, they are released when the reservation is dropped
+pub struct MemoryReservation {
+    tracker: Arc<MemoryTracker>,
+    size: usize,
+}
+
+impl MemoryReservation {
+    pub fn new(tracker: Arc<MemoryTracker>) -> Self {
+        Self { tracker, size: 0 }
+    }
+
+    pub fn tracker(&self) -> &Arc<MemoryTracker> {
+        &self.tracker
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn grow(&mut self, bytes: usize) -> Result<(), MemoryLimitExceeded> {
+        self.tracker.try_reserve(bytes)?;
+        self.size += bytes;
+        Ok(())
+    }
+
+    //Grows the reservation to at least the given size, it is never made smaller
+    pub fn grow_to(&mut self, size: usize) -> Result<(), MemoryLimitExceeded> {
+        if size > self.size {
+            self.grow(size - self.size)?;
+        }
+        Ok(())
+    }
+
+    pub fn shrink(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.size);
+        self.tracker.release(bytes);
+        self.size -= bytes;
+    }
+
+    //Moves bytes to another reservation of the same tracker, they stay reserved in between
+    pub fn transfer(&mut self, other: &mut MemoryReservation, bytes: usize) {
+        debug_assert!(Arc::ptr_eq(&self.tracker, &other.tracker));
+        let bytes = bytes.min(self.size);
+        self.size -= bytes;
+        other.size += bytes;
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.tracker.release(self.size);
+    }
+}
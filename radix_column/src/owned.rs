@@ -3,7 +3,7 @@ use std::{
     mem::MaybeUninit,
 };
 
-use crate::ErrorDesc;
+use crate::{ErrorDesc, RadixError};
 
 fn copy_of_into_boxed_slice<T>(boxed: Box<T>) -> Box<[T]> {
     // *mut T and *mut [T; 1] have the same size and alignment
@@ -44,14 +44,9 @@ impl OwnedColumn {
         T: Send + Sync + 'static,
     {
         let (col, item_type_id) = (self.data as Box<dyn Any>, self.item_type_id);
-        let col = col.downcast::<Vec<T>>().map_err(|_| {
-            format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            )
-        })?;
+        let col = col
+            .downcast::<Vec<T>>()
+            .map_err(|_| RadixError::downcast_failed::<T>(item_type_id))?;
         let col = copy_of_into_boxed_slice(col);
         let mut res: Vec<Vec<T>> = col.into();
         //Should never fail
@@ -64,12 +59,7 @@ impl OwnedColumn {
     {
         match self.data.downcast_mut::<Vec<T>>().map(|v| v.as_mut_slice()) {
             Some(s) => Ok(s),
-            None => Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?,
+            None => Err(RadixError::downcast_failed::<T>(self.item_type_id))?,
         }
     }
     pub fn downcast_ref<T>(&self) -> Result<&[T], ErrorDesc>
@@ -78,12 +68,7 @@ impl OwnedColumn {
     {
         match self.data.downcast_ref::<Vec<T>>().map(|v| v.as_slice()) {
             Some(s) => Ok(s),
-            None => Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?,
+            None => Err(RadixError::downcast_failed::<T>(self.item_type_id))?,
         }
     }
     pub fn downcast_vec<T>(&mut self) -> Result<&mut Vec<T>, ErrorDesc>
@@ -92,12 +77,7 @@ impl OwnedColumn {
     {
         match self.data.downcast_mut::<Vec<T>>() {
             Some(s) => Ok(s),
-            None => Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?,
+            None => Err(RadixError::downcast_failed::<T>(self.item_type_id))?,
         }
     }
 
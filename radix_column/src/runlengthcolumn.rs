@@ -0,0 +1,120 @@
+use std::{any::TypeId, ops::Range};
+
+use super::{ErrorDesc, OwnedColumn, RadixError};
+
+//Items stored as runs of equal values, values[i] is repeated run_lengths[i] times
+//Sorted or clustered data, like the dates of time-ordered logs, needs only a few runs
+#[derive(Debug)]
+pub struct RunLengthColumn {
+    values: OwnedColumn,
+    run_lengths: Vec<usize>,
+    len: usize,
+}
+
+impl RunLengthColumn {
+    pub fn new<T>(values: Vec<T>, run_lengths: Vec<usize>) -> Result<Self, ErrorDesc>
+    where
+        T: Send + Sync + 'static,
+    {
+        if values.len() != run_lengths.len() {
+            Err(RadixError::LengthMismatch {
+                expected: values.len(),
+                found: run_lengths.len(),
+            })?
+        }
+        if run_lengths.contains(&0) {
+            Err("Runs of a run-length encoded column cannot be empty")?
+        }
+        Ok(Self {
+            values: OwnedColumn::new(values),
+            len: run_lengths.iter().sum(),
+            run_lengths,
+        })
+    }
+
+    //Builds the column from (value, run length) pairs, adjacent runs of equal values are merged
+    pub fn encode<T>(runs: impl IntoIterator<Item = (T, usize)>) -> Result<Self, ErrorDesc>
+    where
+        T: PartialEq + Send + Sync + 'static,
+    {
+        let mut values: Vec<T> = vec![];
+        let mut run_lengths: Vec<usize> = vec![];
+        for (v, l) in runs {
+            match (values.last(), run_lengths.last_mut()) {
+                (Some(last), Some(n)) if *last == v => *n += l,
+                _ => {
+                    values.push(v);
+                    run_lengths.push(l);
+                }
+            }
+        }
+        Self::new(values, run_lengths)
+    }
+
+    pub fn item_type_id(&self) -> TypeId {
+        self.values.item_type_id()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn number_of_runs(&self) -> usize {
+        self.run_lengths.len()
+    }
+
+    pub fn downcast_runs_ref<T>(&self) -> Result<(&[T], &[usize]), ErrorDesc>
+    where
+        T: 'static,
+    {
+        Ok((self.values.downcast_ref::<T>()?, &self.run_lengths))
+    }
+
+    //Repeats the value of every run
+    pub fn decode<T>(&self) -> Result<Vec<T>, ErrorDesc>
+    where
+        T: Clone + 'static,
+    {
+        let (values, run_lengths) = self.downcast_runs_ref::<T>()?;
+        let mut data = Vec::with_capacity(self.len);
+        values
+            .iter()
+            .zip(run_lengths.iter())
+            .for_each(|(v, l)| data.resize(data.len() + l, v.clone()));
+        Ok(data)
+    }
+
+    //Copies the runs covering the rows, the first and the last run are shortened to the range
+    pub fn range<T>(&self, rows: Range<usize>) -> Result<Self, ErrorDesc>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if rows.end > self.len {
+            Err(RadixError::OutOfBounds {
+                index: rows.end,
+                len: self.len,
+            })?
+        }
+        let (values, run_lengths) = self.downcast_runs_ref::<T>()?;
+        let mut range_values = vec![];
+        let mut range_run_lengths = vec![];
+        let mut start = 0;
+        for (v, l) in values.iter().zip(run_lengths.iter()) {
+            let end = start + l;
+            let (s, e) = (start.max(rows.start), end.min(rows.end));
+            if s < e {
+                range_values.push(v.clone());
+                range_run_lengths.push(e - s);
+            }
+            if end >= rows.end {
+                break;
+            }
+            start = end;
+        }
+        Self::new(range_values, range_run_lengths)
+    }
+}
@@ -1,6 +1,6 @@
 use std::{marker::PhantomData, mem::MaybeUninit};
 
-use super::ErrorDesc;
+use super::{ErrorDesc, RadixError};
 
 #[derive(Debug)]
 pub struct SliceRef<'a> {
@@ -65,12 +65,7 @@ impl<'a> SliceRef<'a> {
                 Ok(std::slice::from_raw_parts(ptr, len))
             }
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
     pub fn split_off_left<T>(&mut self, pos: usize) -> Result<Self, ErrorDesc>
@@ -78,10 +73,10 @@ impl<'a> SliceRef<'a> {
         T: 'static + Sync,
     {
         if pos > self.len {
-            Err(format!(
-                "Attempt to split at slice at position {}, while the source slice has length {}",
-                pos, self.len,
-            ))?
+            Err(RadixError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            })?
         } else if self.is::<T>() {
             let l = Self {
                 item_type_id: self.item_type_id,
@@ -94,12 +89,7 @@ impl<'a> SliceRef<'a> {
             self.ptr = unsafe { (self.ptr as *const T).offset(pos as isize) as *const u8 };
             Ok(l)
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
 }
@@ -162,12 +152,7 @@ impl<'a> SliceRefMut<'a> {
                 Ok(std::slice::from_raw_parts(ptr, len))
             }
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
     pub fn downcast_mut<T>(&mut self) -> Result<&mut [T], ErrorDesc>
@@ -185,12 +170,7 @@ impl<'a> SliceRefMut<'a> {
                 Ok(std::slice::from_raw_parts_mut(ptr, len))
             }
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
     pub fn split_off_left<T>(&mut self, pos: usize) -> Result<Self, ErrorDesc>
@@ -198,10 +178,10 @@ impl<'a> SliceRefMut<'a> {
         T: 'static + Sync,
     {
         if pos > self.len {
-            Err(format!(
-                "Attempt to split at slice at position {}, while the source slice has length {}",
-                pos, self.len,
-            ))?
+            Err(RadixError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            })?
         } else if self.is::<T>() {
             let l = Self {
                 item_type_id: self.item_type_id,
@@ -214,12 +194,7 @@ impl<'a> SliceRefMut<'a> {
             self.ptr = unsafe { (self.ptr as *mut T).offset(pos as isize) as *mut u8 };
             Ok(l)
         } else {
-            Err(format!(
-                "Downcast failed. Source type is {:?}, target type is ({:?}, {})",
-                self.item_type_id,
-                std::any::TypeId::of::<T>(),
-                std::any::type_name::<T>()
-            ))?
+            Err(RadixError::downcast_failed::<T>(self.item_type_id))?
         }
     }
     //SAFETY: The caller must take care that the column is fully initialized
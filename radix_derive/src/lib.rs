@@ -0,0 +1,160 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
+
+//Derives radix_column::AsBytes, so that the type can be stored in binary columns
+//Every field is encoded by its own AsBytes implementation and prefixed with its length as u32,
+//enums are prefixed with the position of the variant in the declaration as u32
+//The encoding is stable as long as fields and variants are neither reordered nor removed
+#[proc_macro_derive(AsBytes)]
+pub fn derive_as_bytes(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::radix_column::AsBytes));
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (encode, decode) = match &input.data {
+        Data::Struct(data) => {
+            let (bindings, pattern) = bind_fields(&data.fields);
+            let encode = encode_fields(&bindings);
+            let decode = decode_fields(&data.fields, &bindings, quote!(Self));
+            (
+                quote! {
+                    let Self #pattern = self;
+                    #encode
+                },
+                quote! {
+                    let value = #decode;
+                },
+            )
+        }
+        Data::Enum(data) => {
+            let mut encode = vec![];
+            let mut decode = vec![];
+            for (i, variant) in data.variants.iter().enumerate() {
+                let i = i as u32;
+                let ident = &variant.ident;
+                let (bindings, pattern) = bind_fields(&variant.fields);
+                let encode_variant = encode_fields(&bindings);
+                encode.push(quote! {
+                    Self::#ident #pattern => {
+                        out.extend_from_slice(&#i.to_le_bytes());
+                        #encode_variant
+                    }
+                });
+                let decode_variant =
+                    decode_fields(&variant.fields, &bindings, quote!(Self::#ident));
+                decode.push(quote! {
+                    #i => #decode_variant,
+                });
+            }
+            let variants = data.variants.len();
+            (
+                quote! {
+                    match self {
+                        #(#encode)*
+                    }
+                },
+                quote! {
+                    let value = match ::radix_column::read_u32(&mut data)? {
+                        #(#decode)*
+                        variant => Err(::radix_column::RadixError::OutOfBounds {
+                            index: variant as usize,
+                            len: #variants,
+                        })?,
+                    };
+                },
+            )
+        }
+        Data::Union(_) => {
+            return syn::Error::new_spanned(&input.ident, "AsBytes cannot be derived for unions")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let expanded = quote! {
+        impl #impl_generics ::radix_column::AsBytes for #name #ty_generics #where_clause {
+            fn as_bytes(&self) -> ::std::borrow::Cow<'_, [u8]> {
+                #[allow(unused_mut)]
+                let mut out: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
+                #encode
+                ::std::borrow::Cow::Owned(out)
+            }
+
+            fn try_from_bytes(data: &[u8]) -> ::std::result::Result<Self, ::radix_column::RadixError> {
+                let total = data.len();
+                #[allow(unused_mut)]
+                let mut data = data;
+                #decode
+                if !data.is_empty() {
+                    Err(::radix_column::RadixError::LengthMismatch {
+                        expected: total - data.len(),
+                        found: total,
+                    })?
+                }
+                Ok(value)
+            }
+        }
+    };
+    expanded.into()
+}
+
+//Names of the fields in a pattern and the pattern which binds them
+fn bind_fields(fields: &Fields) -> (Vec<TokenStream2>, TokenStream2) {
+    match fields {
+        Fields::Named(named) => {
+            let bindings: Vec<_> = named
+                .named
+                .iter()
+                .map(|f| {
+                    let ident = f.ident.as_ref().unwrap();
+                    quote!(#ident)
+                })
+                .collect();
+            let pattern = quote!({ #(#bindings),* });
+            (bindings, pattern)
+        }
+        Fields::Unnamed(unnamed) => {
+            let bindings: Vec<_> = (0..unnamed.unnamed.len())
+                .map(|i| {
+                    let ident = format_ident!("field_{}", i);
+                    quote!(#ident)
+                })
+                .collect();
+            let pattern = quote!(( #(#bindings),* ));
+            (bindings, pattern)
+        }
+        Fields::Unit => (vec![], quote!()),
+    }
+}
+
+fn encode_fields(bindings: &[TokenStream2]) -> TokenStream2 {
+    quote! {
+        #(
+            ::radix_column::write_field(&mut out, &::radix_column::AsBytes::as_bytes(#bindings));
+        )*
+    }
+}
+
+fn decode_fields(fields: &Fields, bindings: &[TokenStream2], path: TokenStream2) -> TokenStream2 {
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let pattern = match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => quote!(#path),
+    };
+    quote! {
+        {
+            #(
+                let #bindings = <#types as ::radix_column::AsBytes>::try_from_bytes(
+                    ::radix_column::read_field(&mut data)?
+                )?;
+            )*
+            #pattern
+        }
+    }
+}
@@ -13,7 +13,7 @@ macro_rules! operation_load {
                 type T3=$tr;
                 let signature=sig![OP; T2, T3];
                 let op=Operation{
-                    f:  paste!{[<add_ $tl:lower _ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<add_ $tl:lower _ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T1>(),
                     is_assign_op: false,
                     associated_assign_op: Some("+=".to_string()),
@@ -12,7 +12,7 @@ macro_rules! operation_load {
                 type T2=$tr;
                 let signature=sig![OP; T1, T2];
                 let op=Operation{
-                    f:  paste!{[<addassign_ $tl:lower _ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<addassign_ $tl:lower _ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T1>(),
                     is_assign_op: true,
                     associated_assign_op: None,
@@ -11,7 +11,7 @@ macro_rules! operation_load {
                 type T=$tr;
                 let signature=sig![OP; T];
                 let op=Operation{
-                    f:  paste!{[<sum_ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<sum_ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<u64>(),
                     is_assign_op: false,
                     associated_assign_op: None,
@@ -87,13 +87,21 @@ macro_rules! operation_impl_copy {
                     (false, true)=>{
                         assert_eq!(group_ids.len(),c2_data.len());
                         let c2_bitmap=c2_bitmap.downcast_ref()?;
-                        c2_data.iter().zip(c2_bitmap).zip(group_ids).for_each(|((_data, bitmap), group_id)|
-                            c1_data[*group_id]+=*bitmap as u64
-                        );
+                        if number_of_groups==1 {
+                            //A single group counts the valid rows with a popcount of the bitmap words
+                            c1_data[0]+=c2_bitmap.count_ones() as u64;
+                        } else {
+                            c2_bitmap.iter().zip(group_ids).for_each(|(bitmap, group_id)|
+                                c1_data[*group_id]+=bitmap as u64
+                            );
+                        }
                     }
                     (false, false)=>{
-                        assert_eq!(group_ids.len(),c2_data.len());
-                        c2_data.iter().zip(group_ids).for_each(|(_data, group_id)|
+                        //A const, like the input of COUNT(*), counts every row
+                        if !c2.column().is_const() {
+                            assert_eq!(group_ids.len(),c2_data.len());
+                        }
+                        group_ids.iter().for_each(|group_id|
                             c1_data[*group_id]+=1
                         );}
 
@@ -138,7 +146,7 @@ macro_rules! operation_impl_binary {
                 assert!(c1.column().is_owned());
 
                 let (c2_data, c2_bitmap)=(c2.column(), c2.bitmap());
-                let (_c2_datau8, c2_start_pos, _c2_len, _offset)=c2_data.downcast_binary_ref::<T>()?;
+                let c2_len=c2_data.data_len::<T>()?;
 
 
                 let group_ids=c3.column().downcast_ref::<usize>()?;
@@ -166,15 +174,20 @@ macro_rules! operation_impl_binary {
                         );
                     }
                     (false, true)=>{
-                        assert_eq!(group_ids.len(),c2_start_pos.len());
+                        assert_eq!(group_ids.len(),c2_len);
                         let c2_bitmap=c2_bitmap.downcast_ref()?;
-                        c2_start_pos.iter().zip(c2_bitmap).zip(group_ids).for_each(|((_data, bitmap), group_id)|
-                            c1_data[*group_id]+=*bitmap as u64
-                        );
+                        if number_of_groups==1 {
+                            //A single group counts the valid rows with a popcount of the bitmap words
+                            c1_data[0]+=c2_bitmap.count_ones() as u64;
+                        } else {
+                            c2_bitmap.iter().zip(group_ids).for_each(|(bitmap, group_id)|
+                                c1_data[*group_id]+=bitmap as u64
+                            );
+                        }
                     }
                     (false, false)=>{
-                        assert_eq!(group_ids.len(),c2_start_pos.len());
-                        c2_start_pos.iter().zip(group_ids).for_each(|(_data, group_id)|
+                        assert_eq!(group_ids.len(),c2_len);
+                        group_ids.iter().for_each(|group_id|
                             c1_data[*group_id]+=1
                         );}
 
@@ -186,20 +199,20 @@ macro_rules! operation_impl_binary {
 }
 
 operation_impl_copy! {
-    u64 u32
+    u64 u32 Decimal
 }
 
 operation_impl_binary! {
-    String
+    String Blob
 }
 
 pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
     operation_load! {dict;
-        u64 u32
+        u64 u32 Decimal
     };
 
     operation_load! {dict;
-        String
+        String Blob
     };
 }
 
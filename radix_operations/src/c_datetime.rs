@@ -0,0 +1,297 @@
+use std::{any::TypeId, cell::RefCell, str::FromStr, sync::Arc};
+
+use crate::*;
+use radix_column::*;
+
+//EXTRACT(field, value) and DATE_TRUNC(field, value) expect the field name as constant String,
+//CAST#<TYPE> converts its input to the logical type <TYPE>
+
+pub(crate) fn insert(
+    dict: &mut OpDictionary,
+    op: &str,
+    input: Vec<TypeId>,
+    output: TypeId,
+    f: OperationFn,
+) {
+    dict.insert(
+        Signature::new(op, input),
+        Operation {
+            f,
+            output_type_id: output,
+            is_assign_op: false,
+            associated_assign_op: None,
+            associated_input_switch_op: None,
+        },
+    );
+}
+
+pub(crate) fn has_bitmap(input: &InputTypes) -> bool {
+    match input {
+        InputTypes::Ref(c, _) => c.bitmap().is_some(),
+        InputTypes::Owned(c, _) => c.bitmap().is_some(),
+    }
+}
+
+fn const_field(op: &str, input: &InputTypes) -> Result<DateTimeField, ErrorDesc> {
+    let c = match input {
+        InputTypes::Ref(c, _) => *c,
+        InputTypes::Owned(c, _) => c,
+    };
+    if !c.column().is_const() {
+        Err(format!("The field of {} must be a constant", op))?
+    }
+    let (datau8, offsets) = c.column().downcast_binary_ref::<String>()?;
+    String::try_from_bytes(binary_item(datau8, offsets, 0))?.parse()
+}
+
+fn extract<T>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    type_name: &str,
+) -> Result<(), ErrorDesc>
+where
+    T: Temporal + 'static + Send + Sync,
+{
+    let field = const_field("EXTRACT", &input[0])?;
+    if !T::has_field(field) {
+        Err(format!("{} cannot be extracted from {}", field, type_name))?
+    }
+    set_2_sized_sized_unroll::<u32, T, _, _>(
+        c1,
+        &input[1..],
+        &has_bitmap(&input[1]),
+        |c2_data, _c2_bool| c2_data.extract(field),
+        |c2_bitmap| *c2_bitmap,
+    )
+}
+
+fn date_trunc<T>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    type_name: &str,
+) -> Result<(), ErrorDesc>
+where
+    T: Temporal + 'static + Send + Sync,
+{
+    let field = const_field("DATE_TRUNC", &input[0])?;
+    if !T::has_field(field) {
+        Err(format!("{} cannot be truncated to {}", type_name, field))?
+    }
+    set_2_sized_sized_unroll::<T, T, _, _>(
+        c1,
+        &input[1..],
+        &has_bitmap(&input[1]),
+        |c2_data, _c2_bool| c2_data.trunc(field),
+        |c2_bitmap| *c2_bitmap,
+    )
+}
+
+//Invalid strings fail the whole operation, null items are not parsed
+pub(crate) fn parse<T>(c1: &mut ColumnWrapper, input: &[InputTypes]) -> Result<(), ErrorDesc>
+where
+    T: FromStr<Err = RadixError> + Default + 'static + Send + Sync,
+{
+    let error = RefCell::new(None);
+    set_2_sized_binary_unroll::<T, String, _, _>(
+        c1,
+        input,
+        &has_bitmap(&input[0]),
+        |c2_data, c2_bool| {
+            if !*c2_bool {
+                return T::default();
+            }
+            match String::try_from_bytes(c2_data).and_then(|s| s.parse()) {
+                Ok(v) => v,
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    T::default()
+                }
+            }
+        },
+        |c2_bitmap| *c2_bitmap,
+    )?;
+    match error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn convert<T1, T2>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    f: fn(&T2) -> T1,
+) -> Result<(), ErrorDesc>
+where
+    T1: 'static + Send + Sync,
+    T2: 'static + Send + Sync,
+{
+    set_2_sized_sized_unroll::<T1, T2, _, _>(
+        c1,
+        input,
+        &has_bitmap(&input[0]),
+        |c2_data, _c2_bool| f(c2_data),
+        |c2_bitmap| *c2_bitmap,
+    )
+}
+
+//Results out of the range of the type fail the whole operation, null items are not converted
+fn try_convert<T1, T2>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    f: fn(&T2) -> Result<T1, RadixError>,
+) -> Result<(), ErrorDesc>
+where
+    T1: Default + 'static + Send + Sync,
+    T2: 'static + Send + Sync,
+{
+    let error = RefCell::new(None);
+    set_2_sized_sized_unroll::<T1, T2, _, _>(
+        c1,
+        input,
+        &has_bitmap(&input[0]),
+        |c2_data, c2_bool| {
+            if !*c2_bool {
+                return T1::default();
+            }
+            match f(c2_data) {
+                Ok(v) => v,
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    T1::default()
+                }
+            }
+        },
+        |c2_bitmap| *c2_bitmap,
+    )?;
+    match error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+//Results out of the range of the type fail the whole operation, null items are not added
+fn add_interval<T>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    f: fn(&T, &Interval) -> Result<T, RadixError>,
+) -> Result<(), ErrorDesc>
+where
+    T: Default + 'static + Send + Sync,
+{
+    let error = RefCell::new(None);
+    set_3_sized_sized_sized_unroll::<T, T, Interval, _, _>(
+        c1,
+        input,
+        &(has_bitmap(&input[0]) || has_bitmap(&input[1])),
+        |c2_data, c2_bool, c3_data, c3_bool| {
+            if !(*c2_bool && *c3_bool) {
+                return T::default();
+            }
+            match f(c2_data, c3_data) {
+                Ok(v) => v,
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    T::default()
+                }
+            }
+        },
+        |c2_bitmap, c3_bitmap| *c2_bitmap && *c3_bitmap,
+    )?;
+    match error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+macro_rules! temporal_load {
+    ($dict:ident; $(($tr:ty, $name:expr))+) => ($(
+        insert(
+            $dict,
+            "EXTRACT",
+            vec![TypeId::of::<String>(), TypeId::of::<$tr>()],
+            TypeId::of::<u32>(),
+            Arc::new(|c1, _c1_index, input| extract::<$tr>(c1, input, $name)),
+        );
+        insert(
+            $dict,
+            "DATE_TRUNC",
+            vec![TypeId::of::<String>(), TypeId::of::<$tr>()],
+            TypeId::of::<$tr>(),
+            Arc::new(|c1, _c1_index, input| date_trunc::<$tr>(c1, input, $name)),
+        );
+        insert(
+            $dict,
+            &format!("CAST#{}", $name),
+            vec![TypeId::of::<String>()],
+            TypeId::of::<$tr>(),
+            Arc::new(|c1, _c1_index, input| parse::<$tr>(c1, input)),
+        );
+    )+)
+}
+
+macro_rules! interval_arithmetic_load {
+    ($dict:ident; $($tr:ty)+) => ($(
+        insert(
+            $dict,
+            "+",
+            vec![TypeId::of::<$tr>(), TypeId::of::<Interval>()],
+            TypeId::of::<$tr>(),
+            Arc::new(|c1, _c1_index, input| {
+                add_interval::<$tr>(c1, input, |v, i| v.add_interval(i))
+            }),
+        );
+        insert(
+            $dict,
+            "-",
+            vec![TypeId::of::<$tr>(), TypeId::of::<Interval>()],
+            TypeId::of::<$tr>(),
+            Arc::new(|c1, _c1_index, input| {
+                add_interval::<$tr>(c1, input, |v, i| v.add_interval(&i.negate()?))
+            }),
+        );
+    )+)
+}
+
+pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
+    temporal_load! {dict;
+        (Date, "DATE") (Time, "TIME") (Timestamp, "TIMESTAMP")
+    };
+
+    interval_arithmetic_load! {dict;
+        Date Timestamp
+    };
+
+    insert(
+        dict,
+        "CAST#INTERVAL",
+        vec![TypeId::of::<String>()],
+        TypeId::of::<Interval>(),
+        Arc::new(|c1, _c1_index, input| parse::<Interval>(c1, input)),
+    );
+    insert(
+        dict,
+        "CAST#DATE",
+        vec![TypeId::of::<Timestamp>()],
+        TypeId::of::<Date>(),
+        Arc::new(|c1, _c1_index, input| convert::<Date, Timestamp>(c1, input, Timestamp::date)),
+    );
+    insert(
+        dict,
+        "CAST#TIME",
+        vec![TypeId::of::<Timestamp>()],
+        TypeId::of::<Time>(),
+        Arc::new(|c1, _c1_index, input| convert::<Time, Timestamp>(c1, input, Timestamp::time)),
+    );
+    insert(
+        dict,
+        "CAST#TIMESTAMP",
+        vec![TypeId::of::<Date>()],
+        TypeId::of::<Timestamp>(),
+        Arc::new(|c1, _c1_index, input| {
+            try_convert::<Timestamp, Date>(c1, input, |d| {
+                Timestamp::from_date_time(*d, Time(0))
+                    .ok_or_else(|| RadixError::Other(format!("{} is out of range of TIMESTAMP", d)))
+            })
+        }),
+    );
+}
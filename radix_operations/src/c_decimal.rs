@@ -0,0 +1,382 @@
+use std::{any::TypeId, collections::hash_map::Entry, sync::Arc};
+
+use crate::{
+    c_datetime::{convert, has_bitmap, insert, parse},
+    statecolumn::StateColumnInternalOp,
+    udf::aggregate_groups,
+    *,
+};
+use radix_column::*;
+
+//Arithmetic on DECIMAL keeps the scale of its operands, the results are rounded with the rounding mode of the options
+//CAST#DECIMAL(value, precision, scale) converts to DECIMAL(precision, scale), the precision and scale are constants
+
+//What happens to results which do not fit into a decimal, or which are divided by zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    //The result is null
+    Null,
+    //The operation fails
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalOptions {
+    pub overflow: OverflowPolicy,
+    pub rounding: RoundingMode,
+}
+
+impl Default for DecimalOptions {
+    fn default() -> Self {
+        Self {
+            overflow: OverflowPolicy::Error,
+            rounding: RoundingMode::HalfUp,
+        }
+    }
+}
+
+//Partial result of AVG, the sum is an overflow once it does not fit
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalAvg {
+    sum: Decimal,
+    count: u64,
+}
+
+impl DecimalAvg {
+    fn new() -> Self {
+        Self {
+            sum: Decimal::default(),
+            count: 0,
+        }
+    }
+}
+
+impl Dictionary {
+    //The decimal operations are registered again with the new options
+    pub fn set_decimal_options(&mut self, options: DecimalOptions) {
+        load_op_dict(&mut self.op, options);
+    }
+}
+
+//Overflows are replaced by nulls or fail the operation
+fn resolve_overflow(
+    c1: &mut ColumnWrapper,
+    overflow: OverflowPolicy,
+    op: &str,
+) -> Result<(), ErrorDesc> {
+    let (data, bitmap) = c1.get_inner_mut();
+    let data = data.downcast_mut::<Decimal>()?;
+    if !data.iter().any(Decimal::is_overflow) {
+        return Ok(());
+    }
+    if overflow == OverflowPolicy::Error {
+        Err(format!("Decimal overflow or division by zero in {}", op))?
+    }
+    if !bitmap.is_some() {
+        *bitmap = ColumnDataF::new(Bitmap::filled(true, data.len()));
+    }
+    let mut bitmap = bitmap.downcast_mut()?;
+    data.iter_mut()
+        .enumerate()
+        .filter(|(_, value)| value.is_overflow())
+        .for_each(|(i, value)| {
+            *value = Decimal::default();
+            bitmap.set(i, false);
+        });
+    Ok(())
+}
+
+fn arithmetic<T>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    options: DecimalOptions,
+    op: &str,
+    f: fn(&Decimal, &T, RoundingMode) -> Option<Decimal>,
+) -> Result<(), ErrorDesc>
+where
+    T: 'static + Send + Sync,
+{
+    set_3_sized_sized_sized_unroll::<Decimal, Decimal, T, _, _>(
+        c1,
+        input,
+        &(has_bitmap(&input[0]) || has_bitmap(&input[1])),
+        |c2_data, c2_bool, c3_data, c3_bool| match *c2_bool && *c3_bool {
+            true => f(c2_data, c3_data, options.rounding).unwrap_or(Decimal::OVERFLOW),
+            false => Decimal::default(),
+        },
+        |c2_bitmap, c3_bitmap| *c2_bitmap && *c3_bitmap,
+    )?;
+    resolve_overflow(c1, options.overflow, op)
+}
+
+fn const_u8(input: &InputTypes) -> Result<u8, ErrorDesc> {
+    let c = match input {
+        InputTypes::Ref(c, _) => *c,
+        InputTypes::Owned(c, _) => c,
+    };
+    if !c.column().is_const() {
+        Err("The precision and scale of CAST#DECIMAL must be constants")?
+    }
+    Ok(c.column().downcast_ref::<u8>()?[0])
+}
+
+//Brings the converted values to DECIMAL(precision, scale), null items are left unchanged
+fn fit_precision(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    options: DecimalOptions,
+) -> Result<(), ErrorDesc> {
+    let (precision, scale) = (const_u8(&input[1])?, const_u8(&input[2])?);
+    let (data, bitmap) = c1.get_inner_ref();
+    let valid = match bitmap.is_some() {
+        true => Some(bitmap.downcast_ref()?.to_bitmap()),
+        false => None,
+    };
+    let len = data.data_len::<Decimal>()?;
+    let data = c1.column_mut().downcast_mut::<Decimal>()?;
+    (0..len)
+        .filter(|i| valid.as_ref().is_none_or(|v| v[*i]))
+        .for_each(|i| {
+            data[i] = data[i]
+                .with_precision(precision, scale, options.rounding)
+                .unwrap_or(Decimal::OVERFLOW)
+        });
+    resolve_overflow(c1, options.overflow, "CAST")
+}
+
+fn check_precision(input: &[InputTypes]) -> Result<(), ErrorDesc> {
+    Decimal::new(0, const_u8(&input[1])?, const_u8(&input[2])?)?;
+    Ok(())
+}
+
+//Writes one decimal per state, None is null
+fn finalize_groups<S>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    options: DecimalOptions,
+    op: &str,
+    finalize: fn(&S, RoundingMode) -> Option<Decimal>,
+) -> Result<(), ErrorDesc>
+where
+    S: 'static + Send + Sync,
+{
+    let (c2, c2_index) = match &input[0] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
+    let states = c2.column().downcast_ref::<S>()?;
+    let values: Vec<_> = if c2_index.is_some() {
+        c2_index
+            .downcast_ref()?
+            .iter()
+            .map(|i| finalize(&states[*i], options.rounding))
+            .collect()
+    } else {
+        states
+            .iter()
+            .map(|s| finalize(s, options.rounding))
+            .collect()
+    };
+    if values.iter().any(Option::is_none) {
+        c1.bitmap_set(ColumnDataF::new(
+            values.iter().map(Option::is_some).collect::<Bitmap>(),
+        ));
+    }
+    c1.column_mut()
+        .downcast_vec::<Decimal>()?
+        .extend(values.into_iter().map(Option::unwrap_or_default));
+    resolve_overflow(c1, options.overflow, op)
+}
+
+macro_rules! arithmetic_load {
+    ($dict:ident, $options:ident; $(($op:expr, $f:expr))+) => ($(
+        insert(
+            $dict,
+            $op,
+            vec![TypeId::of::<Decimal>(), TypeId::of::<Decimal>()],
+            TypeId::of::<Decimal>(),
+            Arc::new(move |c1, _c1_index, input| {
+                arithmetic::<Decimal>(c1, input, $options, $op, $f)
+            }),
+        );
+    )+)
+}
+
+macro_rules! cast_load {
+    ($dict:ident, $options:ident; $($tr:ty)+) => ($(
+        insert(
+            $dict,
+            "CAST#DECIMAL",
+            vec![TypeId::of::<$tr>()],
+            TypeId::of::<Decimal>(),
+            Arc::new(|c1, _c1_index, input| convert::<Decimal, $tr>(c1, input, |v| Decimal::from(*v))),
+        );
+        insert(
+            $dict,
+            "CAST#DECIMAL",
+            vec![TypeId::of::<$tr>(), TypeId::of::<u8>(), TypeId::of::<u8>()],
+            TypeId::of::<Decimal>(),
+            Arc::new(move |c1, _c1_index, input| {
+                check_precision(input)?;
+                convert::<Decimal, $tr>(c1, &input[..1], |v| Decimal::from(*v))?;
+                fit_precision(c1, input, $options)
+            }),
+        );
+    )+)
+}
+
+pub(crate) fn load_op_dict(dict: &mut OpDictionary, options: DecimalOptions) {
+    arithmetic_load! {dict, options;
+        ("+", |a, b, _| a.checked_add(b))
+        ("-", |a, b, _| a.checked_sub(b))
+        ("*", |a, b, rounding| a.checked_mul(b, rounding))
+        ("/", |a, b, rounding| a.checked_div(b, rounding))
+    };
+    insert(
+        dict,
+        "ROUND",
+        vec![TypeId::of::<Decimal>(), TypeId::of::<u64>()],
+        TypeId::of::<Decimal>(),
+        Arc::new(move |c1, _c1_index, input| {
+            arithmetic::<u64>(c1, input, options, "ROUND", |a, scale, rounding| {
+                a.rescale((*scale).min(Decimal::MAX_PRECISION as u64) as u8, rounding)
+            })
+        }),
+    );
+
+    cast_load! {dict, options;
+        u64 u32
+    };
+    insert(
+        dict,
+        "CAST#DECIMAL",
+        vec![TypeId::of::<String>()],
+        TypeId::of::<Decimal>(),
+        Arc::new(|c1, _c1_index, input| parse::<Decimal>(c1, input)),
+    );
+    insert(
+        dict,
+        "CAST#DECIMAL",
+        vec![
+            TypeId::of::<String>(),
+            TypeId::of::<u8>(),
+            TypeId::of::<u8>(),
+        ],
+        TypeId::of::<Decimal>(),
+        Arc::new(move |c1, _c1_index, input| {
+            check_precision(input)?;
+            parse::<Decimal>(c1, &input[..1])?;
+            fit_precision(c1, input, options)
+        }),
+    );
+    insert(
+        dict,
+        "CAST#DECIMAL",
+        vec![
+            TypeId::of::<Decimal>(),
+            TypeId::of::<u8>(),
+            TypeId::of::<u8>(),
+        ],
+        TypeId::of::<Decimal>(),
+        Arc::new(move |c1, _c1_index, input| {
+            check_precision(input)?;
+            convert::<Decimal, Decimal>(c1, &input[..1], |v| *v)?;
+            fit_precision(c1, input, options)
+        }),
+    );
+
+    //SUM merges the partial sums with itself, an overflow is kept until the sum is finalized
+    insert(
+        dict,
+        "SUM",
+        vec![TypeId::of::<Decimal>()],
+        TypeId::of::<Decimal>(),
+        Arc::new(|c1, _c1_index, input| {
+            aggregate_groups::<Decimal, Decimal>(c1, input, &Decimal::default, &|sum, value| {
+                *sum = sum.checked_add(value).unwrap_or(Decimal::OVERFLOW)
+            })
+        }),
+    );
+    insert(
+        dict,
+        "SUM#FINALIZE",
+        vec![TypeId::of::<Decimal>()],
+        TypeId::of::<Decimal>(),
+        Arc::new(move |c1, _c1_index, input| {
+            finalize_groups::<Decimal>(c1, input, options, "SUM", |sum, _| Some(*sum))
+        }),
+    );
+    insert(
+        dict,
+        "AVG",
+        vec![TypeId::of::<Decimal>()],
+        TypeId::of::<DecimalAvg>(),
+        Arc::new(|c1, _c1_index, input| {
+            aggregate_groups::<Decimal, DecimalAvg>(c1, input, &DecimalAvg::new, &|avg, value| {
+                avg.sum = avg.sum.checked_add(value).unwrap_or(Decimal::OVERFLOW);
+                avg.count += 1;
+            })
+        }),
+    );
+    insert(
+        dict,
+        "AVG#MERGE",
+        vec![TypeId::of::<DecimalAvg>()],
+        TypeId::of::<DecimalAvg>(),
+        Arc::new(|c1, _c1_index, input| {
+            aggregate_groups::<DecimalAvg, DecimalAvg>(
+                c1,
+                input,
+                &DecimalAvg::new,
+                &|avg, other| {
+                    avg.sum = avg.sum.checked_add(&other.sum).unwrap_or(Decimal::OVERFLOW);
+                    avg.count += other.count;
+                },
+            )
+        }),
+    );
+    //The average has the scale of the input, the average of no values is null
+    insert(
+        dict,
+        "AVG#FINALIZE",
+        vec![TypeId::of::<DecimalAvg>()],
+        TypeId::of::<Decimal>(),
+        Arc::new(move |c1, _c1_index, input| {
+            finalize_groups::<DecimalAvg>(c1, input, options, "AVG", |avg, rounding| {
+                match avg.count {
+                    0 => None,
+                    count => Some(
+                        avg.sum
+                            .checked_div_to_scale(&Decimal::from(count), avg.sum.scale(), rounding)
+                            .unwrap_or(Decimal::OVERFLOW),
+                    ),
+                }
+            })
+        }),
+    );
+}
+
+//SUM of decimals is finalized to resolve overflows, the other types have no finalize operation
+pub(crate) fn load_aggregates(dict: &mut Dictionary) {
+    let state_signature = Signature::new("", vec![TypeId::of::<DecimalAvg>()]);
+    if let Entry::Vacant(e) = dict.columninternal.entry(state_signature) {
+        e.insert(Box::new(StateColumnInternalOp::<DecimalAvg>::new()));
+        dict.register_type(LogicalType::sized::<DecimalAvg>(std::any::type_name::<
+            DecimalAvg,
+        >()));
+    }
+    dict.aggregates.insert(
+        "SUM".to_string(),
+        AggregateFunction {
+            merge_op: "SUM".to_string(),
+            finalize_op: Some("SUM#FINALIZE".to_string()),
+        },
+    );
+    dict.aggregates.insert(
+        "AVG".to_string(),
+        AggregateFunction {
+            merge_op: "AVG#MERGE".to_string(),
+            finalize_op: Some("AVG#FINALIZE".to_string()),
+        },
+    );
+}
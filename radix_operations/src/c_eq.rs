@@ -13,7 +13,7 @@ macro_rules! operation_load {
                 type T3=$tr;
                 let signature=sig![OP; T2, T3];
                 let op=Operation{
-                    f:  paste!{[<eq_ $tl:lower _ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<eq_ $tl:lower _ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T1>(),
                     is_assign_op: false,
                     associated_assign_op: None,
@@ -65,18 +65,23 @@ macro_rules! operation_impl_binary {
 
 
 
-                let (c2, _c2_index) = match &input[0] {
+                let (c2, c2_index) = match &input[0] {
                     InputTypes::Ref(c, i) => (*c, *i),
                     InputTypes::Owned(c, i) => (c, i),
                 };
 
-                let (c3, _c3_index) = match &input[1] {
+                let (c3, c3_index) = match &input[1] {
                     InputTypes::Ref(c, i) => (*c, *i),
                     InputTypes::Owned(c, i) => (c, i),
                 };
 
                 let bitmap_update_required=c2.bitmap().is_some()||c3.bitmap().is_some();
 
+                if let (Some(c2_codes), Some(c3_codes)) = (dictionary_codes::<T2>(c2, c3)?, dictionary_codes::<T3>(c3, c2)?) {
+                    let input = [InputTypes::Ref(&c2_codes, c2_index), InputTypes::Ref(&c3_codes, c3_index)];
+                    return set_3_sized_sized_sized_unroll::<T1, u32, u32,_,_,>(c1, &input, &bitmap_update_required, |c2_data, _c2_bool, c3_data,_c3_bool,| {
+                        *c2_data==*c3_data}, |c2_bitmap, c3_bitmap| *c2_bitmap&&*c3_bitmap)
+                }
 
                 set_3_sized_binary_binary_unroll::<T1,T2, T3,_,_,>(c1, &input, &bitmap_update_required, |c2_data, _c2_bool, c3_data,_c3_bool,| {
                     *c2_data==*c3_data}, |c2_bitmap, c3_bitmap| *c2_bitmap&&*c3_bitmap)
@@ -87,17 +92,52 @@ macro_rules! operation_impl_binary {
     )+)
 }
 
+//The codes of c as a u32 column, if c and other can be compared by the codes of one dictionary.
+//This is the case if c is encoded with the dictionary of other, or if c is a const and other is encoded
+fn dictionary_codes<'a, T: BinaryItem>(
+    c: &'a ColumnWrapper,
+    other: &ColumnWrapper,
+) -> Result<Option<ColumnWrapper<'a>>, ErrorDesc> {
+    let values = match (c.column().is_dictionary(), other.column().is_dictionary()) {
+        (true, _) => c.column().downcast_dictionary_ref::<T>()?.1,
+        (false, true) => other.column().downcast_dictionary_ref::<T>()?.1,
+        (false, false) => return Ok(None),
+    };
+    if c.column().is_dictionary() {
+        return match other.column().is_const() || common_dictionary::<T>([c, other]).is_some() {
+            true => Ok(Some(codes_view::<T>(c)?)),
+            false => Ok(None),
+        };
+    }
+    if !c.column().is_const() {
+        return Ok(None);
+    }
+    //A value missing from the dictionary gets a code which no row has
+    let (datau8, offsets) = c.column().downcast_binary_ref::<T>()?;
+    let value = binary_item(datau8, offsets, 0);
+    let (v_datau8, v_offsets) = values.downcast_binary_ref::<T>()?;
+    let code = binary_items(v_datau8, v_offsets)
+        .position(|v| v == value)
+        .map_or(u32::MAX, |code| code as u32);
+    let mut codes =
+        ColumnWrapper::new_from_columndata(ColumnData::Const(OwnedColumn::new(vec![code])));
+    if c.bitmap().is_some() {
+        codes.bitmap_set(ColumnDataF::new_from_slice(c.bitmap().downcast_ref()?));
+    }
+    Ok(Some(codes))
+}
+
 operation_impl_copy! {
-    (u64, u64) (u32,u32)
+    (u64, u64) (u32,u32) (Date, Date) (Time, Time) (Timestamp, Timestamp) (Decimal, Decimal)
 }
 
 operation_impl_binary! {
-    (String, String)
+    (String, String) (Blob, Blob)
 }
 
 pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
     operation_load! {dict;
-        (u64, u64) (u32,u32)  (String, String)
+        (u64, u64) (u32,u32)  (String, String) (Date, Date) (Time, Time) (Timestamp, Timestamp) (Decimal, Decimal) (Blob, Blob)
     };
 }
 
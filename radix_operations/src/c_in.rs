@@ -0,0 +1,111 @@
+use crate::*;
+use radix_column::*;
+
+use paste::paste;
+
+use std::collections::HashSet;
+
+//Tests if the items of the first input are contained in the list of values given by the second input
+const OP: &str = "IN";
+
+macro_rules! operation_load {
+    ($dict:ident; $($tr:ty)+) => ($(
+            {
+                type T1=bool;
+                type T2=$tr;
+                let signature=sig![OP; T2, T2];
+                let op=Operation{
+                    f:  std::sync::Arc::new(paste!{[<in_ $tr:lower>]}),
+                    output_type_id: std::any::TypeId::of::<T1>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+                };
+            $dict.insert(signature, op);
+            }
+    )+)
+}
+
+macro_rules! operation_impl_copy {
+    ($($tr:ty)+) => ($(
+        paste!   {
+            fn [<in_ $tr:lower>](c1: &mut ColumnWrapper, _c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<(),ErrorDesc>
+            {
+                type T1=bool;
+                type T2=$tr;
+
+                let (c2, _c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c3, _c3_index) = match &input[1] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let list = c3.column().downcast_ref::<T2>()?;
+
+                let bitmap_update_required=c2.bitmap().is_some();
+
+                set_2_sized_sized_unroll::<T1,T2,_,_,>(c1, &input[..1], &bitmap_update_required, |c2_data, _c2_bool| {
+                    list.iter().any(|v| *v==*c2_data)}, |c2_bitmap| *c2_bitmap)
+            }
+        }
+    )+)
+}
+
+macro_rules! operation_impl_binary {
+    ($($tr:ty)+) => ($(
+        paste!   {
+            fn [<in_ $tr:lower>](c1: &mut ColumnWrapper, _c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<(),ErrorDesc>
+            {
+                type T1=bool;
+                type T2=$tr;
+
+                let (c2, c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c3, _c3_index) = match &input[1] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let decoded = c3.column().downcast_binary_decoded::<T2>()?;
+                let (datau8, offsets) = decoded.parts();
+                let list: HashSet<&[u8]> = binary_items(datau8, offsets).collect();
+
+                let bitmap_update_required=c2.bitmap().is_some();
+
+                //Each value of the dictionary is tested once, the rows only look up the result by their code
+                if c2.column().is_dictionary() {
+                    let (_, values) = c2.column().downcast_dictionary_ref::<T2>()?;
+                    let (v_datau8, v_offsets) = values.downcast_binary_ref::<T2>()?;
+                    let matches: Vec<bool> = binary_items(v_datau8, v_offsets).map(|v| list.contains(v)).collect();
+                    let codes = codes_view::<T2>(c2)?;
+                    let input = [InputTypes::Ref(&codes, c2_index)];
+                    return set_2_sized_sized_unroll::<T1,u32,_,_,>(c1, &input, &bitmap_update_required, |c2_data, _c2_bool| {
+                        matches[*c2_data as usize]}, |c2_bitmap| *c2_bitmap)
+                }
+
+                set_2_sized_binary_unroll::<T1,T2,_,_,>(c1, &input[..1], &bitmap_update_required, |c2_data, _c2_bool| {
+                    list.contains(c2_data)}, |c2_bitmap| *c2_bitmap)
+            }
+        }
+    )+)
+}
+
+operation_impl_copy! {
+    u64 u32 Date Time Timestamp Decimal
+}
+operation_impl_binary! {
+    String Blob
+}
+
+pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
+    operation_load! {dict;
+        u64 u32 String Date Time Timestamp Decimal Blob
+    };
+}
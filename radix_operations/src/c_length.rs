@@ -0,0 +1,56 @@
+use crate::*;
+use radix_column::*;
+
+use paste::paste;
+
+//Number of bytes of a binary item
+const OP: &str = "LENGTH";
+
+macro_rules! operation_load {
+    ($dict:ident; $($tr:ty)+) => ($(
+            {
+                type T=$tr;
+                let signature=sig![OP; T];
+                let op=Operation{
+                    f:  std::sync::Arc::new(paste!{[<length_ $tr:lower>]}),
+                    output_type_id: std::any::TypeId::of::<u64>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+                };
+            $dict.insert(signature, op);
+            }
+    )+)
+}
+
+macro_rules! operation_impl_binary {
+    ($( $tr:ty)+) => ($(
+        paste!   {
+            fn [<length_ $tr:lower>](c1: &mut ColumnWrapper, _c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<(),ErrorDesc>
+            {
+                type T1=u64;
+                type T2=$tr;
+
+                let (c2, _c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let bitmap_update_required=c2.bitmap().is_some();
+
+                set_2_sized_binary_unroll::<T1,T2,_,_,>(c1, &input, &bitmap_update_required, |c2_data, _c2_bool| {
+                    c2_data.len() as u64}, |c2_bitmap| *c2_bitmap)
+            }
+        }
+    )+)
+}
+
+operation_impl_binary! {
+    Blob
+}
+
+pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
+    operation_load! {dict;
+        Blob
+    };
+}
@@ -13,7 +13,7 @@ macro_rules! operation_load {
                 type T3=$tr;
                 let signature=sig![OP; T2, T3];
                 let op=Operation{
-                    f:  paste!{[<eq_ $tl:lower _ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<eq_ $tl:lower _ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T1>(),
                     is_assign_op: false,
                     associated_assign_op: None,
@@ -88,7 +88,7 @@ macro_rules! operation_impl_binary {
 }
 
 operation_impl_copy! {
-    (u64, u64) (u32,u32)
+    (u64, u64) (u32,u32) (Date, Date) (Time, Time) (Timestamp, Timestamp) (Decimal, Decimal)
 }
 
 operation_impl_binary! {
@@ -97,7 +97,7 @@ operation_impl_binary! {
 
 pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
     operation_load! {dict;
-        (u64, u64) (u32,u32)  (String, String)
+        (u64, u64) (u32,u32)  (String, String) (Date, Date) (Time, Time) (Timestamp, Timestamp) (Decimal, Decimal)
     };
 }
 
@@ -11,7 +11,7 @@ macro_rules! operation_load{
                 type T=$tr;
                 let signature=sig![OP; T];
                 let op=Operation{
-                    f:  paste!{[<max_ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<max_ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T>(),
                     is_assign_op: false,
                     associated_assign_op: None,
@@ -28,122 +28,77 @@ macro_rules! operation_impl_sized {
         paste!   {
             fn [<max_ $tr:lower>](c1: &mut ColumnWrapper, c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<(),ErrorDesc>
             {
-                type T=$tr;
-
-                let (c2, c2_index) = match &input[0] {
-                    InputTypes::Ref(c, i) => (*c, *i),
-                    InputTypes::Owned(c, i) => (c, i),
-                };
-
-
-
-                let (c3, c3_index) = match &input[1] {
-                    InputTypes::Ref(c, i) => (*c, *i),
-                    InputTypes::Owned(c, i) => (c, i),
-                };
-
-
-                let (c4, c4_index) = match &input[2] {
-                    InputTypes::Ref(c, i) => (*c, *i),
-                    InputTypes::Owned(c, i) => (c, i),
-                };
-
-                assert_eq!(c1_index, &ColumnDataIndex::None);
-                assert_eq!(c1.bitmap(), &ColumnDataF::None);
-                assert_eq!(c3_index, &ColumnDataIndex::None);
-                assert_eq!(c4_index, &ColumnDataIndex::None);
-
-                assert!(c4.column().is_const());
-                assert!(c1.column().is_owned());
-
-                let (c2_data, c2_bitmap)=(c2.column(), c2.bitmap());
-                let c2_data=c2_data.downcast_ref::<T>()?;
-
-
-                let group_ids=c3.column().downcast_ref::<usize>()?;
-                let number_of_groups=c4.column().downcast_ref::<usize>()?[0];
-
-                let (c1_data, c1_bitmap)=c1.get_inner_mut();
-                let c1_data=c1_data.downcast_vec::<T>()?;
-                let c1_bitmap=c1_bitmap.downcast_vec()?;
-
-                assert_eq!(c1_data.len(),0);
-                c1_data.reserve(number_of_groups);
-                c1_data.extend((0..number_of_groups).into_iter().map(|_| 0));
-                c1_bitmap.extend((0..number_of_groups).into_iter().map(|_| false));
-
-                match (c2_index.is_some(), c2_bitmap.is_some()){
-                    (true, true)=>{
-                        let c2_index=c2_index.downcast_ref()?;
-                        let c2_bitmap=c2_bitmap.downcast_ref()?;
-                        assert_eq!(group_ids.len(),c2_index.len());
-                        c2_index.iter().zip(group_ids.iter()).for_each(|(index, group_id)|
-                            {
-                                let a=&mut c1_data[*group_id];
-                                let a_bitmap=&mut c1_bitmap[*group_id];
-                                let b=c2_data[*index];
-                                let b_bitmap=c2_bitmap[*index];
-
-                                let keep_a=(*a_bitmap & (*a>b)) as T;
-                                *a=*a*keep_a+b*(1-keep_a);
-                                *a_bitmap|=b_bitmap;
-
-                            }
-                        );
-                    }
-                    (true, false)=>{
-                        let c2_index=c2_index.downcast_ref()?;
-                        assert_eq!(group_ids.len(),c2_index.len());
-                        c2_index.iter().zip(group_ids.iter()).for_each(|(index, group_id)|
-                        {
-                            let a=&mut c1_data[*group_id];
-                            let a_bitmap=&mut c1_bitmap[*group_id];
-                            let b=c2_data[*index];
-
-                            let keep_a=(*a_bitmap & (*a>b)) as T;
-                            *a=*a*keep_a+b*(1-keep_a);
-                            *a_bitmap|=true;
-                        }
-                        );
-                    }
-                    (false, true)=>{
-                        assert_eq!(group_ids.len(),c2_data.len());
-                        let c2_bitmap=c2_bitmap.downcast_ref()?;
-                        c2_data.iter().zip(c2_bitmap).zip(group_ids).for_each(|((data, bitmap), group_id)|
-                        {
-
-                            let a=&mut c1_data[*group_id];
-                            let a_bitmap=&mut c1_bitmap[*group_id];
-                            let b=*data;
-                            let b_bitmap=bitmap;
-
-                            let keep_a=(*a_bitmap & (*a>b)) as T;
-                            *a=*a*keep_a+b*(1-keep_a);
-                            *a_bitmap|=b_bitmap;
-                        }
-                        );
-                    }
-                    (false, false)=>{
-                        assert_eq!(group_ids.len(),c2_data.len());
-                        c2_data.iter().zip(group_ids).for_each(|(data, group_id)|
-                        {
+                extremum_groups::<$tr>(c1, c1_index, input, |b, a| b > a)
+            }
+        }
+    )+)
+}
 
-                            let a=&mut c1_data[*group_id];
-                            let a_bitmap=&mut c1_bitmap[*group_id];
-                            let b=*data;
+//Keeps one value per group, a value b replaces the value a of its group if replace(b, a) is true
+//Null values are skipped, groups without any value which is not null are null
+pub(crate) fn extremum_groups<T>(
+    c1: &mut ColumnWrapper,
+    c1_index: &ColumnDataIndex,
+    input: &[InputTypes],
+    replace: fn(&T, &T) -> bool,
+) -> Result<(), ErrorDesc>
+where
+    T: 'static + Copy + Default + Send + Sync,
+{
+    let (c2, c2_index) = match &input[0] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
+    let (c3, c3_index) = match &input[1] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
+    let (c4, c4_index) = match &input[2] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
 
-                            let keep_a=(*a_bitmap & (*a>b)) as T;
-                            *a=*a*keep_a+b*(1-keep_a);
-                            *a_bitmap|=true;
-                        }
+    assert_eq!(c1_index, &ColumnDataIndex::None);
+    assert_eq!(c1.bitmap(), &ColumnDataF::None);
+    assert_eq!(c3_index, &ColumnDataIndex::None);
+    assert_eq!(c4_index, &ColumnDataIndex::None);
 
-                        );}
+    assert!(c4.column().is_const());
+    assert!(c1.column().is_owned());
 
-                };
-                Ok(())
+    let c2_data = c2.column().downcast_ref::<T>()?;
+    let c2_bitmap = if c2.bitmap().is_some() {
+        Some(c2.bitmap().downcast_ref()?)
+    } else {
+        None
+    };
+    let group_ids = c3.column().downcast_ref::<usize>()?;
+    let number_of_groups = c4.column().downcast_ref::<usize>()?[0];
+    let rows: Vec<usize> = if c2_index.is_some() {
+        c2_index.downcast_ref()?.to_vec()
+    } else {
+        (0..c2_data.len()).collect()
+    };
+    assert_eq!(group_ids.len(), rows.len());
+
+    let c1_data = c1.column_mut().downcast_vec::<T>()?;
+    assert_eq!(c1_data.len(), 0);
+    c1_data.resize(number_of_groups, T::default());
+    let mut valid = vec![false; number_of_groups];
+    rows.iter()
+        .zip(group_ids.iter())
+        .filter(|(row, _)| c2_bitmap.is_none_or(|b| b[**row]))
+        .for_each(|(row, group_id)| {
+            let b = c2_data[*row];
+            if !valid[*group_id] || replace(&b, &c1_data[*group_id]) {
+                c1_data[*group_id] = b;
+                valid[*group_id] = true;
             }
-        }
-    )+)
+        });
+    if valid.iter().any(|v| !v) {
+        c1.bitmap_set(ColumnDataF::new(valid));
+    }
+    Ok(())
 }
 
 macro_rules! operation_impl_binary {
@@ -180,7 +135,7 @@ macro_rules! operation_impl_binary {
                 assert!(c1.column().is_owned());
 
                 let (c2_data, c2_bitmap)=(c2.column(), c2.bitmap());
-                let (_c2_datau8, c2_start_pos, _c2_len, _offset)=c2_data.downcast_binary_ref::<T>()?;
+                let c2_len=c2_data.data_len::<T>()?;
 
 
                 let group_ids=c3.column().downcast_ref::<usize>()?;
@@ -208,15 +163,15 @@ macro_rules! operation_impl_binary {
                         );
                     }
                     (false, true)=>{
-                        assert_eq!(group_ids.len(),c2_start_pos.len());
+                        assert_eq!(group_ids.len(),c2_len);
                         let c2_bitmap=c2_bitmap.downcast_ref()?;
-                        c2_start_pos.iter().zip(c2_bitmap).zip(group_ids).for_each(|((_data, bitmap), group_id)|
-                            c1_data[*group_id]+=*bitmap as u64
+                        c2_bitmap.iter().zip(group_ids).for_each(|(bitmap, group_id)|
+                            c1_data[*group_id]+=bitmap as u64
                         );
                     }
                     (false, false)=>{
-                        assert_eq!(group_ids.len(),c2_start_pos.len());
-                        c2_start_pos.iter().zip(group_ids).for_each(|(_data, group_id)|
+                        assert_eq!(group_ids.len(),c2_len);
+                        group_ids.iter().for_each(|group_id|
                             c1_data[*group_id]+=1
                         );}
 
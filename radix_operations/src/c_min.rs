@@ -0,0 +1,47 @@
+use crate::*;
+use radix_column::*;
+
+use paste::paste;
+
+use crate::c_max::extremum_groups;
+
+const OP: &str = "MIN";
+
+macro_rules! operation_load {
+    ($dict:ident; $($tr:ty)+) => ($(
+            {
+                type T=$tr;
+                let signature=sig![OP; T];
+                let op=Operation{
+                    f:  std::sync::Arc::new(paste!{[<min_ $tr:lower>]}),
+                    output_type_id: std::any::TypeId::of::<T>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+
+                };
+            $dict.insert(signature, op);
+            }
+    )+)
+}
+
+macro_rules! operation_impl_sized {
+    ($( $tr:ty)+) => ($(
+        paste!   {
+            fn [<min_ $tr:lower>](c1: &mut ColumnWrapper, c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<(),ErrorDesc>
+            {
+                extremum_groups::<$tr>(c1, c1_index, input, |b, a| b < a)
+            }
+        }
+    )+)
+}
+
+operation_impl_sized! {
+    u64 u32
+}
+
+pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
+    operation_load! {dict;
+        u64 u32
+    };
+}
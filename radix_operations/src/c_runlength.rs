@@ -0,0 +1,237 @@
+use crate::*;
+use radix_column::*;
+
+use paste::paste;
+
+//Kernels for run-length encoded inputs, which compute once per run instead of once per row
+//Inputs which a kernel cannot handle are decoded and passed to the regular operation, see Dictionary::call_op
+
+//First row after each run, the run of a row is found by a binary search
+fn run_ends(run_lengths: &[usize]) -> Vec<usize> {
+    run_lengths
+        .iter()
+        .scan(0, |end, l| {
+            *end += l;
+            Some(*end)
+        })
+        .collect()
+}
+
+fn run_of(ends: &[usize], row: usize) -> usize {
+    ends.partition_point(|end| *end <= row)
+}
+
+macro_rules! operation_load {
+    ($dict:ident; $op:expr, $name:ident; $($tr:ty)+) => ($(
+            {
+                type T=$tr;
+                let signature=sig![$op; T, T];
+                $dict.insert(signature, std::sync::Arc::new(paste!{[<$name _ $tr:lower>]}) as RunLengthOperationFn);
+            }
+    )+);
+    ($dict:ident; $op:expr, $name:ident, aggregate; $($tr:ty)+) => ($(
+            {
+                type T=$tr;
+                let signature=sig![$op; T];
+                $dict.insert(signature, std::sync::Arc::new(paste!{[<$name _ $tr:lower>]}) as RunLengthOperationFn);
+            }
+    )+)
+}
+
+//Compares the value of every run with a const, the result keeps the runs if the rows are not selected by an index
+macro_rules! comparison_impl {
+    ($name:ident, $op:tt; $($tr:ty)+) => ($(
+        paste!   {
+            fn [<$name _ $tr:lower>](c1: &mut ColumnWrapper, c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<bool,ErrorDesc>
+            {
+                type T=$tr;
+
+                let (c2, c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c3, _c3_index) = match &input[1] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                if !c2.column().is_run_length() || !c3.column().is_const() || c3.bitmap().is_some()
+                    || c1_index.is_some() || !c1.column().is_owned() || c1.column().data_len::<bool>()? != 0 {
+                    return Ok(false);
+                }
+
+                let (values, run_lengths) = c2.column().downcast_runs_ref::<T>()?;
+                let c3_data = c3.column().downcast_ref::<T>()?[0];
+                let results: Vec<bool> = values.iter().map(|v| *v $op c3_data).collect();
+
+                let bitmap = if c2_index.is_some() {
+                    let ends = run_ends(run_lengths);
+                    let index = c2_index.downcast_ref()?;
+                    c1.column_mut().downcast_vec::<bool>()?.extend(index.iter().map(|i| results[run_of(&ends, *i)]));
+                    if c2.bitmap().is_some() {
+                        let bitmap = c2.bitmap().downcast_ref()?;
+                        ColumnDataF::new(index.iter().map(|i| bitmap[*i]).collect::<Bitmap>())
+                    } else {
+                        ColumnDataF::None
+                    }
+                } else {
+                    let column = RunLengthColumn::encode(results.into_iter().zip(run_lengths.iter().copied()))?;
+                    *c1.column_mut() = ColumnData::RunLength(column);
+                    if c2.bitmap().is_some() {
+                        ColumnDataF::new(c2.bitmap().downcast_ref()?.to_bitmap())
+                    } else {
+                        ColumnDataF::None
+                    }
+                };
+                c1.bitmap_set(bitmap);
+                Ok(true)
+            }
+        }
+    )+)
+}
+
+//Consecutive rows of a run which belong to the same group are added up with a single multiplication
+macro_rules! sum_impl {
+    ($($tr:ty)+) => ($(
+        paste!   {
+            fn [<sum_ $tr:lower>](c1: &mut ColumnWrapper, c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<bool,ErrorDesc>
+            {
+                type T=$tr;
+
+                let (c2, c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c3, _c3_index) = match &input[1] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c4, _c4_index) = match &input[2] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                if !c2.column().is_run_length() || c1_index.is_some() {
+                    return Ok(false);
+                }
+
+                let (values, run_lengths) = c2.column().downcast_runs_ref::<T>()?;
+                let bitmap = if c2.bitmap().is_some() {
+                    Some(c2.bitmap().downcast_ref()?)
+                } else {
+                    None
+                };
+                let group_ids = c3.column().downcast_ref::<usize>()?;
+                let number_of_groups = c4.column().downcast_ref::<usize>()?[0];
+
+                let c1_data = c1.column_mut().downcast_vec::<T>()?;
+                assert_eq!(c1_data.len(), 0);
+                c1_data.resize(number_of_groups, 0);
+
+                if c2_index.is_some() {
+                    let ends = run_ends(run_lengths);
+                    let index = c2_index.downcast_ref()?;
+                    assert_eq!(group_ids.len(), index.len());
+                    index.iter().zip(group_ids).for_each(|(i, group_id)| {
+                        if bitmap.is_none_or(|b| b[*i]) {
+                            c1_data[*group_id] += values[run_of(&ends, *i)];
+                        }
+                    });
+                } else {
+                    assert_eq!(group_ids.len(), c2.column().data_len::<T>()?);
+                    let mut row = 0;
+                    for (v, l) in values.iter().zip(run_lengths) {
+                        let end = row + l;
+                        while row < end {
+                            let group_id = group_ids[row];
+                            let chunk_end = row + group_ids[row..end].iter().take_while(|g| **g == group_id).count();
+                            let n = match bitmap {
+                                Some(b) => b.slice(row..chunk_end).count_ones(),
+                                None => chunk_end - row,
+                            };
+                            c1_data[group_id] += *v * n as T;
+                            row = chunk_end;
+                        }
+                    }
+                }
+                Ok(true)
+            }
+        }
+    )+)
+}
+
+//Counting reads only the bitmap, the runs are never expanded
+macro_rules! count_impl {
+    ($($tr:ty)+) => ($(
+        paste!   {
+            fn [<count_ $tr:lower>](c1: &mut ColumnWrapper, c1_index: &ColumnDataIndex, input:&[InputTypes])->Result<bool,ErrorDesc>
+            {
+                type T=$tr;
+
+                let (c2, c2_index) = match &input[0] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c3, _c3_index) = match &input[1] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                let (c4, _c4_index) = match &input[2] {
+                    InputTypes::Ref(c, i) => (*c, *i),
+                    InputTypes::Owned(c, i) => (c, i),
+                };
+
+                if !c2.column().is_run_length() || c1_index.is_some() {
+                    return Ok(false);
+                }
+
+                c2.column().downcast_runs_ref::<T>()?;
+                let group_ids = c3.column().downcast_ref::<usize>()?;
+                let number_of_groups = c4.column().downcast_ref::<usize>()?[0];
+
+                let c1_data = c1.column_mut().downcast_vec::<u64>()?;
+                assert_eq!(c1_data.len(), 0);
+                c1_data.resize(number_of_groups, 0);
+
+                match (c2_index.is_some(), c2.bitmap().is_some()) {
+                    (true, true) => {
+                        let index = c2_index.downcast_ref()?;
+                        let bitmap = c2.bitmap().downcast_ref()?;
+                        assert_eq!(group_ids.len(), index.len());
+                        index.iter().zip(group_ids).for_each(|(i, group_id)| c1_data[*group_id] += bitmap[*i] as u64);
+                    }
+                    (false, true) => {
+                        let bitmap = c2.bitmap().downcast_ref()?;
+                        assert_eq!(group_ids.len(), bitmap.len());
+                        bitmap.iter().zip(group_ids).for_each(|(b, group_id)| c1_data[*group_id] += b as u64);
+                    }
+                    (_, false) => group_ids.iter().for_each(|group_id| c1_data[*group_id] += 1),
+                }
+                Ok(true)
+            }
+        }
+    )+)
+}
+
+comparison_impl! {eq, ==; u64 u32 Date Time Timestamp Decimal}
+comparison_impl! {lt, <; u64 u32 Date Time Timestamp Decimal}
+comparison_impl! {lteq, <=; u64 u32 Date Time Timestamp Decimal}
+comparison_impl! {gt, >; u64 u32 Date Time Timestamp Decimal}
+comparison_impl! {gteq, >=; u64 u32 Date Time Timestamp Decimal}
+sum_impl! {u64 u32 usize}
+count_impl! {u64 u32 Decimal}
+
+pub(crate) fn load_op_dict(dict: &mut RunLengthOpDictionary) {
+    operation_load! {dict; "==", eq; u64 u32 Date Time Timestamp Decimal};
+    operation_load! {dict; "<", lt; u64 u32 Date Time Timestamp Decimal};
+    operation_load! {dict; "<=", lteq; u64 u32 Date Time Timestamp Decimal};
+    operation_load! {dict; ">", gt; u64 u32 Date Time Timestamp Decimal};
+    operation_load! {dict; ">=", gteq; u64 u32 Date Time Timestamp Decimal};
+    operation_load! {dict; "SUM", sum, aggregate; u64 u32 usize};
+    operation_load! {dict; "COUNT", count, aggregate; u64 u32 Decimal};
+}
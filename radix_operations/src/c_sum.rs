@@ -11,7 +11,7 @@ macro_rules! operation_load {
                 type T=$tr;
                 let signature=sig![OP; T];
                 let op=Operation{
-                    f:  paste!{[<sum_ $tr:lower>]},
+                    f:  std::sync::Arc::new(paste!{[<sum_ $tr:lower>]}),
                     output_type_id: std::any::TypeId::of::<T>(),
                     is_assign_op: false,
                     associated_assign_op: None,
@@ -88,7 +88,7 @@ macro_rules! operation_impl {
                         assert_eq!(group_ids.len(),c2_data.len());
                         let c2_bitmap=c2_bitmap.downcast_ref()?;
                         c2_data.iter().zip(c2_bitmap).zip(group_ids).for_each(|((data, bitmap), group_id)|
-                            c1_data[*group_id]+=*data&(*bitmap as T).wrapping_sub(1)
+                            c1_data[*group_id]+=*data&(bitmap as T).wrapping_sub(1)
                         );
                     }
                     (false, false)=>{
@@ -105,12 +105,12 @@ macro_rules! operation_impl {
 }
 
 operation_impl! {
-    u64 u32
+    u64 u32 usize
 }
 
 pub(crate) fn load_op_dict(dict: &mut OpDictionary) {
     operation_load! {dict;
-        u64 u32
+        u64 u32 usize
     };
 }
 
@@ -1,4 +1,10 @@
-use std::{any::TypeId, collections::HashMap, mem::MaybeUninit};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    io::{Read, Write},
+    mem::MaybeUninit,
+    ops::Range,
+};
 
 use crate::*;
 use radix_column::*;
@@ -40,6 +46,47 @@ pub trait ColumnOperations<'a> {
 
     fn truncate(&mut self, dict: &Dictionary) -> Result<(), ErrorDesc>;
 
+    fn unshare(&mut self, dict: &Dictionary) -> Result<(), ErrorDesc>;
+    fn view<'b>(&'b self, dict: &Dictionary) -> Result<ColumnWrapper<'b>, ErrorDesc>;
+    fn view_range<'b>(
+        &'b self,
+        dict: &Dictionary,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'b>, ErrorDesc>;
+    fn concat(
+        dict: &Dictionary,
+        src: &[(&ColumnWrapper, usize)],
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    fn dictionary_encode(
+        dict: &Dictionary,
+        src: &[&ColumnWrapper],
+    ) -> Result<Vec<ColumnWrapper<'static>>, ErrorDesc>;
+    fn run_length_encode(&self, dict: &Dictionary) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    fn decode(&self, dict: &Dictionary) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    fn sort_index(
+        &self,
+        dict: &Dictionary,
+        order: &mut [usize],
+        ascending: bool,
+    ) -> Result<(), ErrorDesc>;
+    fn take(
+        &self,
+        dict: &Dictionary,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    fn allocated_bytes(&self, dict: &Dictionary) -> Result<usize, ErrorDesc>;
+    fn write_to(
+        &self,
+        dict: &Dictionary,
+        src_index: &ColumnDataIndex,
+        out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc>;
+    fn read_from(
+        dict: &Dictionary,
+        item_type_id: TypeId,
+        inp: &mut dyn Read,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+
     fn copy_to(
         &self,
         dict: &Dictionary,
@@ -84,10 +131,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         let c = match internaloperator {
             Some(iop) => iop.new_owned_with_capacity(capacity, binary_capacity, with_bitmap),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         };
         Ok(c)
     }
@@ -130,10 +174,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.len(&self),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -147,10 +188,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.hash_in(&self, src_index, hash_column),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -166,10 +204,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.group_in(&self, src_index, dst, hashmap_buffer, hashmap_binary),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -183,10 +218,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
                 let internaloperator = dict.columninternal.get(&signature);
                 match internaloperator {
                     Some(iop) => iop.truncate(self)?,
-                    None => Err(format!(
-                        "The following internal column operation not found in dictionary: {:?}",
-                        signature
-                    ))?,
+                    None => Err(signature.not_found(dict))?,
                 }
             }
             _ => Err("Truncate of columns other than Owned and BinaryOwned is not allowed")?,
@@ -201,6 +233,157 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         Ok(())
     }
 
+    fn unshare(&mut self, dict: &Dictionary) -> Result<(), ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.unshare(self),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn view<'b>(&'b self, dict: &Dictionary) -> Result<ColumnWrapper<'b>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.view(self),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn view_range<'b>(
+        &'b self,
+        dict: &Dictionary,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'b>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.view_range(self, rows),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn concat(
+        dict: &Dictionary,
+        src: &[(&ColumnWrapper, usize)],
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        if src.is_empty() {
+            Err("Concatenation requires at least one column")?
+        }
+        let signature = Signature::new("" as &str, vec![src[0].0.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.concat(src),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn dictionary_encode(
+        dict: &Dictionary,
+        src: &[&ColumnWrapper],
+    ) -> Result<Vec<ColumnWrapper<'static>>, ErrorDesc> {
+        if src.is_empty() {
+            Err("Dictionary encoding requires at least one column")?
+        }
+        let signature = Signature::new("" as &str, vec![src[0].column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.dictionary_encode(src),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn run_length_encode(&self, dict: &Dictionary) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.run_length_encode(self),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    //Expands a run-length encoded column into a new owned column, other columns are copied as they are
+    fn decode(&self, dict: &Dictionary) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let iop = dict
+            .columninternal
+            .get(&signature)
+            .ok_or_else(|| signature.not_found(dict))?;
+        if !self.column().is_run_length() {
+            return iop.take(self, &ColumnDataIndex::None);
+        }
+        let len = iop.len(self)?;
+        let mut c: ColumnWrapper<'a> = iop.new_uninit(len, 0, self.bitmap().is_some());
+        iop.copy_to(self, &mut c, &ColumnDataIndex::None)?;
+        //SAFETY: copy_to wrote all rows of the column
+        let c = unsafe { iop.assume_init(c)? };
+        c.into_static()
+    }
+
+    fn sort_index(
+        &self,
+        dict: &Dictionary,
+        order: &mut [usize],
+        ascending: bool,
+    ) -> Result<(), ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.sort_index(self, order, ascending),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn take(
+        &self,
+        dict: &Dictionary,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.take(self, src_index),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn allocated_bytes(&self, dict: &Dictionary) -> Result<usize, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.allocated_bytes(self),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn write_to(
+        &self,
+        dict: &Dictionary,
+        src_index: &ColumnDataIndex,
+        out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![self.column().item_type_id()]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.write_to(self, src_index, out),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
+    fn read_from(
+        dict: &Dictionary,
+        item_type_id: TypeId,
+        inp: &mut dyn Read,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let signature = Signature::new("" as &str, vec![item_type_id]);
+        let internaloperator = dict.columninternal.get(&signature);
+        match internaloperator {
+            Some(iop) => iop.read_from(inp),
+            None => Err(signature.not_found(dict))?,
+        }
+    }
+
     fn copy_to(
         &self,
         dict: &Dictionary,
@@ -211,10 +394,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.copy_to(&self, dst, src_index),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -232,7 +412,11 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
 
         let mut input_types: Vec<TypeId> = Vec::new();
         if *is_assign_op {
-            input_types.push(self.column().item_type_id())
+            input_types.push(self.column().item_type_id());
+            //Copy-on-write, other columns referencing the same buffer must not see the update
+            if self.is_shared() {
+                self.unshare(dict)?;
+            }
         };
 
         input_types.extend(input.iter().map(|input| match input {
@@ -241,14 +425,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         }));
 
         let signature = Signature::new(op, input_types);
-        let op = dict.op.get(&signature);
-        match op {
-            Some(op) => (op.f)(self, c1_index, input),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
-        }
+        dict.call_op(&signature, self, c1_index, input)
     }
 
     fn as_string(
@@ -260,10 +437,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.as_string(&self, src_index),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -285,10 +459,7 @@ impl<'a> ColumnOperations<'a> for ColumnWrapper<'a> {
                 iop.copy_to(&self, &mut output_as_col, &ColumnDataIndex::None)?;
                 Ok(unsafe { output.pop().unwrap().assume_init() })
             }
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 }
@@ -324,10 +495,7 @@ impl<'a> ColumnDataOperations<'a> for ColumnData<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.new(Box::new(data)),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -340,10 +508,7 @@ impl<'a> ColumnDataOperations<'a> for ColumnData<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.new_ref(SliceRef::new(data)),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 
@@ -359,10 +524,7 @@ impl<'a> ColumnDataOperations<'a> for ColumnData<'a> {
         let internaloperator = dict.columninternal.get(&signature);
         match internaloperator {
             Some(iop) => iop.new_mut(SliceRefMut::new(data)),
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
     fn new_const<T>(dict: &Dictionary, data: T) -> Result<ColumnData<'static>, ErrorDesc>
@@ -380,10 +542,7 @@ impl<'a> ColumnDataOperations<'a> for ColumnData<'a> {
                     _ => Err("Const column can only be constructed from an owned column")?,
                 }
             }
-            None => Err(format!(
-                "The following internal column operation not found in dictionary: {:?}",
-                signature
-            ))?,
+            None => Err(signature.not_found(dict))?,
         }
     }
 }
@@ -1,12 +1,16 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
+use std::ops::Range;
+use std::sync::Arc;
 use std::{any::Any, collections::VecDeque};
 use std::{
     collections::HashMap,
     hash::{BuildHasher, Hash, Hasher},
 };
 
-use paste::paste;
-
 use crate::Signature;
 use crate::*;
 use radix_column::*;
@@ -16,6 +20,125 @@ fn copy_of_into_boxed_slice<T>(boxed: Box<T>) -> Box<[T]> {
     unsafe { Box::from_raw(Box::into_raw(boxed) as *mut [T; 1]) }
 }
 
+pub(crate) fn bitmap_allocated_bytes(bitmap: &ColumnDataF) -> usize {
+    match bitmap {
+        ColumnDataF::Owned(b) => b.allocated_bytes(),
+        _ => 0,
+    }
+}
+
+//Hashes of binary items, dictionary-encoded columns hash their values the same way
+fn binary_hash_state() -> ahash::RandomState {
+    ahash::RandomState::with_seeds(
+        2194717786824016851,
+        7391161229587532433,
+        8421638162391593347,
+        13425712476683680973,
+    )
+}
+
+//The codes of a dictionary-encoded column as a column of u32, with the bitmap of the column
+pub(crate) fn codes_view<'a, T: BinaryItem>(
+    src: &'a ColumnWrapper,
+) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+    let (codes, _) = src.column().downcast_dictionary_ref::<T>()?;
+    let mut view = ColumnWrapper::new_from_columndata(ColumnData::Slice(SliceRef::new(codes)));
+    if src.bitmap().is_some() {
+        view.bitmap_set(ColumnDataF::new_from_slice(src.bitmap().downcast_ref()?));
+    }
+    Ok(view)
+}
+
+//The values of the dictionary, if all columns are encoded with the same one
+pub(crate) fn common_dictionary<'a, T: BinaryItem>(
+    columns: impl IntoIterator<Item = &'a ColumnWrapper<'a>>,
+) -> Option<&'a DictionaryValues> {
+    let mut common: Option<&DictionaryValues> = None;
+    for c in columns {
+        let (_, values) = c.column().downcast_dictionary_ref::<T>().ok()?;
+        match common {
+            Some(common) if !common.ptr_eq(values) => return None,
+            _ => common = Some(values),
+        }
+    }
+    common
+}
+
+//Expands a run-length encoded column into an owned column, so that it can be processed row by row
+fn run_length_decoded<T: SizedItem>(
+    src: &ColumnWrapper,
+) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+    let data = src.column().downcast_decoded::<T>()?.into_owned();
+    let mut c = ColumnWrapper::new_from_columndata(ColumnData::Owned(OwnedColumn::new(data)));
+    if src.bitmap().is_some() {
+        c.bitmap_set(ColumnDataF::new(src.bitmap().downcast_ref()?.to_bitmap()));
+    }
+    Ok(c)
+}
+
+//Stable sort of the row ids by the keys of the rows, nulls are placed first in ascending order
+fn sort_nullable<K: Ord>(
+    order: &mut [usize],
+    bitmap: Option<BitmapRef>,
+    ascending: bool,
+    key: impl Fn(usize) -> K,
+) {
+    order.sort_by(|a, b| {
+        let o = match bitmap {
+            Some(bitmap) => match (bitmap[*a], bitmap[*b]) {
+                (true, true) => key(*a).cmp(&key(*b)),
+                (a, b) => a.cmp(&b),
+            },
+            None => key(*a).cmp(&key(*b)),
+        };
+        if ascending {
+            o
+        } else {
+            o.reverse()
+        }
+    });
+}
+
+//Header of a column written by write_to: const flag, number of items and the bitmap, one byte per item
+fn write_column_header(
+    out: &mut dyn Write,
+    is_const: bool,
+    len: usize,
+    bitmap: Option<BitmapRef>,
+) -> Result<(), ErrorDesc> {
+    out.write_all(&[is_const as u8, bitmap.is_some() as u8])?;
+    out.write_all(&(len as u64).to_le_bytes())?;
+    if let Some(bitmap) = bitmap {
+        //The bitmap is written packed, one little endian word per 64 items
+        let bitmap: Vec<u8> = (0..bitmap.number_of_words())
+            .flat_map(|k| bitmap.word(k).to_le_bytes())
+            .collect();
+        out.write_all(&bitmap)?;
+    }
+    Ok(())
+}
+
+fn read_column_header(inp: &mut dyn Read) -> Result<(bool, usize, Option<Bitmap>), ErrorDesc> {
+    let mut flags = [0u8; 2];
+    inp.read_exact(&mut flags)?;
+    let len = read_u64(inp)? as usize;
+    let bitmap = if flags[1] != 0 {
+        let words = (0..len.div_ceil(64))
+            .map(|_| read_u64(inp))
+            .collect::<Result<Vec<u64>, ErrorDesc>>()?;
+        Some(Bitmap::from_words(words, len))
+    } else {
+        None
+    };
+    Ok((flags[0] != 0, len, bitmap))
+}
+
+fn read_u64(inp: &mut dyn Read) -> Result<u64, ErrorDesc> {
+    let mut bytes = [0u8; 8];
+    inp.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
 fn copy_to_buckets_part<T: Copy>(
     hash: &Vec<u64>,
     buckets_mask: u64,
@@ -43,6 +166,33 @@ fn copy_to_buckets_part<T: Copy>(
     Ok(items_written)
 }
 
+fn copy_to_buckets_part_bitmap(
+    hash: &[u64],
+    buckets_mask: u64,
+    src: BitmapRef,
+    src_index: &ColumnDataIndex,
+    offsets: &mut VecDeque<usize>,
+    dst: &mut [BitmapMut],
+) -> Result<usize, ErrorDesc> {
+    let mut items_written = 0;
+    if let Ok(src_index) = src_index.downcast_ref() {
+        src_index.iter().zip(hash.iter()).for_each(|(i, h)| {
+            let bucket_id = (*h & buckets_mask) as usize;
+            dst[bucket_id].set(offsets[bucket_id], src[*i]);
+            offsets[bucket_id] += 1;
+            items_written += 1;
+        });
+    } else {
+        src.iter().zip(hash.iter()).for_each(|(val, h)| {
+            let bucket_id = (*h & buckets_mask) as usize;
+            dst[bucket_id].set(offsets[bucket_id], val);
+            offsets[bucket_id] += 1;
+            items_written += 1;
+        });
+    }
+    Ok(items_written)
+}
+
 fn copy_to_buckets_part_uninit<T: Copy>(
     hash: &Vec<u64>,
     buckets_mask: u64,
@@ -72,71 +222,40 @@ fn copy_to_buckets_part_uninit<T: Copy>(
 }
 
 fn copy_to_buckets_binary_part(
-    hash: &Vec<u64>,
+    hash: &[u64],
     buckets_mask: u64,
     src_datau8: &[u8],
-    src_start_pos: &[usize],
-    src_len: &[usize],
-    src_offset: &usize,
+    src_offsets: BinaryOffsetsRef,
     src_index: &ColumnDataIndex,
     offsets: &mut VecDeque<usize>,
-    dst: &mut [(&mut [u8], &[usize], &[usize], usize)],
+    dst: &mut [(&mut [u8], BinaryOffsetsRef)],
 ) -> Result<usize, ErrorDesc> {
     let mut bytes_written: usize = 0;
+    //The offsets of the destination were computed by part2, only the bytes are copied
+    let mut copy = |item: &[u8], h: &u64| {
+        let bucket_id = (*h & buckets_mask) as usize;
+        let (dst_datau8, dst_offsets) = &mut dst[bucket_id];
+        let pos = offsets[bucket_id];
+        dst_datau8[dst_offsets.get(pos)..dst_offsets.get(pos + 1)].copy_from_slice(item);
+        bytes_written += item.len();
+        offsets[bucket_id] += 1;
+    };
     if let Ok(src_index) = src_index.downcast_ref() {
-        src_index.iter().zip(hash.iter()).for_each(|(i, h)| {
-            let bucket_id = (*h & buckets_mask) as usize;
-            let (dst_datau8, dst_start_pos, dst_len, _a) = &mut dst[bucket_id];
-            let start_u8 = src_start_pos[*i] - src_offset;
-            let end_u8 = start_u8 + src_len[*i];
-            let slice_read = &src_datau8[start_u8..end_u8];
-
-            let start_pos_write = dst_start_pos[offsets[bucket_id]];
-            let end_pos_write = start_pos_write + dst_len[offsets[bucket_id]];
-            let slice_write = &mut dst_datau8[start_pos_write..end_pos_write];
-
-            bytes_written += slice_write
-                .iter_mut()
-                .zip(slice_read.iter())
-                .map(|(t, s)| {
-                    *t = *s;
-                    1usize
-                })
-                .sum::<usize>();
-            offsets[bucket_id] += 1;
-        });
-    } else {
-        src_start_pos
+        src_index
             .iter()
-            .zip(src_len)
-            .zip(hash)
-            .for_each(|((start_pos, len), h)| {
-                let bucket_id = (*h & buckets_mask) as usize;
-                let (dst_datau8, dst_start_pos, dst_len, _a) = &mut dst[bucket_id];
-
-                let start_u8 = start_pos - src_offset;
-                let end_u8 = start_u8 + len;
-                let slice_read = &src_datau8[start_u8..end_u8];
-
-                let start_pos_write = dst_start_pos[offsets[bucket_id]];
-                let end_pos_write = start_pos_write + dst_len[offsets[bucket_id]];
-                let slice_write = &mut dst_datau8[start_pos_write..end_pos_write];
-
-                bytes_written += slice_write
-                    .iter_mut()
-                    .zip(slice_read.iter())
-                    .map(|(t, s)| {
-                        *t = *s;
-                        1usize
-                    })
-                    .sum::<usize>();
-            });
+            .zip(hash.iter())
+            .for_each(|(i, h)| copy(binary_item(src_datau8, src_offsets, *i), h));
+    } else {
+        binary_items(src_datau8, src_offsets)
+            .zip(hash.iter())
+            .for_each(|(item, h)| copy(item, h));
     }
     Ok(bytes_written)
 }
 
 ///Operations which all columns must implement
 pub trait ColumnInternalOp {
+    fn type_name(&self) -> &'static str;
     fn len(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc>;
     fn truncate(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc>;
     fn new<'a>(&self, data: Box<dyn Any>) -> Result<ColumnData<'a>, ErrorDesc>;
@@ -167,6 +286,42 @@ pub trait ColumnInternalOp {
     ) -> ColumnWrapper<'static>;
     //SAFETY: The caller must take care that the column is fully initialized
     unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc>;
+    //Turns a shared column into an owned one. The data is copied only if other columns still reference it
+    fn unshare(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc>;
+    //Borrows the data of the column without copying it, const columns stay const
+    fn view<'a>(&self, inp: &'a ColumnWrapper) -> Result<ColumnWrapper<'a>, ErrorDesc>;
+    //Borrows the given rows of the column without copying them, const columns stay const
+    fn view_range<'a>(
+        &self,
+        inp: &'a ColumnWrapper,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'a>, ErrorDesc>;
+    //Appends the columns one after another, a const column is repeated for the given number of rows
+    fn concat(&self, src: &[(&ColumnWrapper, usize)]) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    //Stable sort of the row ids by the values of the column, nulls are placed first in ascending order
+    fn sort_index(
+        &self,
+        src: &ColumnWrapper,
+        order: &mut [usize],
+        ascending: bool,
+    ) -> Result<(), ErrorDesc>;
+    //Copies the rows selected by the index into a new owned column, const columns stay const
+    fn take(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc>;
+    //Bytes of data owned by the column and its bitmap, borrowed and shared data is not counted
+    fn allocated_bytes(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc>;
+    //Writes the rows selected by the index, the data is written as it is laid out in memory
+    fn write_to(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc>;
+    //Reads a column written by write_to into an owned column
+    fn read_from(&self, inp: &mut dyn Read) -> Result<ColumnWrapper<'static>, ErrorDesc>;
     fn hash_in(
         &self,
         src: &ColumnWrapper,
@@ -206,6 +361,60 @@ pub trait ColumnInternalOp {
         hashmap_buffer: &mut HashMapBuffer,
         hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
     ) -> Result<(), ErrorDesc>;
+    //Encodes the columns as codes into one dictionary of their distinct values, which is shared by the results
+    fn dictionary_encode(
+        &self,
+        _src: &[&ColumnWrapper],
+    ) -> Result<Vec<ColumnWrapper<'static>>, ErrorDesc> {
+        Err(format!(
+            "Dictionary encoding is not supported for columns of type {}",
+            self.type_name()
+        ))?
+    }
+    //Stores the column as runs of equal consecutive values
+    fn run_length_encode(&self, _src: &ColumnWrapper) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        Err(format!(
+            "Run-length encoding is not supported for columns of type {}",
+            self.type_name()
+        ))?
+    }
+}
+
+//Items of sized columns, any such type can be registered with Dictionary::register_sized_type
+//Printing and sorting are given by functions, so that types without Display or Ord can be stored
+pub trait SizedItem: Copy + Hash + Eq + Send + Sync + 'static {}
+
+impl<T> SizedItem for T where T: Copy + Hash + Eq + Send + Sync + 'static {}
+
+//Formats a sized item for as_string
+pub type SizedFormat<T> = fn(&T) -> String;
+
+//Order of sized items for sort_index
+pub type SizedCompare<T> = fn(&T, &T) -> std::cmp::Ordering;
+
+fn format_sized_display<T: fmt::Display>(item: &T) -> String {
+    format!("{}", item)
+}
+
+//Items of binary columns, any such type can be registered with Dictionary::register_binary_type
+pub trait BinaryItem: AsBytes + Clone + Send + Sync + 'static {}
+
+impl<T> BinaryItem for T where T: AsBytes + Clone + Send + Sync + 'static {}
+
+//Formats the bytes of a binary item for as_string
+pub type BinaryFormat = fn(&[u8]) -> Result<String, ErrorDesc>;
+
+fn format_display<T: AsBytes + fmt::Display>(data: &[u8]) -> Result<String, ErrorDesc> {
+    Ok(format!("{}", T::try_from_bytes(data)?))
+}
+
+//Lower case hex of the bytes, used for BLOB columns which have no text representation
+pub fn format_hex(data: &[u8]) -> Result<String, ErrorDesc> {
+    let mut out = String::with_capacity(data.len() * 2);
+    data.iter().for_each(|b| {
+        out.push_str(&format!("{:02x}", b));
+    });
+    Ok(out)
 }
 
 const OP: &str = "";
@@ -216,7 +425,7 @@ macro_rules! sized_types_load {
             $dict.insert(
                 signature, Box::new(
                     {
-                        paste!{[<ColumnInternalOp $tr>]::new()}
+                        SizedColumnInternalOp::<$tr>::new(stringify!($tr))
                     }
                 )
             );
@@ -229,1051 +438,2004 @@ macro_rules! binary_types_load {
             $dict.insert(
                 signature, Box::new(
                     {
-                        paste!{[<ColumnBinaryInternalOp $tr>]::new()}
+                        BinaryColumnInternalOp::<$tr>::new(stringify!($tr))
                     }
                 )
             );
     )+)
 }
 
-macro_rules! sized_types_impl {
-    ($($tr:ty)+) => ($(
-        paste!{
-            struct [<ColumnInternalOp $tr>]{}
-            impl [<ColumnInternalOp $tr>]{
-                fn new()->Self{
-                    Self{}
-                }
-            }
-            impl ColumnInternalOp for [<ColumnInternalOp $tr>]
-            {
-                fn len(&self, inp: &ColumnWrapper)->Result<usize, ErrorDesc>
-                {
-                    type T=$tr;
-                    inp.column().downcast_ref::<T>().map(|c| c.len())
-                }
-                fn truncate(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc>{
-                    type T=$tr;
-                    if let ColumnData::Owned(c)=inp.column_mut(){
-                        c.downcast_vec::<T>()?.truncate(0);
-                        Ok(())
-                    } else
-                    {
-                        Err("Only ColumnData::Owned can be truncated")?
-                    }
-                }
-
-                fn new<'a>(&self, data: Box<dyn Any>)->Result<ColumnData<'a>, ErrorDesc>{
-                    type T=$tr;
-                    let col = data.downcast::<Vec<T>>().map_err(|_| {
-                        format!(
-                            "Downcast failed. Target type is Vec<{}>",
-                          std::any::type_name::<T>()
-                        )
-                    })?;
-                    let col = copy_of_into_boxed_slice(col);
-                    let mut res: Vec<Vec<T>> = col.into();
-                    let res = res.pop().unwrap();
-                    Ok(ColumnData::Owned(OwnedColumn::new(res)))
-                }
-                fn new_ref<'a: 'b,'b>(&self, data: SliceRef<'a>)->Result<ColumnData<'b>, ErrorDesc>
-                {
-                    type T=$tr;
-                    if data.item_type_id()==std::any::TypeId::of::<T>(){
-                        Ok(ColumnData::Slice(data))
-                    } else {
-                        //TO-DO: Better error message
-                        Err("Wrong type")?
-                    }
+//Internal column operations of sized types, the items are stored as plain values in a Vec
+pub struct SizedColumnInternalOp<T> {
+    name: &'static str,
+    format: SizedFormat<T>,
+    //Columns of types without an order cannot be sorted
+    compare: Option<SizedCompare<T>>,
+    item: PhantomData<fn() -> T>,
+}
 
-                }
-                fn new_mut<'a: 'b, 'b>(&self, data: SliceRefMut<'a>) -> Result<ColumnData<'b>, ErrorDesc>{
-                    type T=$tr;
-                    if data.item_type_id()==std::any::TypeId::of::<T>(){
-                        Ok(ColumnData::SliceMut(data))
-                    } else {
-                        //TO-DO: Better error message
-                        Err("Wrong type")?
-                    }
-                }
-                fn copy_to<'a>(
-                    &self,
-                    src: &ColumnWrapper<'a>,
-                    dst: &mut ColumnWrapper<'a>,
-                    src_index: &ColumnDataIndex<'a>,
-                ) -> Result<(), ErrorDesc>
-                {
-                    type T=$tr;
+impl<T: Ord + fmt::Display> SizedColumnInternalOp<T> {
+    pub fn new(name: &'static str) -> Self {
+        Self::with_format(name, format_sized_display::<T>).with_compare(T::cmp)
+    }
+}
 
-                    let bitmap_update_required=src.bitmap().is_some();
-                    let input=vec![InputTypes::Ref(src, src_index)];
+impl<T> SizedColumnInternalOp<T> {
+    //For types without Display, the columns cannot be sorted unless with_compare is called
+    pub fn with_format(name: &'static str, format: SizedFormat<T>) -> Self {
+        Self {
+            name,
+            format,
+            compare: None,
+            item: PhantomData,
+        }
+    }
 
+    pub fn with_compare(mut self, compare: SizedCompare<T>) -> Self {
+        self.compare = Some(compare);
+        self
+    }
+}
 
-                    set_2_sized_sized_unroll::<MaybeUninit<T>, T, _,_>(dst, &input, &bitmap_update_required, |c1_data,_c1_bool|
-                        MaybeUninit::new(*c1_data)
-                    , |b| *b)
+impl<T: SizedItem> ColumnInternalOp for SizedColumnInternalOp<T> {
+    fn type_name(&self) -> &'static str {
+        self.name
+    }
+    fn len(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        inp.column().data_len::<T>()
+    }
+    fn truncate(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        if let ColumnData::Owned(c) = inp.column_mut() {
+            c.downcast_vec::<T>()?.truncate(0);
+            Ok(())
+        } else {
+            Err("Only ColumnData::Owned can be truncated")?
+        }
+    }
 
+    fn new<'a>(&self, data: Box<dyn Any>) -> Result<ColumnData<'a>, ErrorDesc> {
+        let col = data.downcast::<Vec<T>>().map_err(|_| {
+            format!(
+                "Downcast failed. Target type is Vec<{}>",
+                std::any::type_name::<T>()
+            )
+        })?;
+        let col = copy_of_into_boxed_slice(col);
+        let mut res: Vec<Vec<T>> = col.into();
+        let res = res.pop().unwrap();
+        Ok(ColumnData::Owned(OwnedColumn::new(res)))
+    }
+    fn new_ref<'a: 'b, 'b>(&self, data: SliceRef<'a>) -> Result<ColumnData<'b>, ErrorDesc> {
+        if data.item_type_id() == std::any::TypeId::of::<T>() {
+            Ok(ColumnData::Slice(data))
+        } else {
+            //TO-DO: Better error message
+            Err("Wrong type")?
+        }
+    }
+    fn new_mut<'a: 'b, 'b>(&self, data: SliceRefMut<'a>) -> Result<ColumnData<'b>, ErrorDesc> {
+        if data.item_type_id() == std::any::TypeId::of::<T>() {
+            Ok(ColumnData::SliceMut(data))
+        } else {
+            //TO-DO: Better error message
+            Err("Wrong type")?
+        }
+    }
+    fn copy_to<'a>(
+        &self,
+        src: &ColumnWrapper<'a>,
+        dst: &mut ColumnWrapper<'a>,
+        src_index: &ColumnDataIndex<'a>,
+    ) -> Result<(), ErrorDesc> {
+        //Run-length encoded columns are decoded, which lets every operation read them row by row
+        if src.column().is_run_length() {
+            return self.copy_to(&run_length_decoded::<T>(src)?, dst, src_index);
+        }
+        let bitmap_update_required = src.bitmap().is_some();
+        let input = vec![InputTypes::Ref(src, src_index)];
+
+        set_2_sized_sized_unroll::<MaybeUninit<T>, T, _, _>(
+            dst,
+            &input,
+            &bitmap_update_required,
+            |c1_data, _c1_bool| MaybeUninit::new(*c1_data),
+            |b| *b,
+        )
+    }
 
-                }
+    fn as_string<'a>(
+        &self,
+        src: &ColumnWrapper<'a>,
+        src_index: &ColumnDataIndex<'a>,
+    ) -> Result<Vec<String>, ErrorDesc> {
+        if src.column().is_run_length() {
+            return self.as_string(&run_length_decoded::<T>(src)?, src_index);
+        }
+        let src = src.column().downcast_ref::<T>()?;
+        if src_index.is_some() {
+            let index = src_index.downcast_ref()?;
+            let out: Vec<_> = index.iter().map(|i| (self.format)(&src[*i])).collect();
+            Ok(out)
+        } else {
+            let out: Vec<_> = src.iter().map(self.format).collect();
+            Ok(out)
+        }
+    }
+    fn new_owned_with_capacity(
+        &self,
+        number_of_items: usize,
+        _binary_capacity: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let c = ColumnData::Owned(OwnedColumn::new(Vec::<T>::with_capacity(number_of_items)));
+        let mut c = ColumnWrapper::new_from_columndata(c);
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, number_of_items)))
+        };
+        c
+    }
+    fn new_uninit(
+        &self,
+        number_of_items: usize,
+        _binary_storage: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let c = ColumnData::Owned(OwnedColumn::new_uninit::<T>(number_of_items));
+        let mut c = ColumnWrapper::new_from_columndata(c);
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, number_of_items)))
+        };
+        c
+    }
 
-                fn as_string<'a>(
-                    &self,
-                    src: &ColumnWrapper<'a>,
-                    src_index: &ColumnDataIndex<'a>,
-                ) -> Result<Vec<String>, ErrorDesc>
-                {
-                    type T=$tr;
-                    let src=src.column().downcast_ref::<T>()?;
-                    if src_index.is_some(){
-                        let index=src_index.downcast_ref()?;
-                        let out:Vec<_>=index.iter().map(|v| format!("{}",v)).collect();
-                        Ok(out)
-                    } else {
-                        let out:Vec<_>=src.iter().map(|v| format!("{}",v)).collect();
-                        Ok(out)
-                    }
+    unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc> {
+        let (column, bitmap) = c.get_inner();
+        let column = column.assume_init::<T>()?;
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        c.bitmap_set(bitmap);
+        Ok(c)
+    }
 
-                }
-                fn new_owned_with_capacity(&self, number_of_items: usize, _binary_capacity: usize, with_bitmap: bool) -> ColumnWrapper<'static>{
-                    type T=$tr;
-                    let c=ColumnData::Owned(OwnedColumn::new(Vec::<T>::with_capacity(number_of_items)));
-                    let mut c=ColumnWrapper::new_from_columndata (c);
-                    if with_bitmap{
-                        c.bitmap_set(ColumnDataF::new(vec![false; number_of_items]))
-                    };
-                    c
-                }
-                fn new_uninit(&self, number_of_items: usize, _binary_storage: usize, with_bitmap: bool) -> ColumnWrapper<'static>{
-                    type T=$tr;
-                    let c=ColumnData::Owned(OwnedColumn::new_uninit::<T>(number_of_items));
-                    let mut c=ColumnWrapper::new_from_columndata (c);
-                    if with_bitmap{
-                        c.bitmap_set(ColumnDataF::new(vec![false; number_of_items]))
-                    };
-                    c
-                }
+    fn unshare(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        if inp.column().is_shared() {
+            let placeholder = ColumnData::Const(OwnedColumn::new(Vec::<T>::new()));
+            let col = match std::mem::replace(inp.column_mut(), placeholder) {
+                ColumnData::Shared(c) => match Arc::try_unwrap(c) {
+                    Ok(c) => c,
+                    Err(c) => OwnedColumn::new(c.downcast_ref::<T>()?.to_vec()),
+                },
+                _ => Err("Column expected to be ColumnData::Shared")?,
+            };
+            *inp.column_mut() = ColumnData::Owned(col);
+        }
+        inp.bitmap_mut().unshare();
+        Ok(())
+    }
 
-                unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc>{
-                    type T=$tr;
-                    let (column, bitmap)=c.get_inner();
-                    let column=column.assume_init::<T>()?;
-                    let mut c=ColumnWrapper::new_from_columndata (column);
-                    c.bitmap_set(bitmap);
-                    Ok(c)
+    fn concat(&self, src: &[(&ColumnWrapper, usize)]) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let total_len = src.iter().map(|(_, len)| *len).sum();
+        let mut data: Vec<T> = Vec::with_capacity(total_len);
+        src.iter()
+            .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                let c_data = c.column().downcast_decoded::<T>()?;
+                if c.column().is_const() {
+                    data.extend(std::iter::repeat(c_data[0]).take(*len));
+                } else if c_data.len() == *len {
+                    data.extend_from_slice(&c_data);
+                } else {
+                    Err(RadixError::LengthMismatch {
+                        expected: *len,
+                        found: c_data.len(),
+                    })?
                 }
-
-                fn hash_in(&self, src: &ColumnWrapper, src_index: &ColumnDataIndex, dst: &mut Vec<u64>)-> Result<(), ErrorDesc>{
-                    type T=$tr;
-                    let src_data=src.column().downcast_ref::<T>()?;
-                    let src_bitmap=src.bitmap();
-
-                    let s=ahash::RandomState::with_seeds(2194717786824016851,7391161229587532433,8421638162391593347, 13425712476683680973);
-
-                    if dst.len()==0{
-                        //We have to do an insert
-                        match (src_index.is_some(), src_bitmap.is_some()){
-                            (true, true)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_index.iter().map(|i| {
-                                    let data=src_data[*i];
-                                    let bitmap=src_bitmap[*i];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()|(bitmap as u64).wrapping_sub(1)
-                                });
-                                dst.extend(itr);
-                            },
-                            (true, false)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let itr=src_index.iter().map(|i| {
-                                    let data=src_data[*i];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, true)=>{
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_data.iter().zip(src_bitmap).map(|(data, bitmap)| {
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()|(*bitmap as u64).wrapping_sub(1)
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, false)=>{
-                                let itr=src_data.iter().map(|data| {
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()
-                                });
-                                dst.extend(itr);
-                            },
+                Ok(())
+            })?;
+        let has_bitmap = src.iter().any(|(c, _)| c.bitmap().is_some());
+        let mut bitmap = Bitmap::new();
+        if has_bitmap {
+            src.iter()
+                .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                    match (c.bitmap().is_some(), c.column().is_const()) {
+                        (true, true) => {
+                            let valid = c.bitmap().downcast_ref()?[0];
+                            bitmap.resize(bitmap.len() + *len, valid)
                         }
-                        } else {
-                            //We have to do an update
+                        (true, false) => bitmap.extend_from_bitmap(c.bitmap().downcast_ref()?),
+                        (false, _) => bitmap.resize(bitmap.len() + *len, true),
+                    };
+                    Ok(())
+                })?;
+        }
+        let mut c = ColumnWrapper::new_from_columndata(ColumnData::Owned(OwnedColumn::new(data)));
+        if has_bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
-                            //First check if src is a const
-                            if src.column().is_const(){
-                                //in case of constant, we have to add its hash to the entire hash vector
-                                let mut h = s.build_hasher();
-                                src_data[0].hash(&mut h);
-                                let mut hash_value=h.finish();
-                                if src_bitmap.is_some() {
-                                    if !src_bitmap.downcast_ref()?[0] {hash_value=u64::MAX};
-                                };
-                                let hash_value=hash_value;
-                                dst.iter_mut().for_each(|h| *h=h.wrapping_add(hash_value));
-                            } else {
-                                //The source is not a constant value, therefore we have to make sure it has the same length as the hash vector
-
-
-                                match (src_index.is_some(), src_bitmap.is_some()){
-                                    (true, true)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        let itr=src_index.iter().map(|i| {
-                                            let data=src_data[*i];
-                                            let bitmap=src_bitmap[*i];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()|(bitmap as u64).wrapping_sub(1)
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (true, false)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        let itr=src_index.iter().map(|i| {
-                                            let data=src_data[*i];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (false, true)=>{
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        assert_eq!(src_data.len(), dst.len());
-                                        let itr=src_data.iter().zip(src_bitmap).map(|(data, bitmap)| {
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()|(*bitmap as u64).wrapping_sub(1)
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (false, false)=>{
-                                        assert_eq!(src_data.len(), dst.len());
-                                        let itr=src_data.iter().map(|data| {
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                }
-                            }
-                        }
-                        Ok(())
+    fn sort_index(
+        &self,
+        src: &ColumnWrapper,
+        order: &mut [usize],
+        ascending: bool,
+    ) -> Result<(), ErrorDesc> {
+        if src.column().is_const() {
+            return Ok(());
+        }
+        let compare = match self.compare {
+            Some(compare) => compare,
+            None => Err(format!("Columns of type {} cannot be sorted", self.name))?,
+        };
+        if src.column().is_run_length() {
+            return self.sort_index(&run_length_decoded::<T>(src)?, order, ascending);
+        }
+        let data = src.column().downcast_ref::<T>()?;
+        let value = |i: usize| &data[i];
+        let bitmap = src.bitmap();
+        if bitmap.is_some() {
+            let bitmap = bitmap.downcast_ref()?;
+            order.sort_by(|a, b| {
+                let o = match (bitmap[*a], bitmap[*b]) {
+                    (true, true) => compare(value(*a), value(*b)),
+                    (a, b) => a.cmp(&b),
+                };
+                if ascending {
+                    o
+                } else {
+                    o.reverse()
                 }
-                fn copy_to_buckets_part1(
-                    &self,
-                    hash: &[Vec<u64>],
-                    buckets_mask: u64,
-                    src_columns: &[Vec<ColumnWrapper>],
-                    src_indexes: &[Vec<ColumnDataIndex>],
-                    col_id: usize,
-                    index_id: &Option<&usize>,
-                    offsets: &VecDeque<usize>,
-                    dst: &mut [ColumnWrapper<'static>],
-                    is_nullable: bool,
-                ) -> Result<usize, ErrorDesc>
-                {
-                    type T=$tr;
-
-                    let mut dst_data: Vec<_>=dst.iter_mut().map(|c| c.column_mut()).map(|c| c.downcast_mut::<MaybeUninit<T>>().unwrap()).collect();
-                    let mut offsets_tmp=offsets.clone();
-                    let mut items_written=0;
-
-                    let index_empty=ColumnDataIndex::None;
-
-                    src_columns.iter().zip(src_indexes.iter()).zip(hash.iter()).for_each(|((src, src_index), hash)|{
-                        let src=src[col_id].column().downcast_ref::<T>().unwrap();
-                        let src_index=match index_id {
-                            Some(i) => &src_index[**i],
-                            None => &index_empty,
-                        };
-                        items_written+=copy_to_buckets_part_uninit(hash, buckets_mask, src, src_index, &mut offsets_tmp, &mut dst_data).unwrap();
-                    });
-
-                    if is_nullable {
-                        let mut offsets_tmp=offsets.clone();
-                        let mut dst_bitmap: Vec<_>=dst.iter_mut().map(|c| c.bitmap_mut().downcast_mut().unwrap()).collect();
-
-                        src_columns.iter().zip(src_indexes.iter()).zip(hash.iter()).for_each(|((src, src_index), hash)|{
-
-                            let src=src[col_id].bitmap().downcast_ref().unwrap();
-                            let src_index=match index_id {
-                                Some(i) => &src_index[**i],
-                                None => &index_empty,
-                            };
-                            items_written+=copy_to_buckets_part(hash, buckets_mask, src, src_index, &mut offsets_tmp, &mut dst_bitmap).unwrap();
-                        });
-                    }
-
-                    Ok(items_written)
+            });
+        } else {
+            order.sort_by(|a, b| {
+                let o = compare(value(*a), value(*b));
+                if ascending {
+                    o
+                } else {
+                    o.reverse()
                 }
+            });
+        }
+        Ok(())
+    }
 
-                fn copy_to_buckets_part2(&self, _dst: &mut ColumnWrapper<'static>) -> Result<usize, ErrorDesc>{Err("copy_to_buckets_part2 called for a sized type")?}
-                fn copy_to_buckets_part3(
-                    &self,
-                    _hash: &[Vec<u64>],
-                    _buckets_mask: u64,
-                    _src_columns: &[Vec<ColumnWrapper>],
-                    _src_indexes: &[Vec<ColumnDataIndex>],
-                    _col_id: usize,
-                    _index_id: &Option<&usize>,
-                    _offsets: &VecDeque<usize>,
-                    _dst: &mut [ColumnWrapper<'static>],
-                ) -> Result<usize, ErrorDesc>{Err("copy_to_buckets_part2 called for a sized type")?}
-
-                fn group_in(
-                    &self,
-                    src: &ColumnWrapper,
-                    src_index: &ColumnDataIndex,
-                    dst: &mut Vec<usize>,
-                    hashmap_buffer: &mut HashMapBuffer,
-                    _hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
-                ) -> Result<(), ErrorDesc>{
-                    type T=$tr;
-                    let src_data=src.column().downcast_ref::<T>()?;
-                    let src_bitmap=src.bitmap();
-
-                    let mut h=hashmap_buffer.pop::<T>();
-
-
-                    if dst.len()==0{
-                        //We have to do an insert
-                        match (src_index.is_some(), src_bitmap.is_some()){
-                            (true, true)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_index.iter().enumerate().map(|(i, index)| {
-                                    let data=src_data[*index];
-                                    let bitmap=src_bitmap[*index];
-
-                                    let new_group_id: usize=*h.entry((0, NullableValue{value: data, bitmap})).or_insert(i);
-                                    new_group_id
-
-                                });
-                                dst.extend(itr);
-                            },
-                            (true, false)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let itr=src_index.iter().enumerate().map(|(i, index)| {
-                                    let data=src_data[*index];
-                                    let bitmap=true;
-                                    let new_group_id: usize=*h.entry((0, NullableValue{value: data, bitmap})).or_insert(i);
-                                    new_group_id
-
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, true)=>{
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_data.iter().zip(src_bitmap).enumerate().map(|(i,(data, bitmap))| {
-                                    let new_group_id: usize=*h.entry((0, NullableValue{value: *data, bitmap: *bitmap})).or_insert(i);
-                                    new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, false)=>{
-                                let itr=src_data.iter().enumerate().map(|(i,data)| {
-                                    let new_group_id: usize=*h.entry((0, NullableValue{value: *data, bitmap: true})).or_insert(i);
-                                    new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                        }
-                        } else {
-                            //We have to do an update
-
-                            //First check if src is a const, do nothing
-                            if src.column().is_const(){
-                            } else {
-                                match (src_index.is_some(), src_bitmap.is_some()){
-                                    (true, true)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(|(i, (index, current_group_id))| {
-                                            let data=src_data[*index];
-                                            let bitmap=src_bitmap[*index];
-
-                                            let new_group_id: usize=*h.entry((*current_group_id, NullableValue{value: data, bitmap})).or_insert(i);
-                                            *current_group_id=new_group_id;
-
-                                        });
-
-                                    },
-                                    (true, false)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(|(i, (index, current_group_id))| {
-                                            let data=src_data[*index];
-                                            let bitmap=true;
-                                            let new_group_id: usize=*h.entry((*current_group_id, NullableValue{value: data, bitmap})).or_insert(i);
-                                            *current_group_id=new_group_id;
-
-                                        });
-
-                                    },
-                                    (false, true)=>{
-                                        assert_eq!(src_data.len(), dst.len());
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        src_data.iter().zip(src_bitmap).zip(dst.iter_mut()).enumerate().for_each(|(i,((data, bitmap), current_group_id))| {
-                                            let new_group_id: usize=*h.entry((*current_group_id, NullableValue{value: *data, bitmap: *bitmap})).or_insert(i);
-                                            *current_group_id=new_group_id;
-                                        });
-
-                                    },
-                                    (false, false)=>{
-                                        assert_eq!(src_data.len(), dst.len());
-                                        src_data.iter().zip(dst.iter_mut()).enumerate().for_each(|(i,(data, current_group_id))| {
-                                            let new_group_id: usize=*h.entry((*current_group_id, NullableValue{value: *data, bitmap: true})).or_insert(i);
-                                            *current_group_id=new_group_id;
-                                        });
-                                    },
-                                }
-                            }
-                        }
-                        Ok(())
-
-
-
-
-
-
+    fn take(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        if src.column().is_run_length() {
+            return self.take(&run_length_decoded::<T>(src)?, src_index);
+        }
+        let data = src.column().downcast_ref::<T>()?;
+        let bitmap = src.bitmap();
+        let bitmap = if bitmap.is_some() {
+            Some(bitmap.downcast_ref()?)
+        } else {
+            None
+        };
+        let (column, bitmap) = if src.column().is_const() {
+            (
+                ColumnData::Const(OwnedColumn::new(data.to_vec())),
+                bitmap.map(|b| b.to_bitmap()),
+            )
+        } else if src_index.is_some() {
+            let index = src_index.downcast_ref()?;
+            let data: Vec<T> = index.iter().map(|i| data[*i]).collect();
+            (
+                ColumnData::Owned(OwnedColumn::new(data)),
+                bitmap.map(|b| index.iter().map(|i| b[*i]).collect()),
+            )
+        } else {
+            (
+                ColumnData::Owned(OwnedColumn::new(data.to_vec())),
+                bitmap.map(|b| b.to_bitmap()),
+            )
+        };
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
-                }
+    fn allocated_bytes(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        let data = match inp.column() {
+            ColumnData::Owned(_) | ColumnData::Const(_) => {
+                inp.column().downcast_ref::<T>()?.len() * std::mem::size_of::<T>()
             }
-
+            ColumnData::RunLength(_) => {
+                let (values, run_lengths) = inp.column().downcast_runs_ref::<T>()?;
+                std::mem::size_of_val(values) + std::mem::size_of_val(run_lengths)
+            }
+            _ => 0,
+        };
+        Ok(data + bitmap_allocated_bytes(inp.bitmap()))
     }
 
+    fn write_to(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc> {
+        let c = self.take(src, src_index)?;
+        let data = c.column().downcast_ref::<T>()?;
+        let bitmap = if c.bitmap().is_some() {
+            Some(c.bitmap().downcast_ref()?)
+        } else {
+            None
+        };
+        write_column_header(out, c.column().is_const(), data.len(), bitmap)?;
+        //SAFETY: sized types are plain values without references
+        let bytes = unsafe {
+            std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+        };
+        out.write_all(bytes)?;
+        Ok(())
+    }
 
+    fn read_from(&self, inp: &mut dyn Read) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let (is_const, len, bitmap) = read_column_header(inp)?;
+        let mut bytes = vec![0u8; len * std::mem::size_of::<T>()];
+        inp.read_exact(&mut bytes)?;
+        let mut data: Vec<T> = Vec::with_capacity(len);
+        //SAFETY: the bytes were written by write_to from values of the same type
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                data.as_mut_ptr() as *mut u8,
+                bytes.len(),
+            );
+            data.set_len(len);
+        }
+        let column = if is_const {
+            ColumnData::Const(OwnedColumn::new(data))
+        } else {
+            ColumnData::Owned(OwnedColumn::new(data))
+        };
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
+    fn view<'a>(&self, inp: &'a ColumnWrapper) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        let column = if let ColumnData::RunLength(c) = inp.column() {
+            //The runs are copied, they are expected to be few
+            ColumnData::RunLength(c.range::<T>(0..c.len())?)
+        } else if inp.column().is_const() {
+            let data = inp.column().downcast_ref::<T>()?;
+            ColumnData::Const(OwnedColumn::new(data.to_vec()))
+        } else {
+            let data = inp.column().downcast_ref::<T>()?;
+            ColumnData::Slice(SliceRef::new(data))
+        };
+        let mut view = ColumnWrapper::new_from_columndata(column);
+        if inp.bitmap().is_some() {
+            view.bitmap_set(ColumnDataF::new_from_slice(inp.bitmap().downcast_ref()?));
+        }
+        Ok(view)
+    }
 
-    )+)
-}
+    fn view_range<'a>(
+        &self,
+        inp: &'a ColumnWrapper,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        let column = if let ColumnData::RunLength(c) = inp.column() {
+            ColumnData::RunLength(c.range::<T>(rows.clone())?)
+        } else if inp.column().is_const() {
+            let data = inp.column().downcast_ref::<T>()?;
+            ColumnData::Const(OwnedColumn::new(data.to_vec()))
+        } else {
+            let data = inp.column().downcast_ref::<T>()?;
+            ColumnData::Slice(SliceRef::new(&data[rows.clone()]))
+        };
+        let mut view = ColumnWrapper::new_from_columndata(column);
+        if inp.bitmap().is_some() {
+            let bitmap = inp.bitmap().downcast_ref()?;
+            let bitmap = if inp.column().is_const() {
+                bitmap
+            } else {
+                bitmap.slice(rows)
+            };
+            view.bitmap_set(ColumnDataF::new_from_slice(bitmap));
+        }
+        Ok(view)
+    }
 
-macro_rules! binary_types_impl {
-    ($($tr:ty)+) => ($(
-        paste!{
-            struct [<ColumnBinaryInternalOp $tr>]{}
-            impl [<ColumnBinaryInternalOp $tr>]{
-                fn new()->Self{
-                    Self{}
-                }
+    fn hash_in(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        dst: &mut Vec<u64>,
+    ) -> Result<(), ErrorDesc> {
+        let s = ahash::RandomState::with_seeds(
+            2194717786824016851,
+            7391161229587532433,
+            8421638162391593347,
+            13425712476683680973,
+        );
+
+        //The value of each run is hashed once
+        if src.column().is_run_length() {
+            if src_index.is_some() {
+                return self.hash_in(&run_length_decoded::<T>(src)?, src_index, dst);
             }
-            impl ColumnInternalOp for [<ColumnBinaryInternalOp $tr>]
-            {
-                fn len(&self, inp: &ColumnWrapper)->Result<usize, ErrorDesc>{
-                    type T=$tr;
-                    inp.column().downcast_binary_ref::<T>().map(|(_,_,c,_)| c.len())
-                }
-
-                fn truncate(&self, _inp: &mut ColumnWrapper) -> Result<(), ErrorDesc>{
-                    Err("Truncate for binary columns should not be done by an internal operation")?
-                }
-                fn new<'a>(&self, data: Box<dyn Any>)->Result<ColumnData<'a>, ErrorDesc>{
-                    type T=$tr;
-                    let col = data.downcast::<Vec<T>>().map_err(|_| {
-                        format!(
-                            "Downcast failed. Target type is Vec<{}>",
-                          std::any::type_name::<T>()
-                        )
-                    })?;
-                    let col = copy_of_into_boxed_slice(col);
-                    let mut res: Vec<Vec<T>> = col.into();
-                    let res = res.pop().unwrap();
-                    Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(res.as_slice())))
-                }
-
-                fn new_ref<'a,'b>(&self, data: SliceRef<'a>)->Result<ColumnData<'b>, ErrorDesc>
-                where
-                    'a: 'b
-                {
-                    type T=$tr;
-                    let col=data.downcast_ref::<T>()?;
-                    Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(col)))
+            let (values, run_lengths) = src.column().downcast_runs_ref::<T>()?;
+            let mut hashes = Vec::with_capacity(self.len(src)?);
+            values
+                .iter()
+                .zip(run_lengths)
+                .for_each(|(v, l)| hashes.resize(hashes.len() + l, s.hash_one(v)));
+            if !src.bitmap().all_valid() {
+                hashes
+                    .iter_mut()
+                    .zip(src.bitmap().downcast_ref()?)
+                    .for_each(|(h, bitmap)| *h |= (bitmap as u64).wrapping_sub(1));
+            }
+            if dst.is_empty() {
+                *dst = hashes;
+            } else {
+                assert_eq!(hashes.len(), dst.len());
+                dst.iter_mut()
+                    .zip(hashes)
+                    .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+            }
+            return Ok(());
+        }
+
+        let src_data = src.column().downcast_ref::<T>()?;
+        let src_bitmap = src.bitmap();
+
+        if dst.len() == 0 {
+            //We have to do an insert, bitmaps without nulls do not change the hashes and are skipped
+            match (src_index.is_some(), !src_bitmap.all_valid()) {
+                (true, true) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = src_index.iter().map(|i| {
+                        let data = src_data[*i];
+                        let bitmap = src_bitmap[*i];
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish() | (bitmap as u64).wrapping_sub(1)
+                    });
+                    dst.extend(itr);
                 }
-                fn new_mut<'a: 'b, 'b>(&self, data: SliceRefMut<'a>) -> Result<ColumnData<'b>, ErrorDesc>{
-                    type T=$tr;
-                    let col=data.downcast_ref::<T>()?;
-                    Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(col)))
+                (true, false) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let itr = src_index.iter().map(|i| {
+                        let data = src_data[*i];
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish()
+                    });
+                    dst.extend(itr);
                 }
-                fn copy_to<'a>(
-                    &self,
-                    src: &ColumnWrapper<'a>,
-                    dst: &mut ColumnWrapper<'a>,
-                    src_index: &ColumnDataIndex<'a>,
-                ) -> Result<(), ErrorDesc>
-                {
-                    type T=$tr;
-
-                    let bitmap_update_required=src.bitmap().is_some();
-                    let input=vec![InputTypes::Ref(src, src_index)];
-
-
-                    set_2_sized_binary_unroll::<MaybeUninit<T>, T, _,_>(dst, &&*input, &bitmap_update_required, |c1_data,_c1_bool|
-                        MaybeUninit::new(<T as AsBytes>::from_bytes(c1_data))
-                    , |b| *b)
-
-
+                (false, true) => {
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = src_data.iter().zip(src_bitmap).map(|(data, bitmap)| {
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish() | (bitmap as u64).wrapping_sub(1)
+                    });
+                    dst.extend(itr);
                 }
-
-                fn as_string<'a>(
-                    &self,
-                    src: &ColumnWrapper<'a>,
-                    src_index: &ColumnDataIndex<'a>,
-                ) -> Result<Vec<String>, ErrorDesc>
-                {
-                    type T=$tr;
-                    let (datau8, start_pos, len,offset)=src.column().downcast_binary_ref::<T>()?;
-
-                    if src_index.is_some(){
-                        let index=src_index.downcast_ref()?;
-
-                        let v: Vec<String>= index.iter().map(|i|
-                            {
-                                let s=start_pos[*i]-offset;
-                                let e=s+len[*i]-offset;
-                                format!("{}", <T as AsBytes>::from_bytes(&datau8[s..e]))
-                            }).collect();
-                        Ok(v)
-                    } else {
-                        assert_eq!(start_pos.len(), len.len());
-                        let v: Vec<_>=start_pos.iter().zip(len.iter()).map(|(s, l)|
-                        {
-                            let s=s-offset;
-                            let e=s+l;
-                            format!("{}", <T as AsBytes>::from_bytes(&datau8[s..e]))
-                        }).collect();
-                        Ok(v)
-                    }
-
+                (false, false) => {
+                    let itr = src_data.iter().map(|data| {
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish()
+                    });
+                    dst.extend(itr);
                 }
-                fn new_owned_with_capacity(&self, number_of_items: usize, binary_capacity: usize, with_bitmap: bool) -> ColumnWrapper<'static>{
-                    type T=$tr;
-                    let mut c=ColumnWrapper::new_from_columndata (ColumnData::BinaryOwned(OnwedBinaryColumn::new_with_capacity(&[] as &[T], number_of_items,binary_capacity)));
-                    if with_bitmap{
-                        c.bitmap_set(ColumnDataF::new(vec![false; number_of_items]))
+            }
+        } else {
+            //We have to do an update
+
+            //First check if src is a const
+            if src.column().is_const() {
+                //in case of constant, we have to add its hash to the entire hash vector
+                let mut h = s.build_hasher();
+                src_data[0].hash(&mut h);
+                let mut hash_value = h.finish();
+                if src_bitmap.is_some() {
+                    if !src_bitmap.downcast_ref()?[0] {
+                        hash_value = u64::MAX
                     };
-                    c
+                };
+                let hash_value = hash_value;
+                dst.iter_mut().for_each(|h| *h = h.wrapping_add(hash_value));
+            } else {
+                //The source is not a constant value, therefore we have to make sure it has the same length as the hash vector
+
+                match (src_index.is_some(), !src_bitmap.all_valid()) {
+                    (true, true) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        let itr = src_index.iter().map(|i| {
+                            let data = src_data[*i];
+                            let bitmap = src_bitmap[*i];
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish() | (bitmap as u64).wrapping_sub(1)
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (true, false) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        let itr = src_index.iter().map(|i| {
+                            let data = src_data[*i];
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish()
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (false, true) => {
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        assert_eq!(src_data.len(), dst.len());
+                        let itr = src_data.iter().zip(src_bitmap).map(|(data, bitmap)| {
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish() | (bitmap as u64).wrapping_sub(1)
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (false, false) => {
+                        assert_eq!(src_data.len(), dst.len());
+                        let itr = src_data.iter().map(|data| {
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish()
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
                 }
-
-                fn new_uninit(&self, number_of_items: usize, binary_storage: usize, with_bitmap: bool) -> ColumnWrapper<'static>{
-                    type T=$tr;
-                    let mut c=ColumnWrapper::new_from_columndata (ColumnData::BinaryOwned(OnwedBinaryColumn::new_uninit::<T>(number_of_items,binary_storage)));
-                    if with_bitmap{
-                        c.bitmap_set(ColumnDataF::new(vec![false; number_of_items]))
+            }
+        }
+        Ok(())
+    }
+    fn copy_to_buckets_part1(
+        &self,
+        hash: &[Vec<u64>],
+        buckets_mask: u64,
+        src_columns: &[Vec<ColumnWrapper>],
+        src_indexes: &[Vec<ColumnDataIndex>],
+        col_id: usize,
+        index_id: &Option<&usize>,
+        offsets: &VecDeque<usize>,
+        dst: &mut [ColumnWrapper<'static>],
+        is_nullable: bool,
+    ) -> Result<usize, ErrorDesc> {
+        let mut dst_data: Vec<_> = dst
+            .iter_mut()
+            .map(|c| c.column_mut())
+            .map(|c| c.downcast_mut::<MaybeUninit<T>>())
+            .collect::<Result<_, _>>()?;
+        let mut offsets_tmp = offsets.clone();
+        let mut items_written = 0;
+
+        let index_empty = ColumnDataIndex::None;
+
+        src_columns
+            .iter()
+            .zip(src_indexes.iter())
+            .zip(hash.iter())
+            .try_for_each(|((src, src_index), hash)| -> Result<(), ErrorDesc> {
+                let src = src[col_id].column().downcast_decoded::<T>()?;
+                let src_index = match index_id {
+                    Some(i) => &src_index[**i],
+                    None => &index_empty,
+                };
+                items_written += copy_to_buckets_part_uninit(
+                    hash,
+                    buckets_mask,
+                    &src,
+                    src_index,
+                    &mut offsets_tmp,
+                    &mut dst_data,
+                )?;
+                Ok(())
+            })?;
+
+        if is_nullable {
+            let mut offsets_tmp = offsets.clone();
+            let mut dst_bitmap: Vec<_> = dst
+                .iter_mut()
+                .map(|c| c.bitmap_mut().downcast_mut())
+                .collect::<Result<_, _>>()?;
+
+            src_columns
+                .iter()
+                .zip(src_indexes.iter())
+                .zip(hash.iter())
+                .try_for_each(|((src, src_index), hash)| -> Result<(), ErrorDesc> {
+                    let src = src[col_id].bitmap().downcast_ref()?;
+                    let src_index = match index_id {
+                        Some(i) => &src_index[**i],
+                        None => &index_empty,
                     };
-                    c
-                }
-
-                unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc>{
-                    Ok(c)
-                }
+                    items_written += copy_to_buckets_part_bitmap(
+                        hash,
+                        buckets_mask,
+                        src,
+                        src_index,
+                        &mut offsets_tmp,
+                        &mut dst_bitmap,
+                    )?;
+                    Ok(())
+                })?;
+        }
+
+        Ok(items_written)
+    }
 
+    fn copy_to_buckets_part2(&self, _dst: &mut ColumnWrapper<'static>) -> Result<usize, ErrorDesc> {
+        Err("copy_to_buckets_part2 called for a sized type")?
+    }
+    fn copy_to_buckets_part3(
+        &self,
+        _hash: &[Vec<u64>],
+        _buckets_mask: u64,
+        _src_columns: &[Vec<ColumnWrapper>],
+        _src_indexes: &[Vec<ColumnDataIndex>],
+        _col_id: usize,
+        _index_id: &Option<&usize>,
+        _offsets: &VecDeque<usize>,
+        _dst: &mut [ColumnWrapper<'static>],
+    ) -> Result<usize, ErrorDesc> {
+        Err("copy_to_buckets_part2 called for a sized type")?
+    }
 
-                fn hash_in(&self, src: &ColumnWrapper, src_index: &ColumnDataIndex, dst: &mut Vec<u64>)-> Result<(), ErrorDesc>{
-                    type T=$tr;
-                    let (datau8, start_pos, len, offset) =src.column().downcast_binary_ref::<T>()?;
-                    let src_bitmap=src.bitmap();
-
-                    let s=ahash::RandomState::with_seeds(2194717786824016851,7391161229587532433,8421638162391593347, 13425712476683680973);
-
-                    if dst.len()==0{
-                        //We have to do an insert
-                        match (src_index.is_some(), src_bitmap.is_some()){
-                            (true, true)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_index.iter().map(|i| {
-                                    let start=start_pos[*i]-offset;
-                                    let end=start+len[*i];
-                                    let data=&datau8[start..end];
-                                    let bitmap=src_bitmap[*i];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()|(bitmap as u64).wrapping_sub(1)
-                                });
-                                dst.extend(itr);
-                            },
-                            (true, false)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let itr=src_index.iter().map(|i| {
-                                    let start=start_pos[*i]-offset;
-                                    let end=start+len[*i];
-                                    let data=&datau8[start..end];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()
-                                });
-                                dst.extend(itr);
+    fn group_in(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        dst: &mut Vec<usize>,
+        hashmap_buffer: &mut HashMapBuffer,
+        _hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
+    ) -> Result<(), ErrorDesc> {
+        if src.column().is_run_length() {
+            return self.group_in(
+                &run_length_decoded::<T>(src)?,
+                src_index,
+                dst,
+                hashmap_buffer,
+                _hashmap_binary,
+            );
+        }
+        let src_data = src.column().downcast_ref::<T>()?;
+        let src_bitmap = src.bitmap();
+
+        //The hash map gets at most one entry per row
+        let rows = if src_index.is_some() {
+            src_index.downcast_ref()?.len()
+        } else {
+            self.len(src)?
+        };
+        hashmap_buffer.reserve::<(usize, NullableValue<T>), usize>(rows)?;
+        let mut h = hashmap_buffer.pop::<T>();
+
+        if dst.len() == 0 {
+            //We have to do an insert
+            match (src_index.is_some(), src_bitmap.is_some()) {
+                (true, true) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = src_index.iter().enumerate().map(|(i, index)| {
+                        let data = src_data[*index];
+                        let bitmap = src_bitmap[*index];
+
+                        let new_group_id: usize = *h
+                            .entry((
+                                0,
+                                NullableValue {
+                                    value: data,
+                                    bitmap,
+                                },
+                            ))
+                            .or_insert(i);
+                        new_group_id
+                    });
+                    dst.extend(itr);
+                }
+                (true, false) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let itr = src_index.iter().enumerate().map(|(i, index)| {
+                        let data = src_data[*index];
+                        let bitmap = true;
+                        let new_group_id: usize = *h
+                            .entry((
+                                0,
+                                NullableValue {
+                                    value: data,
+                                    bitmap,
+                                },
+                            ))
+                            .or_insert(i);
+                        new_group_id
+                    });
+                    dst.extend(itr);
+                }
+                (false, true) => {
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr =
+                        src_data
+                            .iter()
+                            .zip(src_bitmap)
+                            .enumerate()
+                            .map(|(i, (data, bitmap))| {
+                                let new_group_id: usize = *h
+                                    .entry((
+                                        0,
+                                        NullableValue {
+                                            value: *data,
+                                            bitmap,
+                                        },
+                                    ))
+                                    .or_insert(i);
+                                new_group_id
+                            });
+                    dst.extend(itr);
+                }
+                (false, false) => {
+                    let itr = src_data.iter().enumerate().map(|(i, data)| {
+                        let new_group_id: usize = *h
+                            .entry((
+                                0,
+                                NullableValue {
+                                    value: *data,
+                                    bitmap: true,
+                                },
+                            ))
+                            .or_insert(i);
+                        new_group_id
+                    });
+                    dst.extend(itr);
+                }
+            }
+        } else {
+            //We have to do an update
+
+            //First check if src is a const, do nothing
+            if src.column().is_const() {
+            } else {
+                match (src_index.is_some(), src_bitmap.is_some()) {
+                    (true, true) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(
+                            |(i, (index, current_group_id))| {
+                                let data = src_data[*index];
+                                let bitmap = src_bitmap[*index];
+
+                                let new_group_id: usize = *h
+                                    .entry((
+                                        *current_group_id,
+                                        NullableValue {
+                                            value: data,
+                                            bitmap,
+                                        },
+                                    ))
+                                    .or_insert(i);
+                                *current_group_id = new_group_id;
                             },
-                            (false, true)=>{
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=start_pos.iter().zip(len.iter()).zip(src_bitmap).map(|((start_pos, len), bitmap)| {
-                                    let start=start_pos-offset;
-                                    let end=start+len;
-                                    let data=&datau8[start..end];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()|(*bitmap as u64).wrapping_sub(1)
-                                });
-                                dst.extend(itr);
+                        );
+                    }
+                    (true, false) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(
+                            |(i, (index, current_group_id))| {
+                                let data = src_data[*index];
+                                let bitmap = true;
+                                let new_group_id: usize = *h
+                                    .entry((
+                                        *current_group_id,
+                                        NullableValue {
+                                            value: data,
+                                            bitmap,
+                                        },
+                                    ))
+                                    .or_insert(i);
+                                *current_group_id = new_group_id;
                             },
-                            (false, false)=>{
-                                let itr=start_pos.iter().zip(len.iter()).map(|(start_pos, len)| {
-                                    let start=start_pos-offset;
-                                    let end=start+len;
-                                    let data=&datau8[start..end];
-                                    let mut h = s.build_hasher();
-                                    data.hash(&mut h);
-                                    h.finish()
-                                });
-                                dst.extend(itr);
+                        );
+                    }
+                    (false, true) => {
+                        assert_eq!(src_data.len(), dst.len());
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        src_data
+                            .iter()
+                            .zip(src_bitmap)
+                            .zip(dst.iter_mut())
+                            .enumerate()
+                            .for_each(|(i, ((data, bitmap), current_group_id))| {
+                                let new_group_id: usize = *h
+                                    .entry((
+                                        *current_group_id,
+                                        NullableValue {
+                                            value: *data,
+                                            bitmap,
+                                        },
+                                    ))
+                                    .or_insert(i);
+                                *current_group_id = new_group_id;
+                            });
+                    }
+                    (false, false) => {
+                        assert_eq!(src_data.len(), dst.len());
+                        src_data.iter().zip(dst.iter_mut()).enumerate().for_each(
+                            |(i, (data, current_group_id))| {
+                                let new_group_id: usize = *h
+                                    .entry((
+                                        *current_group_id,
+                                        NullableValue {
+                                            value: *data,
+                                            bitmap: true,
+                                        },
+                                    ))
+                                    .or_insert(i);
+                                *current_group_id = new_group_id;
                             },
-                        }
-                        } else {
-                            //We have to do an update
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 
-                            //First check if src is a const
-                            if src.column().is_const(){
-                                //in case of constant, we have to add its hash to the entire hash vector
+    fn run_length_encode(&self, src: &ColumnWrapper) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        if src.column().is_const() {
+            Err("Const columns cannot be run-length encoded")?
+        }
+        let data = src.column().downcast_decoded::<T>()?;
+        let column = ColumnData::RunLength(RunLengthColumn::encode(data.iter().map(|v| (*v, 1)))?);
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if src.bitmap().is_some() {
+            c.bitmap_set(ColumnDataF::new(src.bitmap().downcast_ref()?.to_bitmap()));
+        }
+        Ok(c)
+    }
+}
 
-                                let start=start_pos[0]-offset;
-                                let end=start+len[0];
-                                let data=&datau8[start..end];
+//Internal column operations of binary types, the items are stored as bytes with their start positions and lengths
+pub struct BinaryColumnInternalOp<T> {
+    name: &'static str,
+    format: BinaryFormat,
+    item: PhantomData<fn() -> T>,
+}
 
-                                let mut h = s.build_hasher();
-                                data.hash(&mut h);
-                                let mut hash_value=h.finish();
-                                if src_bitmap.is_some() {
-                                    if !src_bitmap.downcast_ref()?[0] {hash_value=u64::MAX};
-                                };
-                                let hash_value=hash_value;
-                                dst.iter_mut().for_each(|h| *h=h.wrapping_add(hash_value));
-                            } else {
-                                //The source is not a constant value, therefore we have to make sure it has the same length as the hash vector
-
-
-                                match (src_index.is_some(), src_bitmap.is_some()){
-                                    (true, true)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        let itr=src_index.iter().map(|i| {
-                                            let start=start_pos[*i]-offset;
-                                            let end=start+len[*i];
-                                            let data=&datau8[start..end];
-                                            let bitmap=src_bitmap[*i];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()|(bitmap as u64).wrapping_sub(1)
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (true, false)=>{
-                                        let src_index=src_index.downcast_ref()?;
-                                        assert_eq!(src_index.len(), dst.len());
-                                        let itr=src_index.iter().map(|i| {
-                                            let start=start_pos[*i]-offset;
-                                            let end=start+len[*i];
-                                            let data=&datau8[start..end];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (false, true)=>{
-                                        let src_bitmap=src_bitmap.downcast_ref()?;
-                                        assert_eq!(dst.len(), start_pos.len());
-                                        assert_eq!(dst.len(), len.len());
-                                        let itr=start_pos.iter().zip(len.iter()).zip(src_bitmap).map(|((start_pos, len), bitmap)| {
-                                            let start=start_pos-offset;
-                                            let end=start+len;
-                                            let data=&datau8[start..end];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()|(*bitmap as u64).wrapping_sub(1)
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                    (false, false)=>{
-                                        assert_eq!(dst.len(), start_pos.len());
-                                        assert_eq!(dst.len(), len.len());
-                                        let itr=start_pos.iter().zip(len.iter()).map(|(start_pos, len)| {
-                                            let start=start_pos-offset;
-                                            let end=start+len;
-                                            let data=&datau8[start..end];
-                                            let mut h = s.build_hasher();
-                                            data.hash(&mut h);
-                                            h.finish()
-                                        });
-                                        dst.iter_mut().zip(itr).for_each(|(h, hash_value)| *h=h.wrapping_add(hash_value));
-                                    },
-                                }
-                            }
-                        }
-                        Ok(())
-                }
-                fn copy_to_buckets_part1(
-                    &self,
-                    hash: &[Vec<u64>],
-                    buckets_mask: u64,
-                    src_columns: &[Vec<ColumnWrapper>],
-                    src_indexes: &[Vec<ColumnDataIndex>],
-                    col_id: usize,
-                    index_id: &Option<&usize>,
-                    offsets: &VecDeque<usize>,
-                    dst: &mut [ColumnWrapper<'static>],
-                    is_nullable: bool,
-                ) -> Result<usize, ErrorDesc>
-                {
-                    type T=$tr;
-
-                    let mut dst_data: Vec<_>=dst.iter_mut().map(|c| c.column_mut()).map(|c| c.downcast_binary_mut::<T>().unwrap().2).collect();
-                    let mut offsets_tmp=offsets.clone();
-                    let mut items_written=0;
-
-
-                    let index_empty=ColumnDataIndex::None;
-
-                    src_columns.iter().zip(src_indexes.iter()).zip(hash.iter()).for_each(|((src, src_index), hash)|{
-                        let src=src[col_id].column().downcast_binary_ref::<T>().unwrap().2;
-                        let src_index=match index_id {
-                            Some(i) => &src_index[**i],
-                            None => &index_empty,
-                        };
-                        items_written+=copy_to_buckets_part(hash, buckets_mask, src, src_index, &mut offsets_tmp, &mut dst_data).unwrap();
-                    });
+impl<T: AsBytes + fmt::Display> BinaryColumnInternalOp<T> {
+    pub fn new(name: &'static str) -> Self {
+        Self::with_format(name, format_display::<T>)
+    }
+}
 
-                    if is_nullable {
-                        let mut offsets_tmp=offsets.clone();
-                        let mut dst_bitmap: Vec<_>=dst.iter_mut().map(|c| c.bitmap_mut().downcast_mut().unwrap()).collect();
+impl<T> BinaryColumnInternalOp<T> {
+    //For types without Display, or if decoding the items for printing is not wanted
+    pub fn with_format(name: &'static str, format: BinaryFormat) -> Self {
+        Self {
+            name,
+            format,
+            item: PhantomData,
+        }
+    }
+}
 
-                        src_columns.iter().zip(src_indexes.iter()).zip(hash.iter()).for_each(|((src, src_index), hash)|{
+impl<T: BinaryItem> BinaryColumnInternalOp<T> {
+    //Each value of the dictionary is hashed once, the hashes are equal to the ones of the decoded items
+    fn dictionary_hash_in(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        dst: &mut Vec<u64>,
+    ) -> Result<(), ErrorDesc> {
+        let (codes, values) = src.column().downcast_dictionary_ref::<T>()?;
+        let (datau8, offsets) = values.downcast_binary_ref::<T>()?;
+        let s = binary_hash_state();
+        let value_hash: Vec<u64> = binary_items(datau8, offsets)
+            .map(|v| s.hash_one(v))
+            .collect();
+
+        let src_bitmap = src.bitmap();
+        let src_bitmap = if src_bitmap.is_some() {
+            Some(src_bitmap.downcast_ref()?)
+        } else {
+            None
+        };
+        let hash = |i: usize| match src_bitmap {
+            Some(b) => value_hash[codes[i] as usize] | (b[i] as u64).wrapping_sub(1),
+            None => value_hash[codes[i] as usize],
+        };
+        let hashes: Vec<u64> = if src_index.is_some() {
+            src_index.downcast_ref()?.iter().map(|i| hash(*i)).collect()
+        } else {
+            (0..codes.len()).map(hash).collect()
+        };
+
+        if dst.is_empty() {
+            *dst = hashes;
+        } else {
+            assert_eq!(dst.len(), hashes.len());
+            dst.iter_mut()
+                .zip(hashes)
+                .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+        }
+        Ok(())
+    }
+}
 
-                            let src=src[col_id].bitmap().downcast_ref().unwrap();
-                            let src_index=match index_id {
-                                Some(i) => &src_index[**i],
-                                None => &index_empty,
-                            };
-                            items_written+=copy_to_buckets_part(hash, buckets_mask, src, src_index, &mut offsets_tmp, &mut dst_bitmap).unwrap();
-                        });
-                    }
+impl<T: BinaryItem> ColumnInternalOp for BinaryColumnInternalOp<T> {
+    fn type_name(&self) -> &'static str {
+        self.name
+    }
+    fn len(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        inp.column().data_len::<T>()
+    }
 
-                    Ok(items_written)
+    fn truncate(&self, _inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        Err("Truncate for binary columns should not be done by an internal operation")?
+    }
+    fn new<'a>(&self, data: Box<dyn Any>) -> Result<ColumnData<'a>, ErrorDesc> {
+        let col = data.downcast::<Vec<T>>().map_err(|_| {
+            format!(
+                "Downcast failed. Target type is Vec<{}>",
+                std::any::type_name::<T>()
+            )
+        })?;
+        let col = copy_of_into_boxed_slice(col);
+        let mut res: Vec<Vec<T>> = col.into();
+        let res = res.pop().unwrap();
+        Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(
+            res.as_slice(),
+        )))
+    }
+
+    fn new_ref<'a, 'b>(&self, data: SliceRef<'a>) -> Result<ColumnData<'b>, ErrorDesc>
+    where
+        'a: 'b,
+    {
+        let col = data.downcast_ref::<T>()?;
+        Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(col)))
+    }
+    fn new_mut<'a: 'b, 'b>(&self, data: SliceRefMut<'a>) -> Result<ColumnData<'b>, ErrorDesc> {
+        let col = data.downcast_ref::<T>()?;
+        Ok(ColumnData::BinaryOwned(OnwedBinaryColumn::new(col)))
+    }
+    fn copy_to<'a>(
+        &self,
+        src: &ColumnWrapper<'a>,
+        dst: &mut ColumnWrapper<'a>,
+        src_index: &ColumnDataIndex<'a>,
+    ) -> Result<(), ErrorDesc> {
+        let bitmap_update_required = src.bitmap().is_some();
+        let input = vec![InputTypes::Ref(src, src_index)];
+
+        //Items which cannot be decoded fail the copy, the destination is not initialized then
+        let error = RefCell::new(None);
+        set_2_sized_binary_unroll::<MaybeUninit<T>, T, _, _>(
+            dst,
+            &&*input,
+            &bitmap_update_required,
+            |c1_data, _c1_bool| match T::try_from_bytes(c1_data) {
+                Ok(v) => MaybeUninit::new(v),
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    MaybeUninit::uninit()
                 }
+            },
+            |b| *b,
+        )?;
+        match error.into_inner() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
 
-                fn copy_to_buckets_part2(&self, dst: &mut ColumnWrapper<'static>) -> Result<usize, ErrorDesc>{
-                    type T=$tr;
-                    let  (datau8, start_pos, len, offset)=dst.column_mut().downcast_binary_vec::<T>()?;
-                    let mut cur_start_pos=0;
-                    len.iter().zip(start_pos.iter_mut()).for_each(|(l,s)| {*s=cur_start_pos; cur_start_pos+=l});
-                    *datau8=vec![0; cur_start_pos];
-                    *offset=0;
-                    Ok(cur_start_pos)
+    fn as_string<'a>(
+        &self,
+        src: &ColumnWrapper<'a>,
+        src_index: &ColumnDataIndex<'a>,
+    ) -> Result<Vec<String>, ErrorDesc> {
+        if src.column().is_dictionary() {
+            //Each distinct value is formatted once
+            let (codes, values) = src.column().downcast_dictionary_ref::<T>()?;
+            let (datau8, offsets) = values.downcast_binary_ref::<T>()?;
+            let values: Vec<String> = binary_items(datau8, offsets)
+                .map(|v| (self.format)(v))
+                .collect::<Result<_, _>>()?;
+            return if src_index.is_some() {
+                let index = src_index.downcast_ref()?;
+                Ok(index
+                    .iter()
+                    .map(|i| values[codes[*i] as usize].clone())
+                    .collect())
+            } else {
+                Ok(codes.iter().map(|c| values[*c as usize].clone()).collect())
+            };
+        }
+        let (datau8, offsets) = src.column().downcast_binary_ref::<T>()?;
+
+        if src_index.is_some() {
+            let index = src_index.downcast_ref()?;
+
+            index
+                .iter()
+                .map(|i| (self.format)(binary_item(datau8, offsets, *i)))
+                .collect()
+        } else {
+            binary_items(datau8, offsets)
+                .map(|v| (self.format)(v))
+                .collect()
+        }
+    }
+    fn new_owned_with_capacity(
+        &self,
+        number_of_items: usize,
+        binary_capacity: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let mut c = ColumnWrapper::new_from_columndata(ColumnData::BinaryOwned(
+            OnwedBinaryColumn::new_with_capacity(&[] as &[T], number_of_items, binary_capacity),
+        ));
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, number_of_items)))
+        };
+        c
+    }
 
-                }
-                fn copy_to_buckets_part3(
-                    &self,
-                    hash: &[Vec<u64>],
-                    buckets_mask: u64,
-                    src_columns: &[Vec<ColumnWrapper>],
-                    src_indexes: &[Vec<ColumnDataIndex>],
-                    col_id: usize,
-                    index_id: &Option<&usize>,
-                    offsets: &VecDeque<usize>,
-                    dst: &mut [ColumnWrapper<'static>],
-                ) -> Result<usize, ErrorDesc>{
-                    type T=$tr;
-
-                    let mut dst_data: Vec<_>=dst.iter_mut().map(|c| c.column_mut()).map(|c| c.downcast_binary_mut::<T>().unwrap())
-                    .map(|(src_datau8, src_start_pos,src_len,src_offset)|(src_datau8, &*src_start_pos,&*src_len,*src_offset)).collect();
-                    let mut offsets_tmp=offsets.clone();
-                    let mut bytes_written=0;
-
-                    let index_empty=ColumnDataIndex::None;
-
-                    src_columns.iter().zip(src_indexes.iter()).zip(hash.iter()).for_each(|((src, src_index), hash)|{
-                        let (src_datau8, src_start_pos,src_len,src_offset)=src[col_id].column().downcast_binary_ref::<T>().unwrap();
-                        let src_index=match index_id {
-                            Some(i) => &src_index[**i],
-                            None => &index_empty,
+    fn new_uninit(
+        &self,
+        number_of_items: usize,
+        binary_storage: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let mut c = ColumnWrapper::new_from_columndata(ColumnData::BinaryOwned(
+            OnwedBinaryColumn::new_uninit::<T>(number_of_items, binary_storage),
+        ));
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, number_of_items)))
+        };
+        c
+    }
+
+    unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc> {
+        Ok(c)
+    }
+
+    fn unshare(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        if inp.column().is_shared() {
+            let placeholder = ColumnData::BinaryConst(OnwedBinaryColumn::new::<T>(&[]));
+            let col = match std::mem::replace(inp.column_mut(), placeholder) {
+                ColumnData::BinaryShared(c) => Arc::try_unwrap(c).unwrap_or_else(|c| (*c).clone()),
+                _ => Err("Column expected to be ColumnData::BinaryShared")?,
+            };
+            *inp.column_mut() = ColumnData::BinaryOwned(col);
+        }
+        inp.bitmap_mut().unshare();
+        Ok(())
+    }
+
+    fn concat(&self, src: &[(&ColumnWrapper, usize)]) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let total_len = src.iter().map(|(_, len)| *len).sum();
+        //Columns encoded with the same dictionary keep the encoding
+        if let Some(values) = common_dictionary::<T>(src.iter().map(|(c, _)| *c)) {
+            let mut codes: Vec<u32> = Vec::with_capacity(total_len);
+            src.iter()
+                .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                    let (c_codes, _) = c.column().downcast_dictionary_ref::<T>()?;
+                    if c_codes.len() != *len {
+                        Err(RadixError::LengthMismatch {
+                            expected: *len,
+                            found: c_codes.len(),
+                        })?
+                    }
+                    codes.extend_from_slice(c_codes);
+                    Ok(())
+                })?;
+            let mut c = ColumnWrapper::new_from_columndata(ColumnData::Dictionary(
+                DictionaryColumn::new(codes, values.clone())?,
+            ));
+            if src.iter().any(|(c, _)| c.bitmap().is_some()) {
+                let mut bitmap = Bitmap::with_capacity(total_len);
+                src.iter()
+                    .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                        match c.bitmap().is_some() {
+                            true => bitmap.extend_from_bitmap(c.bitmap().downcast_ref()?),
+                            false => bitmap.resize(bitmap.len() + *len, true),
                         };
+                        Ok(())
+                    })?;
+                c.bitmap_set(ColumnDataF::new(bitmap));
+            }
+            return Ok(c);
+        }
+        let mut data: Vec<T> = Vec::with_capacity(total_len);
+        src.iter()
+            .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                let decoded = c.column().downcast_binary_decoded::<T>()?;
+                let (datau8, offsets) = decoded.parts();
+                let mut values = binary_items(datau8, offsets).map(T::try_from_bytes);
+                if c.column().is_const() {
+                    let value = values.next().unwrap()?;
+                    data.extend(std::iter::repeat(value).take(*len));
+                } else if offsets.len() - 1 == *len {
+                    for value in values {
+                        data.push(value?);
+                    }
+                } else {
+                    Err(RadixError::LengthMismatch {
+                        expected: *len,
+                        found: offsets.len() - 1,
+                    })?
+                }
+                Ok(())
+            })?;
+        let has_bitmap = src.iter().any(|(c, _)| c.bitmap().is_some());
+        let mut bitmap = Bitmap::new();
+        if has_bitmap {
+            src.iter()
+                .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                    match (c.bitmap().is_some(), c.column().is_const()) {
+                        (true, true) => {
+                            let valid = c.bitmap().downcast_ref()?[0];
+                            bitmap.resize(bitmap.len() + *len, valid)
+                        }
+                        (true, false) => bitmap.extend_from_bitmap(c.bitmap().downcast_ref()?),
+                        (false, _) => bitmap.resize(bitmap.len() + *len, true),
+                    };
+                    Ok(())
+                })?;
+        }
+        let mut c = ColumnWrapper::new_from_columndata(ColumnData::BinaryOwned(
+            OnwedBinaryColumn::new(&data),
+        ));
+        if has_bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
-                        bytes_written+=copy_to_buckets_binary_part(hash, buckets_mask, src_datau8,src_start_pos, src_len,src_offset , src_index, &mut offsets_tmp, &mut dst_data).unwrap();
-                    });
-                    Ok(bytes_written)
+    fn sort_index(
+        &self,
+        src: &ColumnWrapper,
+        order: &mut [usize],
+        ascending: bool,
+    ) -> Result<(), ErrorDesc> {
+        if src.column().is_const() {
+            return Ok(());
+        }
+        let bitmap = src.bitmap();
+        let bitmap = if bitmap.is_some() {
+            Some(bitmap.downcast_ref()?)
+        } else {
+            None
+        };
+        if src.column().is_dictionary() {
+            //The codes are sorted by the rank of their values, equal values have the same rank
+            let (codes, values) = src.column().downcast_dictionary_ref::<T>()?;
+            let (datau8, offsets) = values.downcast_binary_ref::<T>()?;
+            let value = |i: usize| binary_item(datau8, offsets, i);
+            let mut sorted: Vec<usize> = (0..values.len()).collect();
+            sorted.sort_by(|a, b| value(*a).cmp(&value(*b)));
+            let mut rank = vec![0u32; values.len()];
+            for w in sorted.windows(2) {
+                rank[w[1]] = rank[w[0]] + (value(w[0]) != value(w[1])) as u32;
+            }
+            sort_nullable(order, bitmap, ascending, |i| rank[codes[i] as usize]);
+        } else {
+            let (datau8, offsets) = src.column().downcast_binary_ref::<T>()?;
+            sort_nullable(order, bitmap, ascending, |i| {
+                binary_item(datau8, offsets, i)
+            });
+        }
+        Ok(())
+    }
+
+    fn take(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let bitmap = src.bitmap();
+        let bitmap = if bitmap.is_some() {
+            Some(bitmap.downcast_ref()?)
+        } else {
+            None
+        };
+        let (column, bitmap) = match src.column() {
+            ColumnData::BinaryConst(c) => (
+                ColumnData::BinaryConst(c.clone()),
+                bitmap.map(|b| b.to_bitmap()),
+            ),
+            //The codes are copied, the values stay shared
+            c if c.is_dictionary() => {
+                let (codes, values) = c.downcast_dictionary_ref::<T>()?;
+                if src_index.is_some() {
+                    let index = src_index.downcast_ref()?;
+                    (
+                        ColumnData::Dictionary(DictionaryColumn::new(
+                            index.iter().map(|i| codes[*i]).collect(),
+                            values.clone(),
+                        )?),
+                        bitmap.map(|b| index.iter().map(|i| b[*i]).collect()),
+                    )
+                } else {
+                    (
+                        ColumnData::Dictionary(DictionaryColumn::new(
+                            codes.to_vec(),
+                            values.clone(),
+                        )?),
+                        bitmap.map(|b| b.to_bitmap()),
+                    )
+                }
+            }
+            c => {
+                let (datau8, offsets) = c.downcast_binary_ref::<T>()?;
+                if src_index.is_some() {
+                    let index = src_index.downcast_ref()?;
+                    let data: Vec<T> = index
+                        .iter()
+                        .map(|i| T::try_from_bytes(binary_item(datau8, offsets, *i)))
+                        .collect::<Result<_, _>>()?;
+                    (
+                        ColumnData::BinaryOwned(OnwedBinaryColumn::new(&data)),
+                        bitmap.map(|b| index.iter().map(|i| b[*i]).collect()),
+                    )
+                } else {
+                    //The bytes are copied at once, the offsets are moved to start at 0
+                    let first = offsets.first();
+                    let mut rebased = BinaryOffsets::with_capacity(offsets.len() - 1);
+                    offsets.iter().skip(1).for_each(|o| rebased.push(o - first));
+                    (
+                        ColumnData::BinaryOwned(OnwedBinaryColumn::new_from_offsets::<T>(
+                            datau8[..offsets.last() - first].to_vec(),
+                            rebased,
+                        )?),
+                        bitmap.map(|b| b.to_bitmap()),
+                    )
                 }
+            }
+        };
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
-                fn group_in(
-                    &self,
-                    src: &ColumnWrapper,
-                    src_index: &ColumnDataIndex,
-                    dst: &mut Vec<usize>,
-                    _hashmap_buffer: &mut HashMapBuffer,
-                    hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
-                ) -> Result<(), ErrorDesc>{
-                    type T=$tr;
-                    let (datau8, start_pos,len,offset)=src.column().downcast_binary_ref::<T>().unwrap();
-                    let src_bitmap=src.bitmap();
-                    hashmap_binary.clear();
-
-
-                    if dst.len()==0{
-                        //We have to do an insert
-                        match (src_index.is_some(), src_bitmap.is_some()){
-                            (true, true)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=src_index.iter().enumerate().map(|(i, index)| {
-                                    let start=start_pos[*index]-offset;
-                                    let end=start+len[*index];
-                                    let data=&datau8[start..end];
-                                    let bitmap=src_bitmap[*index];
-                                    let nullableslice=NullableValue{
-                                        value: data,
-                                        bitmap
-                                    };
-
-                                    let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                    //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                    //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                    //        having to drop hashmap_binary.
-                                    let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                    let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                    *new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                            (true, false)=>{
-                                let src_index=src_index.downcast_ref()?;
-                                let itr=src_index.iter().enumerate().map(|(i, index)| {
-                                    let start=start_pos[*index]-offset;
-                                    let end=start+len[*index];
-                                    let data=&datau8[start..end];
-                                    let nullableslice=NullableValue{
-                                        value: data,
-                                        bitmap: true,
-                                    };
-                                    let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                    //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                    //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                    //        having to drop hashmap_binary.
-                                    let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                    let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                    *new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, true)=>{
-                                let src_bitmap=src_bitmap.downcast_ref()?;
-                                let itr=start_pos.iter().zip(len.iter()).zip(src_bitmap).enumerate().map(|(i,((start_pos, len), bitmap))| {
-                                    let start=start_pos-offset;
-                                    let end=start+len;
-                                    let data=&datau8[start..end];
-                                    let nullableslice=NullableValue{
-                                        value: data,
-                                        bitmap: *bitmap,
-                                    };
-                                    let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                    //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                    //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                    //        having to drop hashmap_binary.
-                                    let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                    let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                    *new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                            (false, false)=>{
-                                let itr=start_pos.iter().zip(len.iter()).enumerate().map(|(i,(start_pos, len))| {
-                                    let start=start_pos-offset;
-                                    let end=start+len;
-                                    let data=&datau8[start..end];
-                                    let nullableslice=NullableValue{
-                                        value: data,
-                                        bitmap: true,
-                                    };
-                                    let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                    //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                    //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                    //        having to drop hashmap_binary.
-                                    let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                    let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                    *new_group_id
-                                });
-                                dst.extend(itr);
-                            },
-                        }
-                    } else {
-                        //We have to do an update
-
-
-                        //First check if src is a const
-                        if src.column().is_const(){
-                            //in case of constant, then we have nothing to do
-                        } else {
-
-                            match (src_index.is_some(), src_bitmap.is_some()){
-                                (true, true)=>{
-                                    let src_index=src_index.downcast_ref()?;
-                                    let src_bitmap=src_bitmap.downcast_ref()?;
-                                    src_index.iter().zip(dst.iter_mut()).enumerate().for_each(|(i, (index, current_group_id))| {
-                                        let start=start_pos[*index]-offset;
-                                        let end=start+len[*index];
-                                        let data=&datau8[start..end];
-                                        let bitmap=src_bitmap[*index];
-                                        let nullableslice=NullableValue{
-                                            value: data,
-                                            bitmap
-                                        };
-                                        let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                        //        having to drop hashmap_binary.
-                                        let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                        let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                        *current_group_id=*new_group_id;
-                                    });
+    fn allocated_bytes(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        let data = match inp.column() {
+            c @ ColumnData::BinaryOwned(_) | c @ ColumnData::BinaryConst(_) => {
+                let (datau8, offsets) = c.downcast_binary_ref::<T>()?;
+                datau8.len() + offsets.size_in_bytes()
+            }
+            //The values are shared by the columns encoded with the same dictionary
+            c @ ColumnData::Dictionary(_) => {
+                std::mem::size_of_val(c.downcast_dictionary_ref::<T>()?.0)
+            }
+            _ => 0,
+        };
+        Ok(data + bitmap_allocated_bytes(inp.bitmap()))
+    }
 
-                                },
-                                (true, false)=>{
-                                    let src_index=src_index.downcast_ref()?;
-                                    src_index.iter().zip(dst.iter_mut()).enumerate().for_each(|(i, (index, current_group_id))| {
-                                        let start=start_pos[*index]-offset;
-                                        let end=start+len[*index];
-                                        let data=&datau8[start..end];
-                                        let nullableslice=NullableValue{
-                                            value: data,
-                                            bitmap: true,
-                                        };
-                                        let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
+    //The lengths of the items are written before their bytes
+    fn write_to(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc> {
+        let c = self.take(src, src_index)?;
+        let decoded = c.column().downcast_binary_decoded::<T>()?;
+        let (datau8, offsets) = decoded.parts();
+        let bitmap = if c.bitmap().is_some() {
+            Some(c.bitmap().downcast_ref()?)
+        } else {
+            None
+        };
+        let is_const = matches!(c.column(), ColumnData::BinaryConst(_));
+        write_column_header(out, is_const, offsets.len() - 1, bitmap)?;
+        for len in offsets.item_lens() {
+            out.write_all(&(len as u64).to_le_bytes())?;
+        }
+        out.write_all(&datau8[..offsets.last() - offsets.first()])?;
+        Ok(())
+    }
 
-                                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                        //        having to drop hashmap_binary.
-                                        let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                        let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                        *current_group_id=*new_group_id;
-                                    });
+    fn read_from(&self, inp: &mut dyn Read) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let (is_const, len, bitmap) = read_column_header(inp)?;
+        let mut lens = Vec::with_capacity(len);
+        for _ in 0..len {
+            lens.push(read_u64(inp)? as usize);
+        }
+        let mut data: Vec<T> = Vec::with_capacity(len);
+        let mut bytes = vec![];
+        for len in lens.into_iter() {
+            bytes.resize(len, 0);
+            inp.read_exact(&mut bytes)?;
+            data.push(<T as AsBytes>::try_from_bytes(&bytes)?);
+        }
+        let column = OnwedBinaryColumn::new(&data);
+        let column = if is_const {
+            ColumnData::BinaryConst(column)
+        } else {
+            ColumnData::BinaryOwned(column)
+        };
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
 
-                                },
-                                (false, true)=>{
-                                    let src_bitmap=src_bitmap.downcast_ref()?;
-                                    start_pos.iter().zip(len.iter()).zip(src_bitmap).zip(dst.iter_mut()).enumerate().for_each(|(i,(((start_pos, len), bitmap), current_group_id))| {
-                                        let start=start_pos-offset;
-                                        let end=start+len;
-                                        let data=&datau8[start..end];
-                                        let nullableslice=NullableValue{
-                                            value: data,
-                                            bitmap: *bitmap,
-                                        };
-                                        let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
-
-                                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                        //        having to drop hashmap_binary.
-                                        let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                        let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                        *current_group_id=*new_group_id;
-                                    });
+    fn view<'a>(&self, inp: &'a ColumnWrapper) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        let column = match inp.column() {
+            ColumnData::BinaryConst(c) => ColumnData::BinaryConst(c.clone()),
+            c if c.is_dictionary() => {
+                let (codes, values) = c.downcast_dictionary_ref::<T>()?;
+                ColumnData::DictionarySlice(DictionarySlice::new(codes, values))
+            }
+            c => {
+                let (datau8, offsets) = c.downcast_binary_ref::<T>()?;
+                ColumnData::BinarySlice(BinarySlice::new::<T>(datau8, offsets)?)
+            }
+        };
+        let mut view = ColumnWrapper::new_from_columndata(column);
+        if inp.bitmap().is_some() {
+            view.bitmap_set(ColumnDataF::new_from_slice(inp.bitmap().downcast_ref()?));
+        }
+        Ok(view)
+    }
 
-                                },
-                                (false, false)=>{
-                                    start_pos.iter().zip(len.iter()).zip(dst.iter_mut()).enumerate().for_each(|(i,((start_pos, len), current_group_id))| {
-                                        let start=start_pos-offset;
-                                        let end=start+len;
-                                        let data=&datau8[start..end];
-                                        let nullableslice=NullableValue{
-                                            value: data,
-                                            bitmap: true,
-                                        };
-                                        let val: (usize, NullableValue<&[u8]>)=(0,nullableslice);
+    fn view_range<'a>(
+        &self,
+        inp: &'a ColumnWrapper,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        let (column, is_const) = match inp.column() {
+            ColumnData::BinaryConst(c) => (ColumnData::BinaryConst(c.clone()), true),
+            c if c.is_dictionary() => {
+                let (codes, values) = c.downcast_dictionary_ref::<T>()?;
+                (
+                    ColumnData::DictionarySlice(DictionarySlice::new(&codes[rows.clone()], values)),
+                    false,
+                )
+            }
+            c => {
+                //The slice shares the offset ending the last row with the rest of the column,
+                //its bytes start at the first offset of the rows
+                let (datau8, offsets) = c.downcast_binary_ref::<T>()?;
+                let rows_offsets = offsets.slice(rows.clone());
+                let datau8 = &datau8[rows_offsets.first() - offsets.first()..];
+                (
+                    ColumnData::BinarySlice(BinarySlice::new::<T>(datau8, rows_offsets)?),
+                    false,
+                )
+            }
+        };
+        let mut view = ColumnWrapper::new_from_columndata(column);
+        if inp.bitmap().is_some() {
+            let bitmap = inp.bitmap().downcast_ref()?;
+            let bitmap = if is_const { bitmap } else { bitmap.slice(rows) };
+            view.bitmap_set(ColumnDataF::new_from_slice(bitmap));
+        }
+        Ok(view)
+    }
 
-                                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
-                                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
-                                        //        having to drop hashmap_binary.
-                                        let val: (usize, NullableValue<&[u8]>)=unsafe{std::mem::transmute(val)};
-                                        let new_group_id=hashmap_binary.entry(val).or_insert(i);
-                                        *current_group_id=*new_group_id;
-                                    });
+    fn hash_in(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        dst: &mut Vec<u64>,
+    ) -> Result<(), ErrorDesc> {
+        if src.column().is_dictionary() {
+            return self.dictionary_hash_in(src, src_index, dst);
+        }
+        let (datau8, offsets) = src.column().downcast_binary_ref::<T>()?;
+        let src_bitmap = src.bitmap();
+
+        let s = binary_hash_state();
+
+        if dst.len() == 0 {
+            //We have to do an insert, bitmaps without nulls do not change the hashes and are skipped
+            match (src_index.is_some(), !src_bitmap.all_valid()) {
+                (true, true) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = src_index.iter().map(|i| {
+                        let data = binary_item(datau8, offsets, *i);
+                        let bitmap = src_bitmap[*i];
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish() | (bitmap as u64).wrapping_sub(1)
+                    });
+                    dst.extend(itr);
+                }
+                (true, false) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let itr = src_index.iter().map(|i| {
+                        let data = binary_item(datau8, offsets, *i);
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish()
+                    });
+                    dst.extend(itr);
+                }
+                (false, true) => {
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr =
+                        binary_items(datau8, offsets)
+                            .zip(src_bitmap)
+                            .map(|(data, bitmap)| {
+                                let mut h = s.build_hasher();
+                                data.hash(&mut h);
+                                h.finish() | (bitmap as u64).wrapping_sub(1)
+                            });
+                    dst.extend(itr);
+                }
+                (false, false) => {
+                    let itr = binary_items(datau8, offsets).map(|data| {
+                        let mut h = s.build_hasher();
+                        data.hash(&mut h);
+                        h.finish()
+                    });
+                    dst.extend(itr);
+                }
+            }
+        } else {
+            //We have to do an update
 
-                                },
-                            }
-                        }
-                    }
+            //First check if src is a const
+            if src.column().is_const() {
+                //in case of constant, we have to add its hash to the entire hash vector
 
+                let data = binary_item(datau8, offsets, 0);
 
+                let mut h = s.build_hasher();
+                data.hash(&mut h);
+                let mut hash_value = h.finish();
+                if src_bitmap.is_some() {
+                    if !src_bitmap.downcast_ref()?[0] {
+                        hash_value = u64::MAX
+                    };
+                };
+                let hash_value = hash_value;
+                dst.iter_mut().for_each(|h| *h = h.wrapping_add(hash_value));
+            } else {
+                //The source is not a constant value, therefore we have to make sure it has the same length as the hash vector
+
+                match (src_index.is_some(), !src_bitmap.all_valid()) {
+                    (true, true) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        let itr = src_index.iter().map(|i| {
+                            let data = binary_item(datau8, offsets, *i);
+                            let bitmap = src_bitmap[*i];
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish() | (bitmap as u64).wrapping_sub(1)
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (true, false) => {
+                        let src_index = src_index.downcast_ref()?;
+                        assert_eq!(src_index.len(), dst.len());
+                        let itr = src_index.iter().map(|i| {
+                            let data = binary_item(datau8, offsets, *i);
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish()
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (false, true) => {
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        assert_eq!(dst.len(), offsets.len() - 1);
+                        let itr =
+                            binary_items(datau8, offsets)
+                                .zip(src_bitmap)
+                                .map(|(data, bitmap)| {
+                                    let mut h = s.build_hasher();
+                                    data.hash(&mut h);
+                                    h.finish() | (bitmap as u64).wrapping_sub(1)
+                                });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                    (false, false) => {
+                        assert_eq!(dst.len(), offsets.len() - 1);
+                        let itr = binary_items(datau8, offsets).map(|data| {
+                            let mut h = s.build_hasher();
+                            data.hash(&mut h);
+                            h.finish()
+                        });
+                        dst.iter_mut()
+                            .zip(itr)
+                            .for_each(|(h, hash_value)| *h = h.wrapping_add(hash_value));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    fn copy_to_buckets_part1(
+        &self,
+        hash: &[Vec<u64>],
+        buckets_mask: u64,
+        src_columns: &[Vec<ColumnWrapper>],
+        src_indexes: &[Vec<ColumnDataIndex>],
+        col_id: usize,
+        index_id: &Option<&usize>,
+        offsets: &VecDeque<usize>,
+        dst: &mut [ColumnWrapper<'static>],
+        is_nullable: bool,
+    ) -> Result<usize, ErrorDesc> {
+        let mut dst_data: Vec<_> = dst
+            .iter_mut()
+            .map(|c| c.column_mut())
+            .map(|c| c.downcast_binary_vec::<T>().map(|c| &mut c.1.widen()[1..]))
+            .collect::<Result<_, _>>()?;
+        let mut offsets_tmp = offsets.clone();
+        let mut items_written = 0;
+
+        let index_empty = ColumnDataIndex::None;
+
+        src_columns
+            .iter()
+            .zip(src_indexes.iter())
+            .zip(hash.iter())
+            .try_for_each(|((src, src_index), hash)| -> Result<(), ErrorDesc> {
+                //The length of item k is written at offsets[k + 1], part2 sums them up
+                let decoded = src[col_id].column().downcast_binary_decoded::<T>()?;
+                let src: Vec<usize> = decoded.parts().1.item_lens().collect();
+                let src_index = match index_id {
+                    Some(i) => &src_index[**i],
+                    None => &index_empty,
+                };
+                items_written += copy_to_buckets_part(
+                    hash,
+                    buckets_mask,
+                    &src,
+                    src_index,
+                    &mut offsets_tmp,
+                    &mut dst_data,
+                )?;
+                Ok(())
+            })?;
+
+        if is_nullable {
+            let mut offsets_tmp = offsets.clone();
+            let mut dst_bitmap: Vec<_> = dst
+                .iter_mut()
+                .map(|c| c.bitmap_mut().downcast_mut())
+                .collect::<Result<_, _>>()?;
+
+            src_columns
+                .iter()
+                .zip(src_indexes.iter())
+                .zip(hash.iter())
+                .try_for_each(|((src, src_index), hash)| -> Result<(), ErrorDesc> {
+                    let src = src[col_id].bitmap().downcast_ref()?;
+                    let src_index = match index_id {
+                        Some(i) => &src_index[**i],
+                        None => &index_empty,
+                    };
+                    items_written += copy_to_buckets_part_bitmap(
+                        hash,
+                        buckets_mask,
+                        src,
+                        src_index,
+                        &mut offsets_tmp,
+                        &mut dst_bitmap,
+                    )?;
+                    Ok(())
+                })?;
+        }
+
+        Ok(items_written)
+    }
 
+    fn copy_to_buckets_part2(&self, dst: &mut ColumnWrapper<'static>) -> Result<usize, ErrorDesc> {
+        let (datau8, offsets) = dst.column_mut().downcast_binary_vec::<T>()?;
+        {
+            let offsets = offsets.widen();
+            offsets[0] = 0;
+            (1..offsets.len()).for_each(|k| offsets[k] += offsets[k - 1]);
+        }
+        offsets.narrow();
+        let binary_len = offsets.as_offsets_ref().last();
+        *datau8 = vec![0; binary_len];
+        Ok(binary_len)
+    }
+    fn copy_to_buckets_part3(
+        &self,
+        hash: &[Vec<u64>],
+        buckets_mask: u64,
+        src_columns: &[Vec<ColumnWrapper>],
+        src_indexes: &[Vec<ColumnDataIndex>],
+        col_id: usize,
+        index_id: &Option<&usize>,
+        offsets: &VecDeque<usize>,
+        dst: &mut [ColumnWrapper<'static>],
+    ) -> Result<usize, ErrorDesc> {
+        let mut dst_data: Vec<_> = dst
+            .iter_mut()
+            .map(|c| c.column_mut())
+            .map(|c| c.downcast_binary_mut::<T>())
+            .collect::<Result<_, _>>()?;
+        let mut offsets_tmp = offsets.clone();
+        let mut bytes_written = 0;
+
+        let index_empty = ColumnDataIndex::None;
+
+        src_columns
+            .iter()
+            .zip(src_indexes.iter())
+            .zip(hash.iter())
+            .try_for_each(|((src, src_index), hash)| -> Result<(), ErrorDesc> {
+                let decoded = src[col_id].column().downcast_binary_decoded::<T>()?;
+                let (src_datau8, src_offsets) = decoded.parts();
+                let src_index = match index_id {
+                    Some(i) => &src_index[**i],
+                    None => &index_empty,
+                };
+
+                bytes_written += copy_to_buckets_binary_part(
+                    hash,
+                    buckets_mask,
+                    src_datau8,
+                    src_offsets,
+                    src_index,
+                    &mut offsets_tmp,
+                    &mut dst_data,
+                )?;
+                Ok(())
+            })?;
+        Ok(bytes_written)
+    }
 
+    fn group_in(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+        dst: &mut Vec<usize>,
+        hashmap_buffer: &mut HashMapBuffer,
+        hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
+    ) -> Result<(), ErrorDesc> {
+        //Rows with the same value have the same code, the codes are grouped like a u32 column
+        if src.column().is_dictionary() {
+            return SizedColumnInternalOp::<u32>::new("u32").group_in(
+                &codes_view::<T>(src)?,
+                src_index,
+                dst,
+                hashmap_buffer,
+                hashmap_binary,
+            );
+        }
+        let (datau8, offsets) = src.column().downcast_binary_ref::<T>()?;
+        let src_bitmap = src.bitmap();
+        hashmap_binary.clear();
+        //The binary hash map is accounted in the hash map buffer, it gets at most one entry per row
+        let rows = if src_index.is_some() {
+            src_index.downcast_ref()?.len()
+        } else {
+            offsets.len() - 1
+        };
+        hashmap_buffer.reserve::<(usize, NullableValue<&[u8]>), usize>(rows)?;
+
+        if dst.len() == 0 {
+            //We have to do an insert
+            match (src_index.is_some(), src_bitmap.is_some()) {
+                (true, true) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = src_index.iter().enumerate().map(|(i, index)| {
+                        let data = binary_item(datau8, offsets, *index);
+                        let bitmap = src_bitmap[*index];
+                        let nullableslice = NullableValue {
+                            value: data,
+                            bitmap,
+                        };
 
-                        Ok(())
+                        let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
 
+                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                        //        having to drop hashmap_binary.
+                        let val: (usize, NullableValue<&[u8]>) =
+                            unsafe { std::mem::transmute(val) };
+                        let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                        *new_group_id
+                    });
+                    dst.extend(itr);
+                }
+                (true, false) => {
+                    let src_index = src_index.downcast_ref()?;
+                    let itr = src_index.iter().enumerate().map(|(i, index)| {
+                        let data = binary_item(datau8, offsets, *index);
+                        let nullableslice = NullableValue {
+                            value: data,
+                            bitmap: true,
+                        };
+                        let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                        //        having to drop hashmap_binary.
+                        let val: (usize, NullableValue<&[u8]>) =
+                            unsafe { std::mem::transmute(val) };
+                        let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                        *new_group_id
+                    });
+                    dst.extend(itr);
+                }
+                (false, true) => {
+                    let src_bitmap = src_bitmap.downcast_ref()?;
+                    let itr = binary_items(datau8, offsets)
+                        .zip(src_bitmap)
+                        .enumerate()
+                        .map(|(i, (data, bitmap))| {
+                            let nullableslice = NullableValue {
+                                value: data,
+                                bitmap,
+                            };
+                            let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                            //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                            //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                            //        having to drop hashmap_binary.
+                            let val: (usize, NullableValue<&[u8]>) =
+                                unsafe { std::mem::transmute(val) };
+                            let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                            *new_group_id
+                        });
+                    dst.extend(itr);
+                }
+                (false, false) => {
+                    let itr = binary_items(datau8, offsets).enumerate().map(|(i, data)| {
+                        let nullableslice = NullableValue {
+                            value: data,
+                            bitmap: true,
+                        };
+                        let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                        //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                        //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                        //        having to drop hashmap_binary.
+                        let val: (usize, NullableValue<&[u8]>) =
+                            unsafe { std::mem::transmute(val) };
+                        let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                        *new_group_id
+                    });
+                    dst.extend(itr);
+                }
+            }
+        } else {
+            //We have to do an update
+
+            //First check if src is a const
+            if src.column().is_const() {
+                //in case of constant, then we have nothing to do
+            } else {
+                match (src_index.is_some(), src_bitmap.is_some()) {
+                    (true, true) => {
+                        let src_index = src_index.downcast_ref()?;
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(
+                            |(i, (index, current_group_id))| {
+                                let data = binary_item(datau8, offsets, *index);
+                                let bitmap = src_bitmap[*index];
+                                let nullableslice = NullableValue {
+                                    value: data,
+                                    bitmap,
+                                };
+                                let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                                //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                                //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                                //        having to drop hashmap_binary.
+                                let val: (usize, NullableValue<&[u8]>) =
+                                    unsafe { std::mem::transmute(val) };
+                                let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                                *current_group_id = *new_group_id;
+                            },
+                        );
+                    }
+                    (true, false) => {
+                        let src_index = src_index.downcast_ref()?;
+                        src_index.iter().zip(dst.iter_mut()).enumerate().for_each(
+                            |(i, (index, current_group_id))| {
+                                let data = binary_item(datau8, offsets, *index);
+                                let nullableslice = NullableValue {
+                                    value: data,
+                                    bitmap: true,
+                                };
+                                let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                                //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                                //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                                //        having to drop hashmap_binary.
+                                let val: (usize, NullableValue<&[u8]>) =
+                                    unsafe { std::mem::transmute(val) };
+                                let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                                *current_group_id = *new_group_id;
+                            },
+                        );
+                    }
+                    (false, true) => {
+                        let src_bitmap = src_bitmap.downcast_ref()?;
+                        binary_items(datau8, offsets)
+                            .zip(src_bitmap)
+                            .zip(dst.iter_mut())
+                            .enumerate()
+                            .for_each(|(i, ((data, bitmap), current_group_id))| {
+                                let nullableslice = NullableValue {
+                                    value: data,
+                                    bitmap,
+                                };
+                                let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                                //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                                //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                                //        having to drop hashmap_binary.
+                                let val: (usize, NullableValue<&[u8]>) =
+                                    unsafe { std::mem::transmute(val) };
+                                let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                                *current_group_id = *new_group_id;
+                            });
+                    }
+                    (false, false) => {
+                        binary_items(datau8, offsets)
+                            .zip(dst.iter_mut())
+                            .enumerate()
+                            .for_each(|(i, (data, current_group_id))| {
+                                let nullableslice = NullableValue {
+                                    value: data,
+                                    bitmap: true,
+                                };
+                                let val: (usize, NullableValue<&[u8]>) = (0, nullableslice);
+
+                                //SAFETY: hashmap_binary would outlive the slice to src, however src is guaranteed to be live until the hashmap is cleared.
+                                //        Once the hashmap is cleared, there should be no references to src, and therefore no reason why we need to insist on
+                                //        having to drop hashmap_binary.
+                                let val: (usize, NullableValue<&[u8]>) =
+                                    unsafe { std::mem::transmute(val) };
+                                let new_group_id = hashmap_binary.entry(val).or_insert(i);
+                                *current_group_id = *new_group_id;
+                            });
+                    }
                 }
-
             }
+        }
 
+        Ok(())
+    }
 
+    fn dictionary_encode(
+        &self,
+        src: &[&ColumnWrapper],
+    ) -> Result<Vec<ColumnWrapper<'static>>, ErrorDesc> {
+        let mut positions: HashMap<&[u8], u32> = HashMap::new();
+        let mut values: Vec<T> = vec![];
+        let mut codes: Vec<Vec<u32>> = Vec::with_capacity(src.len());
+        //The decoded items are borrowed by positions until every column is encoded
+        let decoded: Vec<DecodedBinary> = src
+            .iter()
+            .map(|c| c.column().downcast_binary_decoded::<T>())
+            .collect::<Result<_, _>>()?;
+        for (c, decoded) in src.iter().zip(decoded.iter()) {
+            if c.column().is_const() {
+                Err("Const columns cannot be dictionary-encoded")?
+            }
+            let (datau8, offsets) = decoded.parts();
+            let mut c_codes = Vec::with_capacity(offsets.len() - 1);
+            for item in binary_items(datau8, offsets) {
+                let code = match positions.get(item) {
+                    Some(code) => *code,
+                    None => {
+                        //u32::MAX is kept free for values missing from the dictionary
+                        if values.len() >= u32::MAX as usize {
+                            Err("Dictionary encoding supports at most 2^32-1 distinct values")?
+                        }
+                        let code = values.len() as u32;
+                        positions.insert(item, code);
+                        values.push(T::try_from_bytes(item)?);
+                        code
+                    }
+                };
+                c_codes.push(code);
+            }
+            codes.push(c_codes);
+        }
+        let values = DictionaryValues::new(OnwedBinaryColumn::new(&values))?;
+        src.iter()
+            .zip(codes)
+            .map(|(c, codes)| {
+                let mut encoded = ColumnWrapper::new_from_columndata(ColumnData::Dictionary(
+                    DictionaryColumn::new(codes, values.clone())?,
+                ));
+                if c.bitmap().is_some() {
+                    encoded.bitmap_set(ColumnDataF::new(c.bitmap().downcast_ref()?.to_bitmap()));
+                }
+                Ok(encoded)
+            })
+            .collect()
     }
-    )+)
 }
 /*
 fn copy_to_buckets_binary_part(
     hash: &Vec<u64>,
     buckets_mask: u64,
     src_datau8: &[u8],
-    src_start_pos: &[usize],
-    src_len: &[usize],
-    src_offset: &usize,
+    src_offsets: BinaryOffsetsRef,
     src_index: &ColumnDataIndex,
     offsets: &mut VecDeque<usize>,
-    dst: &mut [(&mut [u8], &[usize], &[usize], usize)],
+    dst: &mut [(&mut [u8], BinaryOffsetsRef)],
 )
 */
 
-sized_types_impl! {
-u64 u32 u16 u8 bool usize
-}
-
-binary_types_impl! {
-String
-}
-
 //{ usize u8 u16 u32 u64 u128 isize i8 i16 i32 i64 i128 f32 f64 }
 
 //binary_operation_impl! { (u64,u8) (u64,u16) (u64,u32) (u64,u64) }
 
+//Logical types of the column item types which have internal column operations
+pub fn load_type_dict(types: &mut Vec<LogicalType>) {
+    types.extend(vec![
+        LogicalType::sized::<u64>("u64").numeric().orderable(),
+        LogicalType::sized::<u32>("u32").numeric().orderable(),
+        LogicalType::sized::<u16>("u16").numeric().orderable(),
+        LogicalType::sized::<u8>("u8").numeric().orderable(),
+        LogicalType::sized::<bool>("bool").orderable(),
+        LogicalType::sized::<usize>("usize").numeric().orderable(),
+        LogicalType::binary::<String>("String").orderable(),
+        LogicalType::binary::<Blob>("BLOB"),
+        LogicalType::sized::<Date>("DATE").orderable(),
+        LogicalType::sized::<Time>("TIME").orderable(),
+        LogicalType::sized::<Timestamp>("TIMESTAMP").orderable(),
+        LogicalType::sized::<Interval>("INTERVAL"),
+        LogicalType::sized::<Decimal>("DECIMAL")
+            .numeric()
+            .orderable(),
+    ]);
+}
+
 pub fn load_columninternal_dict(part_dict: &mut ColumnInternalDictionary) {
     //dict.insert(s, columnadd_onwedcolumnvecu64_vecu64);7
     sized_types_load! {part_dict;
-        u64 u32 u16 u8 bool usize
+        u64 u32 u16 u8 bool usize Date Time Timestamp Interval Decimal
     };
 
     binary_types_load! {part_dict;
         String
     };
+
+    part_dict.insert(
+        sig![OP; Blob],
+        Box::new(BinaryColumnInternalOp::<Blob>::with_format(
+            "BLOB", format_hex,
+        )),
+    );
 }
@@ -1,13 +1,48 @@
-use std::collections::HashMap;
+use std::{any::TypeId, collections::HashMap};
 
-use crate::{load_columninternal_dict, ColumnInternalOp, Operation, Signature};
+use radix_column::{
+    register_type_name, ColumnDataIndex, ColumnWrapper, ErrorDesc, LogicalType, RadixError,
+    TypeName,
+};
+
+use crate::{
+    load_columninternal_dict, load_type_dict, BinaryColumnInternalOp, BinaryItem, ColumnInternalOp,
+    ColumnOperations, InputTypes, Operation, RunLengthOperationFn, Signature,
+    SizedColumnInternalOp, SizedCompare, SizedFormat, SizedItem,
+};
 
 pub type ColumnInternalDictionary = HashMap<Signature, Box<dyn ColumnInternalOp + Sync>>;
 pub type OpDictionary = HashMap<Signature, Operation>;
+pub type RunLengthOpDictionary = HashMap<Signature, RunLengthOperationFn>;
+pub type TypeDictionary = HashMap<TypeId, LogicalType>;
+pub type AggregateDictionary = HashMap<String, AggregateFunction>;
+
+//Aggregates run in two phases, the partial results of every batch are combined by the merge operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateFunction {
+    //Aggregate applied to the partial results, e.g. partial counts are summed up
+    pub merge_op: String,
+    //Operation which turns the merged results into the output of the aggregate, None if they are the output
+    //The merged results of types for which the operation is not defined are the output as well
+    pub finalize_op: Option<String>,
+}
+
+impl AggregateFunction {
+    pub fn new(merge_op: &str) -> Self {
+        Self {
+            merge_op: merge_op.to_string(),
+            finalize_op: None,
+        }
+    }
+}
 pub struct Dictionary {
     pub columninternal: ColumnInternalDictionary,
     pub op: OpDictionary,
+    //Kernels of operations for run-length encoded inputs, see call_op
+    pub op_run_length: RunLengthOpDictionary,
     pub op_is_assign: HashMap<String, bool>,
+    pub types: TypeDictionary,
+    pub aggregates: AggregateDictionary,
 }
 
 impl Dictionary {
@@ -21,10 +56,18 @@ impl Dictionary {
         crate::c_eq::load_op_dict(&mut op);
         crate::c_gt::load_op_dict(&mut op);
         crate::c_gteq::load_op_dict(&mut op);
+        crate::c_in::load_op_dict(&mut op);
         crate::c_lt::load_op_dict(&mut op);
         crate::c_lteq::load_op_dict(&mut op);
         crate::c_sum::load_op_dict(&mut op);
         crate::c_count::load_op_dict(&mut op);
+        crate::c_max::load_op_dict(&mut op);
+        crate::c_min::load_op_dict(&mut op);
+        crate::c_length::load_op_dict(&mut op);
+        crate::c_datetime::load_op_dict(&mut op);
+        crate::c_decimal::load_op_dict(&mut op, Default::default());
+        let mut op_run_length: RunLengthOpDictionary = HashMap::new();
+        crate::c_runlength::load_op_dict(&mut op_run_length);
 
         op.iter().for_each(|(signature, op)| {
             let val = op_is_assign
@@ -38,10 +81,216 @@ impl Dictionary {
             }
         });
 
-        Self {
+        let mut dict = Self {
             columninternal,
             op,
+            op_run_length,
             op_is_assign,
+            types: HashMap::new(),
+            aggregates: HashMap::new(),
+        };
+        let mut types = vec![];
+        load_type_dict(&mut types);
+        types.into_iter().for_each(|t| dict.register_type(t));
+        dict.aggregates
+            .insert("SUM".to_string(), AggregateFunction::new("SUM"));
+        dict.aggregates
+            .insert("COUNT".to_string(), AggregateFunction::new("SUM"));
+        dict.aggregates
+            .insert("MAX".to_string(), AggregateFunction::new("MAX"));
+        dict.aggregates
+            .insert("MIN".to_string(), AggregateFunction::new("MIN"));
+        crate::c_decimal::load_aggregates(&mut dict);
+        dict
+    }
+
+    //Runs the operation of the signature, run-length encoded columns are passed to its kernel in op_run_length
+    //If there is no kernel or it cannot handle the input, they are decoded for the operation
+    pub fn call_op(
+        &self,
+        signature: &Signature,
+        c1: &mut ColumnWrapper,
+        c1_index: &ColumnDataIndex,
+        input: &[InputTypes],
+    ) -> Result<(), ErrorDesc> {
+        let op = self
+            .op
+            .get(signature)
+            .ok_or_else(|| signature.not_found(self))?;
+        let is_run_length = |input: &InputTypes| match input {
+            InputTypes::Ref(c, _) => c.column().is_run_length(),
+            InputTypes::Owned(c, _) => c.column().is_run_length(),
+        };
+        if !c1.column().is_run_length() && !input.iter().any(is_run_length) {
+            return (op.f)(c1, c1_index, input);
+        }
+        if let Some(kernel) = self.op_run_length.get(signature) {
+            if kernel(c1, c1_index, input)? {
+                return Ok(());
+            }
+        }
+
+        if c1.column().is_run_length() {
+            *c1 = c1.decode(self)?;
+        }
+        let decoded = input
+            .iter()
+            .map(|input| match input {
+                InputTypes::Ref(c, _) if c.column().is_run_length() => c.decode(self).map(Some),
+                InputTypes::Owned(c, _) if c.column().is_run_length() => c.decode(self).map(Some),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>, ErrorDesc>>()?;
+        let input: Vec<InputTypes> = input
+            .iter()
+            .zip(decoded.iter())
+            .map(|(input, decoded)| match (input, decoded) {
+                (InputTypes::Ref(_, i), Some(c)) => InputTypes::Ref(c, i),
+                (InputTypes::Owned(_, i), Some(c)) => InputTypes::Ref(c, i),
+                (InputTypes::Ref(c, i), None) => InputTypes::Ref(c, i),
+                (InputTypes::Owned(c, i), None) => InputTypes::Ref(c, i),
+            })
+            .collect();
+        (op.f)(c1, c1_index, &input)
+    }
+
+    pub fn aggregate(&self, name: &str) -> Option<&AggregateFunction> {
+        self.aggregates.get(name)
+    }
+
+    pub fn is_aggregate(&self, name: &str) -> bool {
+        self.aggregates.contains_key(name)
+    }
+
+    //Finalize operation of an aggregate for merged results of the given type, None if they are the output
+    pub fn aggregate_finalize(&self, name: &str, partial_type_id: TypeId) -> Option<&Operation> {
+        let finalize_op = self.aggregate(name)?.finalize_op.as_ref()?;
+        self.op
+            .get(&Signature::new(finalize_op, vec![partial_type_id]))
+    }
+
+    //Type of the output of an aggregate whose partial results are of the given type
+    pub fn aggregate_output_type(
+        &self,
+        name: &str,
+        partial_type_id: TypeId,
+    ) -> Result<TypeId, RadixError> {
+        Ok(self
+            .aggregate_finalize(name, partial_type_id)
+            .map_or(partial_type_id, |op| op.output_type_id))
+    }
+
+    //The name of the type is also used for errors and signatures printed without the dictionary
+    pub fn register_type(&mut self, logical_type: LogicalType) {
+        register_type_name(logical_type.item_type_id, logical_type.name);
+        self.types.insert(logical_type.item_type_id, logical_type);
+    }
+
+    //Registers a custom sized type, its columns are stored, hashed, grouped, sorted, repartitioned and printed like the built-in types
+    pub fn register_sized_type<T: SizedItem + Ord + std::fmt::Display>(
+        &mut self,
+        logical_type: LogicalType,
+    ) -> Result<(), RadixError> {
+        let op = SizedColumnInternalOp::<T>::new(logical_type.name);
+        self.insert_sized_type(logical_type, op)
+    }
+
+    //Like register_sized_type for types without Display or Ord, the items are printed with format
+    //Columns can only be sorted if compare is given, it is required for orderable logical types
+    pub fn register_sized_type_with_format<T: SizedItem>(
+        &mut self,
+        logical_type: LogicalType,
+        format: SizedFormat<T>,
+        compare: Option<SizedCompare<T>>,
+    ) -> Result<(), RadixError> {
+        let op = SizedColumnInternalOp::<T>::with_format(logical_type.name, format);
+        let op = match (compare, logical_type.is_orderable) {
+            (Some(compare), _) => op.with_compare(compare),
+            (None, false) => op,
+            (None, true) => Err(format!(
+                "The logical type {} is orderable, but no order of its items is given",
+                logical_type.name
+            ))?,
+        };
+        self.insert_sized_type(logical_type, op)
+    }
+
+    fn insert_sized_type<T: SizedItem>(
+        &mut self,
+        logical_type: LogicalType,
+        op: SizedColumnInternalOp<T>,
+    ) -> Result<(), RadixError> {
+        self.check_new_type::<T>(&logical_type, false)?;
+        self.columninternal.insert(
+            Signature::new("", vec![logical_type.item_type_id]),
+            Box::new(op),
+        );
+        self.register_type(logical_type);
+        Ok(())
+    }
+
+    //Registers a custom binary type, the items are stored as the bytes given by AsBytes
+    pub fn register_binary_type<T: BinaryItem + std::fmt::Display>(
+        &mut self,
+        logical_type: LogicalType,
+    ) -> Result<(), RadixError> {
+        self.check_new_type::<T>(&logical_type, true)?;
+        self.columninternal.insert(
+            Signature::new("", vec![logical_type.item_type_id]),
+            Box::new(BinaryColumnInternalOp::<T>::new(logical_type.name)),
+        );
+        self.register_type(logical_type);
+        Ok(())
+    }
+
+    fn check_new_type<T: 'static>(
+        &self,
+        logical_type: &LogicalType,
+        is_binary: bool,
+    ) -> Result<(), RadixError> {
+        if logical_type.item_type_id != TypeId::of::<T>() {
+            Err(self.type_mismatch(TypeId::of::<T>(), logical_type.item_type_id))?
+        }
+        if logical_type.is_binary != is_binary {
+            Err(format!(
+                "The logical type {} does not match the storage of the column operations",
+                logical_type.name
+            ))?
+        }
+        if self
+            .columninternal
+            .contains_key(&Signature::new("", vec![logical_type.item_type_id]))
+        {
+            Err(format!(
+                "The type {} is already registered",
+                logical_type.name
+            ))?
+        }
+        Ok(())
+    }
+
+    pub fn logical_type(&self, item_type_id: TypeId) -> Option<&LogicalType> {
+        self.types.get(&item_type_id)
+    }
+
+    //Names are matched case insensitive, like type names in SQL
+    pub fn logical_type_by_name(&self, name: &str) -> Option<&LogicalType> {
+        self.types
+            .values()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    //Name of a column item type, None if the type is not registered
+    pub fn type_name(&self, item_type_id: TypeId) -> Option<&'static str> {
+        self.logical_type(item_type_id).map(|t| t.name)
+    }
+
+    pub fn type_mismatch(&self, expected: TypeId, found: TypeId) -> RadixError {
+        RadixError::TypeMismatch {
+            expected,
+            expected_name: TypeName(expected).to_string(),
+            found,
+            found_name: TypeName(found).to_string(),
         }
     }
 }
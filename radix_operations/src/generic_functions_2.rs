@@ -1,4 +1,4 @@
-use crate::InputTypes;
+use crate::{validity::Validity, InputTypes};
 use radix_column::*;
 enum FType<'a, O1, O2, FBool, F1, F2>
 where
@@ -40,13 +40,14 @@ fn f_1_sized_sized<'a, 'i, T1, T2, U2, FBool, F1, F2>(
     c1_index: &ColumnDataIndex,
     bitmap_update_required: &bool,
     c2: U2,
+    validity: Validity<'i>,
     f: FType<'a, T1, T2, FBool, F1, F2>,
 ) -> Result<(), ErrorDesc>
 where
     'a: 'i,
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
-    U2: IntoIterator<Item = (&'i T2, &'i bool)>,
+    U2: IntoIterator<Item = (&'i T2, bool)>,
     U2::IntoIter: ExactSizeIterator,
     U2: Clone,
     FBool: Fn(&bool) -> bool,
@@ -55,6 +56,7 @@ where
 {
     let (c1, c1_bitmap) = c1.get_inner_mut();
     let c2_iter = c2.clone().into_iter();
+    let len = c2_iter.len();
 
     let is_set_operation = match f {
         FType::Set(_) => true,
@@ -78,16 +80,19 @@ where
         };
         match bitmap_update_required {
             true => {
-                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, c2_bitmap)));
+                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, &c2_bitmap)));
                 let c1_bitmap = c1_bitmap.downcast_vec()?;
                 assert_eq!(c1_bitmap.len(), 0);
-                c1_bitmap.extend(
-                    c2.into_iter()
-                        .map(|(_c2_value, c2_bitmap)| f_bitmap(c2_bitmap)),
-                );
+                match validity.map(len, &f_bitmap) {
+                    Some(bitmap) => *c1_bitmap = bitmap,
+                    None => c1_bitmap.extend(
+                        c2.into_iter()
+                            .map(|(_c2_value, c2_bitmap)| f_bitmap(&c2_bitmap)),
+                    ),
+                }
             }
             false => {
-                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, c2_bitmap)))
+                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, &c2_bitmap)))
             }
         }
     } else {
@@ -111,77 +116,91 @@ where
         }
 
         match f {
-            FType::Set((f_data, f_bitmap)) => match (c1_index.is_some(), bitmap_update_required) {
-                (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_index.downcast_ref()?.iter().zip(c2).for_each(
+            FType::Set((f_data, f_bitmap)) => {
+                match (c1_index.is_some(), bitmap_update_required) {
+                    (true, true) => {
+                        let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                        c1_index.downcast_ref()?.iter().zip(c2).for_each(
+                            |(i, (c2_value, c2_bitmap))| {
+                                c1_data[*i] = f_data(c2_value, &c2_bitmap);
+                                c1_bitmap.set(*i, f_bitmap(&c2_bitmap));
+                            },
+                        )
+                    }
+                    (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
                         |(i, (c2_value, c2_bitmap))| {
-                            c1_data[*i] = f_data(c2_value, c2_bitmap);
-                            c1_bitmap[*i] = f_bitmap(c2_bitmap);
+                            c1_data[*i] = f_data(c2_value, &c2_bitmap);
                         },
-                    )
-                }
-                (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
-                    |(i, (c2_value, c2_bitmap))| {
-                        c1_data[*i] = f_data(c2_value, c2_bitmap);
-                    },
-                ),
-                (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .for_each(|((c1_value, c1_bitmap), (c2_value, c2_bitmap))| {
-                            *c1_value = f_data(c2_value, c2_bitmap);
-                            *c1_bitmap = f_bitmap(c2_bitmap);
-                        })
-                }
-                (false, false) => {
-                    c1_data
-                        .iter_mut()
-                        .zip(c2)
-                        .for_each(|(c1_value, (c2_value, c2_bitmap))| {
-                            *c1_value = f_data(c2_value, c2_bitmap);
-                        })
+                    ),
+                    (false, true) => {
+                        let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                        c1_data.iter_mut().zip(c2_iter).for_each(
+                            |(c1_value, (c2_value, c2_bitmap))| {
+                                *c1_value = f_data(c2_value, &c2_bitmap);
+                            },
+                        );
+                        match validity.map(len, &f_bitmap) {
+                            Some(bitmap) => c1_bitmap.copy_from(0, bitmap.as_bitmap_ref()),
+                            None => c2.into_iter().enumerate().for_each(
+                                |(i, (_c2_value, c2_bitmap))| {
+                                    c1_bitmap.set(i, f_bitmap(&c2_bitmap));
+                                },
+                            ),
+                        }
+                    }
+                    (false, false) => {
+                        c1_data
+                            .iter_mut()
+                            .zip(c2)
+                            .for_each(|(c1_value, (c2_value, c2_bitmap))| {
+                                *c1_value = f_data(c2_value, &c2_bitmap);
+                            })
+                    }
                 }
-            },
+            }
 
             FType::Update(f) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).for_each(
                         |(i, (c2_value, c2_bitmap))| {
+                            let mut c1_valid = c1_bitmap.get(*i);
                             f(
                                 c1_data.get_mut(*i).unwrap(),
-                                c1_bitmap.get_mut(*i).unwrap(),
+                                &mut c1_valid,
                                 c2_value,
-                                c2_bitmap,
+                                &c2_bitmap,
                             );
+                            c1_bitmap.set(*i, c1_valid);
                         },
                     )
                 }
                 (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
                     |(i, (c2_value, c2_bitmap))| {
-                        f(c1_data.get_mut(*i).unwrap(), &mut true, c2_value, c2_bitmap);
+                        f(
+                            c1_data.get_mut(*i).unwrap(),
+                            &mut true,
+                            c2_value,
+                            &c2_bitmap,
+                        );
                     },
                 ),
                 (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .for_each(|((c1_value, c1_bitmap), (c2_value, c2_bitmap))| {
-                            f(c1_value, c1_bitmap, c2_value, c2_bitmap);
-                        })
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2).enumerate().for_each(
+                        |(i, (c1_value, (c2_value, c2_bitmap)))| {
+                            let mut c1_valid = c1_bitmap.get(i);
+                            f(c1_value, &mut c1_valid, c2_value, &c2_bitmap);
+                            c1_bitmap.set(i, c1_valid);
+                        },
+                    )
                 }
                 (false, false) => {
                     c1_data
                         .iter_mut()
                         .zip(c2)
                         .for_each(|(c1_value, (c2_value, c2_bitmap))| {
-                            f(c1_value, &mut true, c2_value, c2_bitmap);
+                            f(c1_value, &mut true, c2_value, &c2_bitmap);
                         })
                 }
             },
@@ -211,61 +230,71 @@ where
     F2: Fn(&mut T1, &mut bool, &T2, &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
     let c_data = c.downcast_ref::<T2>()?;
 
-    let c_const_val = || {
-        (0..*len)
-            .into_iter()
-            .map(|_| (&c_data[0], &c_bitmap.downcast_ref().unwrap()[0]))
-    };
-    let c_const_true = || (0..*len).into_iter().map(|_| (&c_data[0], &true));
-    let c_index_bitmap = || {
+    let c_const = |v: bool| (0..*len).map(move |_| (&c_data[0], v));
+    let c_index_const = |v: bool| {
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &c_bitmap.downcast_ref().unwrap()[*i]))
+            .map(move |i| (&c_data[*i], v))
     };
-    let c_index_nobitmap = || {
+    let c_index_bitmap = || {
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &true))
+            .map(move |i| (&c_data[*i], c_bitmap.get(*i)))
     };
-    let c_noindex_bitmap = || {
-        c_data
-            .iter()
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|(v, b)| (v, b))
-    };
-    let c_noindex_nobitmap = || c_data.iter().map(|v| (v, &true));
-
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => f_1_sized_sized(c1, c1_index, bitmap_update_required, c_const_val(), f),
-            false => f_1_sized_sized(c1, c1_index, bitmap_update_required, c_const_true(), f),
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => {
-                f_1_sized_sized(c1, c1_index, bitmap_update_required, c_index_bitmap(), f)
-            }
-            (true, false) => {
-                f_1_sized_sized(c1, c1_index, bitmap_update_required, c_index_nobitmap(), f)
-            }
-            (false, true) => {
-                f_1_sized_sized(c1, c1_index, bitmap_update_required, c_noindex_bitmap(), f)
-            }
-            (false, false) => f_1_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_nobitmap(),
-                f,
-            ),
-        }
+    let c_noindex_const = |v: bool| c_data.iter().map(move |value| (value, v));
+    let c_noindex_bitmap = || c_data.iter().zip(c_bitmap.downcast_ref().unwrap().iter());
+
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => f_1_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_const(v),
+            validity,
+            f,
+        ),
+        (Validity::Const(v), true) => f_1_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_const(v),
+            validity,
+            f,
+        ),
+        (Validity::Const(v), false) => f_1_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_const(v),
+            validity,
+            f,
+        ),
+        (_, true) => f_1_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_bitmap(),
+            validity,
+            f,
+        ),
+        (_, false) => f_1_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_bitmap(),
+            validity,
+            f,
+        ),
     }
 }
 
@@ -302,7 +331,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
@@ -376,13 +405,14 @@ fn f_1_sized_binary<'a, 'i, T1, T2, U2, FBool, F1, F2>(
     c1_index: &ColumnDataIndex,
     bitmap_update_required: &bool,
     c2: U2,
+    validity: Validity<'i>,
     f: FType<'a, T1, [u8], FBool, F1, F2>,
 ) -> Result<(), ErrorDesc>
 where
     'a: 'i,
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
-    U2: IntoIterator<Item = (&'i [u8], &'i bool)>,
+    U2: IntoIterator<Item = (&'i [u8], bool)>,
     U2::IntoIter: ExactSizeIterator,
     U2: Clone,
     FBool: Fn(&bool) -> bool,
@@ -391,6 +421,7 @@ where
 {
     let (c1, c1_bitmap) = c1.get_inner_mut();
     let c2_iter = c2.clone().into_iter();
+    let len = c2_iter.len();
 
     let is_set_operation = match f {
         FType::Set(_) => true,
@@ -405,9 +436,7 @@ where
         // Index should be empty
 
         let c1_data: &mut Vec<T1> = c1.downcast_vec::<T1>()?;
-        let c1_bitmap = c1_bitmap.downcast_vec()?;
 
-        assert_eq!(c1_bitmap.len(), 0);
         assert_eq!(c1_index.len(), None);
 
         let (f_data, f_bitmap): (F1, FBool) = match f {
@@ -416,14 +445,19 @@ where
         };
         match bitmap_update_required {
             true => {
-                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, c2_bitmap)));
-                c1_bitmap.extend(
-                    c2.into_iter()
-                        .map(|(_c2_value, c2_bitmap)| f_bitmap(c2_bitmap)),
-                );
+                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, &c2_bitmap)));
+                let c1_bitmap = c1_bitmap.downcast_vec()?;
+                assert_eq!(c1_bitmap.len(), 0);
+                match validity.map(len, &f_bitmap) {
+                    Some(bitmap) => *c1_bitmap = bitmap,
+                    None => c1_bitmap.extend(
+                        c2.into_iter()
+                            .map(|(_c2_value, c2_bitmap)| f_bitmap(&c2_bitmap)),
+                    ),
+                }
             }
             false => {
-                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, c2_bitmap)))
+                c1_data.extend(c2_iter.map(|(c2_value, c2_bitmap)| f_data(c2_value, &c2_bitmap)))
             }
         }
     } else {
@@ -433,6 +467,7 @@ where
 
         let c1_data = c1.downcast_mut::<T1>()?;
         //let c1_bitmap = c1_bitmap.downcast_mut()?;
+
         if *bitmap_update_required {
             assert_eq!(c1_bitmap.len(), Some(c1_data.len()));
         } else {
@@ -446,72 +481,91 @@ where
         }
 
         match f {
-            FType::Set((f_data, f_bitmap)) => match (c1_index.is_some(), bitmap_update_required) {
+            FType::Set((f_data, f_bitmap)) => {
+                match (c1_index.is_some(), bitmap_update_required) {
+                    (true, true) => {
+                        let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                        c1_index.downcast_ref()?.iter().zip(c2).for_each(
+                            |(i, (c2_value, c2_bitmap))| {
+                                c1_data[*i] = f_data(c2_value, &c2_bitmap);
+                                c1_bitmap.set(*i, f_bitmap(&c2_bitmap));
+                            },
+                        )
+                    }
+                    (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
+                        |(i, (c2_value, c2_bitmap))| {
+                            c1_data[*i] = f_data(c2_value, &c2_bitmap);
+                        },
+                    ),
+                    (false, true) => {
+                        let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                        c1_data.iter_mut().zip(c2_iter).for_each(
+                            |(c1_value, (c2_value, c2_bitmap))| {
+                                *c1_value = f_data(c2_value, &c2_bitmap);
+                            },
+                        );
+                        match validity.map(len, &f_bitmap) {
+                            Some(bitmap) => c1_bitmap.copy_from(0, bitmap.as_bitmap_ref()),
+                            None => c2.into_iter().enumerate().for_each(
+                                |(i, (_c2_value, c2_bitmap))| {
+                                    c1_bitmap.set(i, f_bitmap(&c2_bitmap));
+                                },
+                            ),
+                        }
+                    }
+                    (false, false) => {
+                        c1_data
+                            .iter_mut()
+                            .zip(c2)
+                            .for_each(|(c1_value, (c2_value, c2_bitmap))| {
+                                *c1_value = f_data(c2_value, &c2_bitmap);
+                            })
+                    }
+                }
+            }
+
+            FType::Update(f) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).for_each(
                         |(i, (c2_value, c2_bitmap))| {
-                            c1_data[*i] = f_data(c2_value, c2_bitmap);
-                            c1_bitmap[*i] = f_bitmap(c2_bitmap);
+                            let mut c1_valid = c1_bitmap.get(*i);
+                            f(
+                                c1_data.get_mut(*i).unwrap(),
+                                &mut c1_valid,
+                                c2_value,
+                                &c2_bitmap,
+                            );
+                            c1_bitmap.set(*i, c1_valid);
                         },
                     )
                 }
                 (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
                     |(i, (c2_value, c2_bitmap))| {
-                        c1_data[*i] = f_data(c2_value, c2_bitmap);
-                    },
-                ),
-                (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .for_each(|((c1_value, c1_bitmap), (c2_value, c2_bitmap))| {
-                            *c1_value = f_data(c2_value, c2_bitmap);
-                            *c1_bitmap = f_bitmap(c2_bitmap);
-                        })
-                }
-                (false, false) => {
-                    c1_data
-                        .iter_mut()
-                        .zip(c2)
-                        .for_each(|(c1_value, (c2_value, c2_bitmap))| {
-                            *c1_value = f_data(c2_value, c2_bitmap);
-                        })
-                }
-            },
-
-            FType::Update(f) => match (c1_index.is_some(), bitmap_update_required) {
-                (true, true) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
-                    |(i, (c2_value, c2_bitmap))| {
-                        let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                         f(
                             c1_data.get_mut(*i).unwrap(),
-                            c1_bitmap.get_mut(*i).unwrap(),
+                            &mut true,
                             c2_value,
-                            c2_bitmap,
+                            &c2_bitmap,
                         );
                     },
                 ),
-                (true, false) => c1_index.downcast_ref()?.iter().zip(c2).for_each(
-                    |(i, (c2_value, c2_bitmap))| {
-                        f(c1_data.get_mut(*i).unwrap(), &mut true, c2_value, c2_bitmap);
-                    },
-                ),
-                (false, true) => c1_data
-                    .iter_mut()
-                    .zip(c1_bitmap.downcast_mut().unwrap().iter_mut())
-                    .zip(c2)
-                    .for_each(|((c1_value, c1_bitmap), (c2_value, c2_bitmap))| {
-                        f(c1_value, c1_bitmap, c2_value, c2_bitmap);
-                    }),
+                (false, true) => {
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2).enumerate().for_each(
+                        |(i, (c1_value, (c2_value, c2_bitmap)))| {
+                            let mut c1_valid = c1_bitmap.get(i);
+                            f(c1_value, &mut c1_valid, c2_value, &c2_bitmap);
+                            c1_bitmap.set(i, c1_valid);
+                        },
+                    )
+                }
                 (false, false) => {
                     c1_data
                         .iter_mut()
                         .zip(c2)
                         .for_each(|(c1_value, (c2_value, c2_bitmap))| {
-                            f(c1_value, &mut true, c2_value, c2_bitmap);
+                            f(c1_value, &mut true, c2_value, &c2_bitmap);
                         })
                 }
             },
@@ -523,7 +577,7 @@ where
 
 ////////////////////////////////////////////////////////////////////////////
 
-fn f_2_sized_binary<'a, 'i, T1, T2, FBool, F1, F2>(
+fn f_2_sized_binary<'a, T1, T2, FBool, F1, F2>(
     c1: &'a mut ColumnWrapper,
     c1_index: &ColumnDataIndex,
     bitmap_update_required: &bool,
@@ -533,7 +587,6 @@ fn f_2_sized_binary<'a, 'i, T1, T2, FBool, F1, F2>(
     len: &usize,
 ) -> Result<(), ErrorDesc>
 where
-    'a: 'i,
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     FBool: Fn(&bool) -> bool,
@@ -541,103 +594,80 @@ where
     F2: Fn(&mut T1, &mut bool, &[u8], &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
-    let (c_datau8, c_start_pos, c_len, c_offset) = c.downcast_binary_ref::<T2>()?;
-
-    let c_const_val = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &c_bitmap.downcast_ref().unwrap()[0],
-            )
-        })
-    };
-    let c_const_true = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &true,
-            )
-        })
+    let decoded = c.downcast_binary_decoded::<T2>()?;
+    let (c_datau8, c_offsets) = decoded.parts();
+    let value = |i: usize| binary_item(c_datau8, c_offsets, i);
+
+    let c_const = |v: bool| (0..*len).map(move |_| (value(0), v));
+    let c_index_const = |v: bool| {
+        c_index
+            .downcast_ref()
+            .unwrap()
+            .iter()
+            .map(move |i| (value(*i), v))
     };
     let c_index_bitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &c_bitmap.downcast_ref().unwrap()[*i],
-            )
-        })
-    };
-    let c_index_nobitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &true,
-            )
-        })
-    };
-    let c_noindex_bitmap = || {
-        c_start_pos
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
+        c_index
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|((s, l), b)| (&c_datau8[s - c_offset..s - c_offset + l], b))
+            .map(move |i| (value(*i), c_bitmap.get(*i)))
     };
-    let c_noindex_nobitmap = || {
-        c_start_pos
+    let c_noindex_const = |v: bool| (0..c_offsets.len() - 1).map(move |i| (value(i), v));
+    let c_noindex_bitmap = || {
+        c_bitmap
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .map(|(s, l)| (&c_datau8[s - c_offset..s - c_offset + l], &true))
+            .enumerate()
+            .map(move |(i, b)| (value(i), b))
     };
 
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_const_val(),
-                f,
-            ),
-            false => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_const_true(),
-                f,
-            ),
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_bitmap(),
-                f,
-            ),
-            (true, false) => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_nobitmap(),
-                f,
-            ),
-            (false, true) => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_bitmap(),
-                f,
-            ),
-            (false, false) => f_1_sized_binary::<T1, T2, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_nobitmap(),
-                f,
-            ),
-        }
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => f_1_sized_binary::<T1, T2, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_const(v),
+            validity,
+            f,
+        ),
+        (Validity::Const(v), true) => f_1_sized_binary::<T1, T2, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_const(v),
+            validity,
+            f,
+        ),
+        (Validity::Const(v), false) => f_1_sized_binary::<T1, T2, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_const(v),
+            validity,
+            f,
+        ),
+        (_, true) => f_1_sized_binary::<T1, T2, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_bitmap(),
+            validity,
+            f,
+        ),
+        (_, false) => f_1_sized_binary::<T1, T2, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_bitmap(),
+            validity,
+            f,
+        ),
     }
 }
 
@@ -674,7 +704,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
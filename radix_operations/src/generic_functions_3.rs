@@ -1,4 +1,4 @@
-use crate::InputTypes;
+use crate::{validity::Validity, InputTypes};
 use radix_column::*;
 enum FType<'a, O1, O2, O3, FBool, F1, F2>
 where
@@ -43,6 +43,7 @@ fn f_1_sized_sized_sized<'a, 'i, T1, T2, T3, U2, U3, FBool, F1, F2>(
     bitmap_update_required: &bool,
     c2: U2,
     c3: U3,
+    validity: (Validity<'i>, Validity<'i>),
     f: FType<'a, T1, T2, T3, FBool, F1, F2>,
 ) -> Result<(), ErrorDesc>
 where
@@ -50,10 +51,10 @@ where
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     T3: 'static + Send + Sync,
-    U2: IntoIterator<Item = (&'i T2, &'i bool)>,
+    U2: IntoIterator<Item = (&'i T2, bool)>,
     U2::IntoIter: ExactSizeIterator,
     U2: Clone,
-    U3: IntoIterator<Item = (&'i T3, &'i bool)>,
+    U3: IntoIterator<Item = (&'i T3, bool)>,
     U3::IntoIter: ExactSizeIterator,
     U3: Clone,
     FBool: Fn(&bool, &bool) -> bool,
@@ -65,6 +66,7 @@ where
     let c3_iter = c3.clone().into_iter();
 
     assert_eq!(c2_iter.len(), c3_iter.len());
+    let len = c2_iter.len();
 
     let is_set_operation = match f {
         FType::Set(_) => true,
@@ -90,20 +92,23 @@ where
             true => {
                 c1_data.extend(c2_iter.zip(c3_iter).map(
                     |((c2_value, c2_bitmap), (c3_value, c3_bitmap))| {
-                        f_data(c2_value, c2_bitmap, c3_value, c3_bitmap)
+                        f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap)
                     },
                 ));
                 let c1_bitmap = c1_bitmap.downcast_vec()?;
                 assert_eq!(c1_bitmap.len(), 0);
-                c1_bitmap.extend(c2.into_iter().zip(c3.into_iter()).map(
-                    |((_c2_value, c2_bitmap), (_c3_value, c3_bitmap))| {
-                        f_bitmap(c2_bitmap, c3_bitmap)
-                    },
-                ));
+                match validity.0.zip_map(validity.1, len, &f_bitmap) {
+                    Some(bitmap) => *c1_bitmap = bitmap,
+                    None => c1_bitmap.extend(c2.into_iter().zip(c3.into_iter()).map(
+                        |((_c2_value, c2_bitmap), (_c3_value, c3_bitmap))| {
+                            f_bitmap(&c2_bitmap, &c3_bitmap)
+                        },
+                    )),
+                }
             }
             false => c1_data.extend(c2_iter.zip(c3_iter).map(
                 |((c2_value, c2_bitmap), (c3_value, c3_bitmap))| {
-                    f_data(c2_value, c2_bitmap, c3_value, c3_bitmap)
+                    f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap)
                 },
             )),
         }
@@ -130,56 +135,57 @@ where
         match f {
             FType::Set((f_data, f_bitmap)) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                         |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                            c1_data[*i] = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
-                            c1_bitmap[*i] = f_bitmap(c2_bitmap, c3_bitmap);
+                            c1_data[*i] = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
+                            c1_bitmap.set(*i, f_bitmap(&c2_bitmap, &c3_bitmap));
                         },
                     )
                 }
                 (true, false) => c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                     |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                        c1_data[*i] = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
+                        c1_data[*i] = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
                     },
                 ),
                 (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .zip(c3)
-                        .for_each(
-                            |(
-                                ((c1_value, c1_bitmap), (c2_value, c2_bitmap)),
-                                (c3_value, c3_bitmap),
-                            )| {
-                                *c1_value = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
-                                *c1_bitmap = f_bitmap(c2_bitmap, c3_bitmap);
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2_iter).zip(c3_iter).for_each(
+                        |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
+                            *c1_value = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
+                        },
+                    );
+                    match validity.0.zip_map(validity.1, len, &f_bitmap) {
+                        Some(bitmap) => c1_bitmap.copy_from(0, bitmap.as_bitmap_ref()),
+                        None => c2.into_iter().zip(c3).enumerate().for_each(
+                            |(i, ((_c2_value, c2_bitmap), (_c3_value, c3_bitmap)))| {
+                                c1_bitmap.set(i, f_bitmap(&c2_bitmap, &c3_bitmap));
                             },
-                        )
+                        ),
+                    }
                 }
                 (false, false) => c1_data.iter_mut().zip(c2).zip(c3).for_each(
                     |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                        *c1_value = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
+                        *c1_value = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
                     },
                 ),
             },
 
             FType::Update(f) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                         |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
+                            let mut c1_valid = c1_bitmap.get(*i);
                             f(
                                 c1_data.get_mut(*i).unwrap(),
-                                c1_bitmap.get_mut(*i).unwrap(),
+                                &mut c1_valid,
                                 c2_value,
-                                c2_bitmap,
+                                &c2_bitmap,
                                 c3_value,
-                                c3_bitmap,
+                                &c3_bitmap,
                             );
+                            c1_bitmap.set(*i, c1_valid);
                         },
                     )
                 }
@@ -189,34 +195,33 @@ where
                             c1_data.get_mut(*i).unwrap(),
                             &mut true,
                             c2_value,
-                            c2_bitmap,
+                            &c2_bitmap,
                             c3_value,
-                            c3_bitmap,
+                            &c3_bitmap,
                         );
                     },
                 ),
                 (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .zip(c3)
-                        .for_each(
-                            |(
-                                ((c1_value, c1_bitmap), (c2_value, c2_bitmap)),
-                                (c3_value, c3_bitmap),
-                            )| {
-                                f(
-                                    c1_value, c1_bitmap, c2_value, c2_bitmap, c3_value, c3_bitmap,
-                                );
-                            },
-                        )
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2).zip(c3).enumerate().for_each(
+                        |(i, ((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap)))| {
+                            let mut c1_valid = c1_bitmap.get(i);
+                            f(
+                                c1_value,
+                                &mut c1_valid,
+                                c2_value,
+                                &c2_bitmap,
+                                c3_value,
+                                &c3_bitmap,
+                            );
+                            c1_bitmap.set(i, c1_valid);
+                        },
+                    )
                 }
                 (false, false) => c1_data.iter_mut().zip(c2).zip(c3).for_each(
                     |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
                         f(
-                            c1_value, &mut true, c2_value, c2_bitmap, c3_value, c3_bitmap,
+                            c1_value, &mut true, c2_value, &c2_bitmap, c3_value, &c3_bitmap,
                         );
                     },
                 ),
@@ -236,6 +241,7 @@ fn f_2_sized_sized_sized<'a, 'i, T1, T2, T3, U3, FBool, F1, F2>(
     c: &'a ColumnWrapper,
     c_index: &'a ColumnDataIndex,
     c3: U3,
+    c3_validity: Validity<'a>,
     f: FType<'a, T1, T2, T3, FBool, F1, F2>,
     len: &usize,
 ) -> Result<(), ErrorDesc>
@@ -244,7 +250,7 @@ where
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     T3: 'static + Send + Sync,
-    U3: IntoIterator<Item = (&'i T3, &'i bool)>,
+    U3: IntoIterator<Item = (&'i T3, bool)>,
     U3::IntoIter: ExactSizeIterator,
     U3: Clone,
     FBool: Fn(&bool, &bool) -> bool,
@@ -252,81 +258,76 @@ where
     F2: Fn(&mut T1, &mut bool, &T2, &bool, &T3, &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
     let c_data = c.downcast_ref::<T2>()?;
 
-    let c_const_val = || {
-        (0..*len)
-            .into_iter()
-            .map(|_| (&c_data[0], &c_bitmap.downcast_ref().unwrap()[0]))
-    };
-    let c_const_true = || (0..*len).into_iter().map(|_| (&c_data[0], &true));
-    let c_index_bitmap = || {
+    let c_const = |v: bool| (0..*len).map(move |_| (&c_data[0], v));
+    let c_index_const = |v: bool| {
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &c_bitmap.downcast_ref().unwrap()[*i]))
+            .map(move |i| (&c_data[*i], v))
     };
-    let c_index_nobitmap = || {
+    let c_index_bitmap = || {
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &true))
-    };
-    let c_noindex_bitmap = || {
-        c_data
-            .iter()
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|(v, b)| (v, b))
+            .map(move |i| (&c_data[*i], c_bitmap.get(*i)))
     };
-    let c_noindex_nobitmap = || c_data.iter().map(|v| (v, &true));
-
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => {
-                f_1_sized_sized_sized(c1, c1_index, bitmap_update_required, c_const_val(), c3, f)
-            }
-            false => {
-                f_1_sized_sized_sized(c1, c1_index, bitmap_update_required, c_const_true(), c3, f)
-            }
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => f_1_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_bitmap(),
-                c3,
-                f,
-            ),
-            (true, false) => f_1_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_nobitmap(),
-                c3,
-                f,
-            ),
-            (false, true) => f_1_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_bitmap(),
-                c3,
-                f,
-            ),
-            (false, false) => f_1_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_nobitmap(),
-                c3,
-                f,
-            ),
-        }
+    let c_noindex_const = |v: bool| c_data.iter().map(move |value| (value, v));
+    let c_noindex_bitmap = || c_data.iter().zip(c_bitmap.downcast_ref().unwrap().iter());
+
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => f_1_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_const(v),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (Validity::Const(v), true) => f_1_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_const(v),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (Validity::Const(v), false) => f_1_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_const(v),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (_, true) => f_1_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_bitmap(),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (_, false) => f_1_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_bitmap(),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
     }
 }
 
@@ -353,103 +354,86 @@ where
     F2: Fn(&mut T1, &mut bool, &T2, &bool, &T3, &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
     let c_data = c.downcast_ref::<T3>()?;
 
-    let c_const_val = || {
-        (0..*len)
-            .into_iter()
-            .map(|_| (&c_data[0], &c_bitmap.downcast_ref().unwrap()[0]))
-    };
-    let c_const_true = || (0..*len).into_iter().map(|_| (&c_data[0], &true));
-    let c_index_bitmap = || {
+    let c_const = |v: bool| (0..*len).map(move |_| (&c_data[0], v));
+    let c_index_const = |v: bool| {
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &c_bitmap.downcast_ref().unwrap()[*i]))
+            .map(move |i| (&c_data[*i], v))
     };
-    let c_index_nobitmap = || {
+    let c_index_bitmap = || {
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
         c_index
             .downcast_ref()
             .unwrap()
             .iter()
-            .map(|i| (&c_data[*i], &true))
-    };
-    let c_noindex_bitmap = || {
-        c_data
-            .iter()
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|(v, b)| (v, b))
+            .map(move |i| (&c_data[*i], c_bitmap.get(*i)))
     };
-    let c_noindex_nobitmap = || c_data.iter().map(|v| (v, &true));
-
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_const_val(),
-                f,
-                len,
-            ),
-            false => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_const_true(),
-                f,
-                len,
-            ),
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_index_bitmap(),
-                f,
-                len,
-            ),
-            (true, false) => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_index_nobitmap(),
-                f,
-                len,
-            ),
-            (false, true) => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_noindex_bitmap(),
-                f,
-                len,
-            ),
-            (false, false) => f_2_sized_sized_sized(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_noindex_nobitmap(),
-                f,
-                len,
-            ),
-        }
+    let c_noindex_const = |v: bool| c_data.iter().map(move |value| (value, v));
+    let c_noindex_bitmap = || c_data.iter().zip(c_bitmap.downcast_ref().unwrap().iter());
+
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => f_2_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (Validity::Const(v), true) => f_2_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_index_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (Validity::Const(v), false) => f_2_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_noindex_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (_, true) => f_2_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_index_bitmap(),
+            validity,
+            f,
+            len,
+        ),
+        (_, false) => f_2_sized_sized_sized(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_noindex_bitmap(),
+            validity,
+            f,
+            len,
+        ),
     }
 }
 
@@ -490,7 +474,7 @@ where
     let c3_len = if c3_index.is_some() {
         c3_index.downcast_ref()?.len()
     } else {
-        c3.column().data_len::<T2>()?
+        c3.column().data_len::<T3>()?
     };
 
     let len = std::cmp::max(len, c2_len);
@@ -498,7 +482,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
@@ -571,7 +555,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
@@ -601,23 +585,25 @@ where
 //////////////////    sized binary binary          /////////////////////////
 ////////////////////////////////////////////////////////////////////////////
 
-fn f_1_sized_binary_binary<'a, 'i, T1, T2, T3, U2, U3, FBool, F1, F2>(
+fn f_1_sized_binary_binary<'a, 'i2, 'i3, T1, T2, T3, U2, U3, FBool, F1, F2>(
     c1: &'a mut ColumnWrapper,
     c1_index: &ColumnDataIndex,
     bitmap_update_required: &bool,
     c2: U2,
     c3: U3,
+    validity: (Validity<'i2>, Validity<'i3>),
     f: FType<'a, T1, [u8], [u8], FBool, F1, F2>,
 ) -> Result<(), ErrorDesc>
 where
-    'a: 'i,
+    'a: 'i2,
+    'a: 'i3,
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     T3: 'static + Send + Sync,
-    U2: IntoIterator<Item = (&'i [u8], &'i bool)>,
+    U2: IntoIterator<Item = (&'i2 [u8], bool)>,
     U2::IntoIter: ExactSizeIterator,
     U2: Clone,
-    U3: IntoIterator<Item = (&'i [u8], &'i bool)>,
+    U3: IntoIterator<Item = (&'i3 [u8], bool)>,
     U3::IntoIter: ExactSizeIterator,
     U3: Clone,
     FBool: Fn(&bool, &bool) -> bool,
@@ -629,6 +615,7 @@ where
     let c3_iter = c3.clone().into_iter();
 
     assert_eq!(c2_iter.len(), c3_iter.len());
+    let len = c2_iter.len();
 
     let is_set_operation = match f {
         FType::Set(_) => true,
@@ -643,9 +630,7 @@ where
         // Index should be empty
 
         let c1_data: &mut Vec<T1> = c1.downcast_vec::<T1>()?;
-        let c1_bitmap = c1_bitmap.downcast_vec()?;
 
-        assert_eq!(c1_bitmap.len(), 0);
         assert_eq!(c1_index.len(), None);
 
         let (f_data, f_bitmap): (F1, FBool) = match f {
@@ -656,18 +641,23 @@ where
             true => {
                 c1_data.extend(c2_iter.zip(c3_iter).map(
                     |((c2_value, c2_bitmap), (c3_value, c3_bitmap))| {
-                        f_data(c2_value, c2_bitmap, c3_value, c3_bitmap)
-                    },
-                ));
-                c1_bitmap.extend(c2.into_iter().zip(c3.into_iter()).map(
-                    |((_c2_value, c2_bitmap), (_c3_value, c3_bitmap))| {
-                        f_bitmap(c2_bitmap, c3_bitmap)
+                        f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap)
                     },
                 ));
+                let c1_bitmap = c1_bitmap.downcast_vec()?;
+                assert_eq!(c1_bitmap.len(), 0);
+                match validity.0.zip_map(validity.1, len, &f_bitmap) {
+                    Some(bitmap) => *c1_bitmap = bitmap,
+                    None => c1_bitmap.extend(c2.into_iter().zip(c3.into_iter()).map(
+                        |((_c2_value, c2_bitmap), (_c3_value, c3_bitmap))| {
+                            f_bitmap(&c2_bitmap, &c3_bitmap)
+                        },
+                    )),
+                }
             }
             false => c1_data.extend(c2_iter.zip(c3_iter).map(
                 |((c2_value, c2_bitmap), (c3_value, c3_bitmap))| {
-                    f_data(c2_value, c2_bitmap, c3_value, c3_bitmap)
+                    f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap)
                 },
             )),
         }
@@ -694,56 +684,57 @@ where
         match f {
             FType::Set((f_data, f_bitmap)) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                         |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                            c1_data[*i] = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
-                            c1_bitmap[*i] = f_bitmap(c2_bitmap, c3_bitmap);
+                            c1_data[*i] = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
+                            c1_bitmap.set(*i, f_bitmap(&c2_bitmap, &c3_bitmap));
                         },
                     )
                 }
                 (true, false) => c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                     |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                        c1_data[*i] = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
+                        c1_data[*i] = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
                     },
                 ),
                 (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .zip(c3)
-                        .for_each(
-                            |(
-                                ((c1_value, c1_bitmap), (c2_value, c2_bitmap)),
-                                (c3_value, c3_bitmap),
-                            )| {
-                                *c1_value = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
-                                *c1_bitmap = f_bitmap(c2_bitmap, c3_bitmap);
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2_iter).zip(c3_iter).for_each(
+                        |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
+                            *c1_value = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
+                        },
+                    );
+                    match validity.0.zip_map(validity.1, len, &f_bitmap) {
+                        Some(bitmap) => c1_bitmap.copy_from(0, bitmap.as_bitmap_ref()),
+                        None => c2.into_iter().zip(c3).enumerate().for_each(
+                            |(i, ((_c2_value, c2_bitmap), (_c3_value, c3_bitmap)))| {
+                                c1_bitmap.set(i, f_bitmap(&c2_bitmap, &c3_bitmap));
                             },
-                        )
+                        ),
+                    }
                 }
                 (false, false) => c1_data.iter_mut().zip(c2).zip(c3).for_each(
                     |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
-                        *c1_value = f_data(c2_value, c2_bitmap, c3_value, c3_bitmap);
+                        *c1_value = f_data(c2_value, &c2_bitmap, c3_value, &c3_bitmap);
                     },
                 ),
             },
 
             FType::Update(f) => match (c1_index.is_some(), bitmap_update_required) {
                 (true, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
                     c1_index.downcast_ref()?.iter().zip(c2).zip(c3).for_each(
                         |((i, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
+                            let mut c1_valid = c1_bitmap.get(*i);
                             f(
                                 c1_data.get_mut(*i).unwrap(),
-                                c1_bitmap.get_mut(*i).unwrap(),
+                                &mut c1_valid,
                                 c2_value,
-                                c2_bitmap,
+                                &c2_bitmap,
                                 c3_value,
-                                c3_bitmap,
+                                &c3_bitmap,
                             );
+                            c1_bitmap.set(*i, c1_valid);
                         },
                     )
                 }
@@ -753,34 +744,33 @@ where
                             c1_data.get_mut(*i).unwrap(),
                             &mut true,
                             c2_value,
-                            c2_bitmap,
+                            &c2_bitmap,
                             c3_value,
-                            c3_bitmap,
+                            &c3_bitmap,
                         );
                     },
                 ),
                 (false, true) => {
-                    let c1_bitmap = c1_bitmap.downcast_mut().unwrap();
-                    c1_data
-                        .iter_mut()
-                        .zip(c1_bitmap.iter_mut())
-                        .zip(c2)
-                        .zip(c3)
-                        .for_each(
-                            |(
-                                ((c1_value, c1_bitmap), (c2_value, c2_bitmap)),
-                                (c3_value, c3_bitmap),
-                            )| {
-                                f(
-                                    c1_value, c1_bitmap, c2_value, c2_bitmap, c3_value, c3_bitmap,
-                                );
-                            },
-                        )
+                    let mut c1_bitmap = c1_bitmap.downcast_mut().unwrap();
+                    c1_data.iter_mut().zip(c2).zip(c3).enumerate().for_each(
+                        |(i, ((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap)))| {
+                            let mut c1_valid = c1_bitmap.get(i);
+                            f(
+                                c1_value,
+                                &mut c1_valid,
+                                c2_value,
+                                &c2_bitmap,
+                                c3_value,
+                                &c3_bitmap,
+                            );
+                            c1_bitmap.set(i, c1_valid);
+                        },
+                    )
                 }
                 (false, false) => c1_data.iter_mut().zip(c2).zip(c3).for_each(
                     |((c1_value, (c2_value, c2_bitmap)), (c3_value, c3_bitmap))| {
                         f(
-                            c1_value, &mut true, c2_value, c2_bitmap, c3_value, c3_bitmap,
+                            c1_value, &mut true, c2_value, &c2_bitmap, c3_value, &c3_bitmap,
                         );
                     },
                 ),
@@ -800,6 +790,7 @@ fn f_2_sized_binary_binary<'a, 'i, T1, T2, T3, U3, FBool, F1, F2>(
     c: &'a ColumnWrapper,
     c_index: &'a ColumnDataIndex,
     c3: U3,
+    c3_validity: Validity<'a>,
     f: FType<'a, T1, [u8], [u8], FBool, F1, F2>,
     len: &usize,
 ) -> Result<(), ErrorDesc>
@@ -808,7 +799,7 @@ where
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     T3: 'static + Send + Sync,
-    U3: IntoIterator<Item = (&'i [u8], &'i bool)>,
+    U3: IntoIterator<Item = (&'i [u8], bool)>,
     U3::IntoIter: ExactSizeIterator,
     U3: Clone,
     FBool: Fn(&bool, &bool) -> bool,
@@ -816,115 +807,93 @@ where
     F2: Fn(&mut T1, &mut bool, &[u8], &bool, &[u8], &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
-    let (c_datau8, c_start_pos, c_len, c_offset) = c.downcast_binary_ref::<T2>()?;
+    let decoded = c.downcast_binary_decoded::<T2>()?;
+    let (c_datau8, c_offsets) = decoded.parts();
+    let value = |i: usize| binary_item(c_datau8, c_offsets, i);
 
-    let c_const_val = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &c_bitmap.downcast_ref().unwrap()[0],
-            )
-        })
-    };
-    let c_const_true = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &true,
-            )
-        })
+    let c_const = |v: bool| (0..*len).map(move |_| (value(0), v));
+    let c_index_const = |v: bool| {
+        c_index
+            .downcast_ref()
+            .unwrap()
+            .iter()
+            .map(move |i| (value(*i), v))
     };
     let c_index_bitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &c_bitmap.downcast_ref().unwrap()[*i],
-            )
-        })
-    };
-    let c_index_nobitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &true,
-            )
-        })
-    };
-    let c_noindex_bitmap = || {
-        c_start_pos
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
+        c_index
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|((s, l), b)| (&c_datau8[s - c_offset..s - c_offset + l], b))
+            .map(move |i| (value(*i), c_bitmap.get(*i)))
     };
-    let c_noindex_nobitmap = || {
-        c_start_pos
+    let c_noindex_const = |v: bool| (0..c_offsets.len() - 1).map(move |i| (value(i), v));
+    let c_noindex_bitmap = || {
+        c_bitmap
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .map(|(s, l)| (&c_datau8[s - c_offset..s - c_offset + l], &true))
+            .enumerate()
+            .map(move |(i, b)| (value(i), b))
     };
 
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_const_val(),
-                c3,
-                f,
-            ),
-            false => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_const_true(),
-                c3,
-                f,
-            ),
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_bitmap(),
-                c3,
-                f,
-            ),
-            (true, false) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_index_nobitmap(),
-                c3,
-                f,
-            ),
-            (false, true) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c_noindex_bitmap(),
-                c3,
-                f,
-            ),
-            (false, false) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => {
+            f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
                 c1,
                 c1_index,
                 bitmap_update_required,
-                c_noindex_nobitmap(),
+                c_const(v),
                 c3,
+                (validity, c3_validity),
                 f,
-            ),
+            )
         }
+        (Validity::Const(v), true) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_const(v),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (Validity::Const(v), false) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_const(v),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (_, true) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_index_bitmap(),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
+        (_, false) => f_1_sized_binary_binary::<T1, T2, T3, _, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c_noindex_bitmap(),
+            c3,
+            (validity, c3_validity),
+            f,
+        ),
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////
 
-fn f_3_sized_binary_binary<'a, 'i, T1, T2, T3, FBool, F1, F2>(
+fn f_3_sized_binary_binary<'a, T1, T2, T3, FBool, F1, F2>(
     c1: &'a mut ColumnWrapper,
     c1_index: &ColumnDataIndex,
     bitmap_update_required: &bool,
@@ -936,7 +905,6 @@ fn f_3_sized_binary_binary<'a, 'i, T1, T2, T3, FBool, F1, F2>(
     len: &usize,
 ) -> Result<(), ErrorDesc>
 where
-    'a: 'i,
     T1: 'static + Send + Sync,
     T2: 'static + Send + Sync,
     T3: 'static + Send + Sync,
@@ -945,121 +913,95 @@ where
     F2: Fn(&mut T1, &mut bool, &[u8], &bool, &[u8], &bool),
 {
     let c_is_const = c.column().is_const();
+    let validity = Validity::new(c, c_index);
     let (c, c_bitmap) = c.get_inner_ref();
-    let (c_datau8, c_start_pos, c_len, c_offset) = c.downcast_binary_ref::<T3>()?;
+    let decoded = c.downcast_binary_decoded::<T3>()?;
+    let (c_datau8, c_offsets) = decoded.parts();
+    let value = |i: usize| binary_item(c_datau8, c_offsets, i);
 
-    let c_const_val = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &c_bitmap.downcast_ref().unwrap()[0],
-            )
-        })
-    };
-    let c_const_true = || {
-        (0..*len).into_iter().map(|_| {
-            (
-                &c_datau8[c_start_pos[0] - c_offset..c_start_pos[0] - c_offset + c_len[0]],
-                &true,
-            )
-        })
+    let c_const = |v: bool| (0..*len).map(move |_| (value(0), v));
+    let c_index_const = |v: bool| {
+        c_index
+            .downcast_ref()
+            .unwrap()
+            .iter()
+            .map(move |i| (value(*i), v))
     };
     let c_index_bitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &c_bitmap.downcast_ref().unwrap()[*i],
-            )
-        })
-    };
-    let c_index_nobitmap = || {
-        c_index.downcast_ref().unwrap().iter().map(|i| {
-            (
-                &c_datau8[c_start_pos[*i] - c_offset..c_start_pos[*i] - c_offset + c_len[*i]],
-                &true,
-            )
-        })
-    };
-    let c_noindex_bitmap = || {
-        c_start_pos
+        let c_bitmap = c_bitmap.downcast_ref().unwrap();
+        c_index
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .zip(c_bitmap.downcast_ref().unwrap().iter())
-            .map(|((s, l), b)| (&c_datau8[s - c_offset..s - c_offset + l], b))
+            .map(move |i| (value(*i), c_bitmap.get(*i)))
     };
-    let c_noindex_nobitmap = || {
-        c_start_pos
+    let c_noindex_const = |v: bool| (0..c_offsets.len() - 1).map(move |i| (value(i), v));
+    let c_noindex_bitmap = || {
+        c_bitmap
+            .downcast_ref()
+            .unwrap()
             .iter()
-            .zip(c_len.iter())
-            .map(|(s, l)| (&c_datau8[s - c_offset..s - c_offset + l], &true))
+            .enumerate()
+            .map(move |(i, b)| (value(i), b))
     };
 
-    if c_is_const {
-        match c_bitmap.is_some() {
-            true => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_const_val(),
-                f,
-                len,
-            ),
-            false => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_const_true(),
-                f,
-                len,
-            ),
-        }
-    } else {
-        match (c_index.is_some(), c_bitmap.is_some()) {
-            (true, true) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_index_bitmap(),
-                f,
-                len,
-            ),
-            (true, false) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_index_nobitmap(),
-                f,
-                len,
-            ),
-            (false, true) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_noindex_bitmap(),
-                f,
-                len,
-            ),
-            (false, false) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
-                c1,
-                c1_index,
-                bitmap_update_required,
-                c2,
-                c2_index,
-                c_noindex_nobitmap(),
-                f,
-                len,
-            ),
-        }
+    //Const inputs and inputs without nulls skip the bitmap entirely
+    match (validity, c_index.is_some()) {
+        (Validity::Const(v), _) if c_is_const => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (Validity::Const(v), true) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_index_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (Validity::Const(v), false) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_noindex_const(v),
+            validity,
+            f,
+            len,
+        ),
+        (_, true) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_index_bitmap(),
+            validity,
+            f,
+            len,
+        ),
+        (_, false) => f_2_sized_binary_binary::<T1, T2, T3, _, _, _, _>(
+            c1,
+            c1_index,
+            bitmap_update_required,
+            c2,
+            c2_index,
+            c_noindex_bitmap(),
+            validity,
+            f,
+            len,
+        ),
     }
 }
 
@@ -1108,7 +1050,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
@@ -1181,7 +1123,7 @@ where
 
     if *bitmap_update_required && !c1.bitmap().is_some() {
         if c1.column().is_owned() {
-            c1.bitmap_set(ColumnDataF::new(Vec::with_capacity(len)))
+            c1.bitmap_set(ColumnDataF::new(Bitmap::with_capacity(len)))
         } else {
             Err("Bitmap update is required, but the column is a reference and has no bitmap")?
         }
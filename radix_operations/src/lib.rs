@@ -1,12 +1,18 @@
 pub mod c_add;
 pub mod c_addassign;
 pub mod c_count;
+pub mod c_datetime;
+pub mod c_decimal;
 pub mod c_eq;
 pub mod c_gt;
 pub mod c_gteq;
+pub mod c_in;
+pub mod c_length;
 pub mod c_lt;
 pub mod c_lteq;
 pub mod c_max;
+pub mod c_min;
+pub mod c_runlength;
 pub mod c_sum;
 pub mod column_operations;
 pub mod columnop;
@@ -15,7 +21,11 @@ pub mod generic_functions_2;
 pub mod generic_functions_3;
 pub mod op;
 pub mod signature;
+pub mod statecolumn;
+pub mod udf;
+pub(crate) mod validity;
 
+pub use c_decimal::{DecimalAvg, DecimalOptions, OverflowPolicy};
 pub use column_operations::*;
 pub use columnop::*;
 pub use dictionary::*;
@@ -23,3 +33,4 @@ pub use generic_functions_2::*;
 pub use generic_functions_3::*;
 pub use op::*;
 pub use signature::*;
+pub use udf::*;
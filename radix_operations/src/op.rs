@@ -1,14 +1,28 @@
 use crate::Signature;
 use radix_column::*;
-use std::any::TypeId;
+use std::{any::TypeId, sync::Arc};
 
 pub enum InputTypes<'a> {
     Ref(&'a ColumnWrapper<'a>, &'a ColumnDataIndex<'a>),
     Owned(ColumnWrapper<'static>, ColumnDataIndex<'static>),
 }
+//Writes the result of the operation for the input columns into the first column
+pub type OperationFn = Arc<
+    dyn Fn(&mut ColumnWrapper, &ColumnDataIndex, &[InputTypes]) -> Result<(), ErrorDesc>
+        + Send
+        + Sync,
+>;
+
+//Operation working on the runs of run-length encoded inputs, returns false if it cannot handle the input
+pub type RunLengthOperationFn = Arc<
+    dyn Fn(&mut ColumnWrapper, &ColumnDataIndex, &[InputTypes]) -> Result<bool, ErrorDesc>
+        + Send
+        + Sync,
+>;
+
 #[derive(Clone)]
 pub struct Operation {
-    pub f: fn(&mut ColumnWrapper, &ColumnDataIndex, &[InputTypes]) -> Result<(), ErrorDesc>,
+    pub f: OperationFn,
     pub output_type_id: TypeId,
     pub is_assign_op: bool,
     pub associated_assign_op: Option<String>,
@@ -87,7 +101,7 @@ impl Expression {
                     Ok(acc)
                 });
         let input = input?;
-        let op = dict.op.get(&self.op).unwrap();
+        let op = dict.op.get(&self.op).ok_or_else(|| self.op.not_found(dict))?;
         (op.f)(&mut output, input)?;
         owned_columns.push(output);
         Ok(())
@@ -130,10 +144,7 @@ impl Expression {
                 if let Some(f) = f {
                     owned_columns.push(f());
                 } else {
-                    Err(format!(
-                        "Following operation not found in dictionary: {:?}",
-                        output_sig
-                    ))?
+                    Err(output_sig.not_found(dict))?
                 }
 
                 //println!("{}", output_sig.op_name())
@@ -151,10 +162,7 @@ impl Expression {
             ops.push(op.clone());
             Ok(())
         } else {
-            Err(format!(
-                "Following operation not found in dictionary: {:?}",
-                &self.op
-            ))?
+            Err(self.op.not_found(dict))?
         }
     }
 
@@ -1,9 +1,9 @@
-use radix_column::ErrorDesc;
-use std::any::TypeId;
+use radix_column::{ErrorDesc, RadixError, TypeName};
+use std::{any::TypeId, fmt};
 
 use crate::Dictionary;
 
-#[derive(Clone, Hash, Debug)]
+#[derive(Clone, Hash)]
 pub struct Signature {
     op_name: String,
     input: Vec<TypeId>,
@@ -17,6 +17,19 @@ impl PartialEq for Signature {
 
 impl Eq for Signature {}
 
+//The input types are printed by their registered names
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signature")
+            .field("op_name", &self.op_name)
+            .field(
+                "input",
+                &self.input.iter().map(|t| TypeName(*t)).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
 impl Signature {
     pub fn new(op: &str, input: Vec<TypeId>) -> Self {
         Self {
@@ -53,10 +66,23 @@ impl Signature {
                 input: vec![op.output_type_id],
             })
         } else {
-            Err(format!(
-                "Following operation not found in dictionary: {:?}",
-                &self
-            ))?
+            Err(self.not_found(dict))?
+        }
+    }
+
+    //Error for a signature which is not in the dictionary
+    pub fn not_found(&self, dict: &Dictionary) -> RadixError {
+        RadixError::SignatureNotFound {
+            op_name: self.op_name.clone(),
+            input: self.input.clone(),
+            input_names: self
+                .input
+                .iter()
+                .map(|t| match dict.type_name(*t) {
+                    Some(name) => name.to_string(),
+                    None => TypeName(*t).to_string(),
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,338 @@
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    io::{Read, Write},
+    marker::PhantomData,
+    ops::Range,
+    sync::Arc,
+};
+
+use radix_column::*;
+
+use crate::{bitmap_allocated_bytes, ColumnInternalOp};
+
+//Columns of the intermediate states of user-defined aggregates
+//The states are only moved between the phases of an aggregation, therefore they cannot be hashed, sorted, printed or spilled
+pub(crate) struct StateColumnInternalOp<S> {
+    state: PhantomData<fn() -> S>,
+}
+
+impl<S> StateColumnInternalOp<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    pub(crate) fn new() -> Self {
+        Self { state: PhantomData }
+    }
+
+    fn unsupported(&self, operation: &str) -> RadixError {
+        RadixError::Other(format!(
+            "{} is not supported for columns of the aggregate state {}",
+            operation,
+            self.type_name()
+        ))
+    }
+
+    fn bitmap_of<'a>(c: &'a ColumnWrapper) -> Result<Option<BitmapRef<'a>>, ErrorDesc> {
+        let bitmap = c.bitmap();
+        if bitmap.is_some() {
+            Ok(Some(bitmap.downcast_ref()?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<S> ColumnInternalOp for StateColumnInternalOp<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<S>()
+    }
+
+    fn len(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        inp.column().downcast_ref::<S>().map(|c| c.len())
+    }
+
+    fn truncate(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        if let ColumnData::Owned(c) = inp.column_mut() {
+            c.downcast_vec::<S>()?.truncate(0);
+            Ok(())
+        } else {
+            Err("Only ColumnData::Owned can be truncated")?
+        }
+    }
+
+    fn new<'a>(&self, data: Box<dyn Any>) -> Result<ColumnData<'a>, ErrorDesc> {
+        let data = data
+            .downcast::<Vec<S>>()
+            .map_err(|_| format!("Downcast failed. Target type is Vec<{}>", self.type_name()))?;
+        Ok(ColumnData::Owned(OwnedColumn::new(*data)))
+    }
+
+    fn new_ref<'a: 'b, 'b>(&self, data: SliceRef<'a>) -> Result<ColumnData<'b>, ErrorDesc> {
+        if data.item_type_id() == std::any::TypeId::of::<S>() {
+            Ok(ColumnData::Slice(data))
+        } else {
+            Err(RadixError::downcast_failed::<S>(data.item_type_id()))?
+        }
+    }
+
+    fn new_mut<'a: 'b, 'b>(&self, data: SliceRefMut<'a>) -> Result<ColumnData<'b>, ErrorDesc> {
+        if data.item_type_id() == std::any::TypeId::of::<S>() {
+            Ok(ColumnData::SliceMut(data))
+        } else {
+            Err(RadixError::downcast_failed::<S>(data.item_type_id()))?
+        }
+    }
+
+    fn copy_to<'a>(
+        &self,
+        _src: &ColumnWrapper<'a>,
+        _dst: &mut ColumnWrapper<'a>,
+        _src_index: &ColumnDataIndex<'a>,
+    ) -> Result<(), ErrorDesc> {
+        Err(self.unsupported("copy_to"))?
+    }
+
+    fn as_string<'a>(
+        &self,
+        _src: &ColumnWrapper<'a>,
+        _src_index: &ColumnDataIndex<'a>,
+    ) -> Result<Vec<String>, ErrorDesc> {
+        Err(self.unsupported("as_string"))?
+    }
+
+    fn new_owned_with_capacity(
+        &self,
+        capacity: usize,
+        _binary_capacity: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let c = ColumnData::Owned(OwnedColumn::new(Vec::<S>::with_capacity(capacity)));
+        let mut c = ColumnWrapper::new_from_columndata(c);
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, capacity)))
+        };
+        c
+    }
+
+    fn new_uninit(
+        &self,
+        number_of_items: usize,
+        _binary_storage: usize,
+        with_bitmap: bool,
+    ) -> ColumnWrapper<'static> {
+        let c = ColumnData::Owned(OwnedColumn::new_uninit::<S>(number_of_items));
+        let mut c = ColumnWrapper::new_from_columndata(c);
+        if with_bitmap {
+            c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, number_of_items)))
+        };
+        c
+    }
+
+    unsafe fn assume_init<'b>(&self, c: ColumnWrapper<'b>) -> Result<ColumnWrapper<'b>, ErrorDesc> {
+        let (column, bitmap) = c.get_inner();
+        let column = column.assume_init::<S>()?;
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        c.bitmap_set(bitmap);
+        Ok(c)
+    }
+
+    fn unshare(&self, inp: &mut ColumnWrapper) -> Result<(), ErrorDesc> {
+        if inp.column().is_shared() {
+            let placeholder = ColumnData::Const(OwnedColumn::new(Vec::<S>::new()));
+            let col = match std::mem::replace(inp.column_mut(), placeholder) {
+                ColumnData::Shared(c) => match Arc::try_unwrap(c) {
+                    Ok(c) => c,
+                    Err(c) => OwnedColumn::new(c.downcast_ref::<S>()?.to_vec()),
+                },
+                _ => Err("Column expected to be ColumnData::Shared")?,
+            };
+            *inp.column_mut() = ColumnData::Owned(col);
+        }
+        inp.bitmap_mut().unshare();
+        Ok(())
+    }
+
+    fn view<'a>(&self, inp: &'a ColumnWrapper) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        self.view_range(inp, 0..self.len(inp)?)
+    }
+
+    fn view_range<'a>(
+        &self,
+        inp: &'a ColumnWrapper,
+        rows: Range<usize>,
+    ) -> Result<ColumnWrapper<'a>, ErrorDesc> {
+        let data = inp.column().downcast_ref::<S>()?;
+        let is_const = inp.column().is_const();
+        let column = if is_const {
+            ColumnData::Const(OwnedColumn::new(data.to_vec()))
+        } else {
+            ColumnData::Slice(SliceRef::new(&data[rows.clone()]))
+        };
+        let mut view = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = Self::bitmap_of(inp)? {
+            let bitmap = if is_const { bitmap } else { bitmap.slice(rows) };
+            view.bitmap_set(ColumnDataF::new_from_slice(bitmap));
+        }
+        Ok(view)
+    }
+
+    fn concat(&self, src: &[(&ColumnWrapper, usize)]) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let total_len = src.iter().map(|(_, len)| *len).sum();
+        let mut data: Vec<S> = Vec::with_capacity(total_len);
+        let has_bitmap = src.iter().any(|(c, _)| c.bitmap().is_some());
+        let mut bitmap = Bitmap::new();
+        src.iter()
+            .try_for_each(|(c, len)| -> Result<(), ErrorDesc> {
+                let c_data = c.column().downcast_ref::<S>()?;
+                let c_bitmap = Self::bitmap_of(c)?;
+                if c.column().is_const() {
+                    data.extend(std::iter::repeat_n(c_data[0].clone(), *len));
+                    if has_bitmap {
+                        let valid = c_bitmap.is_none_or(|b| b[0]);
+                        bitmap.extend(std::iter::repeat_n(valid, *len));
+                    }
+                } else if c_data.len() == *len {
+                    data.extend_from_slice(c_data);
+                    if has_bitmap {
+                        match c_bitmap {
+                            Some(b) => bitmap.extend_from_bitmap(b),
+                            None => bitmap.extend(std::iter::repeat_n(true, *len)),
+                        }
+                    }
+                } else {
+                    Err(RadixError::LengthMismatch {
+                        expected: *len,
+                        found: c_data.len(),
+                    })?
+                }
+                Ok(())
+            })?;
+        let mut c = ColumnWrapper::new_from_columndata(ColumnData::Owned(OwnedColumn::new(data)));
+        if has_bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
+
+    fn sort_index(
+        &self,
+        _src: &ColumnWrapper,
+        _order: &mut [usize],
+        _ascending: bool,
+    ) -> Result<(), ErrorDesc> {
+        Err(self.unsupported("Sorting"))?
+    }
+
+    fn take(
+        &self,
+        src: &ColumnWrapper,
+        src_index: &ColumnDataIndex,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        let data = src.column().downcast_ref::<S>()?;
+        let bitmap = Self::bitmap_of(src)?;
+        let (column, bitmap) = if src.column().is_const() {
+            (
+                ColumnData::Const(OwnedColumn::new(data.to_vec())),
+                bitmap.map(|b| b.to_bitmap()),
+            )
+        } else if src_index.is_some() {
+            let index = src_index.downcast_ref()?;
+            let data: Vec<S> = index.iter().map(|i| data[*i].clone()).collect();
+            (
+                ColumnData::Owned(OwnedColumn::new(data)),
+                bitmap.map(|b| index.iter().map(|i| b[*i]).collect()),
+            )
+        } else {
+            (
+                ColumnData::Owned(OwnedColumn::new(data.to_vec())),
+                bitmap.map(|b| b.to_bitmap()),
+            )
+        };
+        let mut c = ColumnWrapper::new_from_columndata(column);
+        if let Some(bitmap) = bitmap {
+            c.bitmap_set(ColumnDataF::new(bitmap));
+        }
+        Ok(c)
+    }
+
+    //Only the inline size of the states is counted, memory they allocate on their own is not known
+    fn allocated_bytes(&self, inp: &ColumnWrapper) -> Result<usize, ErrorDesc> {
+        let data = match inp.column() {
+            ColumnData::Owned(_) | ColumnData::Const(_) => {
+                std::mem::size_of_val(inp.column().downcast_ref::<S>()?)
+            }
+            _ => 0,
+        };
+        Ok(data + bitmap_allocated_bytes(inp.bitmap()))
+    }
+
+    fn write_to(
+        &self,
+        _src: &ColumnWrapper,
+        _src_index: &ColumnDataIndex,
+        _out: &mut dyn Write,
+    ) -> Result<(), ErrorDesc> {
+        Err(self.unsupported("Spilling"))?
+    }
+
+    fn read_from(&self, _inp: &mut dyn Read) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        Err(self.unsupported("Spilling"))?
+    }
+
+    fn hash_in(
+        &self,
+        _src: &ColumnWrapper,
+        _src_index: &ColumnDataIndex,
+        _dst: &mut Vec<u64>,
+    ) -> Result<(), ErrorDesc> {
+        Err(self.unsupported("Hashing"))?
+    }
+
+    fn copy_to_buckets_part1(
+        &self,
+        _hash: &[Vec<u64>],
+        _buckets_mask: u64,
+        _src_columns: &[Vec<ColumnWrapper>],
+        _src_indexes: &[Vec<ColumnDataIndex>],
+        _col_id: usize,
+        _index_id: &Option<&usize>,
+        _offsets: &VecDeque<usize>,
+        _dst: &mut [ColumnWrapper<'static>],
+        _is_nullable: bool,
+    ) -> Result<usize, ErrorDesc> {
+        Err(self.unsupported("Repartitioning"))?
+    }
+
+    fn copy_to_buckets_part2(&self, _dst: &mut ColumnWrapper<'static>) -> Result<usize, ErrorDesc> {
+        Err(self.unsupported("Repartitioning"))?
+    }
+
+    fn copy_to_buckets_part3(
+        &self,
+        _hash: &[Vec<u64>],
+        _buckets_mask: u64,
+        _src_columns: &[Vec<ColumnWrapper>],
+        _src_indexes: &[Vec<ColumnDataIndex>],
+        _col_id: usize,
+        _index_id: &Option<&usize>,
+        _offsets: &VecDeque<usize>,
+        _dst: &mut [ColumnWrapper<'static>],
+    ) -> Result<usize, ErrorDesc> {
+        Err(self.unsupported("Repartitioning"))?
+    }
+
+    fn group_in(
+        &self,
+        _src: &ColumnWrapper,
+        _src_index: &ColumnDataIndex,
+        _dst: &mut Vec<usize>,
+        _hashmap_buffer: &mut HashMapBuffer,
+        _hashmap_binary: &mut HashMap<(usize, NullableValue<&[u8]>), usize, ahash::RandomState>,
+    ) -> Result<(), ErrorDesc> {
+        Err(self.unsupported("Grouping"))?
+    }
+}
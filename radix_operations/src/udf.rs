@@ -0,0 +1,279 @@
+use std::{any::TypeId, collections::hash_map::Entry, sync::Arc};
+
+use radix_column::*;
+
+use crate::{
+    set_3_sized_sized_sized_unroll, statecolumn::StateColumnInternalOp, AggregateFunction,
+    Dictionary, InputTypes, Operation, Signature,
+};
+
+//How a scalar function handles null inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullPolicy {
+    //The output is null where one of the inputs is null
+    Propagate,
+    //The function is called for every row and the output has no nulls,
+    //the values of null inputs are the values stored in the column
+    NeverNull,
+}
+
+impl Dictionary {
+    //Registers a function of two sized columns as an operation, it can be used in expressions and in SQL
+    //SQL function names are matched in upper case
+    pub fn register_scalar<In1, In2, Out, F>(
+        &mut self,
+        name: &str,
+        f: F,
+        null_policy: NullPolicy,
+    ) -> Result<(), ErrorDesc>
+    where
+        In1: 'static + Send + Sync,
+        In2: 'static + Send + Sync,
+        Out: 'static + Send + Sync,
+        F: Fn(&In1, &In2) -> Out + Send + Sync + 'static,
+    {
+        self.check_sized_types(&[
+            TypeId::of::<In1>(),
+            TypeId::of::<In2>(),
+            TypeId::of::<Out>(),
+        ])?;
+        //Registered signatures are never replaced, the binder would pick the one of the other function
+        if self.is_aggregate(name) {
+            Err(format!(
+                "The scalar function {} cannot be registered, an aggregate has the same name",
+                name
+            ))?
+        }
+        if self.op_is_assign.contains_key(name) {
+            Err(format!(
+                "The scalar function {} cannot be registered, an operation has the same name",
+                name
+            ))?
+        }
+
+        let op = Operation {
+            f: Arc::new(move |c1, _c1_index, input| {
+                let bitmap_update_required = match null_policy {
+                    NullPolicy::Propagate => input.iter().any(|c| match c {
+                        InputTypes::Ref(c, _) => c.bitmap().is_some(),
+                        InputTypes::Owned(c, _) => c.bitmap().is_some(),
+                    }),
+                    NullPolicy::NeverNull => false,
+                };
+                set_3_sized_sized_sized_unroll::<Out, In1, In2, _, _>(
+                    c1,
+                    input,
+                    &bitmap_update_required,
+                    |c2_data, _c2_bool, c3_data, _c3_bool| f(c2_data, c3_data),
+                    |c2_bitmap, c3_bitmap| *c2_bitmap && *c3_bitmap,
+                )
+            }),
+            output_type_id: TypeId::of::<Out>(),
+            is_assign_op: false,
+            associated_assign_op: None,
+            associated_input_switch_op: None,
+        };
+        let mut signature = Signature::new_op(name);
+        signature.add_input::<In1>();
+        signature.add_input::<In2>();
+        self.op.insert(signature, op);
+        self.op_is_assign.insert(name.to_string(), false);
+        Ok(())
+    }
+
+    //Registers an aggregate of a sized column, it can be used in SQL like SUM and COUNT
+    //Every batch is aggregated into one state per group, starting with init and calling update for every non null value,
+    //the states of all batches are combined with merge and finalize computes the output of every group
+    pub fn register_aggregate<In, State, Out, I, U, M, F>(
+        &mut self,
+        name: &str,
+        init: I,
+        update: U,
+        merge: M,
+        finalize: F,
+    ) -> Result<(), ErrorDesc>
+    where
+        In: 'static + Send + Sync,
+        State: 'static + Clone + Send + Sync,
+        Out: 'static + Send + Sync,
+        I: Fn() -> State + Send + Sync + 'static,
+        U: Fn(&mut State, &In) + Send + Sync + 'static,
+        M: Fn(&mut State, &State) + Send + Sync + 'static,
+        F: Fn(&State) -> Out + Send + Sync + 'static,
+    {
+        self.check_sized_types(&[TypeId::of::<In>(), TypeId::of::<Out>()])?;
+        let aggregate = AggregateFunction {
+            merge_op: format!("{}#MERGE", name),
+            finalize_op: Some(format!("{}#FINALIZE", name)),
+        };
+        match self.aggregate(name) {
+            Some(a) if *a != aggregate => Err(format!(
+                "The aggregate {} cannot be registered, a built-in aggregate has the same name",
+                name
+            ))?,
+            None if self.op_is_assign.contains_key(name) => Err(format!(
+                "The aggregate {} cannot be registered, an operation has the same name",
+                name
+            ))?,
+            _ => {}
+        }
+
+        //States are stored in columns of their own type, unless the type is already known
+        let state_signature = Signature::new("", vec![TypeId::of::<State>()]);
+        if let Entry::Vacant(e) = self.columninternal.entry(state_signature) {
+            e.insert(Box::new(StateColumnInternalOp::<State>::new()));
+            self.register_type(LogicalType::sized::<State>(std::any::type_name::<State>()));
+        }
+
+        let init = Arc::new(init);
+        let partial_init = init.clone();
+        let ops = vec![
+            (
+                Signature::new(name, vec![TypeId::of::<In>()]),
+                Operation {
+                    f: Arc::new(move |c1, _c1_index, input| {
+                        aggregate_groups(c1, input, &*partial_init, &update)
+                    }),
+                    output_type_id: TypeId::of::<State>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+                },
+            ),
+            (
+                Signature::new(&aggregate.merge_op, vec![TypeId::of::<State>()]),
+                Operation {
+                    f: Arc::new(move |c1, _c1_index, input| {
+                        aggregate_groups(c1, input, &*init, &merge)
+                    }),
+                    output_type_id: TypeId::of::<State>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+                },
+            ),
+            (
+                Signature::new(
+                    aggregate.finalize_op.as_ref().unwrap(),
+                    vec![TypeId::of::<State>()],
+                ),
+                Operation {
+                    f: Arc::new(move |c1, _c1_index, input| finalize_states(c1, input, &finalize)),
+                    output_type_id: TypeId::of::<Out>(),
+                    is_assign_op: false,
+                    associated_assign_op: None,
+                    associated_input_switch_op: None,
+                },
+            ),
+        ];
+        for (signature, op) in ops.into_iter() {
+            self.op_is_assign
+                .insert(signature.op_name().to_string(), false);
+            self.op.insert(signature, op);
+        }
+        self.aggregates.insert(name.to_string(), aggregate);
+        Ok(())
+    }
+
+    fn check_sized_types(&self, types: &[TypeId]) -> Result<(), ErrorDesc> {
+        for t in types.iter() {
+            match self.logical_type(*t) {
+                Some(logical_type) if logical_type.is_sized() => {}
+                Some(logical_type) => Err(format!(
+                    "Functions of binary types are not supported, {} is a binary type",
+                    logical_type.name
+                ))?,
+                None => Err(format!(
+                    "The type {} is not registered in the dictionary",
+                    TypeName(*t)
+                ))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+//Follows the convention of the built-in aggregates: the input is the value column,
+//the group id of every row and the number of groups, the output gets one state per group
+pub(crate) fn aggregate_groups<T, S>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    init: &dyn Fn() -> S,
+    update: &dyn Fn(&mut S, &T),
+) -> Result<(), ErrorDesc>
+where
+    T: 'static + Send + Sync,
+    S: 'static + Send + Sync,
+{
+    let (c2, c2_index) = match &input[0] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
+    let c3 = match &input[1] {
+        InputTypes::Ref(c, _) => *c,
+        InputTypes::Owned(c, _) => c,
+    };
+    let c4 = match &input[2] {
+        InputTypes::Ref(c, _) => *c,
+        InputTypes::Owned(c, _) => c,
+    };
+
+    let c2_data = c2.column().downcast_ref::<T>()?;
+    let c2_bitmap = if c2.bitmap().is_some() {
+        Some(c2.bitmap().downcast_ref()?)
+    } else {
+        None
+    };
+    let group_ids = c3.column().downcast_ref::<usize>()?;
+    let number_of_groups = c4.column().downcast_ref::<usize>()?[0];
+    let rows = if c2_index.is_some() {
+        c2_index.downcast_ref()?.to_vec()
+    } else {
+        (0..c2_data.len()).collect()
+    };
+    if rows.len() != group_ids.len() {
+        Err(RadixError::LengthMismatch {
+            expected: group_ids.len(),
+            found: rows.len(),
+        })?
+    }
+
+    let states = c1.column_mut().downcast_vec::<S>()?;
+    if !states.is_empty() {
+        Err(RadixError::LengthMismatch {
+            expected: 0,
+            found: states.len(),
+        })?
+    }
+    states.reserve(number_of_groups);
+    states.extend((0..number_of_groups).map(|_| init()));
+    rows.iter()
+        .zip(group_ids.iter())
+        .filter(|(row, _)| c2_bitmap.is_none_or(|b| b[**row]))
+        .for_each(|(row, group_id)| update(&mut states[*group_id], &c2_data[*row]));
+    Ok(())
+}
+
+fn finalize_states<S, Out>(
+    c1: &mut ColumnWrapper,
+    input: &[InputTypes],
+    finalize: &dyn Fn(&S) -> Out,
+) -> Result<(), ErrorDesc>
+where
+    S: 'static + Send + Sync,
+    Out: 'static + Send + Sync,
+{
+    let (c2, c2_index) = match &input[0] {
+        InputTypes::Ref(c, i) => (*c, *i),
+        InputTypes::Owned(c, i) => (c, i),
+    };
+    let states = c2.column().downcast_ref::<S>()?;
+    let c1_data = c1.column_mut().downcast_vec::<Out>()?;
+    if c2_index.is_some() {
+        let c2_index = c2_index.downcast_ref()?;
+        c1_data.extend(c2_index.iter().map(|i| finalize(&states[*i])));
+    } else {
+        c1_data.extend(states.iter().map(finalize));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use radix_column::*;
+
+//Validity of the rows of a kernel input. Unless the input is read through an index,
+//the bitmap of the output can be computed a word at a time instead of row by row
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Validity<'a> {
+    //Every row has the same validity: const columns, and columns without a bitmap or without nulls
+    Const(bool),
+    Rows(BitmapRef<'a>),
+    Indexed,
+}
+
+impl<'a> Validity<'a> {
+    pub(crate) fn new(c: &'a ColumnWrapper, index: &ColumnDataIndex) -> Self {
+        match c.bitmap().downcast_ref() {
+            Err(_) => Validity::Const(true),
+            Ok(bitmap) if c.column().is_const() => Validity::Const(bitmap[0]),
+            //A bitmap with all its words full is treated as no bitmap at all
+            Ok(bitmap) if bitmap.all_valid() => Validity::Const(true),
+            Ok(_) if index.is_some() => Validity::Indexed,
+            Ok(bitmap) => Validity::Rows(bitmap),
+        }
+    }
+
+    //Output bitmap of a one input operation, None if it has to be computed row by row
+    pub(crate) fn map(self, len: usize, f: impl Fn(&bool) -> bool) -> Option<Bitmap> {
+        match self {
+            Validity::Const(v) => Some(Bitmap::filled(f(&v), len)),
+            Validity::Rows(bitmap) => Some(Bitmap::map(bitmap, |v| f(&v))),
+            Validity::Indexed => None,
+        }
+    }
+
+    //Output bitmap of a two inputs operation, None if it has to be computed row by row
+    pub(crate) fn zip_map(
+        self,
+        other: Validity,
+        len: usize,
+        f: impl Fn(&bool, &bool) -> bool,
+    ) -> Option<Bitmap> {
+        match (self, other) {
+            (Validity::Indexed, _) | (_, Validity::Indexed) => None,
+            (Validity::Const(v2), Validity::Const(v3)) => Some(Bitmap::filled(f(&v2, &v3), len)),
+            (Validity::Const(v2), Validity::Rows(b3)) => Some(Bitmap::map(b3, |v3| f(&v2, &v3))),
+            (Validity::Rows(b2), Validity::Const(v3)) => Some(Bitmap::map(b2, |v2| f(&v2, &v3))),
+            (Validity::Rows(b2), Validity::Rows(b3)) => {
+                Some(Bitmap::zip_map(b2, b3, |v2, v3| f(&v2, &v3)))
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{
+    compact_group_ids, take_rows, Batch, BoxedOperator, ExecutionBuffers, ExpressionInput,
+    PhysicalOperator,
+};
+
+pub struct AggregateExpr {
+    pub op: String,
+    pub input: ExpressionInput<'static>,
+}
+
+//Aggregation runs in two phases: every batch is aggregated on its own by PartialAggregateOperator,
+//afterwards final_aggregate merges the partial results of all batches
+pub struct PartialAggregateOperator<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    group_by: &'p [ExpressionInput<'static>],
+    aggregates: &'p [AggregateExpr],
+}
+
+impl<'p, 't> PartialAggregateOperator<'p, 't> {
+    pub fn new(
+        input: BoxedOperator<'p, 't>,
+        group_by: &'p [ExpressionInput<'static>],
+        aggregates: &'p [AggregateExpr],
+    ) -> Self {
+        Self {
+            input,
+            group_by,
+            aggregates,
+        }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for PartialAggregateOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "PartialAggregate"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        match self.input.next(dict, buffers)? {
+            Some(batch) => Ok(Some(aggregate_batch(
+                dict,
+                buffers,
+                &batch,
+                self.group_by,
+                self.aggregates,
+            )?)),
+            None => Ok(None),
+        }
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
+
+//Aggregate which combines partial results of the given aggregate
+fn merge_op<'a>(dict: &'a Dictionary, op: &'a str) -> &'a str {
+    match dict.aggregate(op) {
+        Some(aggregate) => &aggregate.merge_op,
+        None => op,
+    }
+}
+
+//Merges partial aggregates, the batches hold the group by columns followed by the aggregates
+pub fn final_aggregate(
+    dict: &Dictionary,
+    buffers: &mut ExecutionBuffers,
+    partial: &[Batch],
+    number_of_keys: usize,
+    aggregates: &[AggregateExpr],
+) -> Result<Batch<'static>, ErrorDesc> {
+    let batch = Batch::concat(dict, partial)?;
+    let group_by: Vec<_> = (0..number_of_keys).map(ExpressionInput::Column).collect();
+    let merge: Vec<_> = aggregates
+        .iter()
+        .enumerate()
+        .map(|(i, a)| AggregateExpr {
+            op: merge_op(dict, &a.op).to_string(),
+            input: ExpressionInput::Column(number_of_keys + i),
+        })
+        .collect();
+    let mut res = aggregate_batch(dict, buffers, &batch, &group_by, &merge)?;
+
+    //User-defined aggregates turn their merged states into the output
+    for (i, a) in aggregates.iter().enumerate() {
+        let column_id = number_of_keys + i;
+        let partial_type_id = res.columns[column_id].column().item_type_id();
+        let op = match dict.aggregate_finalize(&a.op, partial_type_id) {
+            Some(op) => op,
+            None => continue,
+        };
+        let no_index = ColumnDataIndex::None;
+        let output = buffers.buffer.pop_for(dict, op.output_type_id, |output| {
+            (op.f)(
+                output,
+                &no_index,
+                &[InputTypes::Ref(&res.columns[column_id], &no_index)],
+            )
+        })?;
+        let states = std::mem::replace(&mut res.columns[column_id], output);
+        buffers.buffer.push(dict, states);
+    }
+    Ok(res)
+}
+
+//Outputs one row per group: the group by values followed by the aggregates
+//Without group by expressions all rows form a single group
+pub(crate) fn aggregate_batch(
+    dict: &Dictionary,
+    buffers: &mut ExecutionBuffers,
+    batch: &Batch,
+    group_by: &[ExpressionInput],
+    aggregates: &[AggregateExpr],
+) -> Result<Batch<'static>, ErrorDesc> {
+    let keys: Result<Vec<_>, ErrorDesc> = group_by
+        .iter()
+        .map(|g| batch.eval(dict, buffers, g))
+        .collect();
+    let keys = keys?;
+
+    let (group_ids, number_of_groups, first_rows) = if keys.is_empty() {
+        (vec![0; batch.number_of_rows], 1, vec![])
+    } else {
+        let mut group_ids = vec![];
+        for k in keys.iter() {
+            let (c, index) = match k {
+                InputTypes::Ref(c, index) => (*c, *index),
+                InputTypes::Owned(c, index) => (c, index),
+            };
+            c.group_in(
+                dict,
+                index,
+                &mut group_ids,
+                &mut buffers.hashmap_buffer,
+                &mut buffers.hashmap_binary,
+            )?;
+        }
+        let first_rows: Vec<usize> = (0..group_ids.len())
+            .filter(|i| group_ids[*i] == *i)
+            .collect();
+        let number_of_groups = compact_group_ids(&mut group_ids);
+        (group_ids, number_of_groups, first_rows)
+    };
+
+    let mut columns = Vec::with_capacity(keys.len() + aggregates.len());
+    for k in keys.into_iter() {
+        let c = match &k {
+            InputTypes::Ref(c, index) => take_rows(dict, c, index, Some(&first_rows))?,
+            InputTypes::Owned(c, index) => take_rows(dict, c, index, Some(&first_rows))?,
+        };
+        if let InputTypes::Owned(c, _) = k {
+            buffers.buffer.push(dict, c);
+        }
+        columns.push(c);
+    }
+
+    let group_ids = ColumnWrapper::new_from_vec(dict, group_ids);
+    let number_of_groups_const = ColumnWrapper::new_const(dict, number_of_groups);
+    let no_index = ColumnDataIndex::None;
+    for aggregate in aggregates.iter() {
+        let input = batch.eval(dict, buffers, &aggregate.input)?;
+        let item_type_id = match &input {
+            InputTypes::Ref(c, _) => c.column().item_type_id(),
+            InputTypes::Owned(c, _) => c.column().item_type_id(),
+        };
+        let signature = Signature::new(&aggregate.op, vec![item_type_id]);
+        let op = match dict.op.get(&signature) {
+            Some(op) => op,
+            None => Err(signature.not_found(dict))?,
+        };
+        let input = vec![
+            input,
+            InputTypes::Ref(&group_ids, &no_index),
+            InputTypes::Ref(&number_of_groups_const, &no_index),
+        ];
+        let output = buffers.buffer.pop_for(dict, op.output_type_id, |output| {
+            dict.call_op(&signature, output, &no_index, &input)
+        })?;
+        columns.push(output);
+        input.into_iter().for_each(|inp| {
+            if let InputTypes::Owned(c, _) = inp {
+                buffers.buffer.push(dict, c)
+            }
+        });
+    }
+
+    Ok(Batch::new(columns, number_of_groups))
+}
@@ -0,0 +1,284 @@
+use std::{collections::HashMap, ops::Range};
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{ExecutionBuffers, ExpressionInput, Table};
+
+static NO_INDEX: ColumnDataIndex<'static> = ColumnDataIndex::None;
+
+//A part of a table flowing through a pipeline, usually one partition
+//Like in a table, the rows of a column are selected by its index if it has one
+pub struct Batch<'t> {
+    pub columns: Vec<ColumnWrapper<'t>>,
+    pub indexes: Vec<ColumnDataIndex<'t>>,
+    pub columnindexmap: HashMap<usize, usize>,
+    pub number_of_rows: usize,
+}
+
+impl<'t> Batch<'t> {
+    //Creates a batch from columns without an index
+    pub fn new(columns: Vec<ColumnWrapper<'t>>, number_of_rows: usize) -> Self {
+        Self {
+            columns,
+            indexes: vec![],
+            columnindexmap: HashMap::new(),
+            number_of_rows,
+        }
+    }
+
+    //Borrows the given columns of a table partition together with their indexes, no data is copied
+    pub fn from_table_partition(
+        dict: &Dictionary,
+        table: &'t Table<'t>,
+        partition_id: usize,
+        column_ids: &[usize],
+    ) -> Result<Self, ErrorDesc> {
+        let number_of_rows = table.partition_sizes()[partition_id];
+        Self::from_table_rows(dict, table, partition_id, 0..number_of_rows, column_ids)
+    }
+
+    //Borrows a range of rows of a table partition, indexed columns are borrowed whole with a part of their index
+    pub fn from_table_rows(
+        dict: &Dictionary,
+        table: &'t Table<'t>,
+        partition_id: usize,
+        rows: Range<usize>,
+        column_ids: &[usize],
+    ) -> Result<Self, ErrorDesc> {
+        let table_columns = table.partition_columns(partition_id)?;
+        let table_indexes = table.partition_indexes(partition_id)?;
+        let partition_size = table.partition_sizes()[partition_id];
+        if rows.start > rows.end || rows.end > partition_size {
+            Err(format!(
+                "Rows {:?} out of bounds while the partition has only {} rows",
+                rows, partition_size
+            ))?
+        }
+        let whole_partition = rows.start == 0 && rows.end == partition_size;
+
+        let mut columns = Vec::with_capacity(column_ids.len());
+        let mut indexes = vec![];
+        let mut columnindexmap = HashMap::new();
+        //Index positions in the table mapped to index positions in the batch
+        let mut index_positions: HashMap<usize, usize> = HashMap::new();
+
+        for (i, column_id) in column_ids.iter().enumerate() {
+            let c = match table_columns.get(*column_id) {
+                Some(c) => c,
+                None => Err(format!(
+                    "Column index out of bounds: {} while the table has only {} columns",
+                    column_id,
+                    table_columns.len()
+                ))?,
+            };
+            let table_index_pos = table.column_index_map().get(column_id);
+            if whole_partition || table_index_pos.is_some() {
+                columns.push(c.view(dict)?);
+            } else {
+                columns.push(c.view_range(dict, rows.clone())?);
+            }
+            if let Some(table_index_pos) = table_index_pos {
+                let index_pos = match index_positions.get(table_index_pos) {
+                    Some(index_pos) => *index_pos,
+                    None => {
+                        let index = &table_indexes[*table_index_pos];
+                        let index = &index.downcast_ref()?[rows.clone()];
+                        indexes.push(ColumnDataIndex::new_from_slice(index));
+                        index_positions.insert(*table_index_pos, indexes.len() - 1);
+                        indexes.len() - 1
+                    }
+                };
+                columnindexmap.insert(i, index_pos);
+            }
+        }
+
+        Ok(Self {
+            columns,
+            indexes,
+            columnindexmap,
+            number_of_rows: rows.len(),
+        })
+    }
+
+    pub fn number_of_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn column_index(&self, column_id: usize) -> &ColumnDataIndex<'t> {
+        match self.columnindexmap.get(&column_id) {
+            Some(i) => &self.indexes[*i],
+            None => &NO_INDEX,
+        }
+    }
+
+    pub fn input(&self, column_id: usize) -> InputTypes<'_> {
+        InputTypes::Ref(&self.columns[column_id], self.column_index(column_id))
+    }
+
+    //Bytes owned by the columns and indexes of the batch, borrowed data is not counted
+    pub fn allocated_bytes(&self, dict: &Dictionary) -> Result<usize, ErrorDesc> {
+        let mut bytes = 0;
+        for c in self.columns.iter() {
+            bytes += c.allocated_bytes(dict)?;
+        }
+        for index in self.indexes.iter().filter(|index| index.is_owned()) {
+            bytes += std::mem::size_of_val(index.downcast_ref()?);
+        }
+        Ok(bytes)
+    }
+
+    //Column ids of the expression refer to the columns of the batch
+    pub fn eval<'s>(
+        &'s self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+        input: &'s ExpressionInput,
+    ) -> Result<InputTypes<'s>, ErrorDesc> {
+        match input {
+            ExpressionInput::Column(column_id) => {
+                if *column_id >= self.columns.len() {
+                    Err(format!(
+                        "Column index out of bounds: {} while the batch has only {} columns",
+                        column_id,
+                        self.columns.len()
+                    ))?
+                }
+                Ok(self.input(*column_id))
+            }
+            ExpressionInput::Const(c) => Ok(InputTypes::Ref(c, &NO_INDEX)),
+            ExpressionInput::OwnedConst(c) => Ok(InputTypes::Ref(c, &NO_INDEX)),
+            ExpressionInput::Expr(expr) => expr.eval(
+                dict,
+                &mut buffers.buffer,
+                &mut buffers.hashmap_buffer,
+                &mut buffers.hashmap_binary,
+                &self.columns,
+                &self.indexes,
+                &self.columnindexmap,
+            ),
+        }
+    }
+
+    //Copies the given rows of a column into an owned column, all rows are copied if rows is None
+    pub fn take(
+        &self,
+        dict: &Dictionary,
+        column_id: usize,
+        rows: Option<&[usize]>,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        take_rows(
+            dict,
+            &self.columns[column_id],
+            self.column_index(column_id),
+            rows,
+        )
+    }
+
+    //Copies all columns, the result does not borrow anything and has no indexes
+    pub fn materialize(&self, dict: &Dictionary) -> Result<Batch<'static>, ErrorDesc> {
+        let columns: Result<Vec<_>, ErrorDesc> = (0..self.columns.len())
+            .map(|i| self.take(dict, i, None))
+            .collect();
+        Ok(Batch::new(columns?, self.number_of_rows))
+    }
+
+    //Appends the batches one after another, all batches must have the same columns
+    pub fn concat(dict: &Dictionary, batches: &[Batch]) -> Result<Batch<'static>, ErrorDesc> {
+        let number_of_columns = match batches.first() {
+            Some(b) => b.number_of_columns(),
+            None => Err("Concatenation requires at least one batch")?,
+        };
+        if batches
+            .iter()
+            .any(|b| b.number_of_columns() != number_of_columns)
+        {
+            Err("All batches of a concatenation must have the same number of columns")?
+        }
+
+        //Indexed columns have to be copied first
+        let materialized: Result<Vec<_>, ErrorDesc> = batches
+            .iter()
+            .filter(|b| !b.indexes.is_empty())
+            .map(|b| b.materialize(dict))
+            .collect();
+        let materialized = materialized?;
+        let mut materialized = materialized.iter();
+        let batches: Vec<&Batch> = batches
+            .iter()
+            .map(|b| {
+                if b.indexes.is_empty() {
+                    b
+                } else {
+                    materialized.next().unwrap()
+                }
+            })
+            .collect();
+
+        let columns: Result<Vec<_>, ErrorDesc> = (0..number_of_columns)
+            .map(|i| {
+                let src: Vec<_> = batches
+                    .iter()
+                    .map(|b| (&b.columns[i], b.number_of_rows))
+                    .collect();
+                ColumnWrapper::concat(dict, &src)
+            })
+            .collect();
+        let number_of_rows = batches.iter().map(|b| b.number_of_rows).sum();
+        Ok(Batch::new(columns?, number_of_rows))
+    }
+
+    //Keeps only the first rows of the batch, columns without an index get one
+    pub fn truncate(&mut self, len: usize) -> Result<(), ErrorDesc> {
+        if len >= self.number_of_rows {
+            return Ok(());
+        }
+        for index in self.indexes.iter_mut() {
+            if index.is_owned() {
+                index.downcast_vec()?.truncate(len);
+            } else {
+                *index = ColumnDataIndex::new(index.downcast_ref()?[..len].to_vec());
+            }
+        }
+        let columns_without_index: Vec<_> = (0..self.columns.len())
+            .filter(|i| {
+                !self.columnindexmap.contains_key(i) && !self.columns[*i].column().is_const()
+            })
+            .collect();
+        if !columns_without_index.is_empty() {
+            self.indexes.push(ColumnDataIndex::new((0..len).collect()));
+            let index_pos = self.indexes.len() - 1;
+            columns_without_index.into_iter().for_each(|i| {
+                self.columnindexmap.insert(i, index_pos);
+            });
+        }
+        self.number_of_rows = len;
+        Ok(())
+    }
+}
+
+//Maps positions in the index to rows of the column
+pub(crate) fn compose_rows(
+    index: &ColumnDataIndex,
+    rows: &[usize],
+) -> Result<Vec<usize>, ErrorDesc> {
+    if index.is_some() {
+        let index = index.downcast_ref()?;
+        Ok(rows.iter().map(|r| index[*r]).collect())
+    } else {
+        Ok(rows.to_vec())
+    }
+}
+
+//Copies the given rows of a column, the rows refer to positions in the index if the column has one
+pub(crate) fn take_rows(
+    dict: &Dictionary,
+    column: &ColumnWrapper,
+    index: &ColumnDataIndex,
+    rows: Option<&[usize]>,
+) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+    match rows {
+        None => column.take(dict, index),
+        Some(rows) => column.take(dict, &ColumnDataIndex::new(compose_rows(index, rows)?)),
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use radix_column::*;
+use rayon::prelude::*;
+
+use crate::{release_worker_buffers, with_worker_buffers, ExecutionBuffers, Morsel};
+
+pub const DEFAULT_MORSEL_SIZE: usize = 65536;
+pub const DEFAULT_SPILL_BUCKET_BITS: usize = 4;
+
+//How the partitions of a table are cut into tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkingStrategy {
+    //One task per partition
+    Partitions,
+    //Partitions are split into morsels of at most the given number of rows
+    Morsels(usize),
+}
+
+//Shared between the contexts of a query and the code which may cancel it, e.g. a UI thread
+//Operations stop before their next partition or morsel, tasks which are running are completed
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+//Number of tasks of an operation which are completed, a task is a partition or a morsel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+//Called from the worker threads after every completed task
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+//Thread pool, parallelism and memory limits of a query, queries with their own context do not compete for the same workers
+//The pool and the memory budget are shared between the clones of a context
+#[derive(Clone)]
+pub struct ExecutionContext {
+    pool: Arc<rayon::ThreadPool>,
+    max_degree_of_parallelism: usize,
+    chunking: ChunkingStrategy,
+    memory: Arc<MemoryTracker>,
+    //Without a directory, exceeding the memory limit fails the query instead of spilling
+    spill_directory: Option<PathBuf>,
+    spill_bucket_bits: usize,
+    cancellation: CancellationToken,
+    progress: Option<ProgressCallback>,
+}
+
+impl Default for ExecutionContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExecutionContext {
+    //One worker per CPU
+    //Panics if the threads cannot be spawned, use with_threads to handle the error
+    pub fn new() -> Self {
+        Self::with_threads(num_cpus::get()).unwrap()
+    }
+
+    //Tasks run one after another in the order they are given
+    pub fn single_threaded() -> Self {
+        Self::with_threads(1).unwrap()
+    }
+
+    //Fails if the operating system cannot spawn the threads of the pool
+    pub fn with_threads(number_of_threads: usize) -> Result<Self, ErrorDesc> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(number_of_threads.max(1))
+            .build()
+            .map_err(|e| format!("The thread pool cannot be built: {}", e))?;
+        Ok(Self::with_pool(Arc::new(pool)))
+    }
+
+    //Shares the pool with other contexts, e.g. to cap the total number of threads of several queries
+    pub fn with_pool(pool: Arc<rayon::ThreadPool>) -> Self {
+        Self {
+            max_degree_of_parallelism: pool.current_num_threads(),
+            pool,
+            chunking: ChunkingStrategy::Morsels(DEFAULT_MORSEL_SIZE),
+            memory: Arc::new(MemoryTracker::unlimited()),
+            spill_directory: None,
+            spill_bucket_bits: DEFAULT_SPILL_BUCKET_BITS,
+            cancellation: CancellationToken::new(),
+            progress: None,
+        }
+    }
+
+    //At most the given number of tasks run at the same time, even if the pool has more threads
+    pub fn with_max_degree_of_parallelism(mut self, max_degree_of_parallelism: usize) -> Self {
+        self.max_degree_of_parallelism = max_degree_of_parallelism.max(1);
+        self
+    }
+
+    pub fn with_chunking(mut self, chunking: ChunkingStrategy) -> Self {
+        self.chunking = match chunking {
+            ChunkingStrategy::Morsels(size) => ChunkingStrategy::Morsels(size.max(1)),
+            c => c,
+        };
+        self
+    }
+
+    //Reservations of the query fail with MemoryLimitExceeded once more than limit bytes are reserved
+    pub fn with_memory_limit(self, limit: usize) -> Self {
+        self.with_memory(Arc::new(MemoryTracker::with_limit(limit)))
+    }
+
+    pub fn with_memory(mut self, memory: Arc<MemoryTracker>) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn memory(&self) -> &Arc<MemoryTracker> {
+        &self.memory
+    }
+
+    //Operations exceeding the memory limit write their input to temporary files in the directory
+    //and process it one bucket at a time
+    pub fn with_spilling<D: Into<PathBuf>>(mut self, directory: D) -> Self {
+        self.spill_directory = Some(directory.into());
+        self
+    }
+
+    //Spilled data is split into 2^bucket_bits buckets
+    pub fn with_spill_bucket_bits(mut self, spill_bucket_bits: usize) -> Self {
+        self.spill_bucket_bits = spill_bucket_bits;
+        self
+    }
+
+    pub fn spill_directory(&self) -> Option<&Path> {
+        self.spill_directory.as_deref()
+    }
+
+    pub fn spill_bucket_bits(&self) -> usize {
+        self.spill_bucket_bits
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn with_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    //Fails with RadixError::Cancelled once the token of the context has been cancelled
+    pub fn check_cancelled(&self) -> Result<(), ErrorDesc> {
+        if self.cancellation.is_cancelled() {
+            Err(RadixError::Cancelled)?
+        }
+        Ok(())
+    }
+
+    pub fn report_progress(&self, completed: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress(Progress { completed, total });
+        }
+    }
+
+    //Drops the buffers kept by the threads of the pool and by the calling thread, so that their memory is released
+    pub fn release_worker_buffers(&self) {
+        self.pool.broadcast(|_| release_worker_buffers());
+        release_worker_buffers();
+    }
+
+    pub fn number_of_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    pub fn degree_of_parallelism(&self) -> usize {
+        self.max_degree_of_parallelism.min(self.number_of_threads())
+    }
+
+    pub fn chunking(&self) -> ChunkingStrategy {
+        self.chunking
+    }
+
+    //Runs f in the pool of the context, parallel iterators used by f run on its threads
+    pub fn install<R, F>(&self, f: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        self.pool.install(f)
+    }
+
+    //Every partition gets at least one morsel, even an empty one
+    pub fn morsels(&self, partition_sizes: &[usize]) -> Vec<Morsel> {
+        let mut morsels = vec![];
+        for (partition_id, size) in partition_sizes.iter().enumerate() {
+            let morsel_size = match self.chunking {
+                ChunkingStrategy::Partitions => (*size).max(1),
+                ChunkingStrategy::Morsels(morsel_size) => morsel_size,
+            };
+            let mut start = 0;
+            loop {
+                let end = (start + morsel_size).min(*size);
+                morsels.push(Morsel {
+                    partition_id,
+                    rows: start..end,
+                });
+                if end == *size {
+                    break;
+                }
+                start = end;
+            }
+        }
+        morsels
+    }
+
+    //Calls f for every partition, every partition is a task of its own
+    pub fn for_each_partition<P, F>(&self, partitions: &mut [P], f: F) -> Result<(), ErrorDesc>
+    where
+        P: Send,
+        F: Fn(&mut ExecutionBuffers, &mut P) -> Result<(), ErrorDesc> + Sync,
+    {
+        let tasks: Vec<_> = partitions.iter_mut().collect();
+        self.map_tasks(tasks, |buffers, p| {
+            f(buffers, p)?;
+            Ok(vec![])
+        })
+        .map(|_: Vec<()>| ())
+    }
+
+    //Calls f for every task and collects the results in the order of the tasks
+    //Every task is a rayon job of its own, idle threads of the pool steal them from busy ones
+    //If the degree of parallelism is below the number of threads, at most that many workers take the tasks from a shared queue
+    //The cancellation token is checked before every task and progress is reported after every task
+    pub fn map_tasks<P, R, F>(&self, tasks: Vec<P>, f: F) -> Result<Vec<R>, ErrorDesc>
+    where
+        P: Send,
+        R: Send,
+        F: Fn(&mut ExecutionBuffers, P) -> Result<Vec<R>, ErrorDesc> + Sync,
+    {
+        let number_of_tasks = tasks.len();
+        let completed = AtomicUsize::new(0);
+        let run_task = |task: P| -> Result<Vec<R>, ErrorDesc> {
+            self.check_cancelled()?;
+            let res = with_worker_buffers(&self.memory, |buffers| f(buffers, task))?;
+            let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            self.report_progress(completed, number_of_tasks);
+            Ok(res)
+        };
+
+        let results: Vec<_> = if self.degree_of_parallelism() >= self.number_of_threads() {
+            self.install(|| {
+                tasks
+                    .into_par_iter()
+                    .with_max_len(1)
+                    .map(run_task)
+                    .collect()
+            })
+        } else {
+            let tasks: Vec<_> = tasks.into_iter().map(|t| Mutex::new(Some(t))).collect();
+            let results: Vec<_> = (0..number_of_tasks).map(|_| Mutex::new(None)).collect();
+            let next_task = AtomicUsize::new(0);
+            let number_of_workers = self.degree_of_parallelism().min(number_of_tasks);
+            self.install(|| {
+                (0..number_of_workers).into_par_iter().for_each(|_| loop {
+                    let i = next_task.fetch_add(1, Ordering::Relaxed);
+                    if i >= number_of_tasks {
+                        break;
+                    }
+                    let task = tasks[i].lock().unwrap().take().unwrap();
+                    *results[i].lock().unwrap() = Some(run_task(task));
+                })
+            });
+            results
+                .into_iter()
+                .map(|res| res.into_inner().unwrap().unwrap())
+                .collect()
+        };
+
+        let mut output = vec![];
+        for res in results.into_iter() {
+            output.append(&mut res?);
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,322 @@
+use std::{any::TypeId, fmt::Write};
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{
+    item_type_name, AggregateExpr, ExpressionInput, OperatorMetrics, PhysicalPlan, QueryProfile,
+    Schema, TableExpression,
+};
+
+impl PhysicalPlan {
+    //Types of the output columns, the signatures of the expressions are resolved with the dictionary
+    pub fn output_types(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+    ) -> Result<Vec<TypeId>, ErrorDesc> {
+        match self {
+            PhysicalPlan::Scan { table, columns } => {
+                let table = schema.get(table)?;
+                columns
+                    .iter()
+                    .map(|c| Ok(table.column_schema(c)?.item_type_id))
+                    .collect()
+            }
+            PhysicalPlan::Filter { input, .. }
+            | PhysicalPlan::Sort { input, .. }
+            | PhysicalPlan::Limit { input, .. } => input.output_types(dict, schema),
+            PhysicalPlan::Project { input, exprs } => {
+                let input = input.output_types(dict, schema)?;
+                exprs
+                    .iter()
+                    .map(|e| explain_expression(dict, e, &input, 0, None))
+                    .collect()
+            }
+            PhysicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let input = input.output_types(dict, schema)?;
+                let mut types = Vec::with_capacity(group_by.len() + aggregates.len());
+                for g in group_by.iter() {
+                    types.push(explain_expression(dict, g, &input, 0, None)?);
+                }
+                for a in aggregates.iter() {
+                    types.push(explain_aggregate(dict, a, &input, 0, None)?);
+                }
+                Ok(types)
+            }
+            PhysicalPlan::HashJoin { probe, build, .. } => {
+                let mut types = probe.output_types(dict, schema)?;
+                types.append(&mut build.output_types(dict, schema)?);
+                Ok(types)
+            }
+        }
+    }
+
+    //Prints the plan as a tree, every expression is printed with the signature resolved for its input
+    //With a profile, the metrics of the operators which ran for a node are printed below the node
+    pub fn explain(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        profile: Option<&QueryProfile>,
+    ) -> Result<String, ErrorDesc> {
+        let mut out = String::new();
+        self.explain_node(dict, schema, profile, 0, &mut out)?;
+        Ok(out)
+    }
+
+    fn explain_node(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        profile: Option<&QueryProfile>,
+        depth: usize,
+        out: &mut String,
+    ) -> Result<(), ErrorDesc> {
+        let indent = "  ".repeat(depth);
+        let header = match self {
+            PhysicalPlan::Scan { table, columns } => format!("Scan: {} {:?}", table, columns),
+            PhysicalPlan::Filter { .. } => "Filter".to_string(),
+            PhysicalPlan::Project { .. } => "Project".to_string(),
+            PhysicalPlan::Aggregate { .. } => "Aggregate".to_string(),
+            PhysicalPlan::HashJoin { on, .. } => format!("HashJoin: on {:?}", on),
+            PhysicalPlan::Sort { .. } => "Sort".to_string(),
+            PhysicalPlan::Limit { limit, .. } => format!("Limit: {}", limit),
+        };
+        writeln!(out, "{}{}", indent, header)?;
+        if let Some(profile) = profile {
+            for operator in profile.operators(self).iter() {
+                let total = operator.total();
+                writeln!(
+                    out,
+                    "{}  [{}] partitions={} {}",
+                    indent,
+                    operator.name,
+                    operator.partitions.len(),
+                    format_metrics(&total)
+                )?;
+                for m in operator.partitions.iter() {
+                    writeln!(
+                        out,
+                        "{}    partition {}: {}",
+                        indent,
+                        m.partition,
+                        format_metrics(m)
+                    )?;
+                }
+            }
+        }
+
+        let depth = depth + 1;
+        let label = |out: &mut String, label: &str| write!(out, "{}  {}: ", indent, label);
+        match self {
+            PhysicalPlan::Scan { .. } => {}
+            PhysicalPlan::Filter { input, predicates } => {
+                let types = input.output_types(dict, schema)?;
+                for p in predicates.iter() {
+                    label(out, "predicate")?;
+                    explain_table_expression(dict, p, &types, depth, Some(&mut *out))?;
+                }
+                input.explain_node(dict, schema, profile, depth, out)?;
+            }
+            PhysicalPlan::Project { input, exprs } => {
+                let types = input.output_types(dict, schema)?;
+                for e in exprs.iter() {
+                    label(out, "expr")?;
+                    explain_expression(dict, e, &types, depth, Some(&mut *out))?;
+                }
+                input.explain_node(dict, schema, profile, depth, out)?;
+            }
+            PhysicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let types = input.output_types(dict, schema)?;
+                for g in group_by.iter() {
+                    label(out, "group by")?;
+                    explain_expression(dict, g, &types, depth, Some(&mut *out))?;
+                }
+                for a in aggregates.iter() {
+                    label(out, "aggregate")?;
+                    explain_aggregate(dict, a, &types, depth, Some(&mut *out))?;
+                }
+                input.explain_node(dict, schema, profile, depth, out)?;
+            }
+            PhysicalPlan::HashJoin { probe, build, on } => {
+                let probe_types = probe.output_types(dict, schema)?;
+                let build_types = build.output_types(dict, schema)?;
+                for (l, r) in on.iter() {
+                    label(out, "key")?;
+                    let signature = Signature::new("==", vec![probe_types[*l], build_types[*r]]);
+                    write_signature(dict, &signature, out)?;
+                }
+                writeln!(out, "{}  probe:", indent)?;
+                probe.explain_node(dict, schema, profile, depth + 1, out)?;
+                writeln!(out, "{}  build:", indent)?;
+                build.explain_node(dict, schema, profile, depth + 1, out)?;
+            }
+            PhysicalPlan::Sort { input, order_by } => {
+                let types = input.output_types(dict, schema)?;
+                for (o, ascending) in order_by.iter() {
+                    label(
+                        out,
+                        if *ascending {
+                            "order by ASC"
+                        } else {
+                            "order by DESC"
+                        },
+                    )?;
+                    explain_expression(dict, o, &types, depth, Some(&mut *out))?;
+                }
+                input.explain_node(dict, schema, profile, depth, out)?;
+            }
+            PhysicalPlan::Limit { input, .. } => {
+                input.explain_node(dict, schema, profile, depth, out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn format_metrics(m: &OperatorMetrics) -> String {
+    format!(
+        "time={:?} rows_in={} rows_out={} bytes_allocated={} buffer_reuse_hits={}",
+        m.elapsed, m.rows_in, m.rows_out, m.bytes_allocated, m.buffer_reuse_hits
+    )
+}
+
+//Prints op(input types) -> output type, the output type is the one of the operation found in the dictionary
+fn write_signature(
+    dict: &Dictionary,
+    signature: &Signature,
+    out: &mut String,
+) -> Result<TypeId, ErrorDesc> {
+    let op = match dict.op.get(signature) {
+        Some(op) => op,
+        None => Err(signature.not_found(dict))?,
+    };
+    let input: Result<Vec<_>, ErrorDesc> = signature
+        .clone()
+        .to_input()
+        .into_iter()
+        .map(|t| item_type_name(dict, t))
+        .collect();
+    writeln!(
+        out,
+        "{}({}) -> {}",
+        signature.op_name(),
+        input?.join(", "),
+        item_type_name(dict, op.output_type_id)?
+    )?;
+    Ok(op.output_type_id)
+}
+
+//Returns the type of the expression, it is printed as a tree if out is given
+//The first line is written at the current position of out, the inputs of an operation are indented by depth
+fn explain_expression(
+    dict: &Dictionary,
+    expr: &ExpressionInput,
+    input: &[TypeId],
+    depth: usize,
+    out: Option<&mut String>,
+) -> Result<TypeId, ErrorDesc> {
+    match expr {
+        ExpressionInput::Column(column_id) => {
+            let item_type_id = match input.get(*column_id) {
+                Some(t) => *t,
+                None => Err(format!(
+                    "Column index out of bounds: {} while the input has only {} columns",
+                    column_id,
+                    input.len()
+                ))?,
+            };
+            if let Some(out) = out {
+                writeln!(
+                    out,
+                    "#{}: {}",
+                    column_id,
+                    item_type_name(dict, item_type_id)?
+                )?;
+            }
+            Ok(item_type_id)
+        }
+        ExpressionInput::Const(c) => explain_const(dict, c, out),
+        ExpressionInput::OwnedConst(c) => explain_const(dict, c, out),
+        ExpressionInput::Expr(e) => explain_table_expression(dict, e, input, depth, out),
+    }
+}
+
+//The signature is built from the types of the inputs, partition by expressions are not part of it
+fn explain_table_expression(
+    dict: &Dictionary,
+    expr: &TableExpression,
+    input: &[TypeId],
+    depth: usize,
+    out: Option<&mut String>,
+) -> Result<TypeId, ErrorDesc> {
+    let inputs: Result<Vec<_>, ErrorDesc> = expr
+        .input
+        .iter()
+        .map(|i| explain_expression(dict, i, input, 0, None))
+        .collect();
+    let signature = Signature::new(&expr.op, inputs?);
+    let out = match out {
+        Some(out) => out,
+        None => return write_signature(dict, &signature, &mut String::new()),
+    };
+    let indent = "  ".repeat(depth + 1);
+    let output_type_id = write_signature(dict, &signature, out)?;
+    for i in expr.input.iter() {
+        write!(out, "{}", indent)?;
+        explain_expression(dict, i, input, depth + 1, Some(&mut *out))?;
+    }
+    for p in expr.partition_by.iter() {
+        write!(out, "{}partition by: ", indent)?;
+        explain_expression(dict, p, input, depth + 1, Some(&mut *out))?;
+    }
+    Ok(output_type_id)
+}
+
+fn explain_const(
+    dict: &Dictionary,
+    c: &ColumnWrapper,
+    out: Option<&mut String>,
+) -> Result<TypeId, ErrorDesc> {
+    let item_type_id = c.column().item_type_id();
+    if let Some(out) = out {
+        let value = c.as_string(dict, &ColumnDataIndex::None)?;
+        writeln!(
+            out,
+            "const {}: {}",
+            value.join(", "),
+            item_type_name(dict, item_type_id)?
+        )?;
+    }
+    Ok(item_type_id)
+}
+
+fn explain_aggregate(
+    dict: &Dictionary,
+    aggregate: &AggregateExpr,
+    input: &[TypeId],
+    depth: usize,
+    out: Option<&mut String>,
+) -> Result<TypeId, ErrorDesc> {
+    let item_type_id = explain_expression(dict, &aggregate.input, input, 0, None)?;
+    let signature = Signature::new(&aggregate.op, vec![item_type_id]);
+    let partial_type_id = match out {
+        Some(out) => {
+            let partial_type_id = write_signature(dict, &signature, out)?;
+            write!(out, "{}", "  ".repeat(depth + 1))?;
+            explain_expression(dict, &aggregate.input, input, depth + 1, Some(out))?;
+            partial_type_id
+        }
+        None => write_signature(dict, &signature, &mut String::new())?,
+    };
+    dict.aggregate_output_type(&aggregate.op, partial_type_id)
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{compose_rows, Batch, BoxedOperator, ExecutionBuffers, PhysicalOperator};
+
+//Build side of a hash join, all its batches are copied into a single batch before probing starts
+pub struct HashJoinBuild {
+    batch: Batch<'static>,
+    hashes: HashMap<u64, Vec<usize>, ahash::RandomState>,
+}
+
+impl HashJoinBuild {
+    pub fn new(dict: &Dictionary, batches: &[Batch], keys: &[usize]) -> Result<Self, ErrorDesc> {
+        let batch = Batch::concat(dict, batches)?;
+        let mut hashes: HashMap<u64, Vec<usize>, ahash::RandomState> =
+            HashMap::with_capacity_and_hasher(batch.number_of_rows, ahash::RandomState::default());
+        hash_keys(dict, &batch, keys)?
+            .into_iter()
+            .enumerate()
+            .for_each(|(row, h)| hashes.entry(h).or_default().push(row));
+        Ok(Self { batch, hashes })
+    }
+
+    pub fn batch(&self) -> &Batch<'static> {
+        &self.batch
+    }
+}
+
+fn hash_keys(dict: &Dictionary, batch: &Batch, keys: &[usize]) -> Result<Vec<u64>, ErrorDesc> {
+    let mut hash = Vec::with_capacity(batch.number_of_rows);
+    for k in keys {
+        batch.columns[*k].hash_in(dict, batch.column_index(*k), &mut hash)?;
+    }
+    Ok(hash)
+}
+
+//Probe side of an inner hash join, streams the batches of its input
+//Outputs the columns of the probe side followed by the columns of the build side
+pub struct HashJoinProbe<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    build: &'p HashJoinBuild,
+    on: &'p [(usize, usize)],
+}
+
+impl<'p, 't> HashJoinProbe<'p, 't> {
+    pub fn new(
+        input: BoxedOperator<'p, 't>,
+        build: &'p HashJoinBuild,
+        on: &'p [(usize, usize)],
+    ) -> Self {
+        Self { input, build, on }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for HashJoinProbe<'p, 't> {
+    fn name(&self) -> &'static str {
+        "HashJoinProbe"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        let batch = match self.input.next(dict, buffers)? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        let build = &self.build.batch;
+
+        let left_keys: Vec<_> = self.on.iter().map(|(l, _)| *l).collect();
+        let mut left_rows = vec![];
+        let mut right_rows = vec![];
+        hash_keys(dict, &batch, &left_keys)?
+            .iter()
+            .enumerate()
+            .for_each(|(row, h)| {
+                if let Some(rows) = self.build.hashes.get(h) {
+                    rows.iter().for_each(|r| {
+                        left_rows.push(row);
+                        right_rows.push(*r);
+                    });
+                }
+            });
+
+        //Rows with equal hashes are compared, nulls are never equal
+        if !left_rows.is_empty() {
+            let mut keep = vec![true; left_rows.len()];
+            let right_index = ColumnDataIndex::new(right_rows.clone());
+            for (l, r) in self.on.iter() {
+                let left_index =
+                    ColumnDataIndex::new(compose_rows(batch.column_index(*l), &left_rows)?);
+                let (left, right) = (&batch.columns[*l], &build.columns[*r]);
+                let signature = Signature::new(
+                    "==",
+                    vec![left.column().item_type_id(), right.column().item_type_id()],
+                );
+                let op = match dict.op.get(&signature) {
+                    Some(op) => op,
+                    None => Err(signature.not_found(dict))?,
+                };
+                let input = [
+                    InputTypes::Ref(left, &left_index),
+                    InputTypes::Ref(right, &right_index),
+                ];
+                let output = buffers.buffer.pop_for(dict, op.output_type_id, |output| {
+                    dict.call_op(&signature, output, &ColumnDataIndex::None, &input)
+                })?;
+                let equal = output.column().downcast_ref::<bool>()?;
+                keep.iter_mut().zip(equal).for_each(|(k, e)| *k &= *e);
+                if !output.bitmap().all_valid() {
+                    let bitmap = output.bitmap().downcast_ref()?;
+                    keep.iter_mut().zip(bitmap).for_each(|(k, b)| *k &= b);
+                }
+                buffers.buffer.push(dict, output);
+            }
+            let mut keep_iter = keep.iter();
+            left_rows.retain(|_| *keep_iter.next().unwrap());
+            let mut keep_iter = keep.iter();
+            right_rows.retain(|_| *keep_iter.next().unwrap());
+        }
+
+        let mut columns = Vec::with_capacity(batch.number_of_columns() + build.number_of_columns());
+        for i in 0..batch.number_of_columns() {
+            columns.push(batch.take(dict, i, Some(&left_rows))?);
+        }
+        for i in 0..build.number_of_columns() {
+            columns.push(build.take(dict, i, Some(&right_rows))?);
+        }
+        Ok(Some(Batch::new(columns, left_rows.len())))
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
@@ -0,0 +1,22 @@
+pub mod aggregate;
+pub mod batch;
+pub mod context;
+pub mod explain;
+pub mod join;
+pub mod operator;
+pub mod physical;
+pub mod profile;
+pub mod scheduler;
+pub mod sort;
+pub mod spill;
+
+pub use aggregate::*;
+pub use batch::*;
+pub use context::*;
+pub use join::*;
+pub use operator::*;
+pub use physical::*;
+pub use profile::*;
+pub use scheduler::*;
+pub use sort::*;
+pub use spill::*;
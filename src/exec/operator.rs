@@ -0,0 +1,300 @@
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{
+    filter_partition, Batch, ExecutionBuffers, ExpressionInput, Morsel, Table, TableExpression,
+};
+
+//Operators pull batches from their input one at a time
+//A batch passes through the whole pipeline before the next one is read
+pub trait PhysicalOperator<'t> {
+    fn name(&self) -> &'static str;
+    fn open(&mut self) -> Result<(), ErrorDesc>;
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc>;
+    fn close(&mut self) -> Result<(), ErrorDesc>;
+}
+
+pub type BoxedOperator<'p, 't> = Box<dyn PhysicalOperator<'t> + 'p>;
+
+//Returns one batch per morsel, the batches borrow the data of the table
+pub struct ScanOperator<'p, 't> {
+    table: &'t Table<'t>,
+    column_ids: &'p [usize],
+    morsels: Vec<Morsel>,
+    position: usize,
+}
+
+impl<'p, 't> ScanOperator<'p, 't> {
+    pub fn new(table: &'t Table<'t>, column_ids: &'p [usize], morsels: Vec<Morsel>) -> Self {
+        Self {
+            table,
+            column_ids,
+            morsels,
+            position: 0,
+        }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for ScanOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "Scan"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.position = 0;
+        Ok(())
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        _buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        match self.morsels.get(self.position) {
+            Some(morsel) => {
+                self.position += 1;
+                let batch = Batch::from_table_rows(
+                    dict,
+                    self.table,
+                    morsel.partition_id,
+                    morsel.rows.clone(),
+                    self.column_ids,
+                )?;
+                Ok(Some(batch))
+            }
+            None => Ok(None),
+        }
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        Ok(())
+    }
+}
+
+//Returns batches produced by a pipeline breaker
+pub struct BatchSource<'t> {
+    batches: std::vec::IntoIter<Batch<'t>>,
+}
+
+impl<'t> BatchSource<'t> {
+    pub fn new(batches: Vec<Batch<'t>>) -> Self {
+        Self {
+            batches: batches.into_iter(),
+        }
+    }
+}
+
+impl<'t> PhysicalOperator<'t> for BatchSource<'t> {
+    fn name(&self) -> &'static str {
+        "BatchSource"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        Ok(())
+    }
+    fn next(
+        &mut self,
+        _dict: &Dictionary,
+        _buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        Ok(self.batches.next())
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        Ok(())
+    }
+}
+
+//Keeps the rows for which all predicates are true, only the indexes of the batch are changed
+pub struct FilterOperator<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    predicates: &'p [TableExpression<'static>],
+}
+
+impl<'p, 't> FilterOperator<'p, 't> {
+    pub fn new(input: BoxedOperator<'p, 't>, predicates: &'p [TableExpression<'static>]) -> Self {
+        Self { input, predicates }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for FilterOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "Filter"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        let mut batch = match self.input.next(dict, buffers)? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        if self.predicates.is_empty() {
+            return Ok(Some(batch));
+        }
+        let columns_without_index: Vec<_> = (0..batch.number_of_columns())
+            .filter(|i| !batch.columnindexmap.contains_key(i))
+            .collect();
+        batch.number_of_rows = filter_partition(
+            dict,
+            buffers,
+            self.predicates,
+            &batch.columns,
+            &mut batch.indexes,
+            &batch.columnindexmap,
+            !columns_without_index.is_empty(),
+        )?;
+        if !columns_without_index.is_empty() {
+            let index_pos = batch.indexes.len() - 1;
+            columns_without_index.into_iter().for_each(|i| {
+                batch.columnindexmap.insert(i, index_pos);
+            });
+        }
+        Ok(Some(batch))
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
+
+//Computes the expressions of the projection, columns which are only passed through are not copied
+pub struct ProjectOperator<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    exprs: &'p [ExpressionInput<'static>],
+}
+
+impl<'p, 't> ProjectOperator<'p, 't> {
+    pub fn new(input: BoxedOperator<'p, 't>, exprs: &'p [ExpressionInput<'static>]) -> Self {
+        Self { input, exprs }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for ProjectOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "Project"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        let batch = match self.input.next(dict, buffers)? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+
+        //Computed columns are evaluated first, while the input columns are still in place
+        let mut computed: Vec<Option<ColumnWrapper<'static>>> =
+            Vec::with_capacity(self.exprs.len());
+        let mut used: Vec<bool> = vec![false; batch.number_of_columns()];
+        for expr in self.exprs.iter() {
+            let c = match expr {
+                ExpressionInput::Column(column_id) => match used.get_mut(*column_id) {
+                    Some(u) if !*u => {
+                        *u = true;
+                        None
+                    }
+                    //A column selected twice is copied
+                    Some(_) => Some(batch.take(dict, *column_id, None)?),
+                    None => Err(format!(
+                        "Column index out of bounds: {} while the batch has only {} columns",
+                        column_id,
+                        batch.number_of_columns()
+                    ))?,
+                },
+                ExpressionInput::Const(c) => Some(c.view(dict)?.into_static()?),
+                ExpressionInput::OwnedConst(c) => Some(c.view(dict)?.into_static()?),
+                ExpressionInput::Expr(_) => match batch.eval(dict, buffers, expr)? {
+                    InputTypes::Owned(c, index) => {
+                        if index.is_some() {
+                            Err("Aggregates cannot be evaluated in a projection")?
+                        }
+                        Some(c)
+                    }
+                    InputTypes::Ref(..) => Err("An expression must evaluate to an owned column")?,
+                },
+            };
+            computed.push(c);
+        }
+
+        let Batch {
+            columns,
+            indexes,
+            columnindexmap,
+            number_of_rows,
+        } = batch;
+        let mut columns: Vec<Option<ColumnWrapper<'t>>> = columns.into_iter().map(Some).collect();
+        let mut output = Batch {
+            columns: Vec::with_capacity(self.exprs.len()),
+            indexes,
+            columnindexmap: Default::default(),
+            number_of_rows,
+        };
+        for (i, (expr, c)) in self.exprs.iter().zip(computed).enumerate() {
+            match (expr, c) {
+                (_, Some(c)) => output.columns.push(c),
+                (ExpressionInput::Column(column_id), None) => {
+                    output.columns.push(columns[*column_id].take().unwrap());
+                    if let Some(index_pos) = columnindexmap.get(column_id) {
+                        output.columnindexmap.insert(i, *index_pos);
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+        //Indexes which were used only by the dropped columns are kept, they are not copied anyway
+        Ok(Some(output))
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
+
+//Stops reading the input once enough rows were returned
+pub struct LimitOperator<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    remaining: usize,
+}
+
+impl<'p, 't> LimitOperator<'p, 't> {
+    pub fn new(input: BoxedOperator<'p, 't>, limit: usize) -> Self {
+        Self {
+            input,
+            remaining: limit,
+        }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for LimitOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "Limit"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let mut batch = match self.input.next(dict, buffers)? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        batch.truncate(self.remaining)?;
+        self.remaining -= batch.number_of_rows;
+        Ok(Some(batch))
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
@@ -0,0 +1,122 @@
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{AggregateExpr, BoundExpr, ExpressionInput, LogicalPlan, TableExpression};
+
+//Executable form of a logical plan, the expressions are translated to table expressions
+//Filter, Project and the probe side of HashJoin are streamed through a pipeline,
+//Aggregate, Sort, Limit and the build side of HashJoin are pipeline breakers
+pub enum PhysicalPlan {
+    Scan {
+        table: String,
+        columns: Vec<usize>,
+    },
+    Filter {
+        input: Box<PhysicalPlan>,
+        predicates: Vec<TableExpression<'static>>,
+    },
+    Project {
+        input: Box<PhysicalPlan>,
+        exprs: Vec<ExpressionInput<'static>>,
+    },
+    Aggregate {
+        input: Box<PhysicalPlan>,
+        group_by: Vec<ExpressionInput<'static>>,
+        aggregates: Vec<AggregateExpr>,
+    },
+    HashJoin {
+        probe: Box<PhysicalPlan>,
+        build: Box<PhysicalPlan>,
+        on: Vec<(usize, usize)>,
+    },
+    Sort {
+        input: Box<PhysicalPlan>,
+        order_by: Vec<(ExpressionInput<'static>, bool)>,
+    },
+    Limit {
+        input: Box<PhysicalPlan>,
+        limit: usize,
+    },
+}
+
+impl PhysicalPlan {
+    pub fn from_logical(dict: &Dictionary, plan: &LogicalPlan) -> Result<Self, ErrorDesc> {
+        let physical_plan = match plan {
+            LogicalPlan::Scan { table, columns } => PhysicalPlan::Scan {
+                table: table.clone(),
+                columns: columns.clone(),
+            },
+            LogicalPlan::Filter { input, predicates } => {
+                let predicates: Result<Vec<_>, ErrorDesc> = predicates
+                    .iter()
+                    .map(|p| match p {
+                        BoundExpr::Op { .. } => p.to_table_expression(dict, &[]),
+                        _ => Err(format!(
+                            "Filter predicate must be an operation, found {:?}",
+                            p
+                        ))?,
+                    })
+                    .collect();
+                PhysicalPlan::Filter {
+                    input: Box::new(PhysicalPlan::from_logical(dict, input)?),
+                    predicates: predicates?,
+                }
+            }
+            LogicalPlan::Project { input, exprs } => {
+                let exprs: Result<Vec<_>, ErrorDesc> = exprs
+                    .iter()
+                    .map(|p| p.expr.to_expression_input(dict, &[]))
+                    .collect();
+                PhysicalPlan::Project {
+                    input: Box::new(PhysicalPlan::from_logical(dict, input)?),
+                    exprs: exprs?,
+                }
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let group_by: Result<Vec<_>, ErrorDesc> = group_by
+                    .iter()
+                    .map(|g| g.to_expression_input(dict, &[]))
+                    .collect();
+                let aggregates: Result<Vec<_>, ErrorDesc> = aggregates
+                    .iter()
+                    .map(|a| match a {
+                        BoundExpr::Aggregate { op, input, .. } => Ok(AggregateExpr {
+                            op: op.clone(),
+                            input: input.to_expression_input(dict, &[])?,
+                        }),
+                        _ => Err(format!("Expected an aggregate, found {:?}", a))?,
+                    })
+                    .collect();
+                PhysicalPlan::Aggregate {
+                    input: Box::new(PhysicalPlan::from_logical(dict, input)?),
+                    group_by: group_by?,
+                    aggregates: aggregates?,
+                }
+            }
+            LogicalPlan::Join { left, right, on } => PhysicalPlan::HashJoin {
+                probe: Box::new(PhysicalPlan::from_logical(dict, left)?),
+                build: Box::new(PhysicalPlan::from_logical(dict, right)?),
+                on: on.clone(),
+            },
+            LogicalPlan::Sort { input, order_by } => {
+                let order_by: Result<Vec<_>, ErrorDesc> = order_by
+                    .iter()
+                    .map(|o| Ok((o.expr.to_expression_input(dict, &[])?, o.ascending)))
+                    .collect();
+                PhysicalPlan::Sort {
+                    input: Box::new(PhysicalPlan::from_logical(dict, input)?),
+                    order_by: order_by?,
+                }
+            }
+            LogicalPlan::Limit { input, limit } => PhysicalPlan::Limit {
+                input: Box::new(PhysicalPlan::from_logical(dict, input)?),
+                limit: *limit,
+            },
+        };
+        Ok(physical_plan)
+    }
+}
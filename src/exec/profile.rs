@@ -0,0 +1,221 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{Batch, BoxedOperator, ExecutionBuffers, PhysicalOperator, PhysicalPlan};
+
+//Metrics of one operator for one partition, the time spent in the input of the operator is not included
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperatorMetrics {
+    pub partition: usize,
+    pub elapsed: Duration,
+    pub rows_in: usize,
+    pub rows_out: usize,
+    pub bytes_allocated: usize,
+    pub buffer_reuse_hits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorProfile {
+    pub name: &'static str,
+    pub partitions: Vec<OperatorMetrics>,
+}
+
+impl OperatorMetrics {
+    //Sums the metrics, the partition is kept
+    pub fn add(&self, other: &OperatorMetrics) -> OperatorMetrics {
+        OperatorMetrics {
+            partition: self.partition,
+            elapsed: self.elapsed + other.elapsed,
+            rows_in: self.rows_in + other.rows_in,
+            rows_out: self.rows_out + other.rows_out,
+            bytes_allocated: self.bytes_allocated + other.bytes_allocated,
+            buffer_reuse_hits: self.buffer_reuse_hits + other.buffer_reuse_hits,
+        }
+    }
+}
+
+impl OperatorProfile {
+    //Sum of the metrics of all partitions
+    pub fn total(&self) -> OperatorMetrics {
+        self.partitions
+            .iter()
+            .fold(OperatorMetrics::default(), |acc, m| acc.add(m))
+    }
+}
+
+//Metrics collected while a physical plan runs, the operators are recorded per node of the plan
+//Nodes are identified by their address, so the plan must not be moved between the run and the lookups
+#[derive(Default)]
+pub struct QueryProfile {
+    operators: Mutex<HashMap<usize, Vec<OperatorProfile>>>,
+}
+
+fn node_key(node: &PhysicalPlan) -> usize {
+    node as *const PhysicalPlan as usize
+}
+
+impl QueryProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Operators which ran for the node, a node can run several operators e.g. a partial and a final aggregate
+    pub fn operators(&self, node: &PhysicalPlan) -> Vec<OperatorProfile> {
+        let operators = self.operators.lock().unwrap();
+        operators.get(&node_key(node)).cloned().unwrap_or_default()
+    }
+
+    //Metrics of all workers are merged into one profile per operator, ordered by partition
+    //Metrics of the morsels of a partition are added up
+    pub(crate) fn record(
+        &self,
+        node: &PhysicalPlan,
+        name: &'static str,
+        metrics: Vec<OperatorMetrics>,
+    ) {
+        let mut operators = self.operators.lock().unwrap();
+        let profiles = operators.entry(node_key(node)).or_default();
+        let profile = match profiles.iter().position(|p| p.name == name) {
+            Some(i) => &mut profiles[i],
+            None => {
+                profiles.push(OperatorProfile {
+                    name,
+                    partitions: vec![],
+                });
+                profiles.last_mut().unwrap()
+            }
+        };
+        for m in metrics.into_iter() {
+            match profile
+                .partitions
+                .iter_mut()
+                .find(|p| p.partition == m.partition)
+            {
+                Some(p) => *p = p.add(&m),
+                None => profile.partitions.push(m),
+            }
+        }
+        profile.partitions.sort_by_key(|m| m.partition);
+    }
+}
+
+//Running totals of an operator including its input, the next operator of the pipeline
+//subtracts them from its own values to get the share of its own work
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ProfileTotals {
+    elapsed: Duration,
+    rows: usize,
+    bytes: usize,
+    buffer_reuse_hits: usize,
+}
+
+//Measures the operator it wraps, the metrics are recorded in the profile when it is closed
+//Without a node, the operator is only measured so that the next operator knows its input
+pub(crate) struct ProfiledOperator<'p, 't> {
+    operator: BoxedOperator<'p, 't>,
+    node: Option<&'p PhysicalPlan>,
+    profile: &'p QueryProfile,
+    partition_id: usize,
+    totals: Rc<Cell<ProfileTotals>>,
+    input_totals: Option<Rc<Cell<ProfileTotals>>>,
+    metrics: Vec<OperatorMetrics>,
+}
+
+impl<'p, 't> ProfiledOperator<'p, 't> {
+    pub(crate) fn new(
+        operator: BoxedOperator<'p, 't>,
+        node: Option<&'p PhysicalPlan>,
+        profile: &'p QueryProfile,
+        partition_id: usize,
+        input_totals: Option<Rc<Cell<ProfileTotals>>>,
+    ) -> Self {
+        Self {
+            operator,
+            node,
+            profile,
+            partition_id,
+            totals: Rc::new(Cell::new(ProfileTotals::default())),
+            input_totals,
+            metrics: vec![],
+        }
+    }
+
+    pub(crate) fn totals(&self) -> Rc<Cell<ProfileTotals>> {
+        self.totals.clone()
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for ProfiledOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        self.operator.name()
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.operator.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        let input_before = self.input_totals.as_ref().map(|t| t.get());
+        let buffer_reuse_hits = buffers.buffer.reuse_hits();
+        let start = Instant::now();
+        let batch = self.operator.next(dict, buffers)?;
+        let elapsed = start.elapsed();
+        let buffer_reuse_hits = buffers.buffer.reuse_hits() - buffer_reuse_hits;
+        let input_after = self.input_totals.as_ref().map(|t| t.get());
+
+        let (rows, bytes) = match &batch {
+            Some(batch) => (batch.number_of_rows, batch.allocated_bytes(dict)?),
+            None => (0, 0),
+        };
+        let totals = self.totals.get();
+        self.totals.set(ProfileTotals {
+            elapsed: totals.elapsed + elapsed,
+            rows: totals.rows + rows,
+            bytes: totals.bytes + bytes,
+            buffer_reuse_hits: totals.buffer_reuse_hits + buffer_reuse_hits,
+        });
+
+        if batch.is_some() {
+            //A source reads as many rows as it returns
+            let input = match (input_before, input_after) {
+                (Some(before), Some(after)) => ProfileTotals {
+                    elapsed: after.elapsed - before.elapsed,
+                    rows: after.rows - before.rows,
+                    bytes: after.bytes - before.bytes,
+                    buffer_reuse_hits: after.buffer_reuse_hits - before.buffer_reuse_hits,
+                },
+                _ => ProfileTotals {
+                    rows,
+                    ..Default::default()
+                },
+            };
+            self.metrics.push(OperatorMetrics {
+                partition: self.partition_id,
+                elapsed: elapsed.saturating_sub(input.elapsed),
+                rows_in: input.rows,
+                rows_out: rows,
+                bytes_allocated: bytes.saturating_sub(input.bytes),
+                buffer_reuse_hits: buffer_reuse_hits - input.buffer_reuse_hits,
+            });
+        }
+        Ok(batch)
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.operator.close()?;
+        if let Some(node) = self.node {
+            let metrics = std::mem::take(&mut self.metrics);
+            self.profile.record(node, self.operator.name(), metrics);
+        }
+        Ok(())
+    }
+}
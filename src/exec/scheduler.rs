@@ -0,0 +1,538 @@
+use std::{cell::RefCell, collections::HashMap, ops::Range, sync::Arc, time::Instant};
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{
+    aggregate_batch, bind, column_buffer::ColumnBuffer, final_aggregate, limit, sort,
+    AggregateExpr, Batch, BatchSource, BoxedOperator, ColumnSchema, ExecutionContext,
+    ExpressionInput, FilterOperator, HashJoinBuild, HashJoinProbe, LimitOperator, LogicalPlan,
+    OperatorMetrics, Optimizer, PartialAggregateOperator, PhysicalOperator, PhysicalPlan,
+    ProfiledOperator, ProjectOperator, QueryProfile, ScanOperator, Schema, SpillOperator,
+    SpillPartitioner, Table,
+};
+
+//Buffers reused by the operators running on one worker thread
+//The memory they keep for reuse is reserved in the tracker of the query
+pub struct ExecutionBuffers {
+    pub buffer: ColumnBuffer,
+    pub hashmap_buffer: HashMapBuffer,
+    pub hashmap_binary: HashMap<(usize, NullableValue<&'static [u8]>), usize, ahash::RandomState>,
+    memory: Arc<MemoryTracker>,
+}
+
+impl ExecutionBuffers {
+    pub fn new() -> Self {
+        Self::with_memory(Arc::new(MemoryTracker::unlimited()))
+    }
+
+    pub fn with_memory(memory: Arc<MemoryTracker>) -> Self {
+        Self {
+            buffer: ColumnBuffer::with_memory(memory.clone()),
+            hashmap_buffer: HashMapBuffer::with_memory(memory.clone()),
+            hashmap_binary: HashMap::with_capacity_and_hasher(100, ahash::RandomState::default()),
+            memory,
+        }
+    }
+
+    pub fn memory(&self) -> &Arc<MemoryTracker> {
+        &self.memory
+    }
+}
+
+impl Default for ExecutionBuffers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    //Buffers of the worker thread, they are taken out while a task runs so that a nested task gets its own
+    static WORKER_BUFFERS: RefCell<Option<ExecutionBuffers>> = const { RefCell::new(None) };
+}
+
+//Runs f with the buffers of the current thread, they are kept for the next task running on the thread
+//Buffers reserving memory in another tracker are replaced, so that queries do not share buffers
+pub fn with_worker_buffers<R, F>(memory: &Arc<MemoryTracker>, f: F) -> R
+where
+    F: FnOnce(&mut ExecutionBuffers) -> R,
+{
+    let mut buffers = match WORKER_BUFFERS.with(|b| b.borrow_mut().take()) {
+        Some(buffers) if Arc::ptr_eq(buffers.memory(), memory) => buffers,
+        _ => ExecutionBuffers::with_memory(memory.clone()),
+    };
+    let res = f(&mut buffers);
+    buffers.buffer.release_lent();
+    WORKER_BUFFERS.with(|b| *b.borrow_mut() = Some(buffers));
+    res
+}
+
+pub(crate) fn release_worker_buffers() {
+    WORKER_BUFFERS.with(|b| b.borrow_mut().take());
+}
+
+//A range of rows of one partition, the unit of work handed to the workers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Morsel {
+    pub partition_id: usize,
+    pub rows: Range<usize>,
+}
+
+//Runs physical plans in the thread pool of its execution context
+//Work is split into small tasks which idle worker threads steal from busy ones,
+//so a large partition does not hold up the partitions queued behind it
+pub struct Scheduler {
+    context: ExecutionContext,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//Source of the partitions processed by a pipeline
+enum PipelineSource<'p, 't> {
+    Table {
+        table: &'t Table<'t>,
+        column_ids: &'p [usize],
+    },
+    Batches(Vec<Batch<'t>>),
+}
+
+//Pipeline breaker on top of a pipeline, it is applied per worker
+//The node is the one of the plan the sink belongs to
+enum PipelineSink<'p> {
+    None,
+    PartialAggregate {
+        node: &'p PhysicalPlan,
+        group_by: &'p [ExpressionInput<'static>],
+        aggregates: &'p [AggregateExpr],
+    },
+    Limit {
+        node: &'p PhysicalPlan,
+        limit: usize,
+    },
+    Spill {
+        node: &'p PhysicalPlan,
+        keys: &'p [ExpressionInput<'static>],
+        partitioner: &'p SpillPartitioner,
+    },
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::with_context(ExecutionContext::new())
+    }
+
+    pub fn with_context(context: ExecutionContext) -> Self {
+        Self { context }
+    }
+
+    pub fn context(&self) -> &ExecutionContext {
+        &self.context
+    }
+
+    //Optimizes the plan and translates it to a physical plan
+    fn plan(
+        dict: &Dictionary,
+        schema: &Schema,
+        plan: &LogicalPlan,
+    ) -> Result<(Vec<ColumnSchema>, PhysicalPlan), ErrorDesc> {
+        let plan = Optimizer::new().optimize(dict, plan.clone())?;
+        let columns_schema = plan.output_schema(schema)?;
+        Ok((columns_schema, PhysicalPlan::from_logical(dict, &plan)?))
+    }
+
+    //Optimizes the plan and runs it, the result does not borrow from the tables of the schema
+    pub fn execute(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        plan: &LogicalPlan,
+    ) -> Result<Table<'static>, ErrorDesc> {
+        let (columns_schema, physical_plan) = Self::plan(dict, schema, plan)?;
+        let batches = self.run(dict, schema, &physical_plan)?;
+        Table::from_batches(dict, &batches, columns_schema)
+    }
+
+    //Prints the physical plan which execute would run
+    pub fn explain(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        plan: &LogicalPlan,
+    ) -> Result<String, ErrorDesc> {
+        let (_, physical_plan) = Self::plan(dict, schema, plan)?;
+        physical_plan.explain(dict, schema, None)
+    }
+
+    //Runs the plan like execute and prints the physical plan together with the metrics of every operator
+    pub fn explain_analyze(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        plan: &LogicalPlan,
+    ) -> Result<(Table<'static>, String), ErrorDesc> {
+        let (columns_schema, physical_plan) = Self::plan(dict, schema, plan)?;
+        let profile = QueryProfile::new();
+        let batches = self.run_profiled(dict, schema, &physical_plan, &profile)?;
+        let explain = physical_plan.explain(dict, schema, Some(&profile))?;
+        Ok((
+            Table::from_batches(dict, &batches, columns_schema)?,
+            explain,
+        ))
+    }
+
+    pub fn execute_sql(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        query: &str,
+    ) -> Result<Table<'static>, ErrorDesc> {
+        let select = bind(schema, dict, query)?;
+        let plan = LogicalPlan::from_select(schema, &select)?;
+        self.execute(dict, schema, &plan)
+    }
+
+    //Runs the plan and returns its output as batches
+    pub fn run<'t>(
+        &self,
+        dict: &Dictionary,
+        schema: &'t Schema<'t>,
+        plan: &PhysicalPlan,
+    ) -> Result<Vec<Batch<'t>>, ErrorDesc> {
+        self.run_plan(dict, schema, plan, None)
+    }
+
+    //Runs the plan like run, the metrics of every operator are recorded in the profile
+    pub fn run_profiled<'t>(
+        &self,
+        dict: &Dictionary,
+        schema: &'t Schema<'t>,
+        plan: &PhysicalPlan,
+        profile: &QueryProfile,
+    ) -> Result<Vec<Batch<'t>>, ErrorDesc> {
+        self.run_plan(dict, schema, plan, Some(profile))
+    }
+
+    fn run_plan<'t>(
+        &self,
+        dict: &Dictionary,
+        schema: &'t Schema<'t>,
+        plan: &PhysicalPlan,
+        profile: Option<&QueryProfile>,
+    ) -> Result<Vec<Batch<'t>>, ErrorDesc> {
+        match plan {
+            PhysicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let in_memory = || {
+                    let sink = PipelineSink::PartialAggregate {
+                        node: plan,
+                        group_by,
+                        aggregates,
+                    };
+                    let partial = self.run_pipeline(dict, schema, input, sink, profile)?;
+                    self.run_breaker(dict, plan, "FinalAggregate", &partial, profile, |b| {
+                        final_aggregate(dict, b, &partial, group_by.len(), aggregates)
+                    })
+                };
+                let res = match in_memory() {
+                    Err(RadixError::MemoryLimitExceeded(_))
+                        if self.context.spill_directory().is_some() =>
+                    {
+                        self.run_spilled_aggregate(dict, schema, plan, profile)?
+                    }
+                    res => res?,
+                };
+                Ok(vec![res])
+            }
+            PhysicalPlan::Sort { input, order_by } => {
+                let batches = self.run_plan(dict, schema, input, profile)?;
+                if batches.is_empty() {
+                    return Ok(batches);
+                }
+                let res = self.run_breaker(dict, plan, "Sort", &batches, profile, |b| {
+                    sort(dict, b, &batches, order_by)
+                })?;
+                Ok(vec![res])
+            }
+            PhysicalPlan::Limit { input, limit: n } => {
+                let sink = PipelineSink::Limit {
+                    node: plan,
+                    limit: *n,
+                };
+                let batches = self.run_pipeline(dict, schema, input, sink, profile)?;
+                limit(batches, *n)
+            }
+            _ => self.run_pipeline(dict, schema, plan, PipelineSink::None, profile),
+        }
+    }
+
+    //Grace hash aggregation: the input rows are spilled into buckets by the hash of their group by keys,
+    //afterwards the buckets are aggregated one after another, so that only one bucket is in memory at a time
+    fn run_spilled_aggregate(
+        &self,
+        dict: &Dictionary,
+        schema: &Schema,
+        plan: &PhysicalPlan,
+        profile: Option<&QueryProfile>,
+    ) -> Result<Batch<'static>, ErrorDesc> {
+        let (input, group_by, aggregates) = match plan {
+            PhysicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => (input, group_by, aggregates),
+            _ => Err("Only an aggregate can be spilled")?,
+        };
+        let directory = match self.context.spill_directory() {
+            Some(directory) => directory,
+            None => Err("Spilling is not enabled in the execution context")?,
+        };
+        //The buffers of the failed attempt still hold their reservations
+        self.context.release_worker_buffers();
+
+        let partitioner = SpillPartitioner::create(directory, self.context.spill_bucket_bits())?;
+        let sink = PipelineSink::Spill {
+            node: plan,
+            keys: group_by,
+            partitioner: &partitioner,
+        };
+        self.run_pipeline(dict, schema, input, sink, profile)?;
+
+        let mut results = vec![];
+        for mut bucket in partitioner.into_buckets().into_iter() {
+            self.context.check_cancelled()?;
+            if bucket.number_of_batches() == 0 {
+                continue;
+            }
+            let batches = bucket.read(dict)?;
+            drop(bucket);
+            let res = self.run_breaker(dict, plan, "SpilledAggregate", &batches, profile, |b| {
+                let partial: Result<Vec<_>, ErrorDesc> = batches
+                    .iter()
+                    .map(|batch| aggregate_batch(dict, b, batch, group_by, aggregates))
+                    .collect();
+                final_aggregate(dict, b, &partial?, group_by.len(), aggregates)
+            })?;
+            results.push(res);
+        }
+        Batch::concat(dict, &results)
+    }
+
+    //Runs a pipeline breaker over all batches of its input on the calling thread
+    fn run_breaker<F>(
+        &self,
+        dict: &Dictionary,
+        node: &PhysicalPlan,
+        name: &'static str,
+        input: &[Batch],
+        profile: Option<&QueryProfile>,
+        f: F,
+    ) -> Result<Batch<'static>, ErrorDesc>
+    where
+        F: FnOnce(&mut ExecutionBuffers) -> Result<Batch<'static>, ErrorDesc>,
+    {
+        let (res, elapsed, buffer_reuse_hits) =
+            with_worker_buffers(self.context.memory(), |buffers| {
+                let buffer_reuse_hits = buffers.buffer.reuse_hits();
+                let start = Instant::now();
+                let res = f(buffers);
+                (
+                    res,
+                    start.elapsed(),
+                    buffers.buffer.reuse_hits() - buffer_reuse_hits,
+                )
+            });
+        let batch = res?;
+        if let Some(profile) = profile {
+            let metrics = OperatorMetrics {
+                partition: 0,
+                elapsed,
+                rows_in: input.iter().map(|b| b.number_of_rows).sum(),
+                rows_out: batch.number_of_rows,
+                bytes_allocated: batch.allocated_bytes(dict)?,
+                buffer_reuse_hits,
+            };
+            profile.record(node, name, vec![metrics]);
+        }
+        Ok(batch)
+    }
+
+    //Filters, projections and join probes are chained, so that every partition is processed in one pass
+    fn run_pipeline<'p, 't>(
+        &self,
+        dict: &Dictionary,
+        schema: &'t Schema<'t>,
+        plan: &'p PhysicalPlan,
+        sink: PipelineSink<'p>,
+        profile: Option<&'p QueryProfile>,
+    ) -> Result<Vec<Batch<'t>>, ErrorDesc> {
+        //Streaming operators from the top of the pipeline down to its source
+        let mut chain = vec![];
+        let mut node = plan;
+        loop {
+            match node {
+                PhysicalPlan::Filter { input, .. } | PhysicalPlan::Project { input, .. } => {
+                    chain.push(node);
+                    node = input;
+                }
+                PhysicalPlan::HashJoin { probe, .. } => {
+                    chain.push(node);
+                    node = probe;
+                }
+                _ => break,
+            }
+        }
+
+        //The build sides of the joins are complete before any partition is probed
+        let builds: Result<Vec<Option<HashJoinBuild>>, ErrorDesc> = chain
+            .iter()
+            .map(|node| match node {
+                PhysicalPlan::HashJoin { build, on, .. } => {
+                    let batches = self.run_plan(dict, schema, build, profile)?;
+                    let keys: Vec<_> = on.iter().map(|(_, r)| *r).collect();
+                    let start = Instant::now();
+                    let build = HashJoinBuild::new(dict, &batches, &keys)?;
+                    if let Some(profile) = profile {
+                        let metrics = OperatorMetrics {
+                            partition: 0,
+                            elapsed: start.elapsed(),
+                            rows_in: batches.iter().map(|b| b.number_of_rows).sum(),
+                            rows_out: build.batch().number_of_rows,
+                            bytes_allocated: build.batch().allocated_bytes(dict)?,
+                            buffer_reuse_hits: 0,
+                        };
+                        profile.record(node, "HashJoinBuild", vec![metrics]);
+                    }
+                    Ok(Some(build))
+                }
+                _ => Ok(None),
+            })
+            .collect();
+        let builds = builds?;
+
+        let source = match node {
+            PhysicalPlan::Scan { table, columns } => PipelineSource::Table {
+                table: schema.get(table)?,
+                column_ids: columns,
+            },
+            _ => PipelineSource::Batches(self.run_plan(dict, schema, node, profile)?),
+        };
+
+        let drain = |buffers: &mut ExecutionBuffers,
+                     mut op: Box<dyn PhysicalOperator<'t> + '_>|
+         -> Result<Vec<Batch<'t>>, ErrorDesc> {
+            let mut output = vec![];
+            op.open()?;
+            while let Some(batch) = op.next(dict, buffers)? {
+                output.push(batch);
+            }
+            op.close()?;
+            Ok(output)
+        };
+
+        let pipeline = Pipeline {
+            chain,
+            builds,
+            sink,
+            profile,
+        };
+        //Every morsel runs through its own instance of the pipeline
+        match source {
+            PipelineSource::Table { table, column_ids } => self.context.map_tasks(
+                self.context.morsels(table.partition_sizes()),
+                |buffers, morsel| {
+                    let partition_id = morsel.partition_id;
+                    let scan = ScanOperator::new(table, column_ids, vec![morsel]);
+                    let op = pipeline.build(Box::new(scan), Some(node), partition_id);
+                    drain(buffers, op)
+                },
+            ),
+            PipelineSource::Batches(batches) => self.context.map_tasks(
+                batches.into_iter().enumerate().collect(),
+                |buffers, (partition_id, batch)| {
+                    let source = BatchSource::new(vec![batch]);
+                    let op = pipeline.build(Box::new(source), None, partition_id);
+                    drain(buffers, op)
+                },
+            ),
+        }
+    }
+}
+
+//Operators of a pipeline, every worker stacks its own instances of them on top of its source
+struct Pipeline<'p> {
+    //Streaming operators ordered from the top of the pipeline to the bottom
+    chain: Vec<&'p PhysicalPlan>,
+    builds: Vec<Option<HashJoinBuild>>,
+    sink: PipelineSink<'p>,
+    profile: Option<&'p QueryProfile>,
+}
+
+impl<'p> Pipeline<'p> {
+    //The source node is profiled only if it is a node of the plan, batches of a breaker were profiled already
+    fn build<'s, 't>(
+        &'s self,
+        source: BoxedOperator<'s, 't>,
+        source_node: Option<&'s PhysicalPlan>,
+        partition_id: usize,
+    ) -> BoxedOperator<'s, 't>
+    where
+        't: 's,
+    {
+        let mut input_totals = None;
+        let mut profiled = |op: BoxedOperator<'s, 't>, node: Option<&'s PhysicalPlan>| {
+            let profile = match self.profile {
+                Some(profile) => profile,
+                None => return op,
+            };
+            let op = ProfiledOperator::new(op, node, profile, partition_id, input_totals.take());
+            input_totals = Some(op.totals());
+            Box::new(op) as BoxedOperator<'s, 't>
+        };
+
+        let mut op = profiled(source, source_node);
+        for (node, build) in self.chain.iter().zip(self.builds.iter()).rev() {
+            op = match (node, build) {
+                (PhysicalPlan::Filter { predicates, .. }, _) => {
+                    Box::new(FilterOperator::new(op, predicates))
+                }
+                (PhysicalPlan::Project { exprs, .. }, _) => {
+                    Box::new(ProjectOperator::new(op, exprs))
+                }
+                (PhysicalPlan::HashJoin { on, .. }, Some(build)) => {
+                    Box::new(HashJoinProbe::new(op, build, on))
+                }
+                _ => unreachable!(),
+            };
+            op = profiled(op, Some(node));
+        }
+        match &self.sink {
+            PipelineSink::None => op,
+            PipelineSink::PartialAggregate {
+                node,
+                group_by,
+                aggregates,
+            } => profiled(
+                Box::new(PartialAggregateOperator::new(op, group_by, aggregates)),
+                Some(node),
+            ),
+            PipelineSink::Limit { node, limit } => {
+                profiled(Box::new(LimitOperator::new(op, *limit)), Some(node))
+            }
+            PipelineSink::Spill {
+                node,
+                keys,
+                partitioner,
+            } => profiled(
+                Box::new(SpillOperator::new(op, partitioner, keys)),
+                Some(node),
+            ),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{Batch, ExecutionBuffers, ExpressionInput};
+
+//Sorts all rows of the batches, the sort is stable
+//The keys are applied from the last one to the first one, so the first key has the highest priority
+pub fn sort(
+    dict: &Dictionary,
+    buffers: &mut ExecutionBuffers,
+    batches: &[Batch],
+    order_by: &[(ExpressionInput<'static>, bool)],
+) -> Result<Batch<'static>, ErrorDesc> {
+    let batch = Batch::concat(dict, batches)?;
+    let mut order: Vec<usize> = (0..batch.number_of_rows).collect();
+    for (key, ascending) in order_by.iter().rev() {
+        match batch.eval(dict, buffers, key)? {
+            InputTypes::Ref(c, _) => c.sort_index(dict, &mut order, *ascending)?,
+            InputTypes::Owned(c, _) => {
+                c.sort_index(dict, &mut order, *ascending)?;
+                buffers.buffer.push(dict, c);
+            }
+        }
+    }
+    let columns: Result<Vec<_>, ErrorDesc> = (0..batch.number_of_columns())
+        .map(|i| batch.take(dict, i, Some(&order)))
+        .collect();
+    Ok(Batch::new(columns?, batch.number_of_rows))
+}
+
+//Keeps the first rows of the batches in their order
+pub fn limit(batches: Vec<Batch>, limit: usize) -> Result<Vec<Batch>, ErrorDesc> {
+    let mut remaining = limit;
+    let mut output = vec![];
+    for mut batch in batches.into_iter() {
+        if remaining == 0 {
+            break;
+        }
+        batch.truncate(remaining)?;
+        remaining -= batch.number_of_rows;
+        output.push(batch);
+    }
+    Ok(output)
+}
@@ -0,0 +1,223 @@
+use std::{
+    any::TypeId,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use radix_column::*;
+use radix_operations::*;
+
+use crate::{Batch, BoxedOperator, ExecutionBuffers, ExpressionInput, PhysicalOperator};
+
+static SPILL_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+//Batches written to a temporary file, the file is removed when the spill file is dropped
+//Every batch is written as its number of rows followed by its columns, all batches have the same column types
+pub struct SpillFile {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    types: Vec<TypeId>,
+    number_of_batches: usize,
+    number_of_rows: usize,
+}
+
+impl SpillFile {
+    pub fn create(directory: &Path) -> Result<Self, ErrorDesc> {
+        let id = SPILL_FILE_ID.fetch_add(1, Ordering::Relaxed);
+        let path = directory.join(format!("radix-spill-{}-{}.bin", std::process::id(), id));
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self {
+            path,
+            writer: Some(writer),
+            types: vec![],
+            number_of_batches: 0,
+            number_of_rows: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn number_of_batches(&self) -> usize {
+        self.number_of_batches
+    }
+
+    pub fn number_of_rows(&self) -> usize {
+        self.number_of_rows
+    }
+
+    pub fn write(&mut self, dict: &Dictionary, batch: &Batch) -> Result<(), ErrorDesc> {
+        let types: Vec<_> = batch
+            .columns
+            .iter()
+            .map(|c| c.column().item_type_id())
+            .collect();
+        if self.number_of_batches == 0 {
+            self.types = types;
+        } else if self.types != types {
+            Err("All batches of a spill file must have the same column types")?
+        }
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => Err("A spill file cannot be written after it has been read")?,
+        };
+        writer.write_all(&(batch.number_of_rows as u64).to_le_bytes())?;
+        for (i, c) in batch.columns.iter().enumerate() {
+            c.write_to(dict, batch.column_index(i), writer)?;
+        }
+        self.number_of_batches += 1;
+        self.number_of_rows += batch.number_of_rows;
+        Ok(())
+    }
+
+    //Returns the batches in the order they were written
+    pub fn read(&mut self, dict: &Dictionary) -> Result<Vec<Batch<'static>>, ErrorDesc> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        let mut inp = BufReader::new(File::open(&self.path)?);
+        let mut batches = Vec::with_capacity(self.number_of_batches);
+        for _ in 0..self.number_of_batches {
+            let mut number_of_rows = [0u8; 8];
+            inp.read_exact(&mut number_of_rows)?;
+            let columns: Result<Vec<_>, ErrorDesc> = self
+                .types
+                .iter()
+                .map(|t| ColumnWrapper::read_from(dict, *t, &mut inp))
+                .collect();
+            batches.push(Batch::new(
+                columns?,
+                u64::from_le_bytes(number_of_rows) as usize,
+            ));
+        }
+        Ok(batches)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        self.writer = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+//Spreads rows over 2^bucket_bits spill files by their hash, rows with equal keys end up in the same file
+//Batches without rows are written to the first bucket, so that it holds at least one batch
+pub struct SpillPartitioner {
+    buckets: Vec<Mutex<SpillFile>>,
+}
+
+impl SpillPartitioner {
+    pub fn create(directory: &Path, bucket_bits: usize) -> Result<Self, ErrorDesc> {
+        let buckets: Result<Vec<_>, ErrorDesc> = (0..1usize << bucket_bits)
+            .map(|_| Ok(Mutex::new(SpillFile::create(directory)?)))
+            .collect();
+        Ok(Self { buckets: buckets? })
+    }
+
+    pub fn number_of_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn write(&self, dict: &Dictionary, batch: &Batch, hash: &[u64]) -> Result<(), ErrorDesc> {
+        if batch.number_of_rows == 0 {
+            return self.buckets[0].lock().unwrap().write(dict, batch);
+        }
+        let mask = (self.buckets.len() - 1) as u64;
+        let mut rows = vec![vec![]; self.buckets.len()];
+        hash.iter()
+            .enumerate()
+            .for_each(|(row, h)| rows[(h & mask) as usize].push(row));
+        for (bucket, rows) in self.buckets.iter().zip(rows.iter()) {
+            if rows.is_empty() {
+                continue;
+            }
+            let columns: Result<Vec<_>, ErrorDesc> = (0..batch.number_of_columns())
+                .map(|i| batch.take(dict, i, Some(rows)))
+                .collect();
+            let part = Batch::new(columns?, rows.len());
+            bucket.lock().unwrap().write(dict, &part)?;
+        }
+        Ok(())
+    }
+
+    pub fn into_buckets(self) -> Vec<SpillFile> {
+        self.buckets
+            .into_iter()
+            .map(|b| b.into_inner().unwrap())
+            .collect()
+    }
+}
+
+//Hash of the values of the expressions for every row of the batch
+pub(crate) fn hash_expressions(
+    dict: &Dictionary,
+    buffers: &mut ExecutionBuffers,
+    batch: &Batch,
+    exprs: &[ExpressionInput],
+) -> Result<Vec<u64>, ErrorDesc> {
+    if exprs.is_empty() {
+        return Ok(vec![0; batch.number_of_rows]);
+    }
+    let mut hash = Vec::with_capacity(batch.number_of_rows);
+    for e in exprs.iter() {
+        match batch.eval(dict, buffers, e)? {
+            InputTypes::Ref(c, index) => c.hash_in(dict, index, &mut hash)?,
+            InputTypes::Owned(c, index) => {
+                c.hash_in(dict, &index, &mut hash)?;
+                buffers.buffer.push(dict, c);
+            }
+        }
+    }
+    Ok(hash)
+}
+
+//Writes all batches of its input to the partitioner by the hash of the keys, it returns no batches
+pub struct SpillOperator<'p, 't> {
+    input: BoxedOperator<'p, 't>,
+    partitioner: &'p SpillPartitioner,
+    keys: &'p [ExpressionInput<'static>],
+}
+
+impl<'p, 't> SpillOperator<'p, 't> {
+    pub fn new(
+        input: BoxedOperator<'p, 't>,
+        partitioner: &'p SpillPartitioner,
+        keys: &'p [ExpressionInput<'static>],
+    ) -> Self {
+        Self {
+            input,
+            partitioner,
+            keys,
+        }
+    }
+}
+
+impl<'p, 't> PhysicalOperator<'t> for SpillOperator<'p, 't> {
+    fn name(&self) -> &'static str {
+        "Spill"
+    }
+    fn open(&mut self) -> Result<(), ErrorDesc> {
+        self.input.open()
+    }
+    fn next(
+        &mut self,
+        dict: &Dictionary,
+        buffers: &mut ExecutionBuffers,
+    ) -> Result<Option<Batch<'t>>, ErrorDesc> {
+        while let Some(batch) = self.input.next(dict, buffers)? {
+            let hash = hash_expressions(dict, buffers, &batch, self.keys)?;
+            self.partitioner.write(dict, &batch, &hash)?;
+        }
+        Ok(None)
+    }
+    fn close(&mut self) -> Result<(), ErrorDesc> {
+        self.input.close()
+    }
+}
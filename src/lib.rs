@@ -1,17 +1,27 @@
+mod exec;
+mod plan;
 mod schema;
+mod sql;
 mod table;
 
+pub use exec::*;
+pub use plan::*;
+pub use schema::*;
+pub use sql::*;
 pub use table::*;
 
 #[cfg(test)]
 mod tests {
 
     use crate::{
-        filter, tabletotable::TableToTableMap, ExpressionInput, PartitionedIndex, Table,
+        bind, filter, tabletotable::TableToTableMap, Batch, BoundExpr, BoundSelectItem,
+        CancellationToken, ChunkingStrategy, ExecutionContext, ExpressionInput, Literal,
+        LogicalPlan, Optimizer, OrderByItem, PartitionedIndex, Scheduler, Schema, SpillFile, Table,
         TableExpression,
     };
     use radix_column::*;
     use radix_operations::*;
+    use std::any::TypeId;
 
     #[test]
     fn binarycolumninit() {
@@ -30,9 +40,10 @@ mod tests {
             .map(|b| *b)
             .collect();
         let len: Vec<_> = names.iter().map(|s| s.as_bytes().len()).collect();
-        let (res_data, _res_start_pos, res_len, _) =
-            bnames.downcast_binary_owned::<String>().unwrap();
+        let (res_data, res_offsets) = bnames.downcast_binary_owned::<String>().unwrap();
         assert_eq!(res_data, datau8);
+        assert_eq!(res_offsets.len(), len.len() + 1);
+        let res_len: Vec<_> = res_offsets.as_offsets_ref().item_lens().collect();
         assert_eq!(res_len, len);
     }
 
@@ -127,6 +138,7 @@ mod tests {
             "Peter".to_string(),
         ];
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
 
         let mut t: Table = Table::new(vec![2, 2, 1]);
         t.push(&dict, &names).unwrap();
@@ -134,14 +146,15 @@ mod tests {
 
         assert_eq!(p_column.len(), 3);
 
-        let v = t.materialize::<String>(&dict, &0).unwrap();
+        let v = t.materialize::<String>(&dict, &ctx, &0).unwrap();
         assert_eq!(v.0, names);
-        //t.print(&dict).unwrap();
+        //t.print(&dict, &ctx).unwrap();
     }
     #[test]
     fn copycolumnmaterialize() {
         let names: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
         t.push(&dict, &names).unwrap();
@@ -149,7 +162,7 @@ mod tests {
 
         assert_eq!(p_column.len(), 5);
 
-        let v = t.materialize::<u32>(&dict, &0).unwrap();
+        let v = t.materialize::<u32>(&dict, &ctx, &0).unwrap();
         assert_eq!(v.0, names);
         assert!(!v.1.is_some());
     }
@@ -158,6 +171,7 @@ mod tests {
         let names: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
         let bitmap: Vec<bool> = vec![true, false, true, true, true, true, true, true, true];
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
         t.push_with_bitmap(&dict, &names, &bitmap).unwrap();
@@ -165,15 +179,16 @@ mod tests {
 
         assert_eq!(p_column.len(), 5);
 
-        let v = t.materialize::<u32>(&dict, &0).unwrap();
+        let v = t.materialize::<u32>(&dict, &ctx, &0).unwrap();
         assert_eq!(v.0, names);
-        assert_eq!(v.1.downcast_ref().unwrap(), bitmap.as_slice());
-        //t.print(&dict).unwrap();
+        assert_eq!(v.1.downcast_ref().unwrap().to_vec(), bitmap);
+        //t.print(&dict, &ctx).unwrap();
     }
 
     #[test]
     fn columns_addassign() {
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
@@ -207,21 +222,22 @@ mod tests {
             .unwrap();
 
         let expected_result = vec!["2", "(null)", "6", "8", "10", "12", "14", "16", "(null)"];
-        let result = t.materialize_as_string(&dict, &0).unwrap();
+        let result = t.materialize_as_string(&dict, &ctx, &0).unwrap();
         assert_eq!(result, expected_result);
 
-        //t.print(&dict).unwrap();
+        //t.print(&dict, &ctx).unwrap();
     }
     #[test]
     fn columns_eq_copy() {
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
         let mut c1_names: Vec<bool> = vec![
             false, false, false, false, false, false, false, false, false,
         ];
-        let mut c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
+        let c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
 
         let c2_names: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
         let c2_bitmap: Vec<bool> = vec![true, false, true, true, true, true, true, true, true];
@@ -232,7 +248,7 @@ mod tests {
 
         t.push_with_bitmap(&dict, &c2_names, &c2_bitmap).unwrap();
 
-        t.push_mut_with_bitmap(&mut c1_names, &mut c1_bitmap)
+        t.push_mut_with_bitmap(&mut c1_names, Bitmap::from(c1_bitmap))
             .unwrap();
 
         let c3_index: PartitionedIndex = vec![
@@ -245,31 +261,28 @@ mod tests {
 
         t.push_index(c3_index, &[0]).unwrap();
 
-        t.op(&dict, "==", &2, &[1, 0]).unwrap();
+        t.op(&dict, &ctx, "==", &2, &[1, 0]).unwrap();
 
         let expected_result = vec![
             "true", "(null)", "true", "false", "true", "false", "true", "false", "(null)",
         ];
-        let result = t.materialize_as_string(&dict, &2).unwrap();
+        let result = t.materialize_as_string(&dict, &ctx, &2).unwrap();
         assert_eq!(result, expected_result);
 
-        //t.print(&dict).unwrap();
+        //t.print(&dict, &ctx).unwrap();
     }
 
     #[test]
     fn columns_eq_binary() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
         let mut c1_names: Vec<bool> = vec![
             false, false, false, false, false, false, false, false, false,
         ];
-        let mut c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
+        let c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
 
         let c2_names: Vec<String> = vec![
             "1A".to_string(),
@@ -300,7 +313,7 @@ mod tests {
 
         t.push_with_bitmap(&dict, &c2_names, &c2_bitmap).unwrap();
 
-        t.push_mut_with_bitmap(&mut c1_names, &mut c1_bitmap)
+        t.push_mut_with_bitmap(&mut c1_names, Bitmap::from(c1_bitmap))
             .unwrap();
 
         let c2_index: PartitionedIndex = vec![
@@ -313,12 +326,12 @@ mod tests {
 
         t.push_index(c2_index, &[1]).unwrap();
 
-        t.op(&dict, "==", &2, &[1, 0]).unwrap();
+        t.op(&dict, &ctx, "==", &2, &[1, 0]).unwrap();
 
         let expected_result = vec![
             "true", "(null)", "true", "false", "true", "false", "true", "false", "(null)",
         ];
-        let result = t.materialize_as_string(&dict, &2).unwrap();
+        let result = t.materialize_as_string(&dict, &ctx, &2).unwrap();
         assert_eq!(result, expected_result);
     }
     #[test]
@@ -349,11 +362,8 @@ mod tests {
 
     #[test]
     fn columns_filter() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
@@ -395,25 +405,26 @@ mod tests {
         ];
 
         t.push_index(c2_index, &[1]).unwrap();
-        t.print(&dict).unwrap();
+        t.print(&dict, &ctx).unwrap();
         let e = TableExpression::new("==", &[0, 1]);
 
-        t.filter(&dict, &e).unwrap();
-        t.print(&dict).unwrap();
+        t.filter(&dict, &ctx, &e).unwrap();
+        t.print(&dict, &ctx).unwrap();
         let expected_result = vec!["1A", "3A", "5A", "7A"];
 
-        let result = t.materialize_as_string(&dict, &1).unwrap();
+        let result = t.materialize_as_string(&dict, &ctx, &1).unwrap();
         assert_eq!(result, expected_result);
     }
 
     #[test]
     fn test_expression() {
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
         let mut c1_names: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
-        let mut c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
+        let c1_bitmap: Vec<bool> = vec![true, true, true, true, false, true, true, true, true];
 
         let c2_names: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
         let c2_bitmap: Vec<bool> = vec![true, false, true, true, true, true, true, true, true];
@@ -424,7 +435,7 @@ mod tests {
 
         t.push_with_bitmap(&dict, &c2_names, &c2_bitmap).unwrap();
 
-        t.push_mut_with_bitmap(&mut c1_names, &mut c1_bitmap)
+        t.push_mut_with_bitmap(&mut c1_names, Bitmap::from(c1_bitmap))
             .unwrap();
 
         let c3_index: PartitionedIndex = vec![
@@ -436,7 +447,7 @@ mod tests {
         ];
 
         t.push_index(c3_index, &[0]).unwrap();
-        t.print(&dict).unwrap();
+        t.print(&dict, &ctx).unwrap();
 
         let mut e = TableExpression::new("<", &[500, 1000]);
         e.expand_node(500, "+", &[0, 500]).unwrap();
@@ -447,23 +458,2581 @@ mod tests {
         let const_val = &ColumnWrapper::new_const(&dict, 16u32);
         e.expand_node_as_const(1000, &mut Some(const_val)).unwrap();
 
-        t.add_expression_as_new_column(&dict, &e);
-        t.print(&dict).unwrap();
+        t.add_expression_as_new_column(&dict, &ctx, &e).unwrap();
+        t.print(&dict, &ctx).unwrap();
 
         let expected_result = vec![
             "true", "(null)", "true", "true", "(null)", "false", "false", "false", "(null)",
         ];
-        let result = t.materialize_as_string(&dict, &3).unwrap();
+        let result = t.materialize_as_string(&dict, &ctx, &3).unwrap();
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn columns_shared() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let mut t1: Table = Table::new(vec![2, 2, 1]);
+
+        let c1_names: Vec<u32> = vec![1, 2, 3, 4, 5];
+        let c1_bitmap: Vec<bool> = vec![true, true, false, true, true];
+        let c2_names: Vec<u32> = vec![10, 20, 30, 40, 50];
+
+        t1.push_with_bitmap(&dict, &c1_names, &c1_bitmap).unwrap();
+        t1.push(&dict, &c2_names).unwrap();
+
+        let e = TableExpression::new("+", &[0, 1]);
+        t1.add_expression_as_new_column(&dict, &ctx, &e).unwrap();
+        t1.make_shared(&2).unwrap();
+
+        let mut t2: Table = Table::new(vec![2, 2, 1]);
+        let c3_names: Vec<u32> = vec![100, 100, 100, 100, 100];
+        let c3_bitmap: Vec<bool> = vec![true, true, true, true, true];
+        t2.push_with_bitmap(&dict, &c3_names, &c3_bitmap).unwrap();
+        t2.push_shared(&dict, t1.share_column(&2).unwrap()).unwrap();
+        assert!(matches!(
+            t2.share_column(&2),
+            Err(RadixError::OutOfBounds { index: 2, len: 2 })
+        ));
+        assert!(matches!(
+            t2.push_shared(&dict, t1.share_column(&2).unwrap().split_off(1)),
+            Err(RadixError::LengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        ));
+
+        let expected_result = vec!["11", "22", "(null)", "44", "55"];
+        assert_eq!(
+            t2.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            expected_result
+        );
+
+        //Copy-on-write: t1 must not see the update
+        t2.op(&dict, &ctx, "+=", &1, &[0]).unwrap();
+
+        let expected_result_t2 = vec!["111", "122", "(null)", "144", "155"];
+        assert_eq!(
+            t2.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            expected_result_t2
+        );
+        assert_eq!(
+            t1.materialize_as_string(&dict, &ctx, &2).unwrap(),
+            expected_result
+        );
+
+        //Only owned or shared columns can be shared
+        assert!(t1.make_shared(&0).is_err());
+        assert!(t1.share_column(&0).is_err());
+    }
+
+    #[test]
+    fn columns_schema() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let mut c4_names: Vec<u32> = vec![1, 1, 1, 1, 1];
+
+        let mut t: Table = Table::new(vec![2, 2, 1]);
+
+        let c1_names: Vec<u32> = vec![1, 2, 3, 4, 5];
+        let c1_bitmap: Vec<bool> = vec![true, true, false, true, true];
+        let c2_names: Vec<u32> = vec![10, 20, 30, 40, 50];
+        let c3_names: Vec<String> = vec![
+            "A".to_string(),
+            "B".to_string(),
+            "C".to_string(),
+            "D".to_string(),
+            "E".to_string(),
+        ];
+
+        t.push_with_bitmap(&dict, &c1_names, &c1_bitmap).unwrap();
+        t.push(&dict, &c2_names).unwrap();
+        t.push(&dict, &c3_names).unwrap();
+        t.push_mut(&mut c4_names).unwrap();
+
+        assert_eq!(t.column_schema(&0).unwrap().name, "col_0");
+        t.set_column_name(&0, "quantity").unwrap();
+        t.set_column_name(&1, "price").unwrap();
+        t.set_column_name(&2, "product").unwrap();
+        t.set_column_name(&3, "discount").unwrap();
+        t.set_column_description(&2, "Product code").unwrap();
+        assert!(t.set_column_name(&1, "quantity").is_err());
+
+        let schema = t.schema();
+        assert_eq!(schema.len(), 4);
+        assert_eq!(schema[0].type_name(&dict).unwrap(), "u32");
+        assert!(schema[0].nullable);
+        assert!(!schema[1].nullable);
+        assert_eq!(schema[2].type_name(&dict).unwrap(), "String");
+        assert_eq!(schema[2].description, Some("Product code".to_string()));
+
+        let e = TableExpression::new_by_name(&t, "+", &["quantity", "price"]).unwrap();
+        t.add_expression_as_new_column(&dict, &ctx, &e).unwrap();
+        t.set_column_name(&4, "total").unwrap();
+        t.op_by_name(&dict, &ctx, "+=", "discount", &["price"])
+            .unwrap();
+        assert_eq!(
+            t.materialize_as_string_by_name(&dict, &ctx, "discount")
+                .unwrap(),
+            vec!["11", "21", "31", "41", "51"]
+        );
+
+        let expected_result = vec!["11", "22", "(null)", "44", "55"];
+        let result = t
+            .materialize_as_string_by_name(&dict, &ctx, "total")
+            .unwrap();
+        assert_eq!(result, expected_result);
+        assert_eq!(
+            t.materialize_by_name::<String>(&dict, &ctx, "product")
+                .unwrap()
+                .0,
+            c3_names
+        );
+        assert!(t
+            .materialize_as_string_by_name(&dict, &ctx, "missing")
+            .is_err());
+
+        t.print(&dict, &ctx).unwrap();
+    }
+
+    #[test]
+    fn schema_catalog() {
+        let dict = Dictionary::new();
+
+        let c1_names: Vec<u32> = vec![1, 2, 3, 4, 5];
+        let c2_names: Vec<u64> = vec![1, 2, 3];
+
+        let mut t1: Table = Table::new(vec![2, 2, 1]);
+        t1.push(&dict, &c1_names).unwrap();
+        t1.set_column_name(&0, "id").unwrap();
+
+        let mut t2: Table = Table::new(vec![3]);
+        t2.push(&dict, &c2_names).unwrap();
+
+        let mut t3: Table = Table::new(vec![1, 1]);
+        t3.push(&dict, &c2_names[0..2]).unwrap();
+
+        let mut schema = Schema::new();
+        schema.register("customers", t1).unwrap();
+        schema.register("orders", t2).unwrap();
+        schema.register("items", t3).unwrap();
+        assert!(schema.register("orders", Table::new(vec![1])).is_err());
+
+        assert_eq!(schema.get("customers").unwrap().number_of_rows(), 5);
+        assert!(schema.get("missing").is_err());
+
+        schema.rename("orders", "sales").unwrap();
+        assert!(schema.get("orders").is_err());
+        assert!(schema.rename("sales", "items").is_err());
+
+        let t1 = schema.drop_table("customers").unwrap();
+        assert_eq!(t1.column_id("id").unwrap(), 0);
+        assert!(!schema.contains("customers"));
+
+        schema
+            .get_mut("items")
+            .unwrap()
+            .set_column_name(&0, "item_id")
+            .unwrap();
+
+        let list = schema.list();
+        let names: Vec<_> = list.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["items", "sales"]);
+        assert_eq!(list[0].number_of_rows, 2);
+        assert_eq!(list[0].columns[0].name, "item_id");
+        assert_eq!(list[1].number_of_rows, 3);
+        assert_eq!(list[1].columns[0].type_name(&dict).unwrap(), "u64");
+    }
+
+    #[test]
+    fn sql_bind() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let c1_names: Vec<u32> = vec![1, 2, 3, 4, 5, 6];
+        let c2_names: Vec<u32> = vec![5, 1, 3, 7, 2, 4];
+        let c2_bitmap: Vec<bool> = vec![true, true, false, true, true, true];
+        let c3_names: Vec<String> = vec![
+            "A".to_string(),
+            "B".to_string(),
+            "A".to_string(),
+            "B".to_string(),
+            "A".to_string(),
+            "C".to_string(),
+        ];
+
+        let mut t: Table = Table::new(vec![6]);
+        t.push(&dict, &c1_names).unwrap();
+        t.push_with_bitmap(&dict, &c2_names, &c2_bitmap).unwrap();
+        t.push(&dict, &c3_names).unwrap();
+        t.set_column_name(&0, "id").unwrap();
+        t.set_column_name(&1, "qty").unwrap();
+        t.set_column_name(&2, "product").unwrap();
+
+        let mut schema = Schema::new();
+        schema.register("sales", t).unwrap();
+
+        let q = bind(
+            &schema,
+            &dict,
+            "SELECT id, qty + 1 AS q FROM sales s WHERE s.qty >= 4",
+        )
+        .unwrap();
+        assert_eq!(q.table, "sales");
+        assert_eq!(q.projection[1].name, "q");
+        assert!(!q.is_aggregate());
+
+        let filter = q.filter.unwrap().to_table_expression(&dict, &[]).unwrap();
+        let projection = q.projection[1]
+            .expr
+            .to_table_expression(&dict, &[])
+            .unwrap();
+        let t = schema.get_mut("sales").unwrap();
+        t.filter(&dict, &ctx, &filter).unwrap();
+        t.add_expression_as_new_column(&dict, &ctx, &projection)
+            .unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["1", "4", "6"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &3).unwrap(),
+            vec!["6", "8", "5"]
+        );
+
+        let q = bind(
+            &schema,
+            &dict,
+            "select product, SUM(qty) AS total FROM sales GROUP BY product HAVING SUM(qty) > 3 ORDER BY 2 DESC, product LIMIT 5",
+        )
+        .unwrap();
+        assert!(q.is_aggregate());
+        assert_eq!(q.group_by.len(), 1);
+        assert_eq!(q.projection[1].name, "total");
+        assert!(matches!(q.projection[1].expr, BoundExpr::Aggregate { .. }));
+        assert_eq!(q.order_by[0].expr, q.projection[1].expr);
+        assert!(!q.order_by[0].ascending);
+        assert!(q.order_by[1].ascending);
+        assert_eq!(q.limit, Some(5));
+        assert!(q.projection[1]
+            .expr
+            .to_table_expression(&dict, &q.group_by)
+            .is_ok());
+
+        let err = bind(&schema, &dict, "SELECT FOO(id) FROM sales")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Unknown function FOO at line 1, column 8\nSELECT FOO(id) FROM sales\n       ^"
+        );
+
+        let err = bind(&schema, &dict, "SELECT id FROM sales\nWHERE product > 5")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Type mismatch for literal 5"));
+        assert!(err.contains("at line 2, column 17"));
+
+        let err = bind(&schema, &dict, "SELECT id + product FROM sales")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(
+            "Type mismatch: operation + is not defined for types (u32, String) at line 1, column 11"
+        ));
+
+        let err = bind(
+            &schema,
+            &dict,
+            "SELECT id, SUM(qty) FROM sales GROUP BY product",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.starts_with("id must appear in the GROUP BY clause"));
+
+        assert!(bind(&schema, &dict, "SELECT id FROM sales WHERE SUM(qty) > 1").is_err());
+        assert!(bind(&schema, &dict, "SELECT missing FROM sales").is_err());
+        assert!(bind(&schema, &dict, "SELECT id FROM missing").is_err());
+        assert!(bind(&schema, &dict, "SELECT FROM sales").is_err());
+        assert!(bind(&schema, &dict, "SELECT id FROM sales WHERE id = 5000000000").is_err());
+    }
+
+    #[test]
+    fn logical_plan_optimizer() {
+        let dict = Dictionary::new();
+
+        let products: Vec<String> = vec!["A", "B", "A", "C"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let regions: Vec<String> = vec!["EU".to_string(), "US".to_string()];
+
+        let mut sales: Table = Table::new(vec![4]);
+        sales.push(&dict, &[1u32, 2, 3, 4]).unwrap();
+        sales.push(&dict, &[5u32, 1, 3, 7]).unwrap();
+        sales.push(&dict, &products).unwrap();
+        sales.push(&dict, &[10u64, 2, 4, 1]).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "qty").unwrap();
+        sales.set_column_name(&2, "product").unwrap();
+        sales.set_column_name(&3, "price").unwrap();
+
+        let mut customers: Table = Table::new(vec![2]);
+        customers.push(&dict, &[1u32, 2]).unwrap();
+        customers.push(&dict, &regions).unwrap();
+        customers.set_column_name(&0, "cust_id").unwrap();
+        customers.set_column_name(&1, "region").unwrap();
+
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+        schema.register("customers", customers).unwrap();
+
+        let col = |column_id: usize, item_type_id: TypeId| BoundExpr::Column {
+            column_id,
+            item_type_id,
+        };
+        let lit = |value: Literal, item_type_id: TypeId| BoundExpr::Literal {
+            value,
+            item_type_id,
+        };
+        let op = |op: &str, input: Vec<BoundExpr>| BoundExpr::Op {
+            op: op.to_string(),
+            input,
+            output_type_id: TypeId::of::<bool>(),
+        };
+        let item = |name: &str, expr: BoundExpr| BoundSelectItem {
+            name: name.to_string(),
+            expr,
+        };
+        let (t_u32, t_u64, t_string) = (
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<String>(),
+        );
+        let optimizer = Optimizer::new();
+
+        //Constant folding, removal of always true predicates and pruning of the unused column
+        let q = bind(
+            &schema,
+            &dict,
+            "SELECT id, qty FROM sales WHERE price > 1 + 2 ORDER BY id",
+        )
+        .unwrap();
+        let plan = LogicalPlan::from_select(&schema, &q).unwrap();
+        assert_eq!(plan.number_of_columns(), 2);
+        let plan = optimizer.optimize(&dict, plan).unwrap();
+        let expected = LogicalPlan::Project {
+            input: Box::new(LogicalPlan::Sort {
+                input: Box::new(LogicalPlan::Filter {
+                    input: Box::new(LogicalPlan::Scan {
+                        table: "sales".to_string(),
+                        columns: vec![0, 1, 3],
+                    }),
+                    predicates: vec![op(
+                        ">",
+                        vec![col(2, t_u64), lit(Literal::Integer(3), t_u64)],
+                    )],
+                }),
+                order_by: vec![OrderByItem {
+                    expr: col(0, t_u32),
+                    ascending: true,
+                }],
+            }),
+            exprs: vec![item("id", col(0, t_u32)), item("qty", col(1, t_u32))],
+        };
+        assert_eq!(plan, expected);
+
+        let q = bind(&schema, &dict, "SELECT id FROM sales WHERE 1 == 1").unwrap();
+        let plan = LogicalPlan::from_select(&schema, &q).unwrap();
+        assert_eq!(
+            optimizer.optimize(&dict, plan).unwrap(),
+            LogicalPlan::Project {
+                input: Box::new(LogicalPlan::Scan {
+                    table: "sales".to_string(),
+                    columns: vec![0],
+                }),
+                exprs: vec![item("id", col(0, t_u32))],
+            }
+        );
+
+        //Adjacent filters are merged and the predicates are pushed to the side of the join they use
+        let join = LogicalPlan::Join {
+            left: Box::new(LogicalPlan::scan(&schema, "sales").unwrap()),
+            right: Box::new(LogicalPlan::scan(&schema, "customers").unwrap()),
+            on: vec![(0, 0)],
+        };
+        let plan = LogicalPlan::Project {
+            input: Box::new(LogicalPlan::Filter {
+                input: Box::new(LogicalPlan::Filter {
+                    input: Box::new(join),
+                    predicates: vec![op(
+                        ">",
+                        vec![col(3, t_u64), lit(Literal::Integer(3), t_u64)],
+                    )],
+                }),
+                predicates: vec![
+                    op(
+                        "==",
+                        vec![
+                            col(5, t_string),
+                            lit(Literal::Str("EU".to_string()), t_string),
+                        ],
+                    ),
+                    op(">=", vec![col(1, t_u32), col(4, t_u32)]),
+                ],
+            }),
+            exprs: vec![item("id", col(0, t_u32)), item("region", col(5, t_string))],
+        };
+        let expected = LogicalPlan::Project {
+            input: Box::new(LogicalPlan::Filter {
+                input: Box::new(LogicalPlan::Join {
+                    left: Box::new(LogicalPlan::Filter {
+                        input: Box::new(LogicalPlan::Scan {
+                            table: "sales".to_string(),
+                            columns: vec![0, 1, 3],
+                        }),
+                        predicates: vec![op(
+                            ">",
+                            vec![col(2, t_u64), lit(Literal::Integer(3), t_u64)],
+                        )],
+                    }),
+                    right: Box::new(LogicalPlan::Filter {
+                        input: Box::new(LogicalPlan::Scan {
+                            table: "customers".to_string(),
+                            columns: vec![0, 1],
+                        }),
+                        predicates: vec![op(
+                            "==",
+                            vec![
+                                col(1, t_string),
+                                lit(Literal::Str("EU".to_string()), t_string),
+                            ],
+                        )],
+                    }),
+                    on: vec![(0, 0)],
+                }),
+                predicates: vec![op(">=", vec![col(1, t_u32), col(3, t_u32)])],
+            }),
+            exprs: vec![item("id", col(0, t_u32)), item("region", col(4, t_string))],
+        };
+        assert_eq!(optimizer.optimize(&dict, plan).unwrap(), expected);
+
+        //HAVING on a group by column is moved below the aggregation
+        let q = bind(
+            &schema,
+            &dict,
+            "SELECT product, SUM(qty) FROM sales GROUP BY product HAVING product == 'A'",
+        )
+        .unwrap();
+        let plan = LogicalPlan::from_select(&schema, &q).unwrap();
+        let plan = optimizer.optimize(&dict, plan).unwrap();
+        match plan {
+            LogicalPlan::Project { input, .. } => match *input {
+                LogicalPlan::Aggregate { input, .. } => assert!(matches!(
+                    *input,
+                    LogicalPlan::Filter { ref predicates, .. } if predicates.len() == 1
+                )),
+                p => panic!("Unexpected plan {:?}", p),
+            },
+            p => panic!("Unexpected plan {:?}", p),
+        }
+    }
+
+    #[test]
+    fn filter_all_constant_folding() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let mut t: Table = Table::new(vec![3, 3]);
+        t.push(&dict, &[1u32, 5, 3, 7, 4, 9]).unwrap();
+        t.push_with_bitmap(
+            &dict,
+            &[1u32, 2, 3, 4, 5, 6],
+            &[true, true, false, true, true, true],
+        )
+        .unwrap();
+
+        let one = ColumnWrapper::new_const(&dict, 1u32);
+        let two = ColumnWrapper::new_const(&dict, 2u32);
+        let mut expr = TableExpression {
+            op: ">".to_string(),
+            input: vec![
+                ExpressionInput::Column(0),
+                ExpressionInput::Expr(TableExpression {
+                    op: "+".to_string(),
+                    input: vec![ExpressionInput::Const(&one), ExpressionInput::Const(&two)],
+                    partition_by: vec![],
+                }),
+            ],
+            partition_by: vec![],
+        };
+        assert!(!expr.is_const());
+        expr.fold_constants(&dict).unwrap();
+        match &expr.input[1] {
+            ExpressionInput::OwnedConst(c) => assert_eq!(c.to_const::<u32>(&dict).unwrap(), 3),
+            _ => panic!("Constant sub-expression was not folded"),
+        }
+
+        let three = ColumnWrapper::new_const(&dict, 3u32);
+        let second = TableExpression {
+            op: ">=".to_string(),
+            input: vec![ExpressionInput::Column(1), ExpressionInput::Const(&three)],
+            partition_by: vec![],
+        };
+        t.filter_all(&dict, &ctx, &[expr, second]).unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["7", "4", "9"]
+        );
+        assert_eq!(t.number_of_rows(), 3);
+    }
+
+    #[test]
+    fn filter_updates_partition_sizes() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let mut t: Table = Table::new(vec![3, 3]);
+        t.push(&dict, &[1u32, 5, 3, 7, 4, 9]).unwrap();
+        t.push(&dict, &[1u64, 2, 3, 4, 5, 6]).unwrap();
+
+        let four = ColumnWrapper::new_const(&dict, 4u32);
+        let nine = ColumnWrapper::new_const(&dict, 9u32);
+        let gt = TableExpression {
+            op: ">".to_string(),
+            input: vec![ExpressionInput::Column(0), ExpressionInput::Const(&four)],
+            partition_by: vec![],
+        };
+        let lt = TableExpression {
+            op: "<".to_string(),
+            input: vec![ExpressionInput::Column(0), ExpressionInput::Const(&nine)],
+            partition_by: vec![],
+        };
+        //The first filter adds the index, the second one filters it
+        t.filter(&dict, &ctx, &gt).unwrap();
+        assert_eq!(t.partition_sizes(), &[1, 2]);
+        t.filter(&dict, &ctx, &lt).unwrap();
+        assert_eq!(t.partition_sizes(), &[1, 1]);
+        assert_eq!(t.number_of_rows(), 2);
+        assert_eq!(t.materialize::<u64>(&dict, &ctx, &1).unwrap().0, vec![2, 4]);
+    }
+
+    #[test]
+    fn physical_execution_pipeline() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let products: Vec<String> = vec!["A", "B", "A", "C", "B", "A"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let regions: Vec<String> = vec!["EU", "US", "EU"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let mut sales: Table = Table::new(vec![2, 2, 2]);
+        sales.push(&dict, &[1u32, 2, 3, 4, 5, 6]).unwrap();
+        sales.push(&dict, &[5u32, 1, 3, 7, 2, 4]).unwrap();
+        sales.push(&dict, &products).unwrap();
+        sales.push(&dict, &[10u64, 2, 4, 1, 8, 6]).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "qty").unwrap();
+        sales.set_column_name(&2, "product").unwrap();
+        sales.set_column_name(&3, "price").unwrap();
+
+        let mut customers: Table = Table::new(vec![1, 2]);
+        customers.push(&dict, &[1u32, 2, 3]).unwrap();
+        customers.push(&dict, &regions).unwrap();
+        customers.set_column_name(&0, "cust_id").unwrap();
+        customers.set_column_name(&1, "region").unwrap();
+
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+        schema.register("customers", customers).unwrap();
+
+        //Partitions larger than a morsel are split, empty partitions still get a morsel
+        let morsels: Vec<_> = ExecutionContext::single_threaded()
+            .with_chunking(ChunkingStrategy::Morsels(2))
+            .morsels(&[3, 0, 2])
+            .into_iter()
+            .map(|m| (m.partition_id, m.rows))
+            .collect();
+        assert_eq!(morsels, vec![(0, 0..2), (0, 2..3), (1, 0..0), (2, 0..2)]);
+        let morsels: Vec<_> = ExecutionContext::single_threaded()
+            .with_chunking(ChunkingStrategy::Partitions)
+            .morsels(&[3, 0, 2])
+            .into_iter()
+            .map(|m| (m.partition_id, m.rows))
+            .collect();
+        assert_eq!(morsels, vec![(0, 0..3), (1, 0..0), (2, 0..2)]);
+
+        let scheduler =
+            Scheduler::with_context(ctx.clone().with_chunking(ChunkingStrategy::Morsels(1)));
+
+        //Filter and partial aggregation run in one pass per morsel, the partial results are merged
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT product, SUM(price) AS total FROM sales WHERE qty > 1 GROUP BY product ORDER BY product",
+            )
+            .unwrap();
+        assert_eq!(t.column_id("total").unwrap(), 1);
+        assert_eq!(
+            t.materialize_as_string_by_name(&dict, &ctx, "product")
+                .unwrap(),
+            vec!["A", "B", "C"]
+        );
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![20u64, 8, 1]
+        );
+
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT id, price + price FROM sales WHERE price > 2 ORDER BY qty DESC LIMIT 3",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &0).unwrap().0,
+            vec![1u32, 6, 3]
+        );
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![20u64, 12, 8]
+        );
+
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT COUNT(id) FROM sales WHERE price > 2",
+            )
+            .unwrap();
+        assert_eq!(t.materialize::<u64>(&dict, &ctx, &0).unwrap().0, vec![4u64]);
+
+        //COUNT(*) counts every row, also if every column can contain nulls
+        let mut nullable: Table = Table::new(vec![2, 2]);
+        nullable
+            .push_with_bitmap(&dict, &[1u64, 2, 1, 2], &[true, false, false, true])
+            .unwrap();
+        nullable
+            .push_with_bitmap(&dict, &[1u64, 1, 2, 2], &[true; 4])
+            .unwrap();
+        nullable.set_column_name(&0, "v").unwrap();
+        nullable.set_column_name(&1, "g").unwrap();
+        let mut nullable_schema = Schema::new();
+        nullable_schema.register("n", nullable).unwrap();
+        let counts = |query: &str| -> Vec<Vec<String>> {
+            let res = scheduler
+                .execute_sql(&dict, &nullable_schema, query)
+                .unwrap();
+            (0..res.number_of_columns().unwrap())
+                .map(|column_id| res.materialize_as_string(&dict, &ctx, &column_id).unwrap())
+                .collect()
+        };
+        assert_eq!(
+            counts("SELECT COUNT(*), COUNT(v) FROM n"),
+            vec![vec!["4"], vec!["2"]]
+        );
+        assert_eq!(
+            counts("SELECT COUNT(*) FROM n WHERE v = 1"),
+            vec![vec!["1"]]
+        );
+        assert_eq!(
+            counts("SELECT g, COUNT(*), COUNT(v) FROM n GROUP BY g ORDER BY g"),
+            vec![vec!["1", "2"], vec!["2", "2"], vec!["1", "1"]]
+        );
+        //MIN and MAX skip the nulls, also when the partial results of the morsels are merged
+        assert_eq!(
+            counts("SELECT g, MIN(v), MAX(v) FROM n GROUP BY g ORDER BY g"),
+            vec![vec!["1", "2"], vec!["1", "2"], vec!["1", "2"]]
+        );
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT product, MIN(price), MAX(qty) FROM sales GROUP BY product ORDER BY product",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![4u64, 2, 1]
+        );
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &2).unwrap().0,
+            vec![5u32, 2, 7]
+        );
+
+        //Nothing passes the filter
+        let t = scheduler
+            .execute_sql(&dict, &schema, "SELECT id FROM sales WHERE price > 100")
+            .unwrap();
+        assert_eq!(t.number_of_rows(), 0);
+
+        //The customers are the build side of the join, the sales are streamed through the probe
+        let t_string = TypeId::of::<String>();
+        let plan = LogicalPlan::Project {
+            input: Box::new(LogicalPlan::Filter {
+                input: Box::new(LogicalPlan::Join {
+                    left: Box::new(LogicalPlan::scan(&schema, "sales").unwrap()),
+                    right: Box::new(LogicalPlan::scan(&schema, "customers").unwrap()),
+                    on: vec![(0, 0)],
+                }),
+                predicates: vec![BoundExpr::Op {
+                    op: "==".to_string(),
+                    input: vec![
+                        BoundExpr::Column {
+                            column_id: 5,
+                            item_type_id: t_string,
+                        },
+                        BoundExpr::Literal {
+                            value: Literal::Str("EU".to_string()),
+                            item_type_id: t_string,
+                        },
+                    ],
+                    output_type_id: TypeId::of::<bool>(),
+                }],
+            }),
+            exprs: vec![
+                BoundSelectItem {
+                    name: "id".to_string(),
+                    expr: BoundExpr::Column {
+                        column_id: 0,
+                        item_type_id: TypeId::of::<u32>(),
+                    },
+                },
+                BoundSelectItem {
+                    name: "region".to_string(),
+                    expr: BoundExpr::Column {
+                        column_id: 5,
+                        item_type_id: t_string,
+                    },
+                },
+            ],
+        };
+        let t = scheduler.execute(&dict, &schema, &plan).unwrap();
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &0).unwrap().0,
+            vec![1u32, 3]
+        );
+        assert_eq!(
+            t.materialize_as_string_by_name(&dict, &ctx, "region")
+                .unwrap(),
+            vec!["EU", "EU"]
+        );
+
+        //Table operations use the buffers of the scheduler as well
+        let mut sales: Table = Table::new(vec![3, 3]);
+        sales.push(&dict, &[1u32, 2, 3, 4, 5, 6]).unwrap();
+        sales.push(&dict, &[10u64, 2, 4, 1, 8, 6]).unwrap();
+        let groups = sales.build_groups(&dict, &ctx, &[1]).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], (vec![0, 1, 2], 3));
+    }
+
+    #[test]
+    fn execution_context_isolation() {
+        let dict = Dictionary::new();
+        let mut sales: Table = Table::new(vec![3, 1, 2]);
+        sales.push(&dict, &[1u32, 2, 3, 4, 5, 6]).unwrap();
+        sales.push(&dict, &[10u64, 2, 4, 1, 8, 6]).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "price").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+
+        //Tasks run in the pool of the context, a single worker takes them in order
+        let ctx = ExecutionContext::with_threads(4)
+            .unwrap()
+            .with_max_degree_of_parallelism(1);
+        assert_eq!(ctx.degree_of_parallelism(), 1);
+        let res = ctx
+            .map_tasks((0..8).collect(), |_buffers, i| {
+                Ok(vec![(i, rayon::current_num_threads())])
+            })
+            .unwrap();
+        assert_eq!(res, (0..8).map(|i| (i, 4)).collect::<Vec<_>>());
+        let order = std::sync::Mutex::new(vec![]);
+        ExecutionContext::single_threaded()
+            .for_each_partition(&mut [0, 1, 2, 3], |_buffers, p| {
+                order.lock().unwrap().push(*p);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(order.into_inner().unwrap(), vec![0, 1, 2, 3]);
+
+        //Errors of the tasks are returned
+        let res: Result<Vec<()>, _> = ctx.map_tasks(vec![0, 1], |_buffers, i| {
+            if i == 1 {
+                Err("task failed")?
+            }
+            Ok(vec![])
+        });
+        assert_eq!(res.unwrap_err().to_string(), "task failed");
+
+        //Concurrent queries with their own pools return the same result
+        let query = "SELECT id, price FROM sales WHERE price > 2 ORDER BY price";
+        let contexts = vec![
+            ExecutionContext::single_threaded().with_chunking(ChunkingStrategy::Morsels(1)),
+            ExecutionContext::with_threads(3)
+                .unwrap()
+                .with_chunking(ChunkingStrategy::Partitions),
+        ];
+        let (dict, schema) = (&dict, &schema);
+        let results: Vec<_> = std::thread::scope(|s| {
+            let handles: Vec<_> = contexts
+                .into_iter()
+                .map(|ctx| {
+                    s.spawn(move || {
+                        let t = Scheduler::with_context(ctx.clone())
+                            .execute_sql(dict, schema, query)
+                            .unwrap();
+                        (
+                            t.materialize::<u32>(dict, &ctx, &0).unwrap().0,
+                            t.materialize::<u64>(dict, &ctx, &1).unwrap().0,
+                        )
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(results[0], (vec![3, 6, 5, 1], vec![4, 6, 8, 10]));
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn skewed_partition_work_stealing() {
+        let dict = Dictionary::new();
+        //One partition holds almost all the rows
+        let partition_sizes = vec![10000, 10, 10];
+        let values: Vec<u64> = (0..10020).collect();
+        let mut t: Table = Table::new(partition_sizes.clone());
+        t.push(&dict, &values).unwrap();
+        t.set_column_name(&0, "v").unwrap();
+
+        //The large partition is split into morsels which are stolen by the idle threads
+        let ctx = ExecutionContext::with_threads(2)
+            .unwrap()
+            .with_chunking(ChunkingStrategy::Morsels(1000));
+        let morsels = ctx.morsels(&partition_sizes);
+        assert_eq!(morsels.iter().filter(|m| m.partition_id == 0).count(), 10);
+        assert_eq!(morsels.len(), 12);
+
+        //The buffers of a thread are kept for its next task, only the first task of every thread allocates
+        let res = ctx
+            .map_tasks(morsels, |buffers, m| {
+                let c = buffers.buffer.pop(&dict, TypeId::of::<u64>())?;
+                let reuse_hits = buffers.buffer.reuse_hits();
+                buffers.buffer.push(&dict, c);
+                Ok(vec![(m.rows.len(), reuse_hits)])
+            })
+            .unwrap();
+        assert_eq!(res.iter().map(|(rows, _)| rows).sum::<usize>(), 10020);
+        assert!(res.iter().filter(|(_, hits)| *hits == 0).count() <= ctx.number_of_threads());
+
+        let mut schema = Schema::new();
+        schema.register("t", t).unwrap();
+        let sum = Scheduler::with_context(ctx.clone())
+            .execute_sql(&dict, &schema, "SELECT SUM(v) FROM t")
+            .unwrap();
+        assert_eq!(
+            sum.materialize::<u64>(&dict, &ctx, &0).unwrap().0,
+            vec![values.iter().sum::<u64>()]
+        );
+    }
+
+    #[test]
+    fn memory_limit_exceeded() {
+        let dict = Dictionary::new();
+        let ids: Vec<u32> = (0..1000).collect();
+        let prices = vec![1u64; 1000];
+        let mut sales: Table = Table::new(vec![500, 500]);
+        sales.push(&dict, &ids).unwrap();
+        sales.push(&dict, &prices).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "price").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+        let query = "SELECT id, SUM(price) FROM sales GROUP BY id";
+
+        //The hash maps of the grouping do not fit into the budget
+        let ctx = ExecutionContext::single_threaded().with_memory_limit(1024);
+        let err = Scheduler::with_context(ctx.clone())
+            .execute_sql(&dict, &schema, query)
+            .err()
+            .unwrap();
+        let err = match err {
+            RadixError::MemoryLimitExceeded(e) => e,
+            e => panic!("Unexpected error: {}", e),
+        };
+        assert_eq!(err.limit, 1024);
+        assert!(err.requested > 1024);
+        assert!(ctx.memory().peak() <= 1024);
+
+        let err = schema
+            .get("sales")
+            .unwrap()
+            .build_groups(&dict, &ctx, &[0])
+            .err()
+            .unwrap();
+        assert!(matches!(err, RadixError::MemoryLimitExceeded(_)));
+
+        //With a large enough budget the query runs, the memory kept by the buffers stays reserved
+        let ctx = ExecutionContext::single_threaded().with_memory_limit(1 << 20);
+        let t = Scheduler::with_context(ctx.clone())
+            .execute_sql(&dict, &schema, query)
+            .unwrap();
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![1u64; 1000]
+        );
+        assert!(ctx.memory().peak() > 0);
+        assert!(ctx.memory().peak() <= 1 << 20);
+
+        //Outputs of operations are reserved once they are written, a single output can exceed the budget
+        let small = ExecutionContext::single_threaded().with_memory_limit(2048);
+        let err = Scheduler::with_context(small.clone())
+            .execute_sql(&dict, &schema, "SELECT price + price FROM sales")
+            .err()
+            .unwrap();
+        assert!(matches!(err, RadixError::MemoryLimitExceeded(_)));
+        let err = schema
+            .get("sales")
+            .unwrap()
+            .materialize::<u64>(&dict, &small, &1)
+            .err()
+            .unwrap();
+        assert!(matches!(err, RadixError::MemoryLimitExceeded(_)));
+        let small = ExecutionContext::single_threaded().with_memory_limit(256);
+        let mut filtered: Table = Table::new(vec![500, 500]);
+        filtered.push(&dict, &ids).unwrap();
+        filtered.push(&dict, &ids).unwrap();
+        let err = filtered
+            .filter(&dict, &small, &TableExpression::new("==", &[0, 1]))
+            .unwrap_err();
+        assert!(matches!(err, RadixError::MemoryLimitExceeded(_)));
+        assert_eq!(small.memory().reserved(), 0);
+        assert!(small.memory().peak() <= 256);
+
+        //Reservations are released when they are dropped
+        let tracker = std::sync::Arc::new(MemoryTracker::with_limit(100));
+        let mut reservation = MemoryReservation::new(tracker.clone());
+        reservation.grow(60).unwrap();
+        assert_eq!(
+            reservation.grow(60).unwrap_err(),
+            MemoryLimitExceeded {
+                requested: 60,
+                reserved: 60,
+                limit: 100
+            }
+        );
+        drop(reservation);
+        assert_eq!(tracker.reserved(), 0);
+        assert_eq!(tracker.peak(), 60);
+
+        //The buckets of a repartitioned column keep their memory reserved until they are dropped
+        let sales = schema.get("sales").unwrap();
+        let hash = sales.build_hash(&dict, &ctx, &[0]).unwrap();
+        let tmap = TableToTableMap::new(&ctx, &hash, 2, 1);
+        let reserved = ctx.memory().reserved();
+        let res = unsafe { sales.column_repartition(&dict, &ctx, &hash, &tmap, &1) }.unwrap();
+        assert_eq!(res.reservation.size(), 1000 * std::mem::size_of::<u64>());
+        assert_eq!(ctx.memory().reserved(), reserved + res.reservation.size());
+        drop(res);
+        assert_eq!(ctx.memory().reserved(), reserved);
+    }
+
+    #[test]
+    fn spill_to_disk() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let directory = std::env::temp_dir().join(format!("radix-spill-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        //Columns are read back with their nulls, const columns stay const
+        let names: Vec<String> = vec!["a", "", "ccc"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut c1 = ColumnWrapper::new_from_vec(&dict, names.clone());
+        c1.bitmap_set(ColumnDataF::new(vec![true, false, true]));
+        let c2 = ColumnWrapper::new_from_vec(&dict, vec![1u64, 2, 3]);
+        let c3 = ColumnWrapper::new_const(&dict, 7u32);
+        let batch = Batch::new(vec![c1, c2, c3], 3);
+        let mut file = SpillFile::create(&directory).unwrap();
+        file.write(&dict, &batch).unwrap();
+        let mut indexed = Batch::new(vec![batch.take(&dict, 0, None).unwrap()], 3);
+        indexed.truncate(2).unwrap();
+        assert!(file.write(&dict, &indexed).is_err());
+        let read = file.read(&dict).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(
+            read[0].columns[0]
+                .as_string(&dict, &ColumnDataIndex::None)
+                .unwrap(),
+            names
+        );
+        assert_eq!(
+            read[0].columns[0].bitmap().downcast_ref().unwrap().to_vec(),
+            [true, false, true]
+        );
+        assert_eq!(
+            read[0].columns[1].column().downcast_ref::<u64>().unwrap(),
+            &[1, 2, 3]
+        );
+        assert!(read[0].columns[2].column().is_const());
+        let path = file.path().to_path_buf();
+        assert!(path.exists());
+        drop(file);
+        assert!(!path.exists());
+
+        //The aggregate does not fit into the budget, its input is spilled and aggregated bucket by bucket
+        let ids: Vec<u32> = (0..1000).map(|i| i % 400).collect();
+        let prices: Vec<u64> = (0..1000).collect();
+        let mut sales: Table = Table::new(vec![600, 400]);
+        sales.push(&dict, &ids).unwrap();
+        sales.push(&dict, &prices).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "price").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+        let query = "SELECT id, SUM(price) FROM sales GROUP BY id";
+
+        let groups = |t: &Table| {
+            let ids = t.materialize::<u32>(&dict, &ctx, &0).unwrap().0;
+            let sums = t.materialize::<u64>(&dict, &ctx, &1).unwrap().0;
+            let mut groups: Vec<_> = ids.into_iter().zip(sums).collect();
+            groups.sort();
+            groups
+        };
+        let expected = groups(&Scheduler::new().execute_sql(&dict, &schema, query).unwrap());
+        assert_eq!(expected.len(), 400);
+
+        let ctx = ExecutionContext::single_threaded().with_memory_limit(8192);
+        assert!(Scheduler::with_context(ctx)
+            .execute_sql(&dict, &schema, query)
+            .is_err());
+        let ctx = ExecutionContext::with_threads(2)
+            .unwrap()
+            .with_memory_limit(8192)
+            .with_spilling(&directory)
+            .with_spill_bucket_bits(4);
+        let plan =
+            LogicalPlan::from_select(&schema, &bind(&schema, &dict, query).unwrap()).unwrap();
+        let (t, explain) = Scheduler::with_context(ctx.clone())
+            .explain_analyze(&dict, &schema, &plan)
+            .unwrap();
+        assert_eq!(groups(&t), expected);
+        assert!(explain.contains("[SpilledAggregate] partitions=1"));
+        assert!(ctx.memory().peak() <= 8192);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 0);
+
+        //Rows are spilled to the bucket of their hash
+        let sales = schema.get("sales").unwrap();
+        let hash = sales.build_hash(&dict, &ctx, &[0]).unwrap();
+        let mut buckets = sales
+            .spill_repartition(&dict, &ctx, &hash, 2, &[0, 1])
+            .unwrap();
+        assert_eq!(buckets.len(), 4);
+        let rows: usize = buckets.iter().map(|b| b.number_of_rows()).sum();
+        assert_eq!(rows, 1000);
+        for (i, bucket) in buckets.iter_mut().enumerate() {
+            for batch in bucket.read(&dict).unwrap().iter() {
+                let mut h = vec![];
+                batch.columns[0]
+                    .hash_in(&dict, &ColumnDataIndex::None, &mut h)
+                    .unwrap();
+                assert!(h.iter().all(|h| (h & 3) as usize == i));
+            }
+        }
+        drop(buckets);
+        assert!(ExecutionContext::single_threaded()
+            .with_spilling(&directory)
+            .spill_directory()
+            .is_some());
+        std::fs::remove_dir(&directory).unwrap();
+    }
+
+    #[test]
+    fn cancellation_and_progress() {
+        let dict = Dictionary::new();
+        let mut t: Table = Table::new(vec![2, 2, 2, 2]);
+        t.push(&dict, &[1u32, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        t.push(&dict, &[1u32, 0, 3, 0, 5, 0, 7, 0]).unwrap();
+        let e = TableExpression::new("==", &[0, 1]);
+
+        //Progress is reported after every partition
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let r = reports.clone();
+        let ctx = ExecutionContext::single_threaded().with_progress(move |p| {
+            r.lock().unwrap().push((p.completed, p.total));
+        });
+        let groups = t.build_groups(&dict, &ctx, &[0]).unwrap();
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            reports.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec![
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ]
+        );
+
+        let hash = t.build_hash(&dict, &ctx, &[0]).unwrap();
+        reports.lock().unwrap().clear();
+        let tmap = TableToTableMap::new(&ctx, &hash, 2, 1);
+        let res = unsafe { t.column_repartition(&dict, &ctx, &hash, &tmap, &0) }
+            .unwrap()
+            .buckets;
+        assert_eq!(res.len(), 2);
+        assert_eq!(reports.lock().unwrap().last(), Some(&(2, 2)));
+
+        //The operation stops at the next partition once the token is cancelled
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let r = reports.clone();
+        let ctx = ExecutionContext::single_threaded()
+            .with_cancellation(token.clone())
+            .with_progress(move |p| {
+                r.lock().unwrap().push(p.completed);
+                if p.completed == 2 {
+                    cancel.cancel();
+                }
+            });
+        let mut filtered: Table = Table::new(vec![2, 2, 2, 2]);
+        filtered.push(&dict, &[1u32, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        filtered.push(&dict, &[1u32, 0, 3, 0, 5, 0, 7, 0]).unwrap();
+        let err = filtered.filter(&dict, &ctx, &e).unwrap_err();
+        assert!(matches!(err, RadixError::Cancelled));
+        assert_eq!(*reports.lock().unwrap(), vec![1, 2]);
+
+        //A cancelled token fails every operation of the context
+        assert!(token.is_cancelled());
+        let err = t.build_groups(&dict, &ctx, &[0]).unwrap_err();
+        assert!(matches!(err, RadixError::Cancelled));
+        let err = unsafe { t.column_repartition(&dict, &ctx, &hash, &tmap, &0) }
+            .err()
+            .unwrap();
+        assert!(matches!(err, RadixError::Cancelled));
+        let err = t.op(&dict, &ctx, "+=", &0, &[1]).unwrap_err();
+        assert!(matches!(err, RadixError::Cancelled));
+        let err = t.materialize::<u32>(&dict, &ctx, &0).err().unwrap();
+        assert!(matches!(err, RadixError::Cancelled));
+
+        t.set_column_name(&0, "a").unwrap();
+        t.set_column_name(&1, "b").unwrap();
+        let mut schema = Schema::new();
+        schema.register("t", t).unwrap();
+        let err = Scheduler::with_context(ctx)
+            .execute_sql(&dict, &schema, "SELECT a FROM t WHERE a = b")
+            .unwrap_err();
+        assert!(matches!(err, RadixError::Cancelled));
+    }
+
+    #[test]
+    fn typed_errors() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::with_threads(2).unwrap();
+        let mut c0 = vec![1u32, 2, 3, 4];
+        let mut t: Table = Table::new(vec![2, 2]);
+        t.push_mut(&mut c0).unwrap();
+        t.push(&dict, &[1u64, 2, 3, 4]).unwrap();
+        t.push(&dict, &[1u32, 2, 3, 4]).unwrap();
+
+        //Errors of the partitions are returned from the worker threads instead of panicking
+        let err = t.op(&dict, &ctx, "+=", &0, &[1]).unwrap_err();
+        match err {
+            RadixError::SignatureNotFound {
+                op_name,
+                input,
+                input_names,
+            } => {
+                assert_eq!(op_name, "+=");
+                assert_eq!(
+                    input,
+                    vec![std::any::TypeId::of::<u32>(), std::any::TypeId::of::<u64>()]
+                );
+                assert_eq!(input_names, vec!["u32", "u64"]);
+            }
+            e => panic!("Unexpected error: {}", e),
+        }
+        let e = TableExpression::new("==", &[0, 1]);
+        let err = t.filter(&dict, &ctx, &e).unwrap_err();
+        assert!(matches!(err, RadixError::SignatureNotFound { .. }));
+
+        let err = t.materialize::<u64>(&dict, &ctx, &0).unwrap_err();
+        match err {
+            RadixError::TypeMismatch {
+                expected_name,
+                found_name,
+                ..
+            } => assert_eq!(
+                (expected_name.as_str(), found_name.as_str()),
+                ("u64", "u32")
+            ),
+            e => panic!("Unexpected error: {}", e),
+        }
+
+        let c = ColumnWrapper::new_from_vec(&dict, vec![1u32]);
+        let err = c.column().downcast_ref::<u64>().unwrap_err();
+        assert!(matches!(
+            err,
+            RadixError::DowncastFailed {
+                target_name: "u64",
+                ..
+            }
+        ));
+
+        //The table can still be used after a failed operation
+        t.op(&dict, &ctx, "+=", &0, &[2]).unwrap();
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &0).unwrap().0,
+            vec![2, 4, 6, 8]
+        );
+    }
+
+    #[test]
+    fn logical_type_names() {
+        let mut dict = Dictionary::new();
+        let string = dict.logical_type(std::any::TypeId::of::<String>()).unwrap();
+        assert_eq!(string.name, "String");
+        assert!(string.is_binary && string.is_orderable && !string.is_numeric);
+        let u64_type = dict.logical_type(std::any::TypeId::of::<u64>()).unwrap();
+        assert!(u64_type.is_sized() && u64_type.is_numeric);
+
+        //Errors and signatures print the registered names instead of the TypeIds
+        let c = ColumnWrapper::new_from_vec(&dict, vec![1u32]);
+        let err = c.column().downcast_ref::<String>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Downcast failed. Source type is u32, target type is String"
+        );
+        let signature = Signature::new("+", vec![std::any::TypeId::of::<u64>()]);
+        assert_eq!(
+            format!("{:?}", signature),
+            "Signature { op_name: \"+\", input: [u64] }"
+        );
+
+        dict.register_type(LogicalType::sized::<i64>("i64").numeric().orderable());
+        assert_eq!(dict.type_name(std::any::TypeId::of::<i64>()), Some("i64"));
+        assert_eq!(TypeName(std::any::TypeId::of::<i64>()).to_string(), "i64");
+    }
+
+    #[test]
+    fn scalar_udf() {
+        let mut dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        dict.register_scalar::<u64, u32, u64, _>(
+            "WEIGHTED",
+            |price, qty| price * *qty as u64,
+            NullPolicy::Propagate,
+        )
+        .unwrap();
+        dict.register_scalar::<u64, u32, bool, _>(
+            "CHEAP",
+            |price, qty| *price < 5 || *qty == 0,
+            NullPolicy::NeverNull,
+        )
+        .unwrap();
+        assert!(dict
+            .register_scalar::<String, u32, u64, _>(
+                "LEN",
+                |s, _| s.len() as u64,
+                NullPolicy::Propagate
+            )
+            .is_err());
+        //Names of registered operations and aggregates cannot be reused
+        for name in ["WEIGHTED", "+", "+=", "SUM", "MIN"].iter() {
+            assert!(dict
+                .register_scalar::<u64, u32, u64, _>(name, |price, _| *price, NullPolicy::Propagate)
+                .is_err());
+        }
+
+        let mut sales: Table = Table::new(vec![2, 2]);
+        sales.push(&dict, &[10u64, 2, 4, 6]).unwrap();
+        sales
+            .push_with_bitmap(&dict, &[1u32, 2, 3, 4], &[true, true, false, true])
+            .unwrap();
+        sales.set_column_name(&0, "price").unwrap();
+        sales.set_column_name(&1, "qty").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+
+        //Functions are called like the built-in operations, nulls are propagated by the first one
+        let t = Scheduler::new()
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT WEIGHTED(price, qty), CHEAP(price, qty) FROM sales",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["10", "4", "(null)", "24"]
+        );
+        assert_eq!(
+            t.materialize::<bool>(&dict, &ctx, &1).unwrap().0,
+            vec![false, true, true, false]
+        );
+        assert!(!t.materialize::<bool>(&dict, &ctx, &1).unwrap().1.is_some());
+
+        let err = Scheduler::new()
+            .execute_sql(&dict, &schema, "SELECT WEIGHTED(qty, price) FROM sales")
+            .unwrap_err();
+        assert!(err.to_string().contains("not defined for types (u32, u64)"));
+    }
+
+    #[test]
+    fn custom_column_types() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        struct CustomerId(u64);
+        impl std::fmt::Display for CustomerId {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "C{}", self.0)
+            }
+        }
+
+        #[derive(Clone, Debug, AsBytes)]
+        struct Sku(String);
+        impl std::fmt::Display for Sku {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "#{}", self.0)
+            }
+        }
+
+        let mut dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        dict.register_sized_type::<CustomerId>(
+            LogicalType::sized::<CustomerId>("customer_id").orderable(),
+        )
+        .unwrap();
+        dict.register_binary_type::<Sku>(LogicalType::binary::<Sku>("sku"))
+            .unwrap();
+        assert!(dict
+            .register_sized_type::<CustomerId>(LogicalType::sized::<CustomerId>("customer_id"))
+            .is_err());
+        assert!(dict
+            .register_sized_type::<u64>(LogicalType::sized::<u32>("u32"))
+            .is_err());
+
+        //Types without Display or Ord are printed by a function, they are grouped but not sorted
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        struct Point(u32, u32);
+        let format_point: SizedFormat<Point> = |p| format!("({}, {})", p.0, p.1);
+        assert!(dict
+            .register_sized_type_with_format::<Point>(
+                LogicalType::sized::<Point>("point").orderable(),
+                format_point,
+                None
+            )
+            .is_err());
+        dict.register_sized_type_with_format::<Point>(
+            LogicalType::sized::<Point>("point"),
+            format_point,
+            None,
+        )
+        .unwrap();
+
+        let mut orders: Table = Table::new(vec![3, 3]);
+        let customers = [2, 1, 2, 1, 3, 2].map(CustomerId);
+        orders.push(&dict, &customers).unwrap();
+        let skus: Vec<Sku> = vec!["a", "bb", "a", "ccc", "bb", "a"]
+            .into_iter()
+            .map(|s| Sku(s.to_string()))
+            .collect();
+        orders.push(&dict, &skus).unwrap();
+        orders.push(&dict, &[5u64, 1, 2, 3, 4, 6]).unwrap();
+        orders.set_column_name(&0, "customer").unwrap();
+        orders.set_column_name(&1, "sku").unwrap();
+        orders.set_column_name(&2, "amount").unwrap();
+        assert_eq!(
+            orders.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            vec!["#a", "#bb", "#a", "#ccc", "#bb", "#a"]
+        );
+
+        //Rows are copied to the bucket of the hash of their customer
+        let ctx = ExecutionContext::with_threads(2).unwrap();
+        let hash = orders.build_hash(&dict, &ctx, &[0]).unwrap();
+        let tmap = TableToTableMap::new(&ctx, &hash, 2, 1);
+        for col_id in [0, 1] {
+            let res = unsafe { orders.column_repartition(&dict, &ctx, &hash, &tmap, &col_id) }
+                .unwrap()
+                .buckets;
+            let rows: usize = res.iter().map(|c| c.len(&dict).unwrap()).sum();
+            assert_eq!(rows, 6);
+        }
+
+        let mut schema = Schema::new();
+        schema.register("orders", orders).unwrap();
+        let t = Scheduler::with_context(ctx.clone())
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT customer, SUM(amount) FROM orders GROUP BY customer ORDER BY customer",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["C1", "C2", "C3"]
+        );
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![4, 13, 4]
+        );
+        assert_eq!(
+            t.column_schema(&0).unwrap().type_name(&dict).unwrap(),
+            "customer_id"
+        );
+
+        let points = [Point(1, 2), Point(0, 0), Point(1, 2)];
+        let mut shapes: Table = Table::new(vec![2, 1]);
+        shapes.push(&dict, &points).unwrap();
+        shapes.push(&dict, &[1u64, 2, 3]).unwrap();
+        shapes.set_column_name(&0, "p").unwrap();
+        shapes.set_column_name(&1, "n").unwrap();
+        assert_eq!(
+            shapes.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["(1, 2)", "(0, 0)", "(1, 2)"]
+        );
+        schema.register("shapes", shapes).unwrap();
+        let scheduler = Scheduler::new();
+        let t = scheduler
+            .execute_sql(&dict, &schema, "SELECT p, SUM(n) FROM shapes GROUP BY p")
+            .unwrap();
+        assert_eq!(t.number_of_rows(), 2);
+        let err = scheduler
+            .execute_sql(&dict, &schema, "SELECT p FROM shapes ORDER BY p")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Columns of type point cannot be sorted");
+    }
+
+    #[test]
+    fn as_bytes_derive() {
+        #[derive(Clone, Debug, PartialEq, AsBytes)]
+        struct Record {
+            id: u64,
+            name: String,
+            payload: Vec<u8>,
+            checksum: [u8; 4],
+        }
+        impl std::fmt::Display for Record {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}:{}", self.id, self.name)
+            }
+        }
+        #[derive(Clone, Debug, PartialEq, AsBytes)]
+        enum Shape {
+            Point,
+            Circle(u32),
+            Rect { w: u16, h: u16 },
+        }
+
+        let records = vec![
+            Record {
+                id: 1,
+                name: "first".to_string(),
+                payload: vec![0, 255, 7],
+                checksum: [1, 2, 3, 4],
+            },
+            Record {
+                id: 2,
+                name: String::new(),
+                payload: vec![],
+                checksum: [0; 4],
+            },
+        ];
+        let c = OnwedBinaryColumn::new(&records);
+        let (data, offsets) = c.downcast_binary_ref::<Record>().unwrap();
+        let decoded: Vec<Record> = binary_items(data, offsets)
+            .map(|v| Record::try_from_bytes(v).unwrap())
+            .collect();
+        assert_eq!(decoded, records);
+
+        let shapes = [Shape::Point, Shape::Circle(3), Shape::Rect { w: 4, h: 5 }];
+        for shape in shapes.iter() {
+            assert_eq!(&Shape::from_bytes(&shape.as_bytes()), shape);
+        }
+        //The encoding is stable: variant, then every field with its length
+        assert_eq!(
+            Shape::Rect { w: 4, h: 5 }.as_bytes().as_ref(),
+            &[2, 0, 0, 0, 2, 0, 0, 0, 4, 0, 2, 0, 0, 0, 5, 0]
+        );
+
+        //Invalid bytes are reported instead of panicking
+        assert!(String::try_from_bytes(&[0xff, 0xfe]).is_err());
+        assert!(Shape::try_from_bytes(&[7, 0, 0, 0]).is_err());
+        assert!(Shape::try_from_bytes(&[1, 0, 0, 0, 4, 0]).is_err());
+        let mut bytes = Shape::Point.as_bytes().into_owned();
+        bytes.push(0);
+        assert!(matches!(
+            Shape::try_from_bytes(&bytes),
+            Err(RadixError::LengthMismatch { .. })
+        ));
+        assert!(<[u8; 2]>::try_from_bytes(&[1, 2, 3]).is_err());
+        let blob: Box<[u8]> = vec![9u8, 8].into_boxed_slice();
+        assert_eq!(Box::<[u8]>::from_bytes(&blob.as_bytes()), blob);
+
+        //Derived types can be registered as binary column types
+        let mut dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        dict.register_binary_type::<Record>(LogicalType::binary::<Record>("record"))
+            .unwrap();
+        let mut t: Table = Table::new(vec![2]);
+        t.push(&dict, &records).unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["1:first", "2:"]
+        );
+    }
+
+    #[test]
+    fn blob_column() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let mut t: Table = Table::new(vec![2, 3]);
+        t.push(&dict, &[1u64, 2, 3, 4, 5]).unwrap();
+        let digests: Vec<Blob> = vec![
+            vec![0xde, 0xad],
+            vec![0xff, 0xfe, 0x00],
+            vec![0xde, 0xad],
+            vec![],
+            vec![0x80],
+        ];
+        t.push(&dict, &digests).unwrap();
+        let expected: Vec<Blob> = vec![vec![0xde, 0xad], vec![0xff], vec![], vec![], vec![0x80]];
+        t.push(&dict, &expected).unwrap();
+        t.set_column_name(&0, "id").unwrap();
+        t.set_column_name(&1, "digest").unwrap();
+        t.set_column_name(&2, "expected").unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            vec!["dead", "fffe00", "dead", "", "80"]
+        );
+        assert_eq!(
+            t.column_schema(&1).unwrap().type_name(&dict).unwrap(),
+            "BLOB"
+        );
+
+        let ctx = ExecutionContext::with_threads(2).unwrap();
+        let hash = t.build_hash(&dict, &ctx, &[1]).unwrap();
+        let tmap = TableToTableMap::new(&ctx, &hash, 2, 1);
+        let res = unsafe { t.column_repartition(&dict, &ctx, &hash, &tmap, &1) }
+            .unwrap()
+            .buckets;
+        let rows: usize = res.iter().map(|c| c.len(&dict).unwrap()).sum();
+        assert_eq!(rows, 5);
+
+        let mut schema = Schema::new();
+        schema.register("t", t).unwrap();
+        let scheduler = Scheduler::with_context(ctx.clone());
+        let grouped = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT digest, COUNT(id), SUM(id) FROM t GROUP BY digest ORDER BY digest",
+            )
+            .unwrap();
+        assert_eq!(
+            grouped.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["", "80", "dead", "fffe00"]
+        );
+        assert_eq!(
+            grouped.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![1, 1, 2, 1]
+        );
+        assert_eq!(
+            grouped.materialize::<u64>(&dict, &ctx, &2).unwrap().0,
+            vec![4, 5, 4, 2]
+        );
+
+        let matching = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT id, LENGTH(digest) AS len FROM t WHERE digest = expected ORDER BY id",
+            )
+            .unwrap();
+        assert_eq!(
+            matching.materialize::<u64>(&dict, &ctx, &0).unwrap().0,
+            vec![1, 4, 5]
+        );
+        assert_eq!(
+            matching.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![2, 0, 1]
+        );
+    }
+
+    #[test]
+    fn datetime_types() {
+        let ts: Timestamp = "2024-03-10T08:30:00.250+01:00".parse().unwrap();
+        assert_eq!(ts.to_string(), "2024-03-10T08:30:00.250+01:00");
+        assert_eq!(ts.micros(), 1710055800250000);
+        assert_eq!(ts.extract(DateTimeField::Hour), 8);
+        assert_eq!(
+            ts.trunc(DateTimeField::Month).to_string(),
+            "2024-03-01T00:00:00+01:00"
+        );
+        let utc: Timestamp = "2024-03-10 07:30:00.25Z".parse().unwrap();
+        assert_eq!(ts, utc);
+        assert_eq!(
+            Timestamp::from_nanos(1).to_string(),
+            "1970-01-01T00:00:00.000000001"
+        );
+        assert_eq!(Date::from_ymd(1969, 12, 31).unwrap(), Date(-1));
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-03-10T25:00".parse::<Timestamp>().is_err());
+        assert_eq!(
+            "23:59:59.5".parse::<Time>().unwrap().to_string(),
+            "23:59:59.500"
+        );
+        let interval: Interval = "1 year 2 months 3 days 4 hours 30 minutes".parse().unwrap();
+        assert_eq!(interval.to_string(), "P1Y2M3DT4H30M");
+        assert_eq!("P1Y2M3DT4H30M".parse::<Interval>().unwrap(), interval);
+        assert_eq!(
+            "PT-1.5S".parse::<Interval>().unwrap(),
+            Interval::new(0, 0, -1_500_000_000)
+        );
+        //The day is clamped to the end of the month
+        assert_eq!(
+            Date::from_ymd(2024, 1, 31)
+                .unwrap()
+                .add_interval(&Interval::new(1, 0, 0))
+                .unwrap()
+                .to_string(),
+            "2024-02-29"
+        );
+
+        //Intervals and results out of range are errors instead of wrapping around
+        assert!("100000000000 hours".parse::<Interval>().is_err());
+        assert!("3000000000 days".parse::<Interval>().is_err());
+        assert!("PT9000000000000000000S".parse::<Interval>().is_err());
+        assert!("2147483647 days 1 day".parse::<Interval>().is_err());
+        assert!(Date::from_ymd(2024, 1, 31)
+            .unwrap()
+            .add_interval(&Interval::new(0, i32::MAX, 0))
+            .is_err());
+        assert!("2024-01-31T00:00:00"
+            .parse::<Timestamp>()
+            .unwrap()
+            .add_interval(&Interval::new(0, 200_000, 0))
+            .is_err());
+        assert!("9999-12-31".parse::<Timestamp>().is_err());
+        assert!("1000-01-01T00:00:00".parse::<Timestamp>().is_err());
+        assert!("2262-04-11T23:47:16.854775807-00:01"
+            .parse::<Timestamp>()
+            .is_err());
+        assert!(
+            Timestamp::from_date_time(Date::from_ymd(9999, 12, 31).unwrap(), Time(0)).is_none()
+        );
+        assert!(Timestamp::from_micros(i64::MAX).is_none());
+
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let mut sales: Table = Table::new(vec![2, 3]);
+        sales.push(&dict, &[1u64, 2, 3, 4, 5]).unwrap();
+        let days: Vec<Date> = [
+            "2024-01-31",
+            "2024-02-01",
+            "2024-02-29",
+            "2024-03-01",
+            "2023-02-15",
+        ]
+        .iter()
+        .map(|d| d.parse().unwrap())
+        .collect();
+        sales.push(&dict, &days).unwrap();
+        let times: Vec<Timestamp> = [
+            "2024-01-31T23:15:00Z",
+            "2024-02-01T08:00:00+02:00",
+            "2024-02-29T12:30:45",
+            "2024-03-01T00:00:00.5Z",
+            "2023-02-15T18:00:00Z",
+        ]
+        .iter()
+        .map(|t| t.parse().unwrap())
+        .collect();
+        sales.push(&dict, &times).unwrap();
+        sales.push(&dict, &[10u64, 20, 30, 40, 50]).unwrap();
+        let notes: Vec<String> = vec!["2024-01-01", "2024-13-01", "x", "", "y"]
+            .into_iter()
+            .map(|n| n.to_string())
+            .collect();
+        sales.push(&dict, &notes).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "day").unwrap();
+        sales.set_column_name(&2, "ts").unwrap();
+        sales.set_column_name(&3, "amount").unwrap();
+        sales.set_column_name(&4, "note").unwrap();
+        assert_eq!(
+            sales.column_schema(&1).unwrap().type_name(&dict).unwrap(),
+            "DATE"
+        );
+        assert_eq!(
+            sales.materialize_as_string(&dict, &ctx, &2).unwrap()[1],
+            "2024-02-01T08:00:00+02:00"
+        );
+
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+        let scheduler = Scheduler::with_context(ExecutionContext::with_threads(2).unwrap());
+        let run = |query: &str| scheduler.execute_sql(&dict, &schema, query);
+
+        let monthly = run(
+            "SELECT DATE_TRUNC('month', day) AS month, SUM(amount) FROM sales \
+             WHERE day >= '2024-01-01' GROUP BY DATE_TRUNC('month', day) ORDER BY 1",
+        )
+        .unwrap();
+        assert_eq!(
+            monthly.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["2024-01-01", "2024-02-01", "2024-03-01"]
+        );
+        assert_eq!(
+            monthly.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![10, 50, 40]
+        );
+
+        let t = run(
+            "SELECT id, EXTRACT(year FROM day), EXTRACT(HOUR FROM ts), day + INTERVAL '1 month', \
+             ts - INTERVAL 'PT1H', CAST(ts AS DATE) FROM sales \
+             WHERE ts < TIMESTAMP '2024-03-01T00:00:00Z' ORDER BY ts",
+        )
+        .unwrap();
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &0).unwrap().0,
+            vec![5, 1, 2, 3]
+        );
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &1).unwrap().0,
+            vec![2023, 2024, 2024, 2024]
+        );
+        assert_eq!(
+            t.materialize::<u32>(&dict, &ctx, &2).unwrap().0,
+            vec![18, 23, 8, 12]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &3).unwrap(),
+            vec!["2023-03-15", "2024-02-29", "2024-03-01", "2024-03-29"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &4).unwrap(),
+            vec![
+                "2023-02-15T17:00:00Z",
+                "2024-01-31T22:15:00Z",
+                "2024-02-01T07:00:00+02:00",
+                "2024-02-29T11:30:45"
+            ]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &5).unwrap(),
+            vec!["2023-02-15", "2024-01-31", "2024-02-01", "2024-02-29"]
+        );
+
+        let parsed = run("SELECT CAST(note AS DATE) FROM sales WHERE id = 1").unwrap();
+        assert_eq!(
+            parsed.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["2024-01-01"]
+        );
+        assert!(run("SELECT CAST(note AS DATE) FROM sales").is_err());
+        assert!(run("SELECT EXTRACT(hour FROM day) FROM sales").is_err());
+        assert!(run("SELECT CAST(amount AS DATE) FROM sales").is_err());
+        assert!(run("SELECT CAST(day AS TIMESTAMP) FROM sales").is_ok());
+        assert!(run("SELECT CAST(day + INTERVAL '1000 years' AS TIMESTAMP) FROM sales").is_err());
+        assert!(run("SELECT CAST(day - INTERVAL '1000 years' AS TIMESTAMP) FROM sales").is_err());
+        assert!(run("SELECT CAST(note AS TIMESTAMP) FROM sales WHERE id = 1").is_ok());
+        assert!(run("SELECT id FROM sales WHERE day = '2024-02-30'").is_err());
+    }
+
+    #[test]
+    fn decimal_type() {
+        let d = |s: &str| s.parse::<Decimal>().unwrap();
+        assert_eq!(d("12.50").to_string(), "12.50");
+        assert_eq!((d("12.50").precision(), d("12.50").scale()), (4, 2));
+        assert_eq!(d("-.05").to_string(), "-0.05");
+        assert_eq!(Decimal::new(-1234, 4, 3).unwrap().to_string(), "-1.234");
+        assert!(Decimal::new(12345, 4, 2).is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        //Values are compared by their numeric value, independent of the scale
+        assert_eq!(d("1.50"), d("1.5"));
+        assert!(d("-2") < d("-1.99") && d("0.10") > d("0.09"));
+        assert_eq!(
+            d("1.10").checked_add(&d("2.255")).unwrap().to_string(),
+            "3.355"
+        );
+        assert_eq!(
+            d("1.25")
+                .checked_mul(&d("0.1"), RoundingMode::HalfUp)
+                .unwrap()
+                .to_string(),
+            "0.125"
+        );
+        assert_eq!(
+            d("10.00")
+                .checked_div(&d("3"), RoundingMode::HalfUp)
+                .unwrap()
+                .to_string(),
+            "3.333333"
+        );
+        assert!(d("1")
+            .checked_div(&d("0.00"), RoundingMode::HalfUp)
+            .is_none());
+        let big = Decimal::new(10i128.pow(37), 38, 0).unwrap();
+        assert!(big.checked_mul(&d("10"), RoundingMode::HalfUp).is_none());
+        let round = |s: &str, mode| d(s).rescale(0, mode).unwrap().to_string();
+        assert_eq!(round("2.5", RoundingMode::HalfUp), "3");
+        assert_eq!(round("2.5", RoundingMode::HalfEven), "2");
+        assert_eq!(round("3.5", RoundingMode::HalfEven), "4");
+        assert_eq!(round("-2.5", RoundingMode::HalfUp), "-3");
+        assert_eq!(round("-2.5", RoundingMode::Down), "-2");
+        assert_eq!(round("-2.1", RoundingMode::Floor), "-3");
+        assert_eq!(round("-2.9", RoundingMode::Ceiling), "-2");
+        assert_eq!(
+            d("9.99")
+                .rescale(1, RoundingMode::HalfUp)
+                .unwrap()
+                .to_string(),
+            "10.0"
+        );
+
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let mut items: Table = Table::new(vec![2, 3]);
+        items.push(&dict, &[1u64, 2, 3, 4, 5]).unwrap();
+        let prices: Vec<Decimal> = ["19.90", "5.25", "0.10", "100.00", "7.5"]
+            .iter()
+            .map(|p| d(p))
+            .collect();
+        items.push(&dict, &prices).unwrap();
+        let categories: Vec<String> = vec!["book", "food", "food", "book", "food"]
+            .into_iter()
+            .map(|c| c.to_string())
+            .collect();
+        items.push(&dict, &categories).unwrap();
+        items.push(&dict, &[3u64, 4, 0, 1, 2]).unwrap();
+        items.set_column_name(&0, "id").unwrap();
+        items.set_column_name(&1, "price").unwrap();
+        items.set_column_name(&2, "category").unwrap();
+        items.set_column_name(&3, "qty").unwrap();
+        assert_eq!(
+            items.column_schema(&1).unwrap().type_name(&dict).unwrap(),
+            "DECIMAL"
+        );
+        assert_eq!(
+            items.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            vec!["19.90", "5.25", "0.10", "100.00", "7.5"]
+        );
+        items.print(&dict, &ctx).unwrap();
+
+        let mut schema = Schema::new();
+        schema.register("items", items).unwrap();
+        let scheduler = Scheduler::with_context(ExecutionContext::with_threads(2).unwrap());
+
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT id, price * 1.07, ROUND(price * 1.07, 2), price * CAST(qty AS DECIMAL), \
+                 CAST(price AS DECIMAL(5, 1)), price / 4 FROM items \
+                 WHERE price < 19.9 ORDER BY price DESC",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &0).unwrap().0,
+            vec![5, 2, 3]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            vec!["8.025", "5.6175", "0.1070"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &2).unwrap(),
+            vec!["8.03", "5.62", "0.11"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &3).unwrap(),
+            vec!["15.0", "21.00", "0.00"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &4).unwrap(),
+            vec!["7.5", "5.3", "0.1"]
+        );
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &5).unwrap(),
+            vec!["1.87500", "1.312500", "0.025000"]
+        );
+        t.print(&dict, &ctx).unwrap();
+        let ids = |query: &str| {
+            scheduler
+                .execute_sql(&dict, &schema, query)
+                .unwrap()
+                .materialize::<u64>(&dict, &ctx, &0)
+                .unwrap()
+                .0
+        };
+        assert_eq!(ids("SELECT id FROM items WHERE price = 19.9"), vec![1]);
+        assert_eq!(
+            ids("SELECT id FROM items WHERE price > 10 ORDER BY id"),
+            vec![1, 4]
+        );
+
+        //SUM and AVG keep the scale of the values
+        let totals = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT category, SUM(price), AVG(price), COUNT(price) FROM items \
+                 GROUP BY category ORDER BY 1",
+            )
+            .unwrap();
+        assert_eq!(
+            totals.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            vec!["119.90", "12.85"]
+        );
+        assert_eq!(
+            totals.materialize_as_string(&dict, &ctx, &2).unwrap(),
+            vec!["59.95", "4.28"]
+        );
+        assert_eq!(
+            totals.materialize::<u64>(&dict, &ctx, &3).unwrap().0,
+            vec![2, 3]
+        );
+
+        //Overflows and divisions by zero fail by default, or are null
+        assert!(scheduler
+            .execute_sql(&dict, &schema, "SELECT price / 0 FROM items")
+            .is_err());
+        assert!(scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT CAST(price AS DECIMAL(3, 1)) FROM items"
+            )
+            .is_err());
+        assert!(scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT CAST(price AS DECIMAL(40, 2)) FROM items"
+            )
+            .is_err());
+        let mut null_dict = Dictionary::new();
+        null_dict.set_decimal_options(DecimalOptions {
+            overflow: OverflowPolicy::Null,
+            rounding: RoundingMode::HalfEven,
+        });
+        let t = scheduler
+            .execute_sql(
+                &null_dict,
+                &schema,
+                "SELECT CAST(price AS DECIMAL(3, 1)), price / 0, ROUND(price, 0) FROM items ORDER BY id",
+            )
+            .unwrap();
+        let (values, valid) = t.materialize::<Decimal>(&null_dict, &ctx, &0).unwrap();
+        assert_eq!(
+            valid.downcast_ref().unwrap().to_vec(),
+            [true, true, true, false, true]
+        );
+        assert_eq!(values[2].to_string(), "0.1");
+        assert!(t
+            .materialize::<Decimal>(&null_dict, &ctx, &1)
+            .unwrap()
+            .1
+            .downcast_ref()
+            .unwrap()
+            .iter()
+            .all(|v| !v));
+        assert_eq!(
+            t.materialize_as_string(&null_dict, &ctx, &2).unwrap(),
+            vec!["20", "5", "0", "100", "8"]
+        );
+
+        let mut large: Table = Table::new(vec![2]);
+        let digits = "9".repeat(38);
+        let values = [d(&digits), d(&digits)];
+        large.push(&dict, &values).unwrap();
+        large.set_column_name(&0, "v").unwrap();
+        let mut schema = Schema::new();
+        schema.register("large", large).unwrap();
+        assert!(scheduler
+            .execute_sql(&dict, &schema, "SELECT SUM(v) FROM large")
+            .is_err());
+        let sum = scheduler
+            .execute_sql(&null_dict, &schema, "SELECT SUM(v), COUNT(v) FROM large")
+            .unwrap();
+        assert_eq!(
+            sum.materialize::<Decimal>(&null_dict, &ctx, &0)
+                .unwrap()
+                .1
+                .downcast_ref()
+                .unwrap()
+                .to_vec(),
+            [false]
+        );
+    }
+
+    #[test]
+    fn dictionary_encoded_strings() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let statuses: Vec<String> = [
+            "active", "pending", "active", "closed", "active", "pending", "closed", "active",
+            "pending", "active", "closed", "active",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let mut bitmap = vec![true; 12];
+        bitmap[6] = false;
+
+        let plain = ColumnWrapper::new_from_slice(&dict, &statuses);
+        let encoded = ColumnWrapper::dictionary_encode(&dict, &[&plain]).unwrap();
+        assert!(encoded[0].column().is_dictionary());
+        assert!(encoded[0].allocated_bytes(&dict).unwrap() < plain.allocated_bytes(&dict).unwrap());
+        //The codes are compared instead of the values, so a dictionary holds each value once
+        let values = ["active", "closed", "active"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert!(DictionaryValues::new(OnwedBinaryColumn::new(&values)).is_err());
+        let values = DictionaryValues::new(OnwedBinaryColumn::new(&values[..2])).unwrap();
+        assert!(DictionaryColumn::new(vec![0, 1, 1], values.clone()).is_ok());
+        assert!(DictionaryColumn::new(vec![0, 2], values).is_err());
+
+        let ids: Vec<u64> = (1..=12).collect();
+        let mut t: Table = Table::new(vec![5, 4, 3]);
+        t.push(&dict, &ids).unwrap();
+        t.push_with_bitmap(&dict, &statuses, &bitmap).unwrap();
+        t.push_with_bitmap(&dict, &statuses, &bitmap).unwrap();
+        t.set_column_name(&0, "id").unwrap();
+        t.set_column_name(&1, "status").unwrap();
+        t.set_column_name(&2, "plain").unwrap();
+        t.dictionary_encode(&dict, &1).unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            t.materialize_as_string(&dict, &ctx, &2).unwrap()
+        );
+        assert_eq!(
+            t.materialize::<String>(&dict, &ctx, &1).unwrap().0,
+            statuses
+        );
+
+        let mut schema = Schema::new();
+        schema.register("t", t).unwrap();
+        let scheduler = Scheduler::with_context(ExecutionContext::with_threads(2).unwrap());
+        let run = |query: &str| -> Vec<Vec<String>> {
+            let res = scheduler.execute_sql(&dict, &schema, query).unwrap();
+            (0..res.number_of_columns().unwrap())
+                .map(|column_id| res.materialize_as_string(&dict, &ctx, &column_id).unwrap())
+                .collect()
+        };
+        //Every query gives the same result on the encoded and on the plain column
+        for query in [
+            "SELECT id FROM t WHERE status = 'pending' ORDER BY id",
+            "SELECT id FROM t WHERE status = 'unknown' ORDER BY id",
+            "SELECT id FROM t WHERE status IN ('closed', 'pending', 'unknown') ORDER BY id",
+            "SELECT id FROM t WHERE status > 'b' ORDER BY id",
+            "SELECT status, COUNT(id) FROM t GROUP BY status ORDER BY status",
+            "SELECT id, status FROM t ORDER BY status, id",
+        ]
+        .iter()
+        {
+            assert_eq!(run(query), run(&query.replace("status", "plain")));
+        }
+        assert_eq!(
+            run("SELECT id FROM t WHERE status IN ('closed', 'pending') ORDER BY id"),
+            vec![vec!["2", "4", "6", "9", "11"]]
+        );
+        assert_eq!(
+            run("SELECT id FROM t WHERE status = plain ORDER BY id")[0].len(),
+            11
+        );
+    }
+
+    #[test]
+    fn run_length_encoded_columns() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        //Days of time-ordered events, each day repeats for several rows
+        let days: Vec<u64> = vec![1, 1, 1, 2, 2, 2, 2, 3, 3, 5, 5, 5, 5, 5, 8, 8];
+        let mut bitmap = vec![true; 16];
+        bitmap[5] = false;
+
+        let plain = ColumnWrapper::new_from_vec(&dict, days.clone());
+        let encoded = plain.run_length_encode(&dict).unwrap();
+        assert!(encoded.column().is_run_length());
+        assert_eq!(
+            encoded.column().downcast_runs_ref::<u64>().unwrap().0.len(),
+            5
+        );
+        assert!(encoded.allocated_bytes(&dict).unwrap() < plain.allocated_bytes(&dict).unwrap());
+        assert_eq!(
+            encoded
+                .decode(&dict)
+                .unwrap()
+                .column()
+                .downcast_ref::<u64>()
+                .unwrap(),
+            &days[..]
+        );
+
+        //Comparisons with a const keep the runs
+        let mut output = ColumnWrapper::new_from_vec(&dict, Vec::<bool>::new());
+        let c = ColumnWrapper::new_const(&dict, 3u64);
+        let no_index = ColumnDataIndex::None;
+        output
+            .op(
+                &dict,
+                "<",
+                &no_index,
+                &[
+                    InputTypes::Ref(&encoded, &no_index),
+                    InputTypes::Ref(&c, &no_index),
+                ],
+            )
+            .unwrap();
+        assert!(output.column().is_run_length());
+        assert_eq!(
+            output.column().downcast_runs_ref::<bool>().unwrap().1,
+            &[7, 9]
+        );
+
+        let ids: Vec<u64> = (1..=16).collect();
+        let weeks: Vec<u64> = days.iter().map(|d| d / 7).collect();
+        let mut t: Table = Table::new(vec![6, 6, 4]);
+        t.push(&dict, &ids).unwrap();
+        t.push_with_bitmap(&dict, &days, &bitmap).unwrap();
+        t.push_with_bitmap(&dict, &days, &bitmap).unwrap();
+        t.set_column_name(&0, "id").unwrap();
+        t.set_column_name(&1, "day").unwrap();
+        t.push(&dict, &weeks).unwrap();
+        t.set_column_name(&2, "plain").unwrap();
+        t.set_column_name(&3, "week").unwrap();
+        t.run_length_encode(&dict, &1).unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &1).unwrap(),
+            t.materialize_as_string(&dict, &ctx, &2).unwrap()
+        );
+
+        let mut schema = Schema::new();
+        schema.register("t", t).unwrap();
+        let scheduler = Scheduler::with_context(ExecutionContext::with_threads(2).unwrap());
+        let run = |query: &str| -> Vec<Vec<String>> {
+            let res = scheduler.execute_sql(&dict, &schema, query).unwrap();
+            (0..res.number_of_columns().unwrap())
+                .map(|column_id| res.materialize_as_string(&dict, &ctx, &column_id).unwrap())
+                .collect()
+        };
+        //Every query gives the same result on the encoded and on the plain column
+        for query in [
+            "SELECT id FROM t WHERE day = 5 ORDER BY id",
+            "SELECT id FROM t WHERE day >= 3 ORDER BY id",
+            "SELECT week, COUNT(day) FROM t GROUP BY week ORDER BY week",
+            "SELECT day, COUNT(id) FROM t GROUP BY day ORDER BY day",
+            "SELECT id, day + 1 FROM t ORDER BY day, id",
+        ]
+        .iter()
+        {
+            assert_eq!(run(query), run(&query.replace("day", "plain")));
+        }
+        assert_eq!(
+            run("SELECT id FROM t WHERE day = 5 ORDER BY id"),
+            vec![vec!["10", "11", "12", "13", "14"]]
+        );
+        //Sums are computed per run, the null day is skipped
+        assert_eq!(
+            run("SELECT week, SUM(day), COUNT(day) FROM t GROUP BY week ORDER BY week"),
+            vec![vec!["0", "1"], vec!["40", "16"], vec!["13", "2"]]
+        );
+        assert_eq!(
+            run("SELECT week, SUM(day) FROM t WHERE day > 1 GROUP BY week ORDER BY week"),
+            vec![vec!["0", "1"], vec!["37", "16"]]
+        );
+    }
+
+    #[test]
+    fn bit_packed_bitmaps() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        let valid: Vec<bool> = (0..200).map(|i| i % 3 != 0).collect();
+        let bitmap = Bitmap::from(valid.as_slice());
+        assert_eq!(bitmap.words().len(), 4);
+        assert_eq!(bitmap.allocated_bytes(), 32);
+        assert_eq!(bitmap.null_count(), 67);
+        assert_eq!(bitmap.count_ones(), 133);
+        assert!(!bitmap.all_valid());
+        assert!(Bitmap::filled(true, 130).all_valid());
+
+        //Slices start in the middle of a word
+        let slice = bitmap.as_bitmap_ref().slice(61..131);
+        assert_eq!(slice.to_vec(), valid[61..131].to_vec());
+        assert_eq!(
+            slice.count_ones(),
+            valid[61..131].iter().filter(|v| **v).count()
+        );
+
+        let mut appended = Bitmap::from(&valid[..5]);
+        appended.extend_from_bitmap(slice);
+        assert_eq!(appended.len(), 75);
+        assert_eq!(appended.iter().skip(5).collect::<Vec<_>>(), slice.to_vec());
+
+        let other: Vec<bool> = (0..200).map(|i| i % 5 != 0).collect();
+        let expected: Vec<bool> = valid
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| *a && *b)
+            .collect();
+        let other = Bitmap::from(other);
+        let both = Bitmap::and(bitmap.as_bitmap_ref(), other.as_bitmap_ref());
+        assert_eq!(both.to_vec(), expected);
+
+        //Operations on nullable columns propagate the nulls a word at a time
+        let mut c1 = ColumnWrapper::new_from_vec(&dict, (0..200).collect::<Vec<u32>>());
+        c1.bitmap_set(ColumnDataF::new(bitmap));
+        let mut c2 = ColumnWrapper::new_from_vec(&dict, vec![1u32; 200]);
+        c2.bitmap_set(ColumnDataF::new(other));
+        c1.op(
+            &dict,
+            "+=",
+            &ColumnDataIndex::None,
+            &[InputTypes::Ref(&c2, &ColumnDataIndex::None)],
+        )
+        .unwrap();
+        assert_eq!(
+            c1.bitmap().null_count(),
+            200 - expected.iter().filter(|v| **v).count()
+        );
+
+        //Partitions do not start on word boundaries
+        let c1_bitmap = c1.bitmap().downcast_ref().unwrap().to_vec();
+        let mut t: Table = Table::new(vec![70, 30, 100]);
+        t.push_with_bitmap::<u32>(&dict, c1.column().downcast_ref().unwrap(), &c1_bitmap)
+            .unwrap();
+        let (values, output_bitmap) = t.materialize::<u32>(&dict, &ctx, &0).unwrap();
+        assert_eq!(output_bitmap.downcast_ref().unwrap().to_vec(), expected);
+        assert_eq!(values[1], 2);
+        let result = t.materialize_as_string(&dict, &ctx, &0).unwrap();
+        assert_eq!(result[..4].to_vec(), vec!["(null)", "2", "3", "(null)"]);
+    }
+
+    #[test]
+    fn assign_op_nulls_on_pushed_mut_column() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+
+        let mut c1_values: Vec<u32> = (0..100).collect();
+        let c1_bitmap: Vec<bool> = (0..100).map(|i| i % 7 != 0).collect();
+        let c2_values: Vec<u32> = vec![1; 100];
+        let c2_bitmap: Vec<bool> = (0..100).map(|i| i % 5 != 0).collect();
+        let expected: Vec<bool> = c1_bitmap
+            .iter()
+            .zip(c2_bitmap.iter())
+            .map(|(a, b)| *a && *b)
+            .collect();
+
+        //Partitions do not start on word boundaries
+        let mut t: Table = Table::new(vec![70, 30]);
+        t.push_mut_with_bitmap(&mut c1_values, Bitmap::from(c1_bitmap))
+            .unwrap();
+        t.push_with_bitmap(&dict, &c2_values, &c2_bitmap).unwrap();
+        t.op(&dict, &ctx, "+=", &0, &[1]).unwrap();
+
+        //The values are written through, the nulls are read back from the table
+        let (_, output_bitmap) = t.materialize::<u32>(&dict, &ctx, &0).unwrap();
+        assert_eq!(output_bitmap.downcast_ref().unwrap().to_vec(), expected);
+        drop(t);
+        assert_eq!(c1_values[1], 2);
+        assert_eq!(c1_values[99], 100);
+    }
+
+    #[test]
+    fn binary_offsets_layout() {
+        let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
+        let names: Vec<String> = ["Jane", "Merry", "", "Christopher", "Bo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        //n+1 offsets starting at 0, item i being data[offsets[i]..offsets[i + 1]]
+        //The offsets are u32 while the bytes fit into the Arrow i32 offsets
+        let c = OnwedBinaryColumn::new(&names);
+        let (data, offsets) = c.downcast_binary_ref::<String>().unwrap();
+        assert_eq!(offsets.iter().collect::<Vec<_>>(), vec![0, 4, 9, 9, 20, 22]);
+        assert!(matches!(offsets, BinaryOffsetsRef::U32(_)));
+        assert_eq!(binary_item(data, offsets, 3), b"Christopher");
+
+        //The buffers are exported and taken back without copying or rebasing them
+        let (data, offsets) = c.downcast_binary_owned::<String>().unwrap();
+        assert_eq!(offsets, BinaryOffsets::U32(vec![0, 4, 9, 9, 20, 22]));
+        let (data_ptr, offsets_ptr) = match &offsets {
+            BinaryOffsets::U32(o) => (data.as_ptr(), o.as_ptr()),
+            BinaryOffsets::Usize(_) => unreachable!(),
+        };
+        let c = OnwedBinaryColumn::new_from_offsets::<String>(data, offsets).unwrap();
+        let (data, offsets) = c.downcast_binary_ref::<String>().unwrap();
+        assert_eq!(data.as_ptr(), data_ptr);
+        assert!(matches!(offsets, BinaryOffsetsRef::U32(o) if o.as_ptr() == offsets_ptr));
+        let wide = OnwedBinaryColumn::new_from_offsets::<String>(vec![0; 4], vec![0usize, 1, 4]);
+        assert_eq!(wide.unwrap().len(), 2);
+        assert!(
+            OnwedBinaryColumn::new_from_offsets::<String>(vec![0; 4], vec![0u32, 3, 2]).is_err()
+        );
+        assert!(OnwedBinaryColumn::new_from_offsets::<String>(vec![0; 4], vec![0u32, 5]).is_err());
+        assert!(OnwedBinaryColumn::new_from_offsets::<String>(vec![0; 4], vec![1u32, 4]).is_err());
+
+        //Bytes which are not the encoding of a String fail the materialization
+        let invalid =
+            OnwedBinaryColumn::new_from_offsets::<String>(vec![b'a', 0xff, 0xfe], vec![0u32, 1, 3])
+                .unwrap();
+        let mut t: Table = Table::new(vec![2]);
+        t.push_shared(
+            &dict,
+            vec![
+                ColumnWrapper::new_from_columndata(ColumnData::BinaryOwned(invalid))
+                    .into_shared()
+                    .unwrap(),
+            ],
+        )
+        .unwrap();
+        assert!(matches!(
+            t.materialize::<String>(&dict, &ctx, &0),
+            Err(RadixError::DowncastFailed { .. })
+        ));
+        assert!(t.dictionary_encode(&dict, &0).is_err());
+        t.push_index(vec![ColumnDataIndex::new(vec![1, 0])], &[0])
+            .unwrap();
+        assert!(t.materialize::<String>(&dict, &ctx, &0).is_err());
+
+        //Offsets which do not fit are widened
+        let mut wide_offsets = BinaryOffsets::with_capacity(2);
+        wide_offsets.push(4);
+        wide_offsets.push(i32::MAX as usize + 1);
+        assert_eq!(
+            wide_offsets,
+            BinaryOffsets::Usize(vec![0, 4, i32::MAX as usize + 1])
+        );
+
+        //Slices share the offset between them, their bytes start at their first offset
+        let mut right = BinarySlice::new::<String>(data, offsets).unwrap();
+        let left = right.split_off_left::<String>(2).unwrap();
+        let items = |s: &BinarySlice| {
+            let (data, offsets) = s.downcast_binary_ref::<String>().unwrap();
+            binary_items(data, offsets)
+                .map(String::from_bytes)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(items(&left), names[..2].to_vec());
+        assert_eq!(items(&right), names[2..].to_vec());
+        assert_eq!(right.offset(), 9);
+        assert!(BinarySlice::new::<String>(&data[..21], offsets).is_err());
+        assert!(BinarySliceMut::new::<String>(&mut [0; 4], offsets).is_err());
+
+        let cw = ColumnWrapper::new_from_vec(&dict, names.clone());
+        assert_eq!(
+            cw.allocated_bytes(&dict).unwrap(),
+            22 + 6 * std::mem::size_of::<u32>()
+        );
+
+        //Without an index the items are copied at the offsets computed from their lengths
+        let mut t: Table = Table::new(vec![3, 2]);
+        t.push(&dict, &names).unwrap();
+        let h = t.build_hash(&dict, &ctx, &[0]).unwrap();
+        let tmap = TableToTableMap::new(&ctx, &h, 2, 1);
+        let res = unsafe { t.column_repartition(&dict, &ctx, &h, &tmap, &0) }
+            .unwrap()
+            .buckets;
+        assert!(res.iter().all(|c| matches!(
+            c.column().downcast_binary_ref::<String>().unwrap().1,
+            BinaryOffsetsRef::U32(_)
+        )));
+        let mut repartitioned: Vec<String> = res
+            .iter()
+            .flat_map(|c| c.as_string(&dict, &ColumnDataIndex::None).unwrap())
+            .collect();
+        repartitioned.sort();
+        let mut expected = names.clone();
+        expected.sort();
+        assert_eq!(repartitioned, expected);
+    }
+
+    #[test]
+    fn aggregate_udf() {
+        let mut dict = Dictionary::new();
+        let ctx = ExecutionContext::new();
+        //Every value counts at most 5 towards the sum
+        dict.register_aggregate::<u64, u64, u64, _, _, _, _>(
+            "CAPPED_SUM",
+            || 0,
+            |s, v| *s += (*v).min(5),
+            |s, other| *s += *other,
+            |s| *s,
+        )
+        .unwrap();
+        //The state is a list of all values of the group, the lower median is the output
+        dict.register_aggregate::<u64, Vec<u64>, u64, _, _, _, _>(
+            "MEDIAN",
+            Vec::new,
+            |s, v| s.push(*v),
+            |s, other| s.extend_from_slice(other),
+            |s| {
+                let mut s = s.clone();
+                s.sort_unstable();
+                s.get(s.len().saturating_sub(1) / 2).copied().unwrap_or(0)
+            },
+        )
+        .unwrap();
+        assert!(dict
+            .register_aggregate::<u64, u64, u64, _, _, _, _>(
+                "SUM",
+                || 0,
+                |s, v| *s += *v,
+                |s, other| *s += *other,
+                |s| *s,
+            )
+            .is_err());
+
+        let products: Vec<String> = vec!["A", "B", "A", "B", "A", "B", "A"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut sales: Table = Table::new(vec![3, 2, 2]);
+        sales.push(&dict, &products).unwrap();
+        sales
+            .push_with_bitmap(
+                &dict,
+                &[1u64, 9, 7, 2, 3, 4, 100],
+                &[true, true, true, true, true, true, false],
+            )
+            .unwrap();
+        sales.set_column_name(&0, "product").unwrap();
+        sales.set_column_name(&1, "price").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+
+        //The states of every morsel are merged before the output is computed, nulls are skipped
+        let scheduler = Scheduler::with_context(
+            ExecutionContext::with_threads(2)
+                .unwrap()
+                .with_chunking(ChunkingStrategy::Morsels(1)),
+        );
+        let t = scheduler
+            .execute_sql(
+                &dict,
+                &schema,
+                "SELECT product, CAPPED_SUM(price), MEDIAN(price) FROM sales GROUP BY product ORDER BY product",
+            )
+            .unwrap();
+        assert_eq!(
+            t.materialize_as_string(&dict, &ctx, &0).unwrap(),
+            vec!["A", "B"]
+        );
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &1).unwrap().0,
+            vec![9, 11]
+        );
+        assert_eq!(t.materialize::<u64>(&dict, &ctx, &2).unwrap().0, vec![3, 4]);
+
+        //A partitioned table aggregates every group in one pass
+        let mut t: Table = Table::new(vec![4]);
+        t.push(&dict, &[1u32, 2, 1, 2]).unwrap();
+        t.push(&dict, &[10u64, 1, 4, 2]).unwrap();
+        let mut e = TableExpression::new("CAPPED_SUM", &[1]);
+        e.partition_by.push(ExpressionInput::Column(0));
+        t.add_expression_as_new_column(&dict, &ExecutionContext::single_threaded(), &e)
+            .unwrap();
+        assert_eq!(
+            t.materialize::<u64>(&dict, &ctx, &2).unwrap().0,
+            vec![9, 3, 9, 3]
+        );
+    }
+
+    #[test]
+    fn explain_analyze_operators() {
+        let dict = Dictionary::new();
+
+        let products: Vec<String> = vec!["A", "B", "A", "C", "B", "A"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut sales: Table = Table::new(vec![2, 2, 2]);
+        sales.push(&dict, &[1u32, 2, 3, 4, 5, 6]).unwrap();
+        sales.push(&dict, &products).unwrap();
+        sales.push(&dict, &[10u64, 2, 4, 1, 8, 6]).unwrap();
+        sales.set_column_name(&0, "id").unwrap();
+        sales.set_column_name(&1, "product").unwrap();
+        sales.set_column_name(&2, "price").unwrap();
+        let mut schema = Schema::new();
+        schema.register("sales", sales).unwrap();
+
+        let select = bind(
+            &schema,
+            &dict,
+            "SELECT product, SUM(price + price) FROM sales WHERE price > 2 GROUP BY product",
+        )
+        .unwrap();
+        let plan = LogicalPlan::from_select(&schema, &select).unwrap();
+        let scheduler = Scheduler::with_context(
+            ExecutionContext::single_threaded().with_chunking(ChunkingStrategy::Morsels(1)),
+        );
+
+        //Every expression is printed with the signature found in the dictionary
+        let explain = scheduler.explain(&dict, &schema, &plan).unwrap();
+        let expected = vec![
+            "Project",
+            "  expr: #0: String",
+            "  expr: #1: u64",
+            "  Aggregate",
+            "    group by: #0: String",
+            "    aggregate: SUM(u64) -> u64",
+            "      +(u64, u64) -> u64",
+            "        #1: u64",
+            "        #1: u64",
+            "    Filter",
+            "      predicate: >(u64, u64) -> bool",
+            "        #1: u64",
+            "        const 2: u64",
+            "      Scan: sales [1, 2]",
+        ];
+        assert_eq!(explain.lines().collect::<Vec<_>>(), expected);
+
+        //Timings differ from run to run, row counts do not
+        let (t, explain) = scheduler.explain_analyze(&dict, &schema, &plan).unwrap();
+        assert_eq!(t.number_of_rows(), 2);
+        let metrics = |operator: &str| {
+            explain
+                .lines()
+                .find(|l| l.trim_start().starts_with(operator))
+                .unwrap()
+                .to_string()
+        };
+        assert!(metrics("[Scan] partitions=3").contains("rows_in=6 rows_out=6"));
+        assert!(metrics("[Filter] partitions=3").contains("rows_in=6 rows_out=4"));
+        assert!(metrics("[PartialAggregate] partitions=3").contains("rows_in=4 rows_out=4"));
+        assert!(metrics("[FinalAggregate] partitions=1").contains("rows_in=4 rows_out=2"));
+        assert!(metrics("[Project] partitions=1").contains("rows_in=2 rows_out=2"));
+        assert_eq!(
+            explain
+                .lines()
+                .filter(|l| l.trim_start().starts_with("partition "))
+                .count(),
+            11
+        );
+    }
+
     #[test]
     fn columns_hash() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
@@ -505,11 +3074,11 @@ mod tests {
         ];
 
         t.push_index(c2_index, &[1]).unwrap();
-        let h1 = t.build_hash(&dict, &[1]);
+        let h1 = t.build_hash(&dict, &ctx, &[1]).unwrap();
         let h1: Vec<_> = h1.iter().flatten().map(|i| *i).collect();
         t.push(&dict, &h1).unwrap();
 
-        let h2 = t.build_hash(&dict, &[1, 0]);
+        let h2 = t.build_hash(&dict, &ctx, &[1, 0]).unwrap();
         let h2: Vec<_> = h2.iter().flatten().map(|i| *i).collect();
         t.push(&dict, &h2).unwrap();
 
@@ -521,16 +3090,13 @@ mod tests {
 
         assert_eq!(h1[1] - 1, h2[1]);
 
-        //t.print(&dict).unwrap();
+        //t.print(&dict, &ctx).unwrap();
     }
 
     #[test]
     fn columns_tabletotable() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![2, 2, 2, 2, 1]);
 
@@ -563,8 +3129,8 @@ mod tests {
         ];
 
         t.push_index(c2_index, &[1]).unwrap();
-        t.print(&dict).unwrap();
-        let h = t.build_hash(&dict, &[1]);
+        t.print(&dict, &ctx).unwrap();
+        let h = t.build_hash(&dict, &ctx, &[1]).unwrap();
         let h1: Vec<_> = h.iter().flatten().map(|i| *i & 3).collect();
         t.push(&dict, &h1).unwrap();
 
@@ -572,8 +3138,10 @@ mod tests {
             vec![(2, 1), (2, 2), (2, 12), (1024, 1), (1024, 8), (1024, 12)]
         {
             let tmap: TableToTableMap =
-                TableToTableMap::new(&h, number_of_worker_threads, bucket_bits);
-            let res = unsafe { t.column_repartition(&dict, &h, &tmap, &0) };
+                TableToTableMap::new(&ctx, &h, number_of_worker_threads, bucket_bits);
+            let res = unsafe { t.column_repartition(&dict, &ctx, &h, &tmap, &0) }
+                .unwrap()
+                .buckets;
 
             let c_part_1: Vec<_> = res
                 .iter()
@@ -590,7 +3158,9 @@ mod tests {
                 .collect();
             c_part_1.sort_by(|a, b| a.0.cmp(&b.0));
 
-            let res = unsafe { t.column_repartition(&dict, &h, &tmap, &1) };
+            let res = unsafe { t.column_repartition(&dict, &ctx, &h, &tmap, &1) }
+                .unwrap()
+                .buckets;
 
             let c_part_2: Vec<_> = res
                 .iter()
@@ -637,11 +3207,8 @@ mod tests {
     }
     #[test]
     fn columns_groups() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![4, 5]);
 
@@ -681,26 +3248,23 @@ mod tests {
         ];
 
         t.push_index(c2_index, &[1]).unwrap();
-        let h1 = t.build_groups(&dict, &[1]);
+        let h1 = t.build_groups(&dict, &ctx, &[1]).unwrap();
         let h1: Vec<_> = h1.iter().map(|(v, _)| v).flatten().map(|i| *i).collect();
         t.push(&dict, &h1).unwrap();
 
-        let h2 = t.build_groups(&dict, &[1, 0]);
+        let h2 = t.build_groups(&dict, &ctx, &[1, 0]).unwrap();
         let h2: Vec<_> = h2.iter().map(|(v, _)| v).flatten().map(|i| *i).collect();
         t.push(&dict, &h2).unwrap();
 
         assert_eq!(h1, vec![0, 0, 1, 1, 0, 0, 1, 1, 1]);
         assert_eq!(h2, vec![0, 1, 2, 2, 0, 1, 2, 3, 3]);
 
-        t.print(&dict).unwrap();
+        t.print(&dict, &ctx).unwrap();
     }
     #[test]
     fn columns_group_expression() {
-        /*rayon::ThreadPoolBuilder::new()
-        .num_threads(1)
-        .build_global()
-        .unwrap();*/
         let dict = Dictionary::new();
+        let ctx = ExecutionContext::single_threaded();
 
         let mut t: Table = Table::new(vec![4, 5]);
 
@@ -730,7 +3294,7 @@ mod tests {
         ];
 
         t.push_index(c2_index, &[1]).unwrap();
-        let h1 = t.build_groups(&dict, &[1]);
+        let h1 = t.build_groups(&dict, &ctx, &[1]).unwrap();
         let h1_group_id: Vec<_> = h1.iter().map(|(v, _)| v).flatten().map(|i| *i).collect();
         t.push(&dict, &h1_group_id).unwrap();
         let h1_num_groups: Vec<Vec<usize>> = h1
@@ -739,7 +3303,7 @@ mod tests {
             .collect();
         let h1_num_groups: Vec<_> = h1_num_groups.into_iter().flatten().collect();
         t.push(&dict, &h1_num_groups).unwrap();
-        let h2 = t.build_groups(&dict, &[1, 0]);
+        let h2 = t.build_groups(&dict, &ctx, &[1, 0]).unwrap();
         let h2_group_id: Vec<_> = h2.iter().map(|(v, _)| v).flatten().map(|i| *i).collect();
         t.push(&dict, &h2_group_id).unwrap();
 
@@ -750,7 +3314,7 @@ mod tests {
         let h2_num_groups: Vec<_> = h2_num_groups.into_iter().flatten().collect();
         t.push(&dict, &h2_num_groups).unwrap();
 
-        t.print(&dict).unwrap();
+        t.print(&dict, &ctx).unwrap();
 
         let mut e = TableExpression::new("SUM", &[1]);
         e.partition_by.push(ExpressionInput::Column(0));
@@ -760,8 +3324,8 @@ mod tests {
         //let const_val = &ColumnWrapper::new_const(&dict, 16u32);
         //e.expand_node_as_const(1000, &mut Some(const_val)).unwrap();
 
-        t.add_expression_as_new_column(&dict, &e);
-        t.print(&dict).unwrap();
+        t.add_expression_as_new_column(&dict, &ctx, &e).unwrap();
+        t.print(&dict, &ctx).unwrap();
         println!("{:?}", t.indexes);
     }
 
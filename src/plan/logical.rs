@@ -0,0 +1,470 @@
+use radix_column::ErrorDesc;
+
+use crate::{BoundExpr, BoundSelect, BoundSelectItem, ColumnSchema, OrderByItem, Schema};
+
+//Column ids used in the expressions of a node refer to the output columns of its input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogicalPlan {
+    //Reads the given columns of a table registered in the schema
+    Scan {
+        table: String,
+        columns: Vec<usize>,
+    },
+    //Keeps the rows for which all predicates are true, they are applied in a single index pass
+    Filter {
+        input: Box<LogicalPlan>,
+        predicates: Vec<BoundExpr>,
+    },
+    Project {
+        input: Box<LogicalPlan>,
+        exprs: Vec<BoundSelectItem>,
+    },
+    //Outputs the group by expressions followed by the aggregates
+    Aggregate {
+        input: Box<LogicalPlan>,
+        group_by: Vec<BoundExpr>,
+        aggregates: Vec<BoundExpr>,
+    },
+    //Inner equi join on pairs of (left column, right column)
+    //Outputs the columns of the left input followed by the columns of the right input
+    Join {
+        left: Box<LogicalPlan>,
+        right: Box<LogicalPlan>,
+        on: Vec<(usize, usize)>,
+    },
+    Sort {
+        input: Box<LogicalPlan>,
+        order_by: Vec<OrderByItem>,
+    },
+    Limit {
+        input: Box<LogicalPlan>,
+        limit: usize,
+    },
+}
+
+impl LogicalPlan {
+    pub fn scan(schema: &Schema, table: &str) -> Result<Self, ErrorDesc> {
+        let number_of_columns = schema.get(table)?.number_of_columns()?;
+        Ok(LogicalPlan::Scan {
+            table: table.to_string(),
+            columns: (0..number_of_columns).collect(),
+        })
+    }
+
+    //Adds the predicates to an existing filter instead of stacking a new one on top of it
+    pub fn filter(input: LogicalPlan, mut predicates: Vec<BoundExpr>) -> Self {
+        if predicates.is_empty() {
+            return input;
+        }
+        match input {
+            LogicalPlan::Filter {
+                input,
+                predicates: mut existing,
+            } => {
+                existing.append(&mut predicates);
+                LogicalPlan::Filter {
+                    input,
+                    predicates: existing,
+                }
+            }
+            input => LogicalPlan::Filter {
+                input: Box::new(input),
+                predicates,
+            },
+        }
+    }
+
+    pub fn from_select(schema: &Schema, select: &BoundSelect) -> Result<Self, ErrorDesc> {
+        let mut plan = LogicalPlan::scan(schema, &select.table)?;
+        if let Some(filter) = &select.filter {
+            plan = LogicalPlan::filter(plan, split_conjunction(filter));
+        }
+
+        let mut projection = select.projection.clone();
+        let mut order_by = select.order_by.clone();
+
+        if select.is_aggregate() {
+            let mut aggregates = vec![];
+            projection
+                .iter()
+                .for_each(|p| collect_aggregates(&p.expr, &mut aggregates));
+            if let Some(having) = &select.having {
+                collect_aggregates(having, &mut aggregates);
+            }
+            order_by
+                .iter()
+                .for_each(|o| collect_aggregates(&o.expr, &mut aggregates));
+
+            let group_by = &select.group_by;
+            for p in projection.iter_mut() {
+                p.expr = replace_aggregates(&p.expr, group_by, &aggregates)?;
+            }
+            for o in order_by.iter_mut() {
+                o.expr = replace_aggregates(&o.expr, group_by, &aggregates)?;
+            }
+            let having = match &select.having {
+                Some(having) => Some(replace_aggregates(having, group_by, &aggregates)?),
+                None => None,
+            };
+
+            plan = LogicalPlan::Aggregate {
+                input: Box::new(plan),
+                group_by: group_by.clone(),
+                aggregates,
+            };
+            if let Some(having) = having {
+                plan = LogicalPlan::filter(plan, split_conjunction(&having));
+            }
+        }
+
+        //Sorting happens before the projection, so that it can use columns which are not selected
+        if !order_by.is_empty() {
+            plan = LogicalPlan::Sort {
+                input: Box::new(plan),
+                order_by,
+            };
+        }
+        plan = LogicalPlan::Project {
+            input: Box::new(plan),
+            exprs: projection,
+        };
+        if let Some(limit) = select.limit {
+            plan = LogicalPlan::Limit {
+                input: Box::new(plan),
+                limit,
+            };
+        }
+        Ok(plan)
+    }
+
+    pub fn number_of_columns(&self) -> usize {
+        match self {
+            LogicalPlan::Scan { columns, .. } => columns.len(),
+            LogicalPlan::Project { exprs, .. } => exprs.len(),
+            LogicalPlan::Aggregate {
+                group_by,
+                aggregates,
+                ..
+            } => group_by.len() + aggregates.len(),
+            LogicalPlan::Join { left, right, .. } => {
+                left.number_of_columns() + right.number_of_columns()
+            }
+            LogicalPlan::Filter { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Limit { input, .. } => input.number_of_columns(),
+        }
+    }
+
+    //Names, types and nullability of the output columns
+    pub fn output_schema(&self, schema: &Schema) -> Result<Vec<ColumnSchema>, ErrorDesc> {
+        let output_schema = match self {
+            LogicalPlan::Scan { table, columns } => {
+                let table = schema.get(table)?;
+                let columns: Result<Vec<_>, ErrorDesc> = columns
+                    .iter()
+                    .map(|c| table.column_schema(c).cloned())
+                    .collect();
+                columns?
+            }
+            LogicalPlan::Filter { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Limit { input, .. } => input.output_schema(schema)?,
+            LogicalPlan::Project { input, exprs } => {
+                let input = input.output_schema(schema)?;
+                exprs
+                    .iter()
+                    .map(|p| {
+                        ColumnSchema::new(
+                            &p.name,
+                            p.expr.item_type_id(),
+                            p.expr.is_nullable(&input),
+                        )
+                    })
+                    .collect()
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let input = input.output_schema(schema)?;
+                group_by
+                    .iter()
+                    .chain(aggregates.iter())
+                    .enumerate()
+                    .map(|(i, e)| {
+                        let name = match e {
+                            BoundExpr::Column { column_id, .. } => input[*column_id].name.clone(),
+                            _ => ColumnSchema::default_name(i),
+                        };
+                        ColumnSchema::new(&name, e.item_type_id(), e.is_nullable(&input))
+                    })
+                    .collect()
+            }
+            LogicalPlan::Join { left, right, .. } => {
+                let mut output_schema = left.output_schema(schema)?;
+                output_schema.append(&mut right.output_schema(schema)?);
+                output_schema
+            }
+        };
+        Ok(output_schema)
+    }
+
+    pub fn inputs(&self) -> Vec<&LogicalPlan> {
+        match self {
+            LogicalPlan::Scan { .. } => vec![],
+            LogicalPlan::Join { left, right, .. } => vec![left, right],
+            LogicalPlan::Filter { input, .. }
+            | LogicalPlan::Project { input, .. }
+            | LogicalPlan::Aggregate { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Limit { input, .. } => vec![input],
+        }
+    }
+
+    //Rebuilds the node with every input replaced by the result of f
+    pub fn map_inputs<F>(self, mut f: F) -> Result<Self, ErrorDesc>
+    where
+        F: FnMut(LogicalPlan) -> Result<LogicalPlan, ErrorDesc>,
+    {
+        Ok(match self {
+            LogicalPlan::Scan { .. } => self,
+            LogicalPlan::Filter { input, predicates } => LogicalPlan::Filter {
+                input: Box::new(f(*input)?),
+                predicates,
+            },
+            LogicalPlan::Project { input, exprs } => LogicalPlan::Project {
+                input: Box::new(f(*input)?),
+                exprs,
+            },
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => LogicalPlan::Aggregate {
+                input: Box::new(f(*input)?),
+                group_by,
+                aggregates,
+            },
+            LogicalPlan::Join { left, right, on } => LogicalPlan::Join {
+                left: Box::new(f(*left)?),
+                right: Box::new(f(*right)?),
+                on,
+            },
+            LogicalPlan::Sort { input, order_by } => LogicalPlan::Sort {
+                input: Box::new(f(*input)?),
+                order_by,
+            },
+            LogicalPlan::Limit { input, limit } => LogicalPlan::Limit {
+                input: Box::new(f(*input)?),
+                limit,
+            },
+        })
+    }
+
+    //Rebuilds the node with every expression of the node itself replaced by the result of f
+    pub fn map_exprs<F>(self, mut f: F) -> Result<Self, ErrorDesc>
+    where
+        F: FnMut(BoundExpr) -> Result<BoundExpr, ErrorDesc>,
+    {
+        Ok(match self {
+            LogicalPlan::Scan { .. } | LogicalPlan::Join { .. } | LogicalPlan::Limit { .. } => self,
+            LogicalPlan::Filter { input, predicates } => LogicalPlan::Filter {
+                input,
+                predicates: predicates.into_iter().map(f).collect::<Result<_, _>>()?,
+            },
+            LogicalPlan::Project { input, exprs } => LogicalPlan::Project {
+                input,
+                exprs: exprs
+                    .into_iter()
+                    .map(|p| {
+                        Ok(BoundSelectItem {
+                            name: p.name,
+                            expr: f(p.expr)?,
+                        })
+                    })
+                    .collect::<Result<_, ErrorDesc>>()?,
+            },
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => LogicalPlan::Aggregate {
+                input,
+                group_by: group_by.into_iter().map(&mut f).collect::<Result<_, _>>()?,
+                aggregates: aggregates.into_iter().map(f).collect::<Result<_, _>>()?,
+            },
+            LogicalPlan::Sort { input, order_by } => LogicalPlan::Sort {
+                input,
+                order_by: order_by
+                    .into_iter()
+                    .map(|o| {
+                        Ok(OrderByItem {
+                            expr: f(o.expr)?,
+                            ascending: o.ascending,
+                        })
+                    })
+                    .collect::<Result<_, ErrorDesc>>()?,
+            },
+        })
+    }
+}
+
+impl BoundExpr {
+    //True if the expression can be null for the given input columns, COUNT is never null
+    pub fn is_nullable(&self, input: &[ColumnSchema]) -> bool {
+        match self {
+            BoundExpr::Column { column_id, .. } => input[*column_id].nullable,
+            BoundExpr::Literal { .. } => false,
+            BoundExpr::Op { input: i, .. } => i.iter().any(|i| i.is_nullable(input)),
+            BoundExpr::Aggregate { op, input: i, .. } => op != "COUNT" && i.is_nullable(input),
+        }
+    }
+
+    //Appends the ids of all columns referenced by the expression
+    pub fn collect_column_ids(&self, column_ids: &mut Vec<usize>) {
+        match self {
+            BoundExpr::Column { column_id, .. } => column_ids.push(*column_id),
+            BoundExpr::Literal { .. } => {}
+            BoundExpr::Op { input, .. } => {
+                input.iter().for_each(|i| i.collect_column_ids(column_ids))
+            }
+            BoundExpr::Aggregate { input, .. } => input.collect_column_ids(column_ids),
+        }
+    }
+
+    pub fn column_ids(&self) -> Vec<usize> {
+        let mut column_ids = vec![];
+        self.collect_column_ids(&mut column_ids);
+        column_ids.sort_unstable();
+        column_ids.dedup();
+        column_ids
+    }
+
+    //Replaces every column id with the result of f
+    pub fn map_column_ids<F>(&self, f: &F) -> BoundExpr
+    where
+        F: Fn(usize) -> usize,
+    {
+        match self {
+            BoundExpr::Column {
+                column_id,
+                item_type_id,
+            } => BoundExpr::Column {
+                column_id: f(*column_id),
+                item_type_id: *item_type_id,
+            },
+            BoundExpr::Literal { .. } => self.clone(),
+            BoundExpr::Op {
+                op,
+                input,
+                output_type_id,
+            } => BoundExpr::Op {
+                op: op.clone(),
+                input: input.iter().map(|i| i.map_column_ids(f)).collect(),
+                output_type_id: *output_type_id,
+            },
+            BoundExpr::Aggregate {
+                op,
+                input,
+                output_type_id,
+            } => BoundExpr::Aggregate {
+                op: op.clone(),
+                input: Box::new(input.map_column_ids(f)),
+                output_type_id: *output_type_id,
+            },
+        }
+    }
+
+    //Replaces every column with the expression which produces it
+    pub fn substitute(&self, exprs: &[BoundExpr]) -> BoundExpr {
+        match self {
+            BoundExpr::Column { column_id, .. } => exprs[*column_id].clone(),
+            BoundExpr::Literal { .. } => self.clone(),
+            BoundExpr::Op {
+                op,
+                input,
+                output_type_id,
+            } => BoundExpr::Op {
+                op: op.clone(),
+                input: input.iter().map(|i| i.substitute(exprs)).collect(),
+                output_type_id: *output_type_id,
+            },
+            BoundExpr::Aggregate {
+                op,
+                input,
+                output_type_id,
+            } => BoundExpr::Aggregate {
+                op: op.clone(),
+                input: Box::new(input.substitute(exprs)),
+                output_type_id: *output_type_id,
+            },
+        }
+    }
+}
+
+//Splits a chain of AND operations into separate predicates
+pub fn split_conjunction(expr: &BoundExpr) -> Vec<BoundExpr> {
+    match expr {
+        BoundExpr::Op { op, input, .. } if op == "AND" && input.len() == 2 => input
+            .iter()
+            .flat_map(|i| split_conjunction(i).into_iter())
+            .collect(),
+        _ => vec![expr.clone()],
+    }
+}
+
+fn collect_aggregates(expr: &BoundExpr, aggregates: &mut Vec<BoundExpr>) {
+    match expr {
+        BoundExpr::Aggregate { .. } => {
+            if !aggregates.contains(expr) {
+                aggregates.push(expr.clone())
+            }
+        }
+        BoundExpr::Op { input, .. } => input.iter().for_each(|i| collect_aggregates(i, aggregates)),
+        BoundExpr::Column { .. } | BoundExpr::Literal { .. } => {}
+    }
+}
+
+//Rewrites an expression evaluated after the aggregation in terms of the aggregate outputs
+fn replace_aggregates(
+    expr: &BoundExpr,
+    group_by: &[BoundExpr],
+    aggregates: &[BoundExpr],
+) -> Result<BoundExpr, ErrorDesc> {
+    if let Some(i) = group_by.iter().position(|g| g == expr) {
+        return Ok(BoundExpr::Column {
+            column_id: i,
+            item_type_id: expr.item_type_id(),
+        });
+    }
+    match expr {
+        BoundExpr::Aggregate { .. } => {
+            let i = aggregates.iter().position(|a| a == expr).unwrap();
+            Ok(BoundExpr::Column {
+                column_id: group_by.len() + i,
+                item_type_id: expr.item_type_id(),
+            })
+        }
+        BoundExpr::Op {
+            op,
+            input,
+            output_type_id,
+        } => {
+            let input: Result<Vec<_>, ErrorDesc> = input
+                .iter()
+                .map(|i| replace_aggregates(i, group_by, aggregates))
+                .collect();
+            Ok(BoundExpr::Op {
+                op: op.clone(),
+                input: input?,
+                output_type_id: *output_type_id,
+            })
+        }
+        BoundExpr::Literal { .. } => Ok(expr.clone()),
+        BoundExpr::Column { column_id, .. } => Err(format!(
+            "Column {} is neither grouped nor aggregated",
+            column_id
+        ))?,
+    }
+}
@@ -0,0 +1,5 @@
+pub mod logical;
+pub mod optimizer;
+
+pub use logical::*;
+pub use optimizer::*;
@@ -0,0 +1,457 @@
+use radix_column::ErrorDesc;
+use radix_operations::Dictionary;
+
+use crate::{const_to_literal, BoundExpr, Literal, LogicalPlan};
+
+pub trait OptimizerRule {
+    fn name(&self) -> &str;
+    fn apply(&self, dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc>;
+}
+
+//Applies the rules one after another, in the order they were added
+pub struct Optimizer {
+    rules: Vec<Box<dyn OptimizerRule + Sync>>,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self {
+            rules: vec![
+                Box::new(ConstantFolding),
+                Box::new(MergeFilters),
+                Box::new(PredicatePushdown),
+                Box::new(ProjectionPruning),
+            ],
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self { rules: vec![] }
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn OptimizerRule + Sync>) {
+        self.rules.push(rule);
+    }
+
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    pub fn optimize(&self, dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        self.rules
+            .iter()
+            .try_fold(plan, |plan, rule| rule.apply(dict, plan))
+    }
+}
+
+//Evaluates operations whose inputs are all literals, predicates which are always true are removed
+pub struct ConstantFolding;
+
+impl ConstantFolding {
+    pub fn fold_expr(dict: &Dictionary, expr: BoundExpr) -> Result<BoundExpr, ErrorDesc> {
+        match expr {
+            BoundExpr::Op {
+                op,
+                input,
+                output_type_id,
+            } => {
+                let input: Result<Vec<_>, ErrorDesc> = input
+                    .into_iter()
+                    .map(|i| Self::fold_expr(dict, i))
+                    .collect();
+                let expr = BoundExpr::Op {
+                    op,
+                    input: input?,
+                    output_type_id,
+                };
+                let is_const = match &expr {
+                    BoundExpr::Op { input, .. } => {
+                        input.iter().all(|i| matches!(i, BoundExpr::Literal { .. }))
+                    }
+                    _ => false,
+                };
+                if !is_const {
+                    return Ok(expr);
+                }
+                let value = expr.to_table_expression(dict, &[])?.eval_const(dict)?;
+                //Values which cannot be written as a literal are left unfolded
+                match const_to_literal(dict, &value) {
+                    Ok(value) => Ok(BoundExpr::Literal {
+                        value,
+                        item_type_id: output_type_id,
+                    }),
+                    Err(_) => Ok(expr),
+                }
+            }
+            BoundExpr::Aggregate {
+                op,
+                input,
+                output_type_id,
+            } => Ok(BoundExpr::Aggregate {
+                op,
+                input: Box::new(Self::fold_expr(dict, *input)?),
+                output_type_id,
+            }),
+            BoundExpr::Column { .. } | BoundExpr::Literal { .. } => Ok(expr),
+        }
+    }
+}
+
+impl OptimizerRule for ConstantFolding {
+    fn name(&self) -> &str {
+        "constant_folding"
+    }
+
+    fn apply(&self, dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        let plan = plan
+            .map_inputs(|p| self.apply(dict, p))?
+            .map_exprs(|e| Self::fold_expr(dict, e))?;
+        match plan {
+            LogicalPlan::Filter { input, predicates } => {
+                let always_true = BoundExpr::Literal {
+                    value: Literal::Bool(true),
+                    item_type_id: std::any::TypeId::of::<bool>(),
+                };
+                let predicates = predicates
+                    .into_iter()
+                    .filter(|p| *p != always_true)
+                    .collect();
+                Ok(LogicalPlan::filter(*input, predicates))
+            }
+            plan => Ok(plan),
+        }
+    }
+}
+
+//Combines adjacent filters, so that all predicates are applied in a single index pass
+pub struct MergeFilters;
+
+impl MergeFilters {
+    fn merge(plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        match plan.map_inputs(Self::merge)? {
+            LogicalPlan::Filter { input, predicates } => {
+                Ok(LogicalPlan::filter(*input, predicates))
+            }
+            plan => Ok(plan),
+        }
+    }
+}
+
+impl OptimizerRule for MergeFilters {
+    fn name(&self) -> &str {
+        "merge_filters"
+    }
+
+    fn apply(&self, _dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        Self::merge(plan)
+    }
+}
+
+//Moves predicates as close to the scans as possible, below sorts, projections and joins
+//Predicates on the group by columns are moved below the aggregation
+pub struct PredicatePushdown;
+
+impl PredicatePushdown {
+    fn push_all(plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        Ok(Self::push(plan.map_inputs(Self::push_all)?))
+    }
+
+    fn push(plan: LogicalPlan) -> LogicalPlan {
+        let (input, predicates) = match plan {
+            LogicalPlan::Filter { input, predicates } => (*input, predicates),
+            plan => return plan,
+        };
+
+        match input {
+            LogicalPlan::Join { left, right, on } => {
+                let left_width = left.number_of_columns();
+                let mut left_predicates = vec![];
+                let mut right_predicates = vec![];
+                let mut remaining = vec![];
+                for p in predicates.into_iter() {
+                    let column_ids = p.column_ids();
+                    if column_ids.is_empty() {
+                        remaining.push(p)
+                    } else if column_ids.iter().all(|i| *i < left_width) {
+                        left_predicates.push(p)
+                    } else if column_ids.iter().all(|i| *i >= left_width) {
+                        right_predicates.push(p.map_column_ids(&|i| i - left_width))
+                    } else {
+                        remaining.push(p)
+                    }
+                }
+                let join = LogicalPlan::Join {
+                    left: Box::new(Self::push(LogicalPlan::filter(*left, left_predicates))),
+                    right: Box::new(Self::push(LogicalPlan::filter(*right, right_predicates))),
+                    on,
+                };
+                LogicalPlan::filter(join, remaining)
+            }
+            LogicalPlan::Project { input, exprs } => {
+                let projected: Vec<_> = exprs.iter().map(|p| p.expr.clone()).collect();
+                let predicates = predicates
+                    .iter()
+                    .map(|p| p.substitute(&projected))
+                    .collect();
+                LogicalPlan::Project {
+                    input: Box::new(Self::push(LogicalPlan::filter(*input, predicates))),
+                    exprs,
+                }
+            }
+            LogicalPlan::Sort { input, order_by } => LogicalPlan::Sort {
+                input: Box::new(Self::push(LogicalPlan::filter(*input, predicates))),
+                order_by,
+            },
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let (pushed, remaining): (Vec<_>, Vec<_>) = predicates.into_iter().partition(|p| {
+                    let column_ids = p.column_ids();
+                    !column_ids.is_empty() && column_ids.iter().all(|i| *i < group_by.len())
+                });
+                let pushed = pushed.iter().map(|p| p.substitute(&group_by)).collect();
+                let aggregate = LogicalPlan::Aggregate {
+                    input: Box::new(Self::push(LogicalPlan::filter(*input, pushed))),
+                    group_by,
+                    aggregates,
+                };
+                LogicalPlan::filter(aggregate, remaining)
+            }
+            LogicalPlan::Filter {
+                input,
+                predicates: existing,
+            } => {
+                let mut existing = existing;
+                existing.extend(predicates);
+                Self::push(LogicalPlan::Filter {
+                    input,
+                    predicates: existing,
+                })
+            }
+            input @ LogicalPlan::Scan { .. } | input @ LogicalPlan::Limit { .. } => {
+                LogicalPlan::filter(input, predicates)
+            }
+        }
+    }
+}
+
+impl OptimizerRule for PredicatePushdown {
+    fn name(&self) -> &str {
+        "predicate_pushdown"
+    }
+
+    fn apply(&self, _dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        Self::push_all(plan)
+    }
+}
+
+//Removes the columns which are not used by any of the following nodes
+pub struct ProjectionPruning;
+
+impl ProjectionPruning {
+    //Returns the pruned plan and the new position of every column it used to output
+    fn prune(
+        plan: LogicalPlan,
+        required: &[usize],
+    ) -> Result<(LogicalPlan, Vec<Option<usize>>), ErrorDesc> {
+        let number_of_columns = plan.number_of_columns();
+        let keep = |i: usize| required.contains(&i);
+
+        match plan {
+            LogicalPlan::Scan { table, columns } => {
+                let mut mapping = vec![None; number_of_columns];
+                let mut kept = vec![];
+                for (i, c) in columns.into_iter().enumerate() {
+                    if keep(i) {
+                        mapping[i] = Some(kept.len());
+                        kept.push(c);
+                    }
+                }
+                Ok((
+                    LogicalPlan::Scan {
+                        table,
+                        columns: kept,
+                    },
+                    mapping,
+                ))
+            }
+            LogicalPlan::Filter { input, predicates } => {
+                let required = with_columns_of(required, predicates.iter());
+                let (input, mapping) = Self::prune(*input, &required)?;
+                let predicates = remap_all(predicates.iter(), &mapping);
+                Ok((
+                    LogicalPlan::Filter {
+                        input: Box::new(input),
+                        predicates,
+                    },
+                    mapping,
+                ))
+            }
+            LogicalPlan::Sort { input, order_by } => {
+                let required = with_columns_of(required, order_by.iter().map(|o| &o.expr));
+                let (input, mapping) = Self::prune(*input, &required)?;
+                let order_by = order_by
+                    .into_iter()
+                    .map(|mut o| {
+                        o.expr = remap(&o.expr, &mapping);
+                        o
+                    })
+                    .collect();
+                Ok((
+                    LogicalPlan::Sort {
+                        input: Box::new(input),
+                        order_by,
+                    },
+                    mapping,
+                ))
+            }
+            LogicalPlan::Limit { input, limit } => {
+                let (input, mapping) = Self::prune(*input, required)?;
+                Ok((
+                    LogicalPlan::Limit {
+                        input: Box::new(input),
+                        limit,
+                    },
+                    mapping,
+                ))
+            }
+            LogicalPlan::Project { input, exprs } => {
+                let mut mapping = vec![None; number_of_columns];
+                let mut kept = vec![];
+                for (i, p) in exprs.into_iter().enumerate() {
+                    if keep(i) {
+                        mapping[i] = Some(kept.len());
+                        kept.push(p);
+                    }
+                }
+                let input_required = with_columns_of(&[], kept.iter().map(|p| &p.expr));
+                let (input, input_mapping) = Self::prune(*input, &input_required)?;
+                let kept = kept
+                    .into_iter()
+                    .map(|mut p| {
+                        p.expr = remap(&p.expr, &input_mapping);
+                        p
+                    })
+                    .collect();
+                Ok((
+                    LogicalPlan::Project {
+                        input: Box::new(input),
+                        exprs: kept,
+                    },
+                    mapping,
+                ))
+            }
+            //The group by expressions define the groups, so only unused aggregates are removed
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+            } => {
+                let mut mapping: Vec<_> = (0..group_by.len()).map(Some).collect();
+                mapping.resize(number_of_columns, None);
+                let mut kept = vec![];
+                for (i, a) in aggregates.into_iter().enumerate() {
+                    if keep(group_by.len() + i) {
+                        mapping[group_by.len() + i] = Some(group_by.len() + kept.len());
+                        kept.push(a);
+                    }
+                }
+                let input_required = with_columns_of(&[], group_by.iter().chain(kept.iter()));
+                let (input, input_mapping) = Self::prune(*input, &input_required)?;
+                Ok((
+                    LogicalPlan::Aggregate {
+                        input: Box::new(input),
+                        group_by: remap_all(group_by.iter(), &input_mapping),
+                        aggregates: remap_all(kept.iter(), &input_mapping),
+                    },
+                    mapping,
+                ))
+            }
+            LogicalPlan::Join { left, right, on } => {
+                let left_width = left.number_of_columns();
+                let mut left_required: Vec<_> = required
+                    .iter()
+                    .filter(|i| **i < left_width)
+                    .copied()
+                    .chain(on.iter().map(|(l, _)| *l))
+                    .collect();
+                let mut right_required: Vec<_> = required
+                    .iter()
+                    .filter(|i| **i >= left_width)
+                    .map(|i| i - left_width)
+                    .chain(on.iter().map(|(_, r)| *r))
+                    .collect();
+                left_required.sort_unstable();
+                left_required.dedup();
+                right_required.sort_unstable();
+                right_required.dedup();
+
+                let (left, left_mapping) = Self::prune(*left, &left_required)?;
+                let (right, right_mapping) = Self::prune(*right, &right_required)?;
+                let new_left_width = left.number_of_columns();
+                let on = on
+                    .iter()
+                    .map(|(l, r)| (left_mapping[*l].unwrap(), right_mapping[*r].unwrap()))
+                    .collect();
+                let mapping = left_mapping
+                    .into_iter()
+                    .chain(
+                        right_mapping
+                            .into_iter()
+                            .map(|m| m.map(|i| i + new_left_width)),
+                    )
+                    .collect();
+                Ok((
+                    LogicalPlan::Join {
+                        left: Box::new(left),
+                        right: Box::new(right),
+                        on,
+                    },
+                    mapping,
+                ))
+            }
+        }
+    }
+}
+
+impl OptimizerRule for ProjectionPruning {
+    fn name(&self) -> &str {
+        "projection_pruning"
+    }
+
+    fn apply(&self, _dict: &Dictionary, plan: LogicalPlan) -> Result<LogicalPlan, ErrorDesc> {
+        //All columns of the final result are required
+        let required: Vec<_> = (0..plan.number_of_columns()).collect();
+        Self::prune(plan, &required).map(|(plan, _)| plan)
+    }
+}
+
+fn with_columns_of<'e>(
+    required: &[usize],
+    exprs: impl Iterator<Item = &'e BoundExpr>,
+) -> Vec<usize> {
+    let mut required = required.to_vec();
+    exprs.for_each(|e| e.collect_column_ids(&mut required));
+    required.sort_unstable();
+    required.dedup();
+    required
+}
+
+fn remap(expr: &BoundExpr, mapping: &[Option<usize>]) -> BoundExpr {
+    expr.map_column_ids(&|i| mapping[i].unwrap())
+}
+
+fn remap_all<'e>(
+    exprs: impl Iterator<Item = &'e BoundExpr>,
+    mapping: &[Option<usize>],
+) -> Vec<BoundExpr> {
+    exprs.map(|e| remap(e, mapping)).collect()
+}
@@ -1,3 +1,3 @@
-pub mod schema;
+mod schema;
 
 pub use schema::*;
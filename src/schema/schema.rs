@@ -39,10 +39,111 @@
 /// 18) Return Table T3=((P_T1_CUS_NO, P_T1_J),(P_T1_CUS_ID,P_T2_J ))
 use std::collections::HashMap;
 
-use crate::table::Table;
+use radix_column::ErrorDesc;
+
+use crate::table::{ColumnSchema, Table};
 
 #[derive(Debug)]
 pub struct Schema<'a> {
     tables: Vec<Table<'a>>,
     names: HashMap<String, usize>,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    pub name: String,
+    pub number_of_rows: usize,
+    pub columns: Vec<ColumnSchema>,
+}
+
+impl<'a> Default for Schema<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Schema<'a> {
+    pub fn new() -> Self {
+        Self {
+            tables: vec![],
+            names: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: &str, table: Table<'a>) -> Result<(), ErrorDesc> {
+        if self.names.contains_key(name) {
+            Err(format!("Table {} already exists in the schema", name))?
+        }
+        self.names.insert(name.to_string(), self.tables.len());
+        self.tables.push(table);
+        Ok(())
+    }
+
+    fn table_id(&self, name: &str) -> Result<usize, ErrorDesc> {
+        match self.names.get(name) {
+            Some(table_id) => Ok(*table_id),
+            None => Err(format!("Table {} not found in the schema", name))?,
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains_key(name)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Table<'a>, ErrorDesc> {
+        let table_id = self.table_id(name)?;
+        Ok(&self.tables[table_id])
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Result<&mut Table<'a>, ErrorDesc> {
+        let table_id = self.table_id(name)?;
+        Ok(&mut self.tables[table_id])
+    }
+
+    pub fn rename(&mut self, old_name: &str, new_name: &str) -> Result<(), ErrorDesc> {
+        let table_id = self.table_id(old_name)?;
+        if old_name == new_name {
+            return Ok(());
+        }
+        if self.names.contains_key(new_name) {
+            Err(format!("Table {} already exists in the schema", new_name))?
+        }
+        self.names.remove(old_name);
+        self.names.insert(new_name.to_string(), table_id);
+        Ok(())
+    }
+
+    //Removes the table from the schema and returns it
+    pub fn drop_table(&mut self, name: &str) -> Result<Table<'a>, ErrorDesc> {
+        let table_id = self.table_id(name)?;
+        self.names.remove(name);
+        let table = self.tables.swap_remove(table_id);
+        //The last table was moved in place of the removed one
+        let moved_id = self.tables.len();
+        if table_id != moved_id {
+            self.names
+                .values_mut()
+                .filter(|i| **i == moved_id)
+                .for_each(|i| *i = table_id);
+        }
+        Ok(table)
+    }
+
+    //Lists all tables ordered by name
+    pub fn list(&self) -> Vec<TableInfo> {
+        let mut res: Vec<_> = self
+            .names
+            .iter()
+            .map(|(name, table_id)| {
+                let table = &self.tables[*table_id];
+                TableInfo {
+                    name: name.clone(),
+                    number_of_rows: table.number_of_rows(),
+                    columns: table.schema().to_vec(),
+                }
+            })
+            .collect();
+        res.sort_by(|a, b| a.name.cmp(&b.name));
+        res
+    }
+}
@@ -0,0 +1,738 @@
+use std::{any::TypeId, convert::TryFrom};
+
+use radix_column::{
+    ColumnDataIndex, ColumnWrapper, Date, Decimal, ErrorDesc, Interval, Time, Timestamp, TypeName,
+};
+use radix_operations::{ColumnOperations, Dictionary, Signature};
+
+use crate::{
+    sql::{error_at, parse, Expr, SelectItem},
+    ExpressionInput, Schema, Table, TableExpression,
+};
+
+pub(crate) const AGGREGATE_FUNCTIONS: &[&str] = &["SUM", "COUNT", "MAX", "MIN", "AVG"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Literal {
+    Integer(u64),
+    Decimal(String),
+    Str(String),
+    Bool(bool),
+    //Values of IN (...), converted to a column with one row per value
+    List(Vec<Literal>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundExpr {
+    Column {
+        column_id: usize,
+        item_type_id: TypeId,
+    },
+    Literal {
+        value: Literal,
+        item_type_id: TypeId,
+    },
+    Op {
+        op: String,
+        input: Vec<BoundExpr>,
+        output_type_id: TypeId,
+    },
+    Aggregate {
+        op: String,
+        input: Box<BoundExpr>,
+        output_type_id: TypeId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundSelectItem {
+    pub name: String,
+    pub expr: BoundExpr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderByItem {
+    pub expr: BoundExpr,
+    pub ascending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundSelect {
+    pub table: String,
+    pub projection: Vec<BoundSelectItem>,
+    pub filter: Option<BoundExpr>,
+    pub group_by: Vec<BoundExpr>,
+    pub having: Option<BoundExpr>,
+    pub order_by: Vec<OrderByItem>,
+    pub limit: Option<usize>,
+}
+
+impl BoundSelect {
+    pub fn is_aggregate(&self) -> bool {
+        !self.group_by.is_empty()
+            || self.projection.iter().any(|p| p.expr.contains_aggregate())
+            || self.having.is_some()
+    }
+}
+
+impl BoundExpr {
+    pub fn item_type_id(&self) -> TypeId {
+        match self {
+            BoundExpr::Column { item_type_id, .. } => *item_type_id,
+            BoundExpr::Literal { item_type_id, .. } => *item_type_id,
+            BoundExpr::Op { output_type_id, .. } => *output_type_id,
+            BoundExpr::Aggregate { output_type_id, .. } => *output_type_id,
+        }
+    }
+
+    pub fn contains_aggregate(&self) -> bool {
+        match self {
+            BoundExpr::Column { .. } | BoundExpr::Literal { .. } => false,
+            BoundExpr::Op { input, .. } => input.iter().any(|i| i.contains_aggregate()),
+            BoundExpr::Aggregate { .. } => true,
+        }
+    }
+
+    pub fn to_expression_input(
+        &self,
+        dict: &Dictionary,
+        partition_by: &[BoundExpr],
+    ) -> Result<ExpressionInput<'static>, ErrorDesc> {
+        match self {
+            BoundExpr::Column { column_id, .. } => Ok(ExpressionInput::Column(*column_id)),
+            BoundExpr::Literal {
+                value,
+                item_type_id,
+            } => Ok(ExpressionInput::OwnedConst(literal_to_const(
+                dict,
+                value,
+                *item_type_id,
+            )?)),
+            BoundExpr::Op { .. } | BoundExpr::Aggregate { .. } => Ok(ExpressionInput::Expr(
+                self.to_table_expression(dict, partition_by)?,
+            )),
+        }
+    }
+
+    //Aggregates are partitioned by the group by expressions
+    pub fn to_table_expression(
+        &self,
+        dict: &Dictionary,
+        partition_by: &[BoundExpr],
+    ) -> Result<TableExpression<'static>, ErrorDesc> {
+        match self {
+            BoundExpr::Op { op, input, .. } => {
+                let input: Result<Vec<_>, ErrorDesc> = input
+                    .iter()
+                    .map(|i| i.to_expression_input(dict, partition_by))
+                    .collect();
+                Ok(TableExpression {
+                    op: op.clone(),
+                    input: input?,
+                    partition_by: vec![],
+                })
+            }
+            BoundExpr::Aggregate { op, input, .. } => {
+                if partition_by.is_empty() {
+                    Err("Aggregates without GROUP BY cannot be transformed to a TableExpression")?
+                }
+                let partition_by_input: Result<Vec<_>, ErrorDesc> = partition_by
+                    .iter()
+                    .map(|i| i.to_expression_input(dict, &[]))
+                    .collect();
+                Ok(TableExpression {
+                    op: op.clone(),
+                    input: vec![input.to_expression_input(dict, &[])?],
+                    partition_by: partition_by_input?,
+                })
+            }
+            _ => Err("Only operations can be transformed to a TableExpression")?,
+        }
+    }
+}
+
+macro_rules! integer_literal_to_const {
+    ($dict:ident, $value:ident, $item_type_id:ident; $($tr:ty)+) => ($(
+        if $item_type_id == TypeId::of::<$tr>() {
+            return match <$tr>::try_from(*$value) {
+                Ok(v) => Ok(ColumnWrapper::new_const($dict, v)),
+                Err(_) => Err(format!(
+                    "Integer literal {} does not fit into {}",
+                    $value,
+                    stringify!($tr)
+                ))?,
+            };
+        }
+    )+)
+}
+
+//String literals are parsed as values of the other types, like dates in WHERE d >= '2024-01-31'
+macro_rules! str_literal_to_const {
+    ($dict:ident, $value:ident, $item_type_id:ident; $($tr:ty)+) => ($(
+        if $item_type_id == TypeId::of::<$tr>() {
+            return Ok(ColumnWrapper::new_const($dict, $value.parse::<$tr>()?));
+        }
+    )+)
+}
+
+pub fn literal_to_const(
+    dict: &Dictionary,
+    value: &Literal,
+    item_type_id: TypeId,
+) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+    match value {
+        Literal::Integer(value) => {
+            integer_literal_to_const!(dict, value, item_type_id; u64 u32 u16 u8 usize);
+            if item_type_id == TypeId::of::<Decimal>() {
+                return Ok(ColumnWrapper::new_const(dict, Decimal::from(*value)));
+            }
+        }
+        Literal::Decimal(value) => {
+            str_literal_to_const!(dict, value, item_type_id; Decimal);
+        }
+        Literal::Str(value) => {
+            if item_type_id == TypeId::of::<String>() {
+                return Ok(ColumnWrapper::new_const(dict, value.clone()));
+            }
+            str_literal_to_const!(dict, value, item_type_id; Date Time Timestamp Interval Decimal);
+        }
+        Literal::Bool(value) => {
+            if item_type_id == TypeId::of::<bool>() {
+                return Ok(ColumnWrapper::new_const(dict, *value));
+            }
+        }
+        Literal::List(values) => {
+            let values: Result<Vec<_>, ErrorDesc> = values
+                .iter()
+                .map(|v| literal_to_const(dict, v, item_type_id))
+                .collect();
+            let values = values?;
+            let src: Vec<_> = values.iter().map(|c| (c, 1)).collect();
+            return ColumnWrapper::concat(dict, &src);
+        }
+    }
+    Err(format!(
+        "Literal {:?} cannot be used as {}",
+        value,
+        type_name(dict, item_type_id)
+    ))?
+}
+
+//Inverse of literal_to_const, the column must hold exactly one value which is not null
+pub fn const_to_literal(dict: &Dictionary, c: &ColumnWrapper) -> Result<Literal, ErrorDesc> {
+    let item_type_id = c.column().item_type_id();
+    if !c.bitmap().all_valid() {
+        Err("NULL literals are not supported")?
+    }
+    let mut value = c.as_string(dict, &ColumnDataIndex::None)?;
+    if value.len() != 1 {
+        Err("Literal must consist of exactly one value")?
+    }
+    let value = value.pop().unwrap();
+
+    let literal = if [
+        TypeId::of::<u64>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<usize>(),
+    ]
+    .contains(&item_type_id)
+    {
+        Literal::Integer(value.parse::<u64>().map_err(|e| e.to_string())?)
+    } else if item_type_id == TypeId::of::<Decimal>() {
+        Literal::Decimal(value)
+    } else if [
+        TypeId::of::<String>(),
+        TypeId::of::<Date>(),
+        TypeId::of::<Time>(),
+        TypeId::of::<Timestamp>(),
+        TypeId::of::<Interval>(),
+    ]
+    .contains(&item_type_id)
+    {
+        Literal::Str(value)
+    } else if item_type_id == TypeId::of::<bool>() {
+        Literal::Bool(value == "true")
+    } else {
+        Err(format!(
+            "Values of type {} cannot be used as literals",
+            type_name(dict, item_type_id)
+        ))?
+    };
+    Ok(literal)
+}
+
+pub(crate) fn type_name(dict: &Dictionary, item_type_id: TypeId) -> String {
+    match dict.type_name(item_type_id) {
+        Some(name) => name.to_string(),
+        None => TypeName(item_type_id).to_string(),
+    }
+}
+
+//COUNT(*) counts a const, which is never null and so counts every row
+fn count_star_input() -> BoundExpr {
+    BoundExpr::Literal {
+        value: Literal::Integer(1),
+        item_type_id: TypeId::of::<u64>(),
+    }
+}
+
+//Parses the query and resolves all names against the tables registered in the schema
+pub fn bind(schema: &Schema, dict: &Dictionary, query: &str) -> Result<BoundSelect, ErrorDesc> {
+    let statement = parse(query)?;
+
+    let table = schema.get(&statement.from.name).map_err(|_| {
+        error_at(
+            query,
+            statement.from.pos,
+            &format!("Unknown table {}", statement.from.name),
+        )
+    })?;
+
+    let binder = Binder {
+        query,
+        dict,
+        table,
+        table_name: &statement.from.name,
+        table_alias: statement.from.alias.as_deref(),
+    };
+
+    let filter = match &statement.selection {
+        Some(expr) => {
+            let bound = binder.bind_expr(expr, None, false)?;
+            binder.expect_bool(expr, &bound, "WHERE")?;
+            Some(bound)
+        }
+        None => None,
+    };
+
+    let group_by: Result<Vec<_>, ErrorDesc> = statement
+        .group_by
+        .iter()
+        .map(|expr| binder.bind_expr(expr, None, false))
+        .collect();
+    let group_by = group_by?;
+
+    let mut projection = vec![];
+    let mut projection_ast = vec![];
+    for item in statement.projection.iter() {
+        match item {
+            SelectItem::Wildcard { pos } => {
+                for (column_id, c) in table.schema().iter().enumerate() {
+                    projection.push(BoundSelectItem {
+                        name: c.name.clone(),
+                        expr: BoundExpr::Column {
+                            column_id,
+                            item_type_id: c.item_type_id,
+                        },
+                    });
+                    projection_ast.push(Expr::Column {
+                        table: None,
+                        name: c.name.clone(),
+                        pos: *pos,
+                    });
+                }
+            }
+            SelectItem::Expr { expr, alias } => {
+                let name = match (alias, expr) {
+                    (Some(alias), _) => alias.clone(),
+                    (None, Expr::Column { name, .. }) => name.clone(),
+                    (None, expr) => expr.to_string(),
+                };
+                projection.push(BoundSelectItem {
+                    name,
+                    expr: binder.bind_expr(expr, None, true)?,
+                });
+                projection_ast.push(expr.clone());
+            }
+        }
+    }
+
+    let having = match &statement.having {
+        Some(expr) => {
+            let bound = binder.bind_expr(expr, None, true)?;
+            binder.expect_bool(expr, &bound, "HAVING")?;
+            Some((expr, bound))
+        }
+        None => None,
+    };
+
+    let is_aggregate = !group_by.is_empty()
+        || having.is_some()
+        || projection.iter().any(|p| p.expr.contains_aggregate());
+
+    if is_aggregate {
+        projection
+            .iter()
+            .zip(projection_ast.iter())
+            .try_for_each(|(p, expr)| binder.check_grouped(expr, &p.expr, &group_by))?;
+        if let Some((expr, bound)) = &having {
+            binder.check_grouped(expr, bound, &group_by)?;
+        }
+    }
+
+    let mut order_by = vec![];
+    for item in statement.order_by.iter() {
+        let expr = match &item.expr {
+            Expr::Integer { value, pos } => {
+                if *value == 0 || *value as usize > projection.len() {
+                    Err(error_at(
+                        query,
+                        *pos,
+                        &format!("ORDER BY position {} is not in the select list", value),
+                    ))?
+                }
+                projection[*value as usize - 1].expr.clone()
+            }
+            Expr::Column {
+                table: None, name, ..
+            } if projection.iter().any(|p| &p.name == name) => projection
+                .iter()
+                .find(|p| &p.name == name)
+                .unwrap()
+                .expr
+                .clone(),
+            expr => {
+                let bound = binder.bind_expr(expr, None, is_aggregate)?;
+                if is_aggregate {
+                    binder.check_grouped(expr, &bound, &group_by)?;
+                }
+                bound
+            }
+        };
+        order_by.push(OrderByItem {
+            expr,
+            ascending: item.ascending,
+        });
+    }
+
+    Ok(BoundSelect {
+        table: statement.from.name.clone(),
+        projection,
+        filter,
+        group_by,
+        having: having.map(|(_, bound)| bound),
+        order_by,
+        limit: statement.limit,
+    })
+}
+
+struct Binder<'q, 's, 'a> {
+    query: &'q str,
+    dict: &'s Dictionary,
+    table: &'s Table<'a>,
+    table_name: &'s str,
+    table_alias: Option<&'s str>,
+}
+
+impl<'q, 's, 'a> Binder<'q, 's, 'a> {
+    fn error(&self, pos: usize, message: &str) -> ErrorDesc {
+        error_at(self.query, pos, message).into()
+    }
+
+    fn op_exists(&self, op: &str) -> bool {
+        self.dict.op.keys().any(|s| s.op_name() == op)
+    }
+
+    fn expect_bool(&self, expr: &Expr, bound: &BoundExpr, clause: &str) -> Result<(), ErrorDesc> {
+        if bound.item_type_id() != TypeId::of::<bool>() {
+            Err(self.error(
+                expr.pos(),
+                &format!(
+                    "{} condition must be of type bool, found {}",
+                    clause,
+                    type_name(self.dict, bound.item_type_id())
+                ),
+            ))?
+        }
+        Ok(())
+    }
+
+    //Literals without a type hint get a default type
+    fn bind_literal(&self, expr: &Expr, hint: Option<TypeId>) -> Result<BoundExpr, ErrorDesc> {
+        let (value, default_type_id) = match expr {
+            Expr::Integer { value, .. } => (Literal::Integer(*value), TypeId::of::<u64>()),
+            Expr::Decimal { value, .. } => {
+                (Literal::Decimal(value.clone()), TypeId::of::<Decimal>())
+            }
+            Expr::Str { value, .. } => (Literal::Str(value.clone()), TypeId::of::<String>()),
+            Expr::Bool { value, .. } => (Literal::Bool(*value), TypeId::of::<bool>()),
+            Expr::Null { pos } => Err(self.error(*pos, "NULL literals are not supported"))?,
+            _ => unreachable!(),
+        };
+        let item_type_id = hint.unwrap_or(default_type_id);
+        literal_to_const(self.dict, &value, item_type_id).map_err(|e| {
+            self.error(
+                expr.pos(),
+                &format!("Type mismatch for literal {}: {}", expr, e),
+            )
+        })?;
+        Ok(BoundExpr::Literal {
+            value,
+            item_type_id,
+        })
+    }
+
+    fn bind_expr(
+        &self,
+        expr: &Expr,
+        hint: Option<TypeId>,
+        allow_aggregate: bool,
+    ) -> Result<BoundExpr, ErrorDesc> {
+        match expr {
+            Expr::Column { table, name, pos } => {
+                if let Some(table) = table {
+                    if table != self.table_name && Some(table.as_str()) != self.table_alias {
+                        Err(self.error(*pos, &format!("Unknown table {}", table)))?
+                    }
+                }
+                let column_id = self
+                    .table
+                    .column_id(name)
+                    .map_err(|_| self.error(*pos, &format!("Unknown column {}", name)))?;
+                Ok(BoundExpr::Column {
+                    column_id,
+                    item_type_id: self.table.column_schema(&column_id)?.item_type_id,
+                })
+            }
+            Expr::Integer { .. }
+            | Expr::Decimal { .. }
+            | Expr::Str { .. }
+            | Expr::Bool { .. }
+            | Expr::Null { .. } => self.bind_literal(expr, hint),
+            Expr::Binary {
+                op,
+                left,
+                right,
+                pos,
+            } => {
+                //A literal takes the type of the other operand
+                let (left, right) = if left.is_literal() && !right.is_literal() {
+                    let right = self.bind_expr(right, None, allow_aggregate)?;
+                    let left = self.bind_expr(left, Some(right.item_type_id()), allow_aggregate)?;
+                    (left, right)
+                } else {
+                    let left = self.bind_expr(left, None, allow_aggregate)?;
+                    let right =
+                        self.bind_expr(right, Some(left.item_type_id()), allow_aggregate)?;
+                    (left, right)
+                };
+                self.bind_op(op, vec![left, right], *pos)
+            }
+            Expr::Not { input, pos } => {
+                let input = self.bind_expr(input, Some(TypeId::of::<bool>()), allow_aggregate)?;
+                self.bind_op("NOT", vec![input], *pos)
+            }
+            //The items of the list are bound to one literal with the type of the input
+            Expr::InList { input, list, pos } => {
+                let input = self.bind_expr(input, None, allow_aggregate)?;
+                let mut values = Vec::with_capacity(list.len());
+                for item in list.iter() {
+                    if !item.is_literal() {
+                        Err(self.error(item.pos(), "The items of IN must be literals"))?
+                    }
+                    match self.bind_literal(item, Some(input.item_type_id()))? {
+                        BoundExpr::Literal { value, .. } => values.push(value),
+                        _ => unreachable!(),
+                    }
+                }
+                let list = BoundExpr::Literal {
+                    value: Literal::List(values),
+                    item_type_id: input.item_type_id(),
+                };
+                self.bind_op("IN", vec![input, list], *pos)
+            }
+            Expr::Function {
+                name,
+                args,
+                wildcard,
+                pos,
+            } => {
+                if self.dict.is_aggregate(name) {
+                    if !allow_aggregate {
+                        Err(self.error(
+                            *pos,
+                            &format!("Aggregate function {} is not allowed here", name),
+                        ))?
+                    }
+                    let input = if *wildcard {
+                        if name != "COUNT" {
+                            Err(self.error(*pos, &format!("{}(*) is not supported", name)))?
+                        }
+                        count_star_input()
+                    } else {
+                        if args.len() != 1 {
+                            Err(self.error(
+                                *pos,
+                                &format!(
+                                    "Aggregate function {} expects exactly one argument",
+                                    name
+                                ),
+                            ))?
+                        }
+                        self.bind_expr(&args[0], None, false)?
+                    };
+                    let signature = Signature::new(name, vec![input.item_type_id()]);
+                    match self.dict.op.get(&signature) {
+                        Some(op) => Ok(BoundExpr::Aggregate {
+                            op: name.clone(),
+                            input: Box::new(input),
+                            output_type_id: self
+                                .dict
+                                .aggregate_output_type(name, op.output_type_id)?,
+                        }),
+                        None if self.op_exists(name) => Err(self.error(
+                            *pos,
+                            &format!(
+                                "Aggregate function {} is not defined for type {}",
+                                name,
+                                type_name(self.dict, input.item_type_id())
+                            ),
+                        ))?,
+                        None => Err(self.error(*pos, &format!("Unknown function {}", name)))?,
+                    }
+                } else {
+                    if !self.op_exists(name) {
+                        Err(self.error(*pos, &format!("Unknown function {}", name)))?
+                    }
+                    if *wildcard {
+                        Err(self.error(*pos, &format!("{}(*) is not supported", name)))?
+                    }
+                    let input: Result<Vec<_>, ErrorDesc> = args
+                        .iter()
+                        .map(|a| self.bind_expr(a, None, allow_aggregate))
+                        .collect();
+                    self.bind_op(name, input?, *pos)
+                }
+            }
+            Expr::Cast {
+                input,
+                type_name,
+                type_args,
+                pos,
+            } => self.bind_cast(input, type_name, type_args, *pos, allow_aggregate),
+        }
+    }
+
+    //String literals are converted when binding, other values by the operation CAST#<TYPE>
+    //The arguments of the type are passed to the operation as u8 constants, like CAST#DECIMAL(value, 10, 2)
+    fn bind_cast(
+        &self,
+        input: &Expr,
+        target_name: &str,
+        type_args: &[u64],
+        pos: usize,
+        allow_aggregate: bool,
+    ) -> Result<BoundExpr, ErrorDesc> {
+        let target = match self.dict.logical_type_by_name(target_name) {
+            Some(t) => t.item_type_id,
+            None => Err(self.error(pos, &format!("Unknown type {}", target_name)))?,
+        };
+        if type_args.is_empty() {
+            if let Expr::Str { .. } = input {
+                return self.bind_literal(input, Some(target));
+            }
+        }
+        let mut input = vec![self.bind_expr(input, None, allow_aggregate)?];
+        if type_args.is_empty() && input[0].item_type_id() == target {
+            return Ok(input.pop().unwrap());
+        }
+        for value in type_args.iter() {
+            input.push(self.bind_literal(
+                &Expr::Integer { value: *value, pos },
+                Some(TypeId::of::<u8>()),
+            )?);
+        }
+        let op = format!("CAST#{}", target_name);
+        let input_types: Vec<_> = input.iter().map(|i| i.item_type_id()).collect();
+        match self.dict.op.get(&Signature::new(&op, input_types)) {
+            Some(o) => Ok(BoundExpr::Op {
+                op,
+                output_type_id: o.output_type_id,
+                input,
+            }),
+            None => {
+                let args: Vec<_> = type_args.iter().map(|a| a.to_string()).collect();
+                Err(self.error(
+                    pos,
+                    &format!(
+                        "Cannot cast {} to {}{}",
+                        type_name(self.dict, input[0].item_type_id()),
+                        target_name,
+                        match args.is_empty() {
+                            true => String::new(),
+                            false => format!("({})", args.join(", ")),
+                        }
+                    ),
+                ))?
+            }
+        }
+    }
+
+    fn bind_op(&self, op: &str, input: Vec<BoundExpr>, pos: usize) -> Result<BoundExpr, ErrorDesc> {
+        let input_types: Vec<_> = input.iter().map(|i| i.item_type_id()).collect();
+        let signature = Signature::new(op, input_types.clone());
+        match self.dict.op.get(&signature) {
+            Some(o) if !o.is_assign_op => Ok(BoundExpr::Op {
+                op: op.to_string(),
+                input,
+                output_type_id: o.output_type_id,
+            }),
+            _ if self.op_exists(op) => {
+                let input_types: Vec<_> = input_types
+                    .iter()
+                    .map(|t| type_name(self.dict, *t))
+                    .collect();
+                Err(self.error(
+                    pos,
+                    &format!(
+                        "Type mismatch: operation {} is not defined for types ({})",
+                        op,
+                        input_types.join(", ")
+                    ),
+                ))?
+            }
+            _ => Err(self.error(pos, &format!("Unknown operation {}", op)))?,
+        }
+    }
+
+    //In an aggregate query every column must either be grouped or be an input to an aggregate
+    fn check_grouped(
+        &self,
+        expr: &Expr,
+        bound: &BoundExpr,
+        group_by: &[BoundExpr],
+    ) -> Result<(), ErrorDesc> {
+        if group_by.contains(bound) {
+            return Ok(());
+        }
+        match (expr, bound) {
+            (_, BoundExpr::Aggregate { .. }) | (_, BoundExpr::Literal { .. }) => Ok(()),
+            (Expr::Binary { left, right, .. }, BoundExpr::Op { input, .. }) => {
+                self.check_grouped(left, &input[0], group_by)?;
+                self.check_grouped(right, &input[1], group_by)
+            }
+            (Expr::Not { input: expr, .. }, BoundExpr::Op { input, .. })
+            | (Expr::InList { input: expr, .. }, BoundExpr::Op { input, .. }) => {
+                self.check_grouped(expr, &input[0], group_by)
+            }
+            (Expr::Function { args, .. }, BoundExpr::Op { input, .. }) => args
+                .iter()
+                .zip(input.iter())
+                .try_for_each(|(a, i)| self.check_grouped(a, i, group_by)),
+            (Expr::Cast { input: expr, .. }, BoundExpr::Op { input, .. }) => {
+                self.check_grouped(expr, &input[0], group_by)
+            }
+            //Casts to the type of the input are bound to the input itself
+            (Expr::Cast { input: expr, .. }, bound) => self.check_grouped(expr, bound, group_by),
+            (expr, _) => Err(self.error(
+                expr.pos(),
+                &format!(
+                    "{} must appear in the GROUP BY clause or be used in an aggregate function",
+                    expr
+                ),
+            ))?,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use radix_column::ErrorDesc;
+
+use crate::sql::error_at;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword(String),
+    Ident(String),
+    Integer(u64),
+    //Digits with a fraction like 12.50, kept as written so that the scale is not lost
+    Decimal(String),
+    Str(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    //Byte offset of the token in the query text
+    pub pos: usize,
+}
+
+const KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "GROUP", "BY", "HAVING", "ORDER", "LIMIT", "AS", "ASC", "DESC",
+    "AND", "OR", "NOT", "IN", "TRUE", "FALSE", "NULL",
+];
+
+//Longer symbols first, so that "<=" is not split into "<" and "="
+const SYMBOLS: &[&str] = &[
+    "<=", ">=", "<>", "!=", "==", "=", "<", ">", "+", "-", "*", "/", "(", ")", ",", ".",
+];
+
+pub fn tokenize(query: &str) -> Result<Vec<Token>, ErrorDesc> {
+    let bytes = query.as_bytes();
+    let mut tokens = vec![];
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c.is_ascii_whitespace() {
+            pos += 1;
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            let start = pos;
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            let word = &query[start..pos];
+            let upper = word.to_ascii_uppercase();
+            let kind = if KEYWORDS.contains(&upper.as_str()) {
+                TokenKind::Keyword(upper)
+            } else {
+                TokenKind::Ident(word.to_string())
+            };
+            tokens.push(Token { kind, pos: start });
+        } else if c.is_ascii_digit() {
+            let start = pos;
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            if pos + 1 < bytes.len() && bytes[pos] == b'.' && bytes[pos + 1].is_ascii_digit() {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Decimal(query[start..pos].to_string()),
+                    pos: start,
+                });
+                continue;
+            }
+            let value = query[start..pos].parse::<u64>().map_err(|_| {
+                error_at(
+                    query,
+                    start,
+                    &format!("Integer literal {} is too large", &query[start..pos]),
+                )
+            })?;
+            tokens.push(Token {
+                kind: TokenKind::Integer(value),
+                pos: start,
+            });
+        } else if c == b'\'' {
+            let start = pos;
+            let mut value = String::new();
+            pos += 1;
+            loop {
+                match query[pos..].find('\'') {
+                    Some(i) => {
+                        value.push_str(&query[pos..pos + i]);
+                        pos += i + 1;
+                        //Two quotes inside a string literal stand for one quote
+                        if pos < bytes.len() && bytes[pos] == b'\'' {
+                            value.push('\'');
+                            pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    None => Err(error_at(query, start, "Unterminated string literal"))?,
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Str(value),
+                pos: start,
+            });
+        } else {
+            match SYMBOLS.iter().find(|s| query[pos..].starts_with(*s)) {
+                Some(s) => {
+                    tokens.push(Token {
+                        kind: TokenKind::Symbol(s),
+                        pos,
+                    });
+                    pos += s.len();
+                }
+                None => {
+                    let ch = query[pos..].chars().next().unwrap();
+                    Err(error_at(
+                        query,
+                        pos,
+                        &format!("Unexpected character '{}'", ch),
+                    ))?
+                }
+            }
+        }
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        pos: query.len(),
+    });
+    Ok(tokens)
+}
@@ -0,0 +1,27 @@
+pub mod binder;
+pub mod lexer;
+pub mod parser;
+
+pub use binder::*;
+pub use lexer::*;
+pub use parser::*;
+
+//Formats an error message pointing at the given byte offset of the query text
+pub(crate) fn error_at(query: &str, pos: usize, message: &str) -> String {
+    let pos = pos.min(query.len());
+    let line_start = query[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = query[pos..]
+        .find('\n')
+        .map(|i| pos + i)
+        .unwrap_or_else(|| query.len());
+    let line = query[..pos].matches('\n').count() + 1;
+    let column = query[line_start..pos].chars().count() + 1;
+    format!(
+        "{} at line {}, column {}\n{}\n{}^",
+        message,
+        line,
+        column,
+        &query[line_start..line_end],
+        " ".repeat(column - 1)
+    )
+}
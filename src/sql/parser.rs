@@ -0,0 +1,626 @@
+use std::fmt;
+
+use radix_column::ErrorDesc;
+
+use crate::sql::{error_at, tokenize, Token, TokenKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Column {
+        table: Option<String>,
+        name: String,
+        pos: usize,
+    },
+    Integer {
+        value: u64,
+        pos: usize,
+    },
+    Decimal {
+        value: String,
+        pos: usize,
+    },
+    Str {
+        value: String,
+        pos: usize,
+    },
+    Bool {
+        value: bool,
+        pos: usize,
+    },
+    Null {
+        pos: usize,
+    },
+    Binary {
+        op: String,
+        left: Box<Expr>,
+        right: Box<Expr>,
+        pos: usize,
+    },
+    Not {
+        input: Box<Expr>,
+        pos: usize,
+    },
+    //input IN (item, ...)
+    InList {
+        input: Box<Expr>,
+        list: Vec<Expr>,
+        pos: usize,
+    },
+    Function {
+        name: String,
+        args: Vec<Expr>,
+        //COUNT(*)
+        wildcard: bool,
+        pos: usize,
+    },
+    //CAST(input AS type_name) and typed literals like DATE '2024-01-31'
+    Cast {
+        input: Box<Expr>,
+        type_name: String,
+        //Parameters of the type like the precision and scale of DECIMAL(10, 2)
+        type_args: Vec<u64>,
+        pos: usize,
+    },
+}
+
+impl Expr {
+    pub fn pos(&self) -> usize {
+        match self {
+            Expr::Column { pos, .. } => *pos,
+            Expr::Integer { pos, .. } => *pos,
+            Expr::Decimal { pos, .. } => *pos,
+            Expr::Str { pos, .. } => *pos,
+            Expr::Bool { pos, .. } => *pos,
+            Expr::Null { pos } => *pos,
+            Expr::Binary { left, .. } => left.pos(),
+            Expr::Not { pos, .. } => *pos,
+            Expr::InList { input, .. } => input.pos(),
+            Expr::Function { pos, .. } => *pos,
+            Expr::Cast { pos, .. } => *pos,
+        }
+    }
+
+    pub fn is_literal(&self) -> bool {
+        matches!(
+            self,
+            Expr::Integer { .. }
+                | Expr::Decimal { .. }
+                | Expr::Str { .. }
+                | Expr::Bool { .. }
+                | Expr::Null { .. }
+        )
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Column {
+                table: Some(table),
+                name,
+                ..
+            } => write!(f, "{}.{}", table, name),
+            Expr::Column { name, .. } => write!(f, "{}", name),
+            Expr::Integer { value, .. } => write!(f, "{}", value),
+            Expr::Decimal { value, .. } => write!(f, "{}", value),
+            Expr::Str { value, .. } => write!(f, "'{}'", value.replace('\'', "''")),
+            Expr::Bool { value: true, .. } => write!(f, "TRUE"),
+            Expr::Bool { value: false, .. } => write!(f, "FALSE"),
+            Expr::Null { .. } => write!(f, "NULL"),
+            Expr::Binary {
+                op, left, right, ..
+            } => write!(f, "({} {} {})", left, op, right),
+            Expr::Not { input, .. } => write!(f, "NOT {}", input),
+            Expr::InList { input, list, .. } => {
+                let list: Vec<_> = list.iter().map(|i| i.to_string()).collect();
+                write!(f, "({} IN ({}))", input, list.join(", "))
+            }
+            Expr::Function {
+                name,
+                wildcard: true,
+                ..
+            } => write!(f, "{}(*)", name),
+            //EXTRACT(field FROM value) is parsed as EXTRACT('field', value)
+            Expr::Function { name, args, .. }
+                if name == "EXTRACT" && matches!(args.first(), Some(Expr::Str { .. })) =>
+            {
+                match &args[0] {
+                    Expr::Str { value, .. } => write!(f, "EXTRACT({} FROM {})", value, args[1]),
+                    _ => unreachable!(),
+                }
+            }
+            Expr::Function { name, args, .. } => {
+                let args: Vec<_> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Expr::Cast {
+                input,
+                type_name,
+                type_args,
+                ..
+            } if !type_args.is_empty() => {
+                let args: Vec<_> = type_args.iter().map(|a| a.to_string()).collect();
+                write!(f, "CAST({} AS {}({}))", input, type_name, args.join(", "))
+            }
+            Expr::Cast {
+                input, type_name, ..
+            } => write!(f, "CAST({} AS {})", input, type_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectItem {
+    Wildcard { pos: usize },
+    Expr { expr: Expr, alias: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRef {
+    pub name: String,
+    pub alias: Option<String>,
+    pub pos: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    pub ascending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectStatement {
+    pub projection: Vec<SelectItem>,
+    pub from: TableRef,
+    pub selection: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub limit: Option<usize>,
+}
+
+//Type names which can prefix a string literal
+const TYPED_LITERALS: &[&str] = &["DATE", "TIME", "TIMESTAMP", "INTERVAL"];
+
+struct Parser<'q> {
+    query: &'q str,
+    tokens: Vec<Token>,
+    cur: usize,
+}
+
+pub fn parse(query: &str) -> Result<SelectStatement, ErrorDesc> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser {
+        query,
+        tokens,
+        cur: 0,
+    };
+    let statement = parser.parse_select()?;
+    let token = parser.peek();
+    if token.kind != TokenKind::Eof {
+        Err(parser.unexpected(&token))?
+    }
+    Ok(statement)
+}
+
+impl<'q> Parser<'q> {
+    fn peek(&self) -> Token {
+        self.tokens[self.cur].clone()
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.cur].clone();
+        if token.kind != TokenKind::Eof {
+            self.cur += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self, token: &Token) -> String {
+        let found = match &token.kind {
+            TokenKind::Keyword(k) => k.clone(),
+            TokenKind::Ident(i) => i.clone(),
+            TokenKind::Integer(i) => i.to_string(),
+            TokenKind::Decimal(d) => d.clone(),
+            TokenKind::Str(s) => format!("'{}'", s),
+            TokenKind::Symbol(s) => s.to_string(),
+            TokenKind::Eof => "end of query".to_string(),
+        };
+        error_at(self.query, token.pos, &format!("Unexpected {}", found))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Keyword(k) if k == keyword)
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Symbol(s) if *s == symbol)
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn consume_symbol(&mut self, symbol: &str) -> bool {
+        if self.is_symbol(symbol) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ErrorDesc> {
+        if self.consume_keyword(keyword) {
+            Ok(())
+        } else {
+            let token = self.peek();
+            Err(format!(
+                "{} (expected {})",
+                self.unexpected(&token),
+                keyword
+            ))?
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ErrorDesc> {
+        if self.consume_symbol(symbol) {
+            Ok(())
+        } else {
+            let token = self.peek();
+            Err(format!("{} (expected {})", self.unexpected(&token), symbol))?
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, usize), ErrorDesc> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Ident(i) => Ok((i, token.pos)),
+            _ => Err(format!(
+                "{} (expected an identifier)",
+                self.unexpected(&token)
+            ))?,
+        }
+    }
+
+    fn parse_select(&mut self) -> Result<SelectStatement, ErrorDesc> {
+        self.expect_keyword("SELECT")?;
+
+        let mut projection = vec![self.parse_select_item()?];
+        while self.consume_symbol(",") {
+            projection.push(self.parse_select_item()?);
+        }
+
+        self.expect_keyword("FROM")?;
+        let (name, pos) = self.expect_ident()?;
+        let alias = if self.consume_keyword("AS") {
+            Some(self.expect_ident()?.0)
+        } else if let TokenKind::Ident(i) = self.peek().kind {
+            self.next();
+            Some(i)
+        } else {
+            None
+        };
+        let from = TableRef { name, alias, pos };
+
+        let selection = if self.consume_keyword("WHERE") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by = vec![];
+        if self.consume_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.parse_expr()?);
+            while self.consume_symbol(",") {
+                group_by.push(self.parse_expr()?);
+            }
+        }
+
+        let having = if self.consume_keyword("HAVING") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut order_by = vec![];
+        if self.consume_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let expr = self.parse_expr()?;
+                let ascending = if self.consume_keyword("DESC") {
+                    false
+                } else {
+                    self.consume_keyword("ASC");
+                    true
+                };
+                order_by.push(OrderByExpr { expr, ascending });
+                if !self.consume_symbol(",") {
+                    break;
+                }
+            }
+        }
+
+        let limit = if self.consume_keyword("LIMIT") {
+            let token = self.next();
+            match token.kind {
+                TokenKind::Integer(i) => Some(i as usize),
+                _ => Err(format!("{} (expected an integer)", self.unexpected(&token)))?,
+            }
+        } else {
+            None
+        };
+
+        Ok(SelectStatement {
+            projection,
+            from,
+            selection,
+            group_by,
+            having,
+            order_by,
+            limit,
+        })
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem, ErrorDesc> {
+        if self.is_symbol("*") {
+            let pos = self.next().pos;
+            return Ok(SelectItem::Wildcard { pos });
+        }
+        let expr = self.parse_expr()?;
+        let alias = if self.consume_keyword("AS") {
+            Some(self.expect_ident()?.0)
+        } else if let TokenKind::Ident(i) = self.peek().kind {
+            self.next();
+            Some(i)
+        } else {
+            None
+        };
+        Ok(SelectItem::Expr { expr, alias })
+    }
+
+    //Precedence from lowest to highest: OR, AND, NOT, comparison, additive, multiplicative
+    fn parse_expr(&mut self) -> Result<Expr, ErrorDesc> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ErrorDesc> {
+        let mut left = self.parse_and()?;
+        while self.is_keyword("OR") {
+            let pos = self.next().pos;
+            let right = self.parse_and()?;
+            left = Expr::Binary {
+                op: "OR".to_string(),
+                left: Box::new(left),
+                right: Box::new(right),
+                pos,
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ErrorDesc> {
+        let mut left = self.parse_not()?;
+        while self.is_keyword("AND") {
+            let pos = self.next().pos;
+            let right = self.parse_not()?;
+            left = Expr::Binary {
+                op: "AND".to_string(),
+                left: Box::new(left),
+                right: Box::new(right),
+                pos,
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, ErrorDesc> {
+        if self.is_keyword("NOT") {
+            let pos = self.next().pos;
+            let input = self.parse_not()?;
+            Ok(Expr::Not {
+                input: Box::new(input),
+                pos,
+            })
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ErrorDesc> {
+        let left = self.parse_additive()?;
+        let token = self.peek();
+        if self.consume_keyword("IN") {
+            self.expect_symbol("(")?;
+            let mut list = vec![self.parse_additive()?];
+            while self.consume_symbol(",") {
+                list.push(self.parse_additive()?);
+            }
+            self.expect_symbol(")")?;
+            return Ok(Expr::InList {
+                input: Box::new(left),
+                list,
+                pos: token.pos,
+            });
+        }
+        let op = match token.kind {
+            TokenKind::Symbol("=") | TokenKind::Symbol("==") => "==",
+            TokenKind::Symbol("<>") | TokenKind::Symbol("!=") => "!=",
+            TokenKind::Symbol("<") => "<",
+            TokenKind::Symbol("<=") => "<=",
+            TokenKind::Symbol(">") => ">",
+            TokenKind::Symbol(">=") => ">=",
+            _ => return Ok(left),
+        };
+        self.next();
+        let right = self.parse_additive()?;
+        Ok(Expr::Binary {
+            op: op.to_string(),
+            left: Box::new(left),
+            right: Box::new(right),
+            pos: token.pos,
+        })
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, ErrorDesc> {
+        let mut left = self.parse_multiplicative()?;
+        while self.is_symbol("+") || self.is_symbol("-") {
+            let token = self.next();
+            let right = self.parse_multiplicative()?;
+            left = Expr::Binary {
+                op: token_symbol(&token),
+                left: Box::new(left),
+                right: Box::new(right),
+                pos: token.pos,
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, ErrorDesc> {
+        let mut left = self.parse_primary()?;
+        while self.is_symbol("*") || self.is_symbol("/") {
+            let token = self.next();
+            let right = self.parse_primary()?;
+            left = Expr::Binary {
+                op: token_symbol(&token),
+                left: Box::new(left),
+                right: Box::new(right),
+                pos: token.pos,
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ErrorDesc> {
+        let token = self.next();
+        let pos = token.pos;
+        match token.kind {
+            TokenKind::Integer(value) => Ok(Expr::Integer { value, pos }),
+            TokenKind::Decimal(value) => Ok(Expr::Decimal { value, pos }),
+            TokenKind::Str(value) => Ok(Expr::Str { value, pos }),
+            TokenKind::Keyword(k) if k == "TRUE" => Ok(Expr::Bool { value: true, pos }),
+            TokenKind::Keyword(k) if k == "FALSE" => Ok(Expr::Bool { value: false, pos }),
+            TokenKind::Keyword(k) if k == "NULL" => Ok(Expr::Null { pos }),
+            TokenKind::Symbol("(") => {
+                let expr = self.parse_expr()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            TokenKind::Ident(name) => {
+                let upper = name.to_ascii_uppercase();
+                if TYPED_LITERALS.contains(&upper.as_str()) {
+                    if let TokenKind::Str(value) = self.peek().kind {
+                        let literal_pos = self.next().pos;
+                        return Ok(Expr::Cast {
+                            input: Box::new(Expr::Str {
+                                value,
+                                pos: literal_pos,
+                            }),
+                            type_name: upper,
+                            type_args: vec![],
+                            pos,
+                        });
+                    }
+                }
+                if self.consume_symbol("(") {
+                    let name = upper;
+                    if name == "CAST" {
+                        let input = self.parse_expr()?;
+                        self.expect_keyword("AS")?;
+                        let (type_name, _) = self.expect_ident()?;
+                        let mut type_args = vec![];
+                        if self.consume_symbol("(") {
+                            loop {
+                                let token = self.next();
+                                match token.kind {
+                                    TokenKind::Integer(i) => type_args.push(i),
+                                    _ => Err(self.unexpected(&token))?,
+                                }
+                                if !self.consume_symbol(",") {
+                                    break;
+                                }
+                            }
+                            self.expect_symbol(")")?;
+                        }
+                        self.expect_symbol(")")?;
+                        return Ok(Expr::Cast {
+                            input: Box::new(input),
+                            type_name: type_name.to_ascii_uppercase(),
+                            type_args,
+                            pos,
+                        });
+                    }
+                    if name == "EXTRACT" {
+                        let (field, field_pos) = self.expect_ident()?;
+                        self.expect_keyword("FROM")?;
+                        let input = self.parse_expr()?;
+                        self.expect_symbol(")")?;
+                        return Ok(Expr::Function {
+                            name,
+                            args: vec![
+                                Expr::Str {
+                                    value: field.to_ascii_uppercase(),
+                                    pos: field_pos,
+                                },
+                                input,
+                            ],
+                            wildcard: false,
+                            pos,
+                        });
+                    }
+                    if self.consume_symbol("*") {
+                        self.expect_symbol(")")?;
+                        return Ok(Expr::Function {
+                            name,
+                            args: vec![],
+                            wildcard: true,
+                            pos,
+                        });
+                    }
+                    let mut args = vec![];
+                    if !self.consume_symbol(")") {
+                        args.push(self.parse_expr()?);
+                        while self.consume_symbol(",") {
+                            args.push(self.parse_expr()?);
+                        }
+                        self.expect_symbol(")")?;
+                    }
+                    Ok(Expr::Function {
+                        name,
+                        args,
+                        wildcard: false,
+                        pos,
+                    })
+                } else if self.consume_symbol(".") {
+                    let (column, _) = self.expect_ident()?;
+                    Ok(Expr::Column {
+                        table: Some(name),
+                        name: column,
+                        pos,
+                    })
+                } else {
+                    Ok(Expr::Column {
+                        table: None,
+                        name,
+                        pos,
+                    })
+                }
+            }
+            _ => Err(self.unexpected(&token))?,
+        }
+    }
+}
+
+fn token_symbol(token: &Token) -> String {
+    match token.kind {
+        TokenKind::Symbol(s) => s.to_string(),
+        _ => unreachable!(),
+    }
+}
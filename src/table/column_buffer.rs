@@ -1,30 +1,59 @@
-use std::{any::TypeId, collections::VecDeque};
+use std::{any::TypeId, collections::VecDeque, sync::Arc};
 
-use radix_column::{ColumnWrapper, ErrorDesc};
+use radix_column::{ColumnWrapper, ErrorDesc, MemoryReservation, MemoryTracker};
 use radix_operations::{ColumnOperations, Dictionary};
 
 pub struct ColumnBuffer {
-    stored: VecDeque<ColumnWrapper<'static>>,
+    //Columns kept for reuse together with the bytes reserved for them
+    stored: VecDeque<(ColumnWrapper<'static>, usize)>,
+    reservation: MemoryReservation,
+    //Bytes of the columns handed out by pop, they stay reserved until the columns are pushed back or the task ends
+    lent: MemoryReservation,
+    //Number of pops served by a stored column and by a newly allocated one
+    reuse_hits: usize,
+    allocations: usize,
 }
 
 impl<'a> ColumnBuffer {
     pub fn new() -> Self {
+        Self::with_memory(Arc::new(MemoryTracker::unlimited()))
+    }
+    pub fn with_memory(tracker: Arc<MemoryTracker>) -> Self {
         Self {
             stored: VecDeque::new(),
+            reservation: MemoryReservation::new(tracker.clone()),
+            lent: MemoryReservation::new(tracker),
+            reuse_hits: 0,
+            allocations: 0,
         }
     }
+    //The column is dropped instead of kept if its memory cannot be reserved
+    //The bytes of a column handed out before are taken back from the lent bytes instead of being reserved twice
     pub fn push(&mut self, dict: &Dictionary, mut c: ColumnWrapper<'static>) {
+        let bytes = match c.allocated_bytes(dict) {
+            Ok(bytes) => bytes,
+            Err(_) => return,
+        };
+        let returned = bytes.min(self.lent.size());
+        self.lent.transfer(&mut self.reservation, returned);
+        if self.reservation.grow(bytes - returned).is_err() {
+            self.reservation.shrink(returned);
+            return;
+        }
         if c.truncate(dict).is_ok() {
             if !c.bitmap().is_some() {
-                self.stored.push_back(c);
+                self.stored.push_back((c, bytes));
+                return;
             } else {
                 let b = c.bitmap_mut().downcast_vec();
                 if b.is_ok() {
                     b.unwrap().truncate(0);
-                    self.stored.push_back(c);
+                    self.stored.push_back((c, bytes));
+                    return;
                 }
             }
         };
+        self.reservation.shrink(bytes);
     }
     pub fn pop(
         &mut self,
@@ -34,13 +63,56 @@ impl<'a> ColumnBuffer {
         let pos = self
             .stored
             .iter()
-            .position(|c| c.column().item_type_id() == item_type_id);
+            .position(|(c, _)| c.column().item_type_id() == item_type_id);
         match pos {
-            Some(i) => self
-                .stored
-                .swap_remove_front(i)
-                .ok_or_else(|| unreachable!()),
-            None => ColumnWrapper::new_owned_with_capacity(dict, item_type_id, false, 0, 0),
+            Some(i) => {
+                self.reuse_hits += 1;
+                let (c, bytes) = self.stored.swap_remove_front(i).unwrap();
+                self.reservation.transfer(&mut self.lent, bytes);
+                Ok(c)
+            }
+            None => {
+                self.allocations += 1;
+                ColumnWrapper::new_owned_with_capacity(dict, item_type_id, false, 0, 0)
+            }
         }
     }
+    //Hands out a column for write to fill, the bytes it has grown to are reserved before it is returned
+    //Fails if they do not fit into the budget, so that an output cannot take the query over its limit
+    pub fn pop_for<F>(
+        &mut self,
+        dict: &Dictionary,
+        item_type_id: TypeId,
+        write: F,
+    ) -> Result<ColumnWrapper<'static>, ErrorDesc>
+    where
+        F: FnOnce(&mut ColumnWrapper<'static>) -> Result<(), ErrorDesc>,
+    {
+        let lent = self.lent.size();
+        let mut c = self.pop(dict, item_type_id)?;
+        let reused = self.lent.size() - lent;
+        write(&mut c)?;
+        let bytes = c.allocated_bytes(dict)?;
+        self.lent.grow(bytes.saturating_sub(reused))?;
+        Ok(c)
+    }
+    //Reserves the bytes of a column written in place, they stay reserved until the task ends
+    pub fn reserve(&mut self, dict: &Dictionary, c: &ColumnWrapper) -> Result<(), ErrorDesc> {
+        self.lent.grow(c.allocated_bytes(dict)?)?;
+        Ok(())
+    }
+    //Called when a task ends, its outputs are not tracked by the buffer anymore
+    pub fn release_lent(&mut self) {
+        let size = self.lent.size();
+        self.lent.shrink(size);
+    }
+    pub fn reuse_hits(&self) -> usize {
+        self.reuse_hits
+    }
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+    pub fn reserved(&self) -> usize {
+        self.reservation.size()
+    }
 }
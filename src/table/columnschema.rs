@@ -0,0 +1,42 @@
+use std::any::TypeId;
+
+use radix_column::ErrorDesc;
+use radix_operations::Dictionary;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub item_type_id: TypeId,
+    pub nullable: bool,
+    pub description: Option<String>,
+}
+
+impl ColumnSchema {
+    pub fn new(name: &str, item_type_id: TypeId, nullable: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            item_type_id,
+            nullable,
+            description: None,
+        }
+    }
+
+    pub fn default_name(column_id: usize) -> String {
+        format!("col_{}", column_id)
+    }
+
+    //The logical type of the column is the type registered in the dictionary
+    pub fn type_name(&self, dict: &Dictionary) -> Result<&'static str, ErrorDesc> {
+        item_type_name(dict, self.item_type_id)
+    }
+}
+
+pub fn item_type_name(dict: &Dictionary, item_type_id: TypeId) -> Result<&'static str, ErrorDesc> {
+    match dict.type_name(item_type_id) {
+        Some(name) => Ok(name),
+        None => Err(format!(
+            "The type {:?} is not registered in the dictionary",
+            item_type_id
+        ))?,
+    }
+}
@@ -1,17 +1,20 @@
+use std::collections::HashMap;
+
 use radix_column::*;
 use radix_operations::*;
-use rayon::prelude::*;
+
+use crate::{ExecutionBuffers, TableExpression};
 
 pub(crate) fn part_with_sizes<'a, T>(
     dict: &Dictionary,
     inp_data: &'a [T],
     inp_bitmap: &'a [bool],
-    len_vec: &Vec<usize>,
+    len_vec: &[usize],
 ) -> Result<Vec<ColumnWrapper<'a>>, ErrorDesc>
 where
     T: Send + Sync + 'static,
 {
-    let total_len: usize = len_vec.par_iter().sum();
+    let total_len: usize = len_vec.iter().sum();
     if inp_data.len() != total_len {
         Err(format!(
             "Attempt to partition a slice with {} elements into partitions with total elements {}",
@@ -36,10 +39,9 @@ where
                 dict,
                 &inp_data[cur_pos..cur_pos + *i],
             );
+            //The bitmap is packed into words, so every partition owns its part of it
             if inp_bitmap.len() != 0 {
-                s.bitmap_set(ColumnDataF::new_from_slice(
-                    &inp_bitmap[cur_pos..cur_pos + *i],
-                ))
+                s.bitmap_set(ColumnDataF::new(&inp_bitmap[cur_pos..cur_pos + *i]))
             };
             cur_pos += i;
             s
@@ -52,13 +54,13 @@ where
 
 pub(crate) fn part_with_sizes_mut<'a, T>(
     inp_data: &'a mut [T],
-    inp_bitmap: &'a mut [bool],
-    len_vec: &Vec<usize>,
+    inp_bitmap: &Bitmap,
+    len_vec: &[usize],
 ) -> Result<Vec<ColumnWrapper<'a>>, ErrorDesc>
 where
     T: Send + Sync + 'static,
 {
-    let total_len: usize = len_vec.par_iter().sum();
+    let total_len: usize = len_vec.iter().sum();
     if inp_data.len() != total_len {
         Err(format!(
             "Attempt to partition a slice with {} elements into partitions with total elements {}",
@@ -76,7 +78,7 @@ where
 
     let has_bitamp = inp_bitmap.len() != 0;
     let mut inp_data = inp_data;
-    let mut inp_bitmap = inp_bitmap;
+    let mut cur_pos: usize = 0;
 
     let output_vec: Vec<ColumnWrapper> = len_vec
         .iter()
@@ -87,12 +89,16 @@ where
             let mut c =
                 ColumnWrapper::new_from_columndata(ColumnData::SliceMut(SliceRefMut::new(l)));
 
+            //The bitmap is packed into words, so every partition owns a copy of its part of it
             if has_bitamp {
-                let tmp = std::mem::replace(&mut inp_bitmap, &mut []);
-                let (l, r) = tmp.split_at_mut(*i);
-                let _ = std::mem::replace(&mut inp_bitmap, r);
-                c.bitmap_set(ColumnDataF::new_from_slice_mut(l));
+                c.bitmap_set(ColumnDataF::new(
+                    inp_bitmap
+                        .as_bitmap_ref()
+                        .slice(cur_pos..cur_pos + *i)
+                        .to_bitmap(),
+                ));
             }
+            cur_pos += i;
             c
         })
         .collect();
@@ -102,21 +108,26 @@ where
 pub(crate) fn filter(
     index: &mut ColumnDataIndex,
     keep: &[bool],
-    bitmap: &ColumnDataF<bool>,
+    bitmap: &ColumnDataF,
     size_hint: &Option<usize>,
 ) -> Result<usize, ErrorDesc> {
     let size_hint = size_hint.unwrap_or(keep.len() / 2);
     if index.is_some() {
-        assert_eq!(index.len().unwrap(), keep.len());
+        if index.len() != Some(keep.len()) {
+            Err(RadixError::LengthMismatch {
+                expected: keep.len(),
+                found: index.len().unwrap_or(0),
+            })?
+        }
         if index.is_owned() {
             let index = index.downcast_vec()?;
             let mut del = 0;
-            if bitmap.is_some() {
+            if !bitmap.all_valid() {
                 keep.iter()
                     .zip(bitmap.downcast_ref()?.iter())
                     .enumerate()
                     .for_each(|(i, (b, bitmap))| {
-                        let b = *b && *bitmap;
+                        let b = *b && bitmap;
                         let i_new = i - (b as usize) * del;
                         del += !b as usize;
                         index.swap(i_new, i);
@@ -131,21 +142,20 @@ pub(crate) fn filter(
             index.truncate(index.len() - del);
         } else {
             let mut index_new: Vec<usize> = Vec::with_capacity(size_hint);
-            if bitmap.is_some() {
+            if !bitmap.all_valid() {
                 index_new.extend(
                     index
                         .downcast_ref()?
                         .iter()
                         .zip(keep.iter())
-                        .zip(bitmap.downcast_ref().unwrap())
-                        .filter(|((_, b), bitmap)| **b && **bitmap)
+                        .zip(bitmap.downcast_ref()?)
+                        .filter(|((_, b), bitmap)| **b && *bitmap)
                         .map(|((i, _), _)| *i),
                 );
             } else {
                 index_new.extend(
                     index
-                        .downcast_ref()
-                        .unwrap()
+                        .downcast_ref()?
                         .iter()
                         .zip(keep.iter())
                         .filter(|(_, b)| **b)
@@ -160,12 +170,13 @@ pub(crate) fn filter(
         } else {
             Vec::<usize>::with_capacity(size_hint)
         };
-        if bitmap.is_some() {
+        //Bitmaps without nulls are skipped, checking them takes one comparison per word
+        if !bitmap.all_valid() {
             index_new.extend(
                 keep.iter()
                     .zip(bitmap.downcast_ref()?.iter())
                     .enumerate()
-                    .filter(|(_, (b, bitmap))| **b && **bitmap)
+                    .filter(|(_, (b, bitmap))| **b && *bitmap)
                     .map(|(i, _)| i),
             );
         } else {
@@ -175,3 +186,158 @@ pub(crate) fn filter(
     }
     Ok(index.len().unwrap())
 }
+
+//Like filter, for a run-length encoded predicate, every kept run adds a range of rows to the index
+pub(crate) fn filter_runs(
+    index: &mut ColumnDataIndex,
+    keep: &[bool],
+    run_lengths: &[usize],
+    bitmap: &ColumnDataF,
+    size_hint: &Option<usize>,
+) -> Result<usize, ErrorDesc> {
+    let len: usize = run_lengths.iter().sum();
+    if index.is_some() && index.len() != Some(len) {
+        Err(RadixError::LengthMismatch {
+            expected: len,
+            found: index.len().unwrap_or(0),
+        })?
+    }
+    let bitmap = if !bitmap.all_valid() {
+        Some(bitmap.downcast_ref()?)
+    } else {
+        None
+    };
+    let mut index_new = Vec::<usize>::with_capacity(size_hint.unwrap_or(len / 2));
+    let old_index = if index.is_some() {
+        Some(index.downcast_ref()?)
+    } else {
+        None
+    };
+    let mut start = 0;
+    for (b, l) in keep.iter().zip(run_lengths) {
+        let rows = start..start + l;
+        start += l;
+        if !*b {
+            continue;
+        }
+        match (old_index, bitmap) {
+            (Some(old_index), Some(bitmap)) => {
+                index_new.extend(rows.filter(|i| bitmap[*i]).map(|i| old_index[i]))
+            }
+            (Some(old_index), None) => index_new.extend_from_slice(&old_index[rows]),
+            (None, Some(bitmap)) => index_new.extend(rows.filter(|i| bitmap[*i])),
+            (None, None) => index_new.extend(rows),
+        }
+    }
+    *index = ColumnDataIndex::new(index_new);
+    Ok(index.len().unwrap())
+}
+
+//Group ids produced by group_in point at the first row of each group, they are renumbered to 0..number_of_groups
+//Returns the number of groups
+pub(crate) fn compact_group_ids(v: &mut [usize]) -> usize {
+    let mut decrease_index_by = 0;
+    (1..v.len()).for_each(|i| {
+        decrease_index_by += (v[i] != i) as usize;
+        v[i] = v[v[i]];
+        let diff = ((v[i] != i) as usize).wrapping_sub(1); //If equal then 0 else FFFFFF
+        let diff = diff & decrease_index_by;
+        v[i] -= diff;
+    });
+    v.len() - decrease_index_by
+}
+
+//Evaluates all predicates on one partition and filters its indexes in a single pass
+//If add_index is set, a new index is pushed for the columns which did not have one
+//Returns the number of rows left in the partition
+pub(crate) fn filter_partition(
+    dict: &Dictionary,
+    buffers: &mut ExecutionBuffers,
+    exprs: &[TableExpression],
+    columns: &[ColumnWrapper],
+    indexes: &mut Vec<ColumnDataIndex>,
+    columnindexmap: &HashMap<usize, usize>,
+    add_index: bool,
+) -> Result<usize, ErrorDesc> {
+    let results: Result<Vec<_>, ErrorDesc> = exprs
+        .iter()
+        .map(|expr| {
+            let result = expr.eval(
+                dict,
+                &mut buffers.buffer,
+                &mut buffers.hashmap_buffer,
+                &mut buffers.hashmap_binary,
+                columns,
+                indexes,
+                columnindexmap,
+            )?;
+
+            //TO-DO: use index!
+            match result {
+                InputTypes::Owned(res, _index) => Ok(res),
+                _ => Err("A filter predicate must evaluate to an owned column")?,
+            }
+        })
+        .collect();
+    let results = results?;
+
+    //Nulls are treated as false when the predicates are combined
+    let combined: Option<Vec<bool>> = if results.len() > 1 {
+        let len = results[0].column().data_len::<bool>()?;
+        let mut keep = vec![true; len];
+        for result in results.iter() {
+            let b = result.column().downcast_decoded::<bool>()?;
+            keep.iter_mut().zip(b.iter()).for_each(|(k, b)| *k &= *b);
+            if result.bitmap().is_some() {
+                let bitmap = result.bitmap().downcast_ref()?;
+                keep.iter_mut()
+                    .zip(bitmap.iter())
+                    .for_each(|(k, b)| *k &= b);
+            }
+        }
+        Some(keep)
+    } else {
+        None
+    };
+    let no_bitmap = ColumnDataF::None;
+    //A single run-length encoded predicate keeps or drops whole runs
+    let runs = match &combined {
+        None if results[0].column().is_run_length() => {
+            Some(results[0].column().downcast_runs_ref::<bool>()?)
+        }
+        _ => None,
+    };
+    let (b, bitmap) = match (&combined, runs) {
+        (Some(keep), _) => (keep.as_slice(), &no_bitmap),
+        (None, Some((keep, _))) => (keep, results[0].bitmap()),
+        (None, None) => (
+            results[0].column().downcast_ref::<bool>()?,
+            results[0].bitmap(),
+        ),
+    };
+    let filter_index = |index: &mut ColumnDataIndex, hint_size: &Option<usize>| match runs {
+        Some((_, run_lengths)) => filter_runs(index, b, run_lengths, bitmap, hint_size),
+        None => filter(index, b, bitmap, hint_size),
+    };
+
+    let mut hint_size: Option<usize> = None;
+    for i in indexes.iter_mut() {
+        let new_index_len = filter_index(i, &hint_size)?;
+        if hint_size.is_none() {
+            hint_size = Some(new_index_len);
+        }
+    }
+    if add_index {
+        let mut ind: ColumnDataIndex = ColumnDataIndex::None;
+        let new_index_len = filter_index(&mut ind, &hint_size)?;
+        if hint_size.is_none() {
+            hint_size = Some(new_index_len);
+        }
+        indexes.push(ind);
+    }
+    let number_of_rows = hint_size.unwrap_or(results[0].column().data_len::<bool>()?);
+    results
+        .into_iter()
+        .for_each(|result| buffers.buffer.push(dict, result));
+    Ok(number_of_rows)
+}
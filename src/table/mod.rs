@@ -1,9 +1,11 @@
 pub mod column_buffer;
+pub mod columnschema;
 pub mod helpers;
 pub mod table;
 pub mod tableexpression;
 pub mod tabletotable;
 
+pub use columnschema::*;
 pub(crate) use helpers::*;
 pub use table::*;
 pub use tableexpression::*;
@@ -1,9 +1,16 @@
 use rayon::prelude::*;
-use std::{cell::UnsafeCell, collections::HashMap, mem::MaybeUninit};
+use std::{
+    any::TypeId,
+    cell::UnsafeCell,
+    collections::HashMap,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use crate::{
-    column_buffer::ColumnBuffer, filter, part_with_sizes, part_with_sizes_mut,
-    tabletotable::TableToTableMap, TableExpression,
+    compact_group_ids, filter_partition, part_with_sizes, part_with_sizes_mut,
+    tabletotable::TableToTableMap, Batch, ColumnSchema, ExecutionContext, SpillFile,
+    SpillPartitioner, TableExpression,
 };
 use radix_column::*;
 use radix_operations::*;
@@ -13,11 +20,18 @@ type PartitionedColumnMut<'a> = Vec<&'a mut ColumnWrapper<'a>>;
 
 pub type PartitionedIndex<'a> = Vec<ColumnDataIndex<'a>>;
 
+//The buckets written by column_repartition, one column per bucket
+//Their memory stays reserved in the tracker of the context until the reservation is dropped
+pub struct RepartitionedColumn<'a> {
+    pub buckets: Vec<ColumnWrapper<'a>>,
+    pub reservation: MemoryReservation,
+}
+
 #[derive(Debug)]
 pub struct Table<'a> {
     partition_sizes: Vec<usize>,
     columns: Vec<Vec<ColumnWrapper<'a>>>,
-    columns_nullable: Vec<bool>,
+    columns_schema: Vec<ColumnSchema>,
     pub indexes: Vec<Vec<ColumnDataIndex<'a>>>,
     columnindexmap: HashMap<usize, usize>,
     //buffer_columns: BufferColumns<'a>,
@@ -30,7 +44,7 @@ impl<'a> Table<'a> {
         Self {
             partition_sizes,
             columns,
-            columns_nullable: vec![],
+            columns_schema: vec![],
             indexes,
             columnindexmap: HashMap::new(), //buffer_columns: BufferColumns::new(),
         }
@@ -45,10 +59,59 @@ impl<'a> Table<'a> {
             .iter_mut()
             .zip(p_column.into_iter())
             .for_each(|(v, c)| v.push(c));
-        self.columns_nullable.push(false);
+        self.push_schema(TypeId::of::<T>(), false);
         Ok(())
     }
 
+    //Creates a table from columns which are already partitioned, columns[partition][column]
+    pub fn from_partitions(
+        partition_sizes: Vec<usize>,
+        columns: Vec<Vec<ColumnWrapper<'a>>>,
+        columns_schema: Vec<ColumnSchema>,
+    ) -> Result<Self, ErrorDesc> {
+        if partition_sizes.len() != columns.len() {
+            Err(RadixError::LengthMismatch {
+                expected: partition_sizes.len(),
+                found: columns.len(),
+            })?
+        }
+        if let Some(c) = columns.iter().find(|c| c.len() != columns_schema.len()) {
+            Err(RadixError::LengthMismatch {
+                expected: columns_schema.len(),
+                found: c.len(),
+            })?
+        }
+        let indexes = partition_sizes.iter().map(|_| vec![]).collect();
+        Ok(Self {
+            partition_sizes,
+            columns,
+            columns_schema,
+            indexes,
+            columnindexmap: HashMap::new(),
+        })
+    }
+
+    //Copies the batches into a table with one partition per batch
+    pub fn from_batches(
+        dict: &Dictionary,
+        batches: &[Batch],
+        columns_schema: Vec<ColumnSchema>,
+    ) -> Result<Table<'static>, ErrorDesc> {
+        if batches.is_empty() {
+            let columns: Result<Vec<_>, ErrorDesc> = columns_schema
+                .iter()
+                .map(|c| ColumnWrapper::new_owned_with_capacity(dict, c.item_type_id, false, 0, 0))
+                .collect();
+            return Table::from_partitions(vec![0], vec![columns?], columns_schema);
+        }
+        let partitions: Result<Vec<_>, ErrorDesc> =
+            batches.iter().map(|b| b.materialize(dict)).collect();
+        let partitions = partitions?;
+        let partition_sizes = partitions.iter().map(|b| b.number_of_rows).collect();
+        let columns = partitions.into_iter().map(|b| b.columns).collect();
+        Table::from_partitions(partition_sizes, columns, columns_schema)
+    }
+
     pub fn number_of_columns(&self) -> Result<usize, ErrorDesc> {
         if self.columns.is_empty() {
             Err("The table was not properly initialized, therefore the number of columns is unknown")?
@@ -57,24 +120,138 @@ impl<'a> Table<'a> {
         }
     }
 
+    fn check_column_id(&self, column_id: &usize) -> Result<(), ErrorDesc> {
+        let number_of_columns = self.number_of_columns()?;
+        if number_of_columns <= *column_id {
+            Err(RadixError::OutOfBounds {
+                index: *column_id,
+                len: number_of_columns,
+            })?
+        }
+        Ok(())
+    }
+
+    pub fn number_of_rows(&self) -> usize {
+        self.partition_sizes.iter().sum()
+    }
+
+    pub fn partition_sizes(&self) -> &[usize] {
+        &self.partition_sizes
+    }
+
+    pub(crate) fn partition_columns(
+        &self,
+        partition_id: usize,
+    ) -> Result<&[ColumnWrapper<'a>], ErrorDesc> {
+        match self.columns.get(partition_id) {
+            Some(c) => Ok(c),
+            None => Err(RadixError::OutOfBounds {
+                index: partition_id,
+                len: self.columns.len(),
+            })?,
+        }
+    }
+
+    pub(crate) fn partition_indexes(
+        &self,
+        partition_id: usize,
+    ) -> Result<&[ColumnDataIndex<'a>], ErrorDesc> {
+        match self.indexes.get(partition_id) {
+            Some(i) => Ok(i),
+            None => Err(RadixError::OutOfBounds {
+                index: partition_id,
+                len: self.indexes.len(),
+            })?,
+        }
+    }
+
+    pub(crate) fn column_index_map(&self) -> &HashMap<usize, usize> {
+        &self.columnindexmap
+    }
+
+    fn push_schema(&mut self, item_type_id: TypeId, nullable: bool) {
+        let column_id = self.columns_schema.len();
+        let mut name = ColumnSchema::default_name(column_id);
+        let mut suffix = 0;
+        while self.column_id(&name).is_ok() {
+            suffix += 1;
+            name = format!("{}_{}", ColumnSchema::default_name(column_id), suffix);
+        }
+        self.columns_schema
+            .push(ColumnSchema::new(&name, item_type_id, nullable));
+    }
+
+    pub fn schema(&self) -> &[ColumnSchema] {
+        &self.columns_schema
+    }
+
+    pub fn column_schema(&self, column_id: &usize) -> Result<&ColumnSchema, ErrorDesc> {
+        self.columns_schema
+            .get(*column_id)
+            .ok_or(RadixError::OutOfBounds {
+                index: *column_id,
+                len: self.columns_schema.len(),
+            })
+    }
+
+    pub fn column_id(&self, name: &str) -> Result<usize, ErrorDesc> {
+        match self.columns_schema.iter().position(|c| c.name == name) {
+            Some(column_id) => Ok(column_id),
+            None => Err(format!("Column {} not found in table", name))?,
+        }
+    }
+
+    pub fn column_ids(&self, names: &[&str]) -> Result<Vec<usize>, ErrorDesc> {
+        names.iter().map(|name| self.column_id(name)).collect()
+    }
+
+    pub fn set_column_name(&mut self, column_id: &usize, name: &str) -> Result<(), ErrorDesc> {
+        if let Ok(existing_id) = self.column_id(name) {
+            if existing_id != *column_id {
+                Err(format!(
+                    "Column name {} is already used by column {}",
+                    name, existing_id
+                ))?
+            }
+        }
+        self.column_schema(column_id)?;
+        self.columns_schema[*column_id].name = name.to_string();
+        Ok(())
+    }
+
+    pub fn set_column_description(
+        &mut self,
+        column_id: &usize,
+        description: &str,
+    ) -> Result<(), ErrorDesc> {
+        self.column_schema(column_id)?;
+        self.columns_schema[*column_id].description = Some(description.to_string());
+        Ok(())
+    }
+
     pub fn push_index(
         &mut self,
         p_index: PartitionedIndex<'a>,
         applies_for_columns: &[usize],
     ) -> Result<(), ErrorDesc> {
         if self.partition_sizes.len() != p_index.len() {
-            Err(format!(
-                "Mismatch while adding an index to a table: index has {} partitions, while the table has {} partitions",
-                p_index.len(),
-                self.partition_sizes.len()
-            ))?
+            Err(RadixError::LengthMismatch {
+                expected: self.partition_sizes.len(),
+                found: p_index.len(),
+            })?
         }
 
-        let res: Result<(), ErrorDesc>=self.partition_sizes.iter().zip(p_index.iter()).enumerate()
-        .try_for_each(|(partition_id, (p_size,index))| if index.is_empty() || (index.len()==Some(*p_size)) {Ok(())}
-        else {
-            Err(format!("Mismatch while adding an index to a table: index partition {} has lenght {:?}, while the table partitio has length {} ",partition_id, index.len(), p_size))?
-        });
+        let res: Result<(), ErrorDesc> = self
+            .partition_sizes
+            .iter()
+            .zip(p_index.iter())
+            .try_for_each(|(p_size, index)| match index.len() {
+                Some(len) if len != *p_size => Err(RadixError::LengthMismatch {
+                    expected: *p_size,
+                    found: len,
+                })?,
+                _ => Ok(()),
+            });
         res?;
 
         let cur_index_pos = self.indexes[0].len();
@@ -91,10 +268,10 @@ impl<'a> Table<'a> {
                 self.columnindexmap.insert(*i, cur_index_pos);
                 Ok(())
             } else {
-                Err(format!(
-                    "Index should apply for column {}, while the table has only {} columns",
-                    i, number_of_columns
-                ))?
+                Err(RadixError::OutOfBounds {
+                    index: *i,
+                    len: number_of_columns,
+                })?
             }
         });
         res
@@ -115,7 +292,7 @@ impl<'a> Table<'a> {
             .iter_mut()
             .zip(p_column.into_iter())
             .for_each(|(v, c)| v.push(c));
-        self.columns_nullable.push(true);
+        self.push_schema(TypeId::of::<T>(), true);
         Ok(())
     }
 
@@ -124,53 +301,163 @@ impl<'a> Table<'a> {
         T: 'static + Send + Sync,
         'b: 'a,
     {
-        let p_column = part_with_sizes_mut(data, &mut [], &self.partition_sizes)?;
+        let p_column = part_with_sizes_mut(data, &Bitmap::new(), &self.partition_sizes)?;
         self.columns
             .iter_mut()
             .zip(p_column.into_iter())
             .for_each(|(v, c)| v.push(c));
-        self.columns_nullable.push(false);
+        self.push_schema(TypeId::of::<T>(), false);
         Ok(())
     }
 
+    //The table takes the bitmap, the nulls written by assign ops are read back with materialize
     pub fn push_mut_with_bitmap<'b, T>(
         &mut self,
         data: &'b mut [T],
-        bitmap: &'b mut [bool],
+        bitmap: Bitmap,
     ) -> Result<(), ErrorDesc>
     where
         T: 'static + Send + Sync,
         'b: 'a,
     {
-        let p_column = part_with_sizes_mut(data, bitmap, &self.partition_sizes)?;
+        let p_column = part_with_sizes_mut(data, &bitmap, &self.partition_sizes)?;
         self.columns
             .iter_mut()
             .zip(p_column.into_iter())
             .for_each(|(v, c)| v.push(c));
-        self.columns_nullable.push(true);
+        self.push_schema(TypeId::of::<T>(), true);
         Ok(())
     }
 
-    pub(crate) fn get_part_col(&self, column_id: &usize) -> Result<PartitionedColumn, ErrorDesc> {
-        if *column_id <= self.number_of_columns()? {
-            Ok(self.columns.iter().map(|v| &v[*column_id]).collect())
-        } else {
+    //Adds a column whose partitions are shared with other tables, no data is copied
+    pub fn push_shared(
+        &mut self,
+        dict: &Dictionary,
+        p_column: Vec<ColumnWrapper<'static>>,
+    ) -> Result<(), ErrorDesc> {
+        if self.partition_sizes.len() != p_column.len() {
+            Err(RadixError::LengthMismatch {
+                expected: self.partition_sizes.len(),
+                found: p_column.len(),
+            })?
+        }
+
+        let res: Result<(), ErrorDesc> = self
+            .partition_sizes
+            .iter()
+            .zip(p_column.iter())
+            .enumerate()
+            .try_for_each(|(partition_id, (p_size, c))| {
+                if !c.column().is_shared() {
+                    Err(format!(
+                        "Column partition {} is not shared, use into_shared first",
+                        partition_id
+                    ))?
+                }
+                let len = c.len(dict)?;
+                if len != *p_size {
+                    Err(RadixError::LengthMismatch {
+                        expected: *p_size,
+                        found: len,
+                    })?
+                }
+                Ok(())
+            });
+        res?;
+
+        let is_nullable = p_column.iter().any(|c| c.bitmap().is_some());
+        let item_type_id = match p_column.first() {
+            Some(c) => c.column().item_type_id(),
+            None => Err("A shared column must have at least one partition")?,
+        };
+        self.columns
+            .iter_mut()
+            .zip(p_column)
+            .for_each(|(v, c)| v.push(c));
+        self.push_schema(item_type_id, is_nullable);
+        Ok(())
+    }
+
+    //Moves all partitions of an owned column behind an Arc, so that the column can be shared afterwards
+    pub fn make_shared(&mut self, column_id: &usize) -> Result<(), ErrorDesc> {
+        self.check_column_id(column_id)?;
+
+        let can_be_shared = self.columns.iter().all(|v| {
+            let (column, bitmap) = v[*column_id].get_inner_ref();
+            let column_ok = column.is_owned() || column.is_shared();
+            let bitmap_ok = !bitmap.is_some() || bitmap.is_owned() || bitmap.is_shared();
+            column_ok && bitmap_ok
+        });
+        if !can_be_shared {
             Err(format!(
-                "Index {} requested, but table has only {} columns",
-                column_id,
-                self.number_of_columns()?
+                "Column {} cannot be shared, only owned columns can be shared",
+                column_id
             ))?
         }
+
+        self.columns.iter_mut().try_for_each(|v| {
+            let c = v.remove(*column_id).into_shared()?;
+            v.insert(*column_id, c);
+            Ok(())
+        })
+    }
+
+    //Replaces all partitions of a binary column by codes into one dictionary shared by the partitions
+    //Rows keep their positions, so indexes on the column stay valid
+    pub fn dictionary_encode(
+        &mut self,
+        dict: &Dictionary,
+        column_id: &usize,
+    ) -> Result<(), ErrorDesc> {
+        self.check_column_id(column_id)?;
+
+        let src: Vec<&ColumnWrapper> = self.columns.iter().map(|v| &v[*column_id]).collect();
+        let encoded = ColumnWrapper::dictionary_encode(dict, &src)?;
+
+        self.columns
+            .iter_mut()
+            .zip(encoded)
+            .for_each(|(v, c)| v[*column_id] = c);
+        Ok(())
+    }
+
+    //Stores the column as runs of equal consecutive values, every partition is encoded on its own
+    pub fn run_length_encode(
+        &mut self,
+        dict: &Dictionary,
+        column_id: &usize,
+    ) -> Result<(), ErrorDesc> {
+        self.check_column_id(column_id)?;
+
+        let encoded = self
+            .columns
+            .iter()
+            .map(|v| v[*column_id].run_length_encode(dict))
+            .collect::<Result<Vec<_>, ErrorDesc>>()?;
+
+        self.columns
+            .iter_mut()
+            .zip(encoded)
+            .for_each(|(v, c)| v[*column_id] = c);
+        Ok(())
+    }
+
+    //Returns new references to the partitions of a shared column. Indexes are not part of the result
+    pub fn share_column(
+        &self,
+        column_id: &usize,
+    ) -> Result<Vec<ColumnWrapper<'static>>, ErrorDesc> {
+        self.check_column_id(column_id)?;
+        self.columns.iter().map(|v| v[*column_id].share()).collect()
+    }
+
+    pub(crate) fn get_part_col(&self, column_id: &usize) -> Result<PartitionedColumn, ErrorDesc> {
+        self.check_column_id(column_id)?;
+        Ok(self.columns.iter().map(|v| &v[*column_id]).collect())
     }
 
     pub(crate) fn is_const(&self, column_id: &usize) -> Result<bool, ErrorDesc> {
-        let number_of_columns = self.number_of_columns()?;
-        if number_of_columns <= *column_id {
-            Err(format!(
-                "Column index out of bounds: {} while the table has only {} columns",
-                column_id, number_of_columns
-            ))?
-        };
+        self.check_column_id(column_id)?;
         let res: bool = self
             .columns
             .iter()
@@ -182,8 +469,9 @@ impl<'a> Table<'a> {
     pub(crate) fn materialize_const<T: 'static + Send + Sync + Clone>(
         &self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
         column_id: &usize,
-    ) -> Result<(Vec<T>, ColumnDataF<'static, bool>), ErrorDesc> {
+    ) -> Result<(Vec<T>, ColumnDataF<'static>), ErrorDesc> {
         if !self.is_const(column_id)? {
             Err(format!(
                 "materialize_const expects a const column, but column {} is not constant",
@@ -196,24 +484,21 @@ impl<'a> Table<'a> {
         let bitmap = cw_value.bitmap();
 
         let total_len: usize = self.partition_sizes.iter().sum();
+        let mut reservation = MemoryReservation::new(ctx.memory().clone());
+        reservation.grow(total_len * std::mem::size_of::<T>())?;
         let mut output_data: Vec<MaybeUninit<T>> = Vec::with_capacity(total_len);
         unsafe { output_data.set_len(total_len) };
 
-        output_data
-            .par_iter_mut()
-            .for_each(|t| *t = MaybeUninit::new(value.clone()));
+        ctx.install(|| {
+            output_data
+                .par_iter_mut()
+                .for_each(|t| *t = MaybeUninit::new(value.clone()))
+        });
         let output_data: Vec<T> = unsafe { std::mem::transmute(output_data) };
 
         let output_bitmap = if bitmap.is_some() {
-            let bitmap_value = bitmap.downcast_ref().unwrap()[0];
-            let mut output_bitmap: Vec<MaybeUninit<bool>> = Vec::with_capacity(total_len);
-            unsafe { output_bitmap.set_len(total_len) };
-
-            output_bitmap
-                .par_iter_mut()
-                .for_each(|t| *t = MaybeUninit::new(bitmap_value));
-            let output_bitmap: Vec<bool> = unsafe { std::mem::transmute(output_bitmap) };
-            ColumnDataF::new(output_bitmap)
+            let bitmap_value = bitmap.downcast_ref()?[0];
+            ColumnDataF::new(Bitmap::filled(bitmap_value, total_len))
         } else {
             ColumnDataF::None
         };
@@ -224,41 +509,53 @@ impl<'a> Table<'a> {
     pub fn materialize<T: 'static + Send + Sync + Clone>(
         &self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
         column_id: &usize,
-    ) -> Result<(Vec<T>, ColumnDataF<'static, bool>), ErrorDesc> {
+    ) -> Result<(Vec<T>, ColumnDataF<'static>), ErrorDesc> {
         let p_index = self.columnindexmap.get(column_id);
         let p_column = self.get_part_col(column_id)?;
 
-        self.materialize_common(dict, column_id, p_column, p_index)
+        self.materialize_common(dict, ctx, column_id, p_column, p_index)
+    }
+
+    pub fn materialize_by_name<T: 'static + Send + Sync + Clone>(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        name: &str,
+    ) -> Result<(Vec<T>, ColumnDataF<'static>), ErrorDesc> {
+        self.materialize(dict, ctx, &self.column_id(name)?)
     }
 
     //TO-DO - switch to a more general execution framework
     fn materialize_common<T: 'static + Send + Sync + Clone>(
         &self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
         column_id: &usize,
         p_column: PartitionedColumn,
         p_index: Option<&usize>,
-    ) -> Result<(Vec<T>, ColumnDataF<'static, bool>), ErrorDesc> {
+    ) -> Result<(Vec<T>, ColumnDataF<'static>), ErrorDesc> {
         if self.is_const(column_id)? {
-            return self.materialize_const(dict, column_id);
+            return self.materialize_const(dict, ctx, column_id);
         }
 
         //let p_column = p_column.column_vec();
         let total_len: usize = self.partition_sizes.iter().sum();
 
-        let type_check: Result<(), &str> = p_column.iter().try_for_each(|c| {
+        p_column.iter().try_for_each(|c| {
             if c.column().is::<T>() {
                 Ok(())
             } else {
-                Err("Materialize called with type different from the column type")
+                Err(dict.type_mismatch(TypeId::of::<T>(), c.column().item_type_id()))
             }
-        });
-        type_check?;
+        })?;
 
         let has_bitmap = p_column.iter().find(|c| c.bitmap().is_some()).is_some();
 
-        //Reserve memory for the result column
+        //The result is reserved in the tracker of the context while it is built, the caller owns it afterwards
+        let mut reservation = MemoryReservation::new(ctx.memory().clone());
+        reservation.grow(total_len * std::mem::size_of::<T>())?;
 
         let mut output_data: Vec<MaybeUninit<T>> = Vec::with_capacity(total_len);
 
@@ -280,55 +577,44 @@ impl<'a> Table<'a> {
             })
             .collect();
 
-        let mut b: Vec<MaybeUninit<bool>> = Vec::new();
-
+        //Bits of different partitions may share a word, so every partition writes its own bitmap
         if has_bitmap {
-            b.reserve(total_len);
-            //SAFETY: OK to do as the memory has been reserved, and moreover the type is assumed to be not initialized
-            unsafe {
-                b.set_len(total_len);
-            }
-
-            //Initialize b
-            b.par_iter_mut().for_each(|b| *b = MaybeUninit::new(false));
-
-            //SAFETY: OK to do as b has been initialized in the previous step
-            let mut b_slice: &mut [bool] = unsafe { std::mem::transmute(b.as_mut_slice()) };
-
             output_vec
                 .iter_mut()
                 .zip(self.partition_sizes.iter())
-                .for_each(|(c, i)| {
-                    let tmp = std::mem::replace(&mut b_slice, &mut []);
-                    let (l, r) = tmp.split_at_mut(*i);
-                    let _ = std::mem::replace(&mut b_slice, r);
-                    c.bitmap_set(ColumnDataF::new_from_slice_mut(l));
-                });
+                .for_each(|(c, i)| c.bitmap_set(ColumnDataF::new(Bitmap::filled(false, *i))));
         }
 
-        if let Some(ind) = p_index {
-            p_column
-                .par_iter()
-                .zip_eq(&self.indexes)
-                .zip_eq(output_vec.par_iter_mut())
-                .for_each(|((src, src_index), dst)| {
-                    src.copy_to(dict, dst, &src_index[*ind]).unwrap();
-                });
-        } else {
-            p_column
-                .par_iter()
-                .zip_eq(output_vec.par_iter_mut())
-                .for_each(|(src, dst)| src.copy_to(dict, dst, &ColumnDataIndex::None).unwrap());
-        };
+        let mut partitions: Vec<_> = p_column
+            .iter()
+            .zip(self.indexes.iter())
+            .zip(output_vec.iter_mut())
+            .collect();
+        ctx.for_each_partition(&mut partitions, |_buffers, ((src, src_index), dst)| {
+            let index_empty: ColumnDataIndex = ColumnDataIndex::None;
+            let src_index = match p_index {
+                Some(ind) => &src_index[*ind],
+                None => &index_empty,
+            };
+            src.copy_to(dict, dst, src_index)
+        })?;
+        drop(partitions);
 
-        //SAFETY: b is either empty or fully initilized at this point
-        let b: Vec<bool> = unsafe { std::mem::transmute(b) };
-        let output_data: Vec<T> = unsafe { std::mem::transmute(output_data) };
         let output_bitmap = if has_bitmap {
+            let mut b = Bitmap::with_capacity(total_len);
+            output_vec
+                .iter()
+                .try_for_each(|c| -> Result<(), ErrorDesc> {
+                    b.extend_from_bitmap(c.bitmap().downcast_ref()?);
+                    Ok(())
+                })?;
             ColumnDataF::new(b)
         } else {
             ColumnDataF::None
         };
+        drop(output_vec);
+        //SAFETY: output_data is fully initilized at this point
+        let output_data: Vec<T> = unsafe { std::mem::transmute(output_data) };
 
         Ok((output_data, output_bitmap))
     }
@@ -336,6 +622,7 @@ impl<'a> Table<'a> {
     pub fn materialize_as_string(
         &self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
         column_id: &usize,
     ) -> Result<Vec<String>, ErrorDesc> {
         let p_column = self.get_part_col(column_id)?;
@@ -346,31 +633,27 @@ impl<'a> Table<'a> {
         let p_column_str: Vec<_> = p_column
             .iter()
             .map(|c| {
-                (
-                    c.as_string(dict, &ColumnDataIndex::None).unwrap(),
-                    c.bitmap(),
-                )
-            })
-            .map(|(mut s, b)| {
+                let mut s = c.as_string(dict, &ColumnDataIndex::None)?;
+                let b = c.bitmap();
                 let mut s = if !is_const {
                     ColumnWrapper::new_from_vec(dict, s)
                 } else {
                     ColumnWrapper::new_const(dict, s.pop().unwrap())
                 };
                 if b.is_some() {
-                    s.bitmap_set(ColumnDataF::new_from_slice(b.downcast_ref().unwrap()));
+                    s.bitmap_set(ColumnDataF::new_from_slice(b.downcast_ref()?));
                 }
-                s
+                Ok(s)
             })
-            .collect();
+            .collect::<Result<_, ErrorDesc>>()?;
 
         let p_column_str_ref: Vec<_> = p_column_str.iter().collect();
 
         let (mut v, b) =
-            self.materialize_common::<String>(dict, column_id, p_column_str_ref, p_index)?;
+            self.materialize_common::<String>(dict, ctx, column_id, p_column_str_ref, p_index)?;
         if b.is_some() {
-            v.par_iter_mut()
-                .zip_eq(b.downcast_ref().unwrap().par_iter())
+            v.iter_mut()
+                .zip(b.downcast_ref()?.iter())
                 .for_each(|(s, b)| {
                     if !b {
                         *s = "(null)".to_string()
@@ -379,15 +662,36 @@ impl<'a> Table<'a> {
         }
         Ok(v)
     }
-    pub fn print(&self, dict: &Dictionary) -> Result<(), ErrorDesc> {
+
+    pub fn materialize_as_string_by_name(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        name: &str,
+    ) -> Result<Vec<String>, ErrorDesc> {
+        self.materialize_as_string(dict, ctx, &self.column_id(name)?)
+    }
+
+    pub fn print(&self, dict: &Dictionary, ctx: &ExecutionContext) -> Result<(), ErrorDesc> {
         let v: Vec<Vec<String>> = (0usize..self.number_of_columns()?)
-            .into_iter()
-            .enumerate()
-            .map(|(i, _)| self.materialize_as_string(dict, &i).unwrap())
-            .collect();
+            .map(|i| self.materialize_as_string(dict, ctx, &i))
+            .collect::<Result<_, _>>()?;
 
         let mut table = prettytable::Table::new();
 
+        let titles: Result<Vec<_>, ErrorDesc> = self
+            .columns_schema
+            .iter()
+            .map(|c| {
+                Ok(prettytable::Cell::new(&format!(
+                    "{}\n{}",
+                    c.name,
+                    c.type_name(dict)?
+                )))
+            })
+            .collect();
+        table.set_titles(prettytable::Row::new(titles?));
+
         if !v.is_empty() {
             let len = v[0].len();
             (0..len).into_iter().for_each(|i| {
@@ -405,6 +709,7 @@ impl<'a> Table<'a> {
     pub fn op(
         &mut self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
         op: &str,
         c1_id: &usize,
         input_ids: &[usize],
@@ -416,59 +721,85 @@ impl<'a> Table<'a> {
         };
 
         let number_of_columns = self.number_of_columns()?;
-        if (*c1_id >= number_of_columns)
-            || input_ids
-                .iter()
-                .find(|c| **c >= number_of_columns)
-                .is_some()
+        if let Some(id) = std::iter::once(c1_id)
+            .chain(input_ids)
+            .find(|c| **c >= number_of_columns)
         {
-            Err(format!(
-                "Column index out of bounds: write column: {}, column inputs {:?}, while table has {} columns",
-                c1_id, &input_ids, number_of_columns
-            ))?
+            Err(RadixError::OutOfBounds {
+                index: *id,
+                len: number_of_columns,
+            })?
         };
 
-        self.columns
-            .par_iter_mut()
-            .zip_eq(indexes.par_iter())
-            .for_each(|(v_col, v_ind)| {
-                let (s_left, s_right) = v_col.split_at_mut(*c1_id);
-                let (c1, s_right) = s_right.split_at_mut(1);
-                let c1 = &mut c1[0];
-                let index_empty: ColumnDataIndex = ColumnDataIndex::None;
-
-                let c1_index = match columnindexmap.get(c1_id) {
-                    Some(i) => &v_ind[*i],
-                    None => &index_empty,
-                };
+        let mut partitions: Vec<_> = self.columns.iter_mut().zip(indexes.iter()).collect();
+        ctx.for_each_partition(&mut partitions, |buffers, (v_col, v_ind)| {
+            let (s_left, s_right) = v_col.split_at_mut(*c1_id);
+            let (c1, s_right) = s_right.split_at_mut(1);
+            let c1 = &mut c1[0];
+            let index_empty: ColumnDataIndex = ColumnDataIndex::None;
 
-                let input: Vec<_> = input_ids
-                    .iter()
-                    .map(|c_id| {
-                        let c = if c_id < c1_id {
-                            &s_left[*c_id]
-                        } else {
-                            {
-                                &s_right[c_id - c1_id - 1]
-                            }
-                        };
-                        let c_index = match columnindexmap.get(c_id) {
-                            Some(i) => &v_ind[*i],
-                            None => &index_empty,
-                        };
-
-                        InputTypes::Ref(c, c_index)
-                    })
-                    .collect();
-
-                c1.op(dict, op, c1_index, &input).unwrap();
-            });
+            let c1_index = match columnindexmap.get(c1_id) {
+                Some(i) => &v_ind[*i],
+                None => &index_empty,
+            };
 
-        Ok(())
+            let input: Vec<_> = input_ids
+                .iter()
+                .map(|c_id| {
+                    let c = if c_id < c1_id {
+                        &s_left[*c_id]
+                    } else {
+                        {
+                            &s_right[c_id - c1_id - 1]
+                        }
+                    };
+                    let c_index = match columnindexmap.get(c_id) {
+                        Some(i) => &v_ind[*i],
+                        None => &index_empty,
+                    };
+
+                    InputTypes::Ref(c, c_index)
+                })
+                .collect();
+
+            c1.op(dict, op, c1_index, &input)?;
+            buffers.buffer.reserve(dict, c1)
+        })
+    }
+
+    pub fn op_by_name(
+        &mut self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        op: &str,
+        c1_name: &str,
+        input_names: &[&str],
+    ) -> Result<(), ErrorDesc> {
+        let c1_id = self.column_id(c1_name)?;
+        let input_ids = self.column_ids(input_names)?;
+        self.op(dict, ctx, op, &c1_id, &input_ids)
     }
 
     //TO-DO - switch to a more general execution framework
-    pub fn filter(&mut self, dict: &Dictionary, expr: &TableExpression) -> Result<(), ErrorDesc> {
+    pub fn filter(
+        &mut self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        expr: &TableExpression,
+    ) -> Result<(), ErrorDesc> {
+        self.filter_all(dict, ctx, std::slice::from_ref(expr))
+    }
+
+    //Keeps the rows for which all predicates are true, the indexes are filtered only once
+    pub fn filter_all(
+        &mut self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        exprs: &[TableExpression],
+    ) -> Result<(), ErrorDesc> {
+        if exprs.is_empty() {
+            return Ok(());
+        }
         let number_of_columns = self.number_of_columns()?;
         let columnindexmap = &self.columnindexmap;
 
@@ -477,62 +808,28 @@ impl<'a> Table<'a> {
             .filter(|i| self.columnindexmap.get(i).is_none())
             .collect();
 
-        let num_of_cpus = num_cpus::get();
-        let chunk_size = (self.partition_sizes.len() + num_of_cpus - 1) / num_of_cpus;
-        self.indexes
-            .par_chunks_mut(chunk_size)
-            .zip_eq(self.columns.par_chunks(chunk_size))
-            .zip_eq(self.partition_sizes.par_chunks_mut(chunk_size))
-            .for_each(|((indexes, columns), partition_size)| {
-                let mut buffer = ColumnBuffer::new();
-                let mut hashmap_buffer = HashMapBuffer::new();
-                let mut hashmap_binary: HashMap<
-                    (usize, NullableValue<&[u8]>),
-                    usize,
-                    ahash::RandomState,
-                > = HashMap::with_capacity_and_hasher(100, ahash::RandomState::default());
-
-                indexes
-                    .iter_mut()
-                    .zip(columns.iter())
-                    .zip(partition_size.iter_mut())
-                    .for_each(|((indexes, columns), partition_size)| {
-                        let result = expr
-                            .eval(
-                                dict,
-                                &mut buffer,
-                                &mut hashmap_buffer,
-                                &mut hashmap_binary,
-                                columns,
-                                indexes,
-                                columnindexmap,
-                            )
-                            .unwrap();
-
-                        //TO-DO: use index!
-                        let result = match result {
-                            InputTypes::Owned(res, index) => res,
-                            _ => panic!(),
-                        };
-                        let b = result.column().downcast_ref::<bool>().unwrap();
-                        let bitmap = result.bitmap();
-
-                        let mut hint_size: Option<usize> = None;
-                        indexes.iter_mut().for_each(|i| {
-                            let new_index_len = filter(i, b, bitmap, &hint_size).unwrap();
-                            if hint_size.is_none() {
-                                hint_size = Some(new_index_len);
-                                *partition_size = new_index_len;
-                            }
-                        });
-                        if !columns_without_index.is_empty() {
-                            let mut ind: ColumnDataIndex = ColumnDataIndex::None;
-                            filter(&mut ind, b, bitmap, &hint_size).unwrap();
-                            indexes.push(ind);
-                        }
-                        buffer.push(dict, result);
-                    });
-            });
+        let add_index = !columns_without_index.is_empty();
+        let mut partitions: Vec<_> = self
+            .indexes
+            .iter_mut()
+            .zip(self.columns.iter())
+            .zip(self.partition_sizes.iter_mut())
+            .collect();
+        ctx.for_each_partition(
+            &mut partitions,
+            |buffers, ((indexes, columns), partition_size)| {
+                **partition_size = filter_partition(
+                    dict,
+                    buffers,
+                    exprs,
+                    columns,
+                    indexes,
+                    columnindexmap,
+                    add_index,
+                )?;
+                Ok(())
+            },
+        )?;
 
         if !columns_without_index.is_empty() {
             let insert_pos = self.indexes[0].len() - 1;
@@ -544,171 +841,220 @@ impl<'a> Table<'a> {
         Ok(())
     }
 
-    //TO-DO - switch to a more general execution framework
-    pub fn add_expression_as_new_column(&mut self, dict: &Dictionary, expr: &TableExpression) {
-        let (indexes, columnindexmap) = (&mut self.indexes, &mut self.columnindexmap);
-        let num_of_cpus = num_cpus::get();
-        let chunk_size = (self.partition_sizes.len() + num_of_cpus - 1) / num_of_cpus;
-
-        let indexes_num = indexes[0].len();
+    pub fn add_expression_as_new_column(
+        &mut self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        expr: &TableExpression,
+    ) -> Result<(), ErrorDesc> {
+        let columnindexmap = &self.columnindexmap;
+        let indexes_num = self.indexes[0].len();
 
-        self.columns
-            .par_chunks_mut(chunk_size)
-            .zip_eq(indexes.par_chunks_mut(chunk_size))
-            .for_each(|(c, i)| {
-                let mut buffer = ColumnBuffer::new();
-                let mut hashmap_buffer = HashMapBuffer::new();
-                let mut hashmap_binary: HashMap<
-                    (usize, NullableValue<&[u8]>),
-                    usize,
-                    ahash::RandomState,
-                > = HashMap::with_capacity_and_hasher(100, ahash::RandomState::default());
-
-                c.iter_mut().zip(i.iter_mut()).for_each(|(c, i)| {
-                    let res = expr
-                        .eval(
-                            dict,
-                            &mut buffer,
-                            &mut hashmap_buffer,
-                            &mut hashmap_binary,
-                            c,
-                            i,
-                            columnindexmap,
-                        )
-                        .unwrap();
-                    //TO-DO: use index!
-                    match res {
-                        InputTypes::Owned(res, index) => {
-                            c.push(res);
-                            if index.is_some() {
-                                i.push(index);
-                            }
-                        }
-                        _ => panic!(),
+        let mut partitions: Vec<_> = self
+            .columns
+            .iter_mut()
+            .zip(self.indexes.iter_mut())
+            .collect();
+        ctx.for_each_partition(&mut partitions, |buffers, (c, i)| {
+            let res = expr.eval(
+                dict,
+                &mut buffers.buffer,
+                &mut buffers.hashmap_buffer,
+                &mut buffers.hashmap_binary,
+                c,
+                i,
+                columnindexmap,
+            )?;
+            //TO-DO: use index!
+            match res {
+                InputTypes::Owned(res, index) => {
+                    c.push(res);
+                    if index.is_some() {
+                        i.push(index);
                     }
-                });
-            });
+                }
+                _ => Err("An expression must evaluate to an owned column")?,
+            }
+            Ok(())
+        })?;
 
-        if indexes_num != indexes[0].len() {
-            columnindexmap.insert(self.columns[0].len() - 1, indexes_num);
+        if indexes_num != self.indexes[0].len() {
+            self.columnindexmap
+                .insert(self.columns[0].len() - 1, indexes_num);
         }
 
+        let item_type_id = self.columns[0].last().unwrap().column().item_type_id();
         //TO-DO - fix this!!!
-        self.columns_nullable.push(true);
+        self.push_schema(item_type_id, true);
+        Ok(())
     }
 
-    pub fn build_hash(&self, dict: &Dictionary, input_ids: &[usize]) -> Vec<Vec<u64>> {
-        let num_of_cpus = num_cpus::get();
-        let chunk_size = (self.partition_sizes.len() + num_of_cpus - 1) / num_of_cpus;
-
-        //TO-DO: Switch to general execution framework
+    pub fn build_hash(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        input_ids: &[usize],
+    ) -> Result<Vec<Vec<u64>>, ErrorDesc> {
         let mut output: Vec<Vec<u64>> = self
             .partition_sizes
-            .par_iter()
+            .iter()
             .map(|i| Vec::with_capacity(*i))
             .collect();
         let index_empty = ColumnDataIndex::None;
 
-        input_ids.iter().for_each(|col_id| {
+        for col_id in input_ids.iter() {
             let signature = Signature::new(
                 "" as &str,
                 vec![self.columns[0][*col_id].column().item_type_id()],
             );
-            let iop = dict.columninternal.get(&signature).unwrap();
+            let iop = dict
+                .columninternal
+                .get(&signature)
+                .ok_or_else(|| signature.not_found(dict))?;
             let c_index = self.columnindexmap.get(col_id);
-            self.columns
-                .par_chunks(chunk_size)
-                .zip_eq(self.indexes.par_chunks(chunk_size))
-                .zip_eq(output.par_chunks_mut(chunk_size))
-                .for_each(|((columns, indexes), output)| {
-                    columns.iter().zip(indexes).zip(output).for_each(
-                        |((columns, indexes), output)| {
-                            let c_index = match c_index {
-                                Some(i) => &indexes[*i],
-                                None => &index_empty,
-                            };
-                            let c = &columns[*col_id];
-                            iop.hash_in(c, c_index, output).unwrap()
-                        },
-                    );
-                });
-        });
-        output
+            let mut partitions: Vec<_> = self
+                .columns
+                .iter()
+                .zip(self.indexes.iter())
+                .zip(output.iter_mut())
+                .collect();
+            ctx.for_each_partition(&mut partitions, |_buffers, ((columns, indexes), output)| {
+                let c_index = match c_index {
+                    Some(i) => &indexes[*i],
+                    None => &index_empty,
+                };
+                iop.hash_in(&columns[*col_id], c_index, output)
+            })?;
+        }
+        Ok(output)
     }
 
-    pub fn build_groups(&self, dict: &Dictionary, input_ids: &[usize]) -> Vec<(Vec<usize>, usize)> {
-        let num_of_cpus = num_cpus::get();
-        let chunk_size = (self.partition_sizes.len() + num_of_cpus - 1) / num_of_cpus;
-
-        //TO-DO: Switch to general execution framework
+    //Fails if the memory of the hash maps cannot be reserved in the tracker of the context
+    pub fn build_groups(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        input_ids: &[usize],
+    ) -> Result<Vec<(Vec<usize>, usize)>, ErrorDesc> {
         let mut output: Vec<(Vec<usize>, usize)> = self
             .partition_sizes
-            .par_iter()
+            .iter()
             .map(|i| (vec![0usize; *i], *i))
             .collect();
         let index_empty = ColumnDataIndex::None;
 
-        input_ids.iter().for_each(|col_id| {
+        for col_id in input_ids.iter() {
             let signature = Signature::new(
                 "" as &str,
                 vec![self.columns[0][*col_id].column().item_type_id()],
             );
-            let iop = dict.columninternal.get(&signature).unwrap();
+            let iop = dict
+                .columninternal
+                .get(&signature)
+                .ok_or_else(|| signature.not_found(dict))?;
             let c_index = self.columnindexmap.get(col_id);
-            self.columns
-                .par_chunks(chunk_size)
-                .zip_eq(self.indexes.par_chunks(chunk_size))
-                .zip_eq(output.par_chunks_mut(chunk_size))
-                .for_each(|((columns, indexes), output)| {
-                    let mut hashmap_buffer = HashMapBuffer::new();
-                    let mut hashmap_binary: HashMap<
-                        (usize, NullableValue<&[u8]>),
-                        usize,
-                        ahash::RandomState,
-                    > = HashMap::with_capacity_and_hasher(100, ahash::RandomState::default());
-
-                    columns.iter().zip(indexes).zip(output).for_each(
-                        |((columns, indexes), output)| {
-                            let c_index = match c_index {
-                                Some(i) => &indexes[*i],
-                                None => &index_empty,
-                            };
-                            let c = &columns[*col_id];
-                            iop.group_in(
-                                c,
-                                c_index,
-                                &mut output.0,
-                                &mut hashmap_buffer,
-                                &mut hashmap_binary,
-                            )
-                            .unwrap()
-                        },
-                    );
-                });
-        });
-        output.par_chunks_mut(chunk_size).for_each(|output| {
-            output.iter_mut().for_each(|(v, number_of_groups)| {
-                let mut decrease_index_by = 0;
-                (1..v.len()).into_iter().for_each(|i| {
-                    decrease_index_by += (v[i] != i) as usize;
-                    v[i] = v[v[i]];
-                    let diff = ((v[i] != i) as usize).wrapping_sub(1); //If equal then 0 else FFFFFF
-                    let diff = diff & decrease_index_by;
-                    v[i] -= diff;
-                });
-                *number_of_groups -= decrease_index_by;
-            });
-        });
-        output
+            let mut partitions: Vec<_> = self
+                .columns
+                .iter()
+                .zip(self.indexes.iter())
+                .zip(output.iter_mut())
+                .collect();
+            ctx.for_each_partition(&mut partitions, |buffers, ((columns, indexes), output)| {
+                let c_index = match c_index {
+                    Some(i) => &indexes[*i],
+                    None => &index_empty,
+                };
+                iop.group_in(
+                    &columns[*col_id],
+                    c_index,
+                    &mut output.0,
+                    &mut buffers.hashmap_buffer,
+                    &mut buffers.hashmap_binary,
+                )
+            })?;
+        }
+        ctx.for_each_partition(&mut output, |_buffers, (v, number_of_groups)| {
+            *number_of_groups = compact_group_ids(v);
+            Ok(())
+        })?;
+        Ok(output)
     }
 
+    //The chunks of partitions are copied in the thread pool of the context
+    //The memory of the buckets is reserved in the tracker of the context and returned with the buckets
+    //It is estimated from the bytes per row of the column before the buckets are filled
     pub unsafe fn column_repartition(
         &self,
         dict: &Dictionary,
+        ctx: &ExecutionContext,
+        hash: &Vec<Vec<u64>>,
+        tmap: &TableToTableMap,
+        col_id: &usize,
+    ) -> Result<RepartitionedColumn<'_>, ErrorDesc> {
+        let mut source_bytes = 0;
+        let mut source_rows = 0;
+        for c in self.columns.iter() {
+            source_bytes += c[*col_id].allocated_bytes(dict)?;
+            source_rows += c[*col_id].len(dict)?;
+        }
+        let rows: usize = tmap.bucket_number_of_elements.iter().sum();
+        let mut reservation = MemoryReservation::new(ctx.memory().clone());
+        reservation.grow(source_bytes / source_rows.max(1) * rows)?;
+
+        let buckets =
+            ctx.install(|| self.column_repartition_in_pool(dict, ctx, hash, tmap, col_id))?;
+        //The estimate misses the columns which borrow their data, the reservation grows to the size of the buckets
+        let mut bucket_bytes = 0;
+        for c in buckets.iter() {
+            bucket_bytes += c.allocated_bytes(dict)?;
+        }
+        reservation.grow_to(bucket_bytes)?;
+        Ok(RepartitionedColumn {
+            buckets,
+            reservation,
+        })
+    }
+
+    //Repartitions the columns like column_repartition, but the buckets are written to spill files
+    //in the spill directory of the context instead of being kept in memory
+    //Every bucket is read back on its own, rows keep the order they have within a partition
+    pub fn spill_repartition(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
+        hash: &[Vec<u64>],
+        bucket_bits: usize,
+        column_ids: &[usize],
+    ) -> Result<Vec<SpillFile>, ErrorDesc> {
+        let directory = match ctx.spill_directory() {
+            Some(directory) => directory,
+            None => Err("Spilling is not enabled in the execution context")?,
+        };
+        if hash.len() != self.partition_sizes.len() {
+            Err(format!(
+                "The hash has {} partitions while the table has {} partitions",
+                hash.len(),
+                self.partition_sizes.len()
+            ))?
+        }
+        let partitioner = SpillPartitioner::create(directory, bucket_bits)?;
+        let mut partitions: Vec<_> = (0..self.partition_sizes.len()).collect();
+        ctx.for_each_partition(&mut partitions, |_buffers, partition_id| {
+            let batch = Batch::from_table_partition(dict, self, *partition_id, column_ids)?;
+            partitioner.write(dict, &batch, &hash[*partition_id])
+        })?;
+        Ok(partitioner.into_buckets())
+    }
+
+    //Chunks are skipped once the operation is cancelled, the buckets are then dropped without being initialized
+    unsafe fn column_repartition_in_pool(
+        &self,
+        dict: &Dictionary,
+        ctx: &ExecutionContext,
         hash: &Vec<Vec<u64>>,
         tmap: &TableToTableMap,
         col_id: &usize,
-    ) -> Vec<ColumnWrapper> {
+    ) -> Result<Vec<ColumnWrapper<'_>>, ErrorDesc> {
         struct UnsafeOutput {
             data: UnsafeCell<Vec<ColumnWrapper<'static>>>,
         }
@@ -721,8 +1067,11 @@ impl<'a> Table<'a> {
             "" as &str,
             vec![self.columns[0][*col_id].column().item_type_id()],
         );
-        let iop = dict.columninternal.get(&signature).unwrap();
-        let with_bitmap = self.columns_nullable[*col_id];
+        let iop = dict
+            .columninternal
+            .get(&signature)
+            .ok_or_else(|| signature.not_found(dict))?;
+        let with_bitmap = self.columns_schema[*col_id].nullable;
 
         let is_binary = self.columns[0][*col_id].is_binary();
         let c_index = self.columnindexmap.get(col_id);
@@ -730,6 +1079,19 @@ impl<'a> Table<'a> {
         let number_of_buckets = tmap.number_of_buckets;
         let bucket_mask = (number_of_buckets - 1) as u64;
 
+        //Binary columns are copied in two passes over the chunks
+        let number_of_chunks = self.columns.len().div_ceil(chunk_size);
+        let total = if is_binary {
+            2 * number_of_chunks
+        } else {
+            number_of_chunks
+        };
+        let completed = AtomicUsize::new(0);
+        let chunk_completed = || {
+            let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            ctx.report_progress(completed, total);
+        };
+
         if !is_binary {
             let output: Vec<ColumnWrapper> = tmap
                 .bucket_number_of_elements
@@ -741,12 +1103,16 @@ impl<'a> Table<'a> {
                 data: UnsafeCell::new(output),
             };
 
-            self.columns
+            let res: Result<(), ErrorDesc> = self
+                .columns
                 .par_chunks(chunk_size)
                 .zip_eq(self.indexes.par_chunks(chunk_size))
                 .zip_eq(tmap.write_offsets.par_iter())
                 .zip_eq(hash.par_chunks(chunk_size))
-                .for_each(|(((columns, indexes), write_offsets), h)| {
+                .try_for_each(|(((columns, indexes), write_offsets), h)| {
+                    if ctx.cancellation().is_cancelled() {
+                        return Ok(());
+                    }
                     let output = &mut *unsafe_output.data.get();
                     iop.copy_to_buckets_part1(
                         h,
@@ -758,16 +1124,17 @@ impl<'a> Table<'a> {
                         write_offsets,
                         output,
                         with_bitmap,
-                    )
-                    .unwrap();
+                    )?;
+                    chunk_completed();
+                    Ok(())
                 });
             let output = unsafe_output.data.into_inner();
+            res?;
+            if ctx.cancellation().is_cancelled() {
+                return Err(RadixError::Cancelled);
+            }
 
-            let output: Vec<_> = output
-                .into_par_iter()
-                .map(|c| iop.assume_init(c).unwrap())
-                .collect();
-            output
+            output.into_par_iter().map(|c| iop.assume_init(c)).collect()
         } else {
             let output: Vec<ColumnWrapper> = tmap
                 .bucket_number_of_elements
@@ -779,12 +1146,16 @@ impl<'a> Table<'a> {
                 data: UnsafeCell::new(output),
             };
 
-            self.columns
+            let res: Result<(), ErrorDesc> = self
+                .columns
                 .par_chunks(chunk_size)
                 .zip_eq(self.indexes.par_chunks(chunk_size))
                 .zip_eq(tmap.write_offsets.par_iter())
                 .zip_eq(hash.par_chunks(chunk_size))
-                .for_each(|(((columns, indexes), write_offsets), h)| {
+                .try_for_each(|(((columns, indexes), write_offsets), h)| {
+                    if ctx.cancellation().is_cancelled() {
+                        return Ok(());
+                    }
                     let output = &mut *unsafe_output.data.get();
                     iop.copy_to_buckets_part1(
                         h,
@@ -796,25 +1167,34 @@ impl<'a> Table<'a> {
                         write_offsets,
                         output,
                         with_bitmap,
-                    )
-                    .unwrap();
+                    )?;
+                    chunk_completed();
+                    Ok(())
                 });
             let mut output = unsafe_output.data.into_inner();
+            res?;
+            if ctx.cancellation().is_cancelled() {
+                return Err(RadixError::Cancelled);
+            }
 
-            output.iter_mut().for_each(|c| {
-                iop.copy_to_buckets_part2(c).unwrap();
-            });
+            for c in output.iter_mut() {
+                iop.copy_to_buckets_part2(c)?;
+            }
 
             let unsafe_output = UnsafeOutput {
                 data: UnsafeCell::new(output),
             };
 
-            self.columns
+            let res: Result<(), ErrorDesc> = self
+                .columns
                 .par_chunks(chunk_size)
                 .zip_eq(self.indexes.par_chunks(chunk_size))
                 .zip_eq(tmap.write_offsets.par_iter())
                 .zip_eq(hash.par_chunks(chunk_size))
-                .for_each(|(((columns, indexes), write_offsets), h)| {
+                .try_for_each(|(((columns, indexes), write_offsets), h)| {
+                    if ctx.cancellation().is_cancelled() {
+                        return Ok(());
+                    }
                     let output = &mut *unsafe_output.data.get();
                     iop.copy_to_buckets_part3(
                         h,
@@ -825,12 +1205,17 @@ impl<'a> Table<'a> {
                         &c_index,
                         write_offsets,
                         output,
-                    )
-                    .unwrap();
+                    )?;
+                    chunk_completed();
+                    Ok(())
                 });
             let output = unsafe_output.data.into_inner();
+            res?;
+            if ctx.cancellation().is_cancelled() {
+                return Err(RadixError::Cancelled);
+            }
 
-            output
+            Ok(output)
         }
     }
 }
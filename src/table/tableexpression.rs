@@ -1,15 +1,17 @@
 use std::collections::HashMap;
 
 use radix_column::{
-    ColumnDataF, ColumnDataIndex, ColumnWrapper, ErrorDesc, HashMapBuffer, NullableValue,
+    ColumnData, ColumnDataF, ColumnDataIndex, ColumnWrapper, ErrorDesc, HashMapBuffer,
+    NullableValue,
 };
 use radix_operations::{ColumnOperations, Dictionary, InputTypes, Signature};
 
-use crate::column_buffer::ColumnBuffer;
+use crate::{column_buffer::ColumnBuffer, compact_group_ids, sql::AGGREGATE_FUNCTIONS, Table};
 
 pub enum ExpressionInput<'a> {
     Column(usize),
     Const(&'a ColumnWrapper<'a>),
+    OwnedConst(ColumnWrapper<'static>),
     Expr(TableExpression<'a>),
 }
 
@@ -35,6 +37,10 @@ impl<'a> TableExpression<'a> {
             partition_by: vec![],
         }
     }
+
+    pub fn new_by_name(table: &Table, op: &str, col_names: &[&str]) -> Result<Self, ErrorDesc> {
+        Ok(Self::new(op, &table.column_ids(col_names)?))
+    }
     pub fn expand_node(
         &mut self,
         old_col_id: usize,
@@ -61,7 +67,7 @@ impl<'a> TableExpression<'a> {
                         break;
                     }
                 }
-                ExpressionInput::Const(_) => {}
+                ExpressionInput::Const(_) | ExpressionInput::OwnedConst(_) => {}
             }
         }
 
@@ -84,7 +90,7 @@ impl<'a> TableExpression<'a> {
                         break;
                     }
                 }
-                ExpressionInput::Const(_) => {}
+                ExpressionInput::Const(_) | ExpressionInput::OwnedConst(_) => {}
             }
         }
 
@@ -117,7 +123,7 @@ impl<'a> TableExpression<'a> {
                         break;
                     }
                 }
-                ExpressionInput::Const(_) => {}
+                ExpressionInput::Const(_) | ExpressionInput::OwnedConst(_) => {}
             }
         }
         for i in self.partition_by.iter_mut() {
@@ -137,7 +143,7 @@ impl<'a> TableExpression<'a> {
                         break;
                     }
                 }
-                ExpressionInput::Const(_) => {}
+                ExpressionInput::Const(_) | ExpressionInput::OwnedConst(_) => {}
             }
         }
         match found {
@@ -146,6 +152,71 @@ impl<'a> TableExpression<'a> {
         }
     }
 
+    //True if the expression can be evaluated without reading any column
+    pub fn is_const(&self) -> bool {
+        self.partition_by.is_empty()
+            && !AGGREGATE_FUNCTIONS.contains(&self.op.as_str())
+            && self.input.iter().all(|i| match i {
+                ExpressionInput::Column(_) => false,
+                ExpressionInput::Const(_) | ExpressionInput::OwnedConst(_) => true,
+                ExpressionInput::Expr(e) => e.is_const(),
+            })
+    }
+
+    //Evaluates an expression built only from constants, the result is a const column
+    pub fn eval_const(&self, dict: &Dictionary) -> Result<ColumnWrapper<'static>, ErrorDesc> {
+        if !self.is_const() {
+            Err(format!(
+                "Expression {} cannot be evaluated as a constant",
+                self.op
+            ))?
+        }
+        let mut buffer = ColumnBuffer::new();
+        let mut hashmap_buffer = HashMapBuffer::new();
+        let mut hashmap_binary =
+            HashMap::with_capacity_and_hasher(0, ahash::RandomState::default());
+        let result = self.eval(
+            dict,
+            &mut buffer,
+            &mut hashmap_buffer,
+            &mut hashmap_binary,
+            &[],
+            &[],
+            &HashMap::new(),
+        )?;
+        let result = match result {
+            InputTypes::Owned(c, _) => c,
+            _ => Err("Constant expression must evaluate to an owned column")?,
+        };
+        if result.len(dict)? != 1 {
+            Err("Constant expression must evaluate to exactly one value")?
+        }
+        let (column, bitmap) = result.get_inner();
+        let column = match column {
+            ColumnData::Owned(c) => ColumnData::Const(c),
+            c @ ColumnData::BinaryOwned(_) => c.binary_to_const()?,
+            _ => Err("Constant expression must evaluate to an owned column")?,
+        };
+        let mut result = ColumnWrapper::new_from_columndata(column);
+        if bitmap.is_some() {
+            result.bitmap_set(bitmap);
+        }
+        Ok(result)
+    }
+
+    //Replaces every sub-expression built only from constants with its value
+    pub fn fold_constants(&mut self, dict: &Dictionary) -> Result<(), ErrorDesc> {
+        for i in self.input.iter_mut().chain(self.partition_by.iter_mut()) {
+            if let ExpressionInput::Expr(e) = i {
+                e.fold_constants(dict)?;
+                if e.is_const() {
+                    *i = ExpressionInput::OwnedConst(e.eval_const(dict)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn eval(
         &self,
         dict: &Dictionary,
@@ -156,7 +227,14 @@ impl<'a> TableExpression<'a> {
         indexes: &[ColumnDataIndex],
         columnindexmap: &HashMap<usize, usize>,
     ) -> Result<InputTypes, ErrorDesc> {
-        assert!(!dict.op_is_assign.get(&self.op).unwrap());
+        match dict.op_is_assign.get(&self.op) {
+            Some(false) => {}
+            Some(true) => Err(format!(
+                "The assign operation {} cannot be evaluated as an expression",
+                self.op
+            ))?,
+            None => Err(Signature::new_op(&self.op).not_found(dict))?,
+        }
         let index_empty: ColumnDataIndex = ColumnDataIndex::None;
 
         let mut inp: Vec<InputTypes> = self
@@ -168,22 +246,21 @@ impl<'a> TableExpression<'a> {
                         Some(i) => &indexes[*i],
                         None => &index_empty,
                     };
-                    InputTypes::Ref(&columns[*col_id], col_index)
+                    Ok(InputTypes::Ref(&columns[*col_id], col_index))
                 }
-                ExpressionInput::Expr(expr) => expr
-                    .eval(
-                        dict,
-                        buffer,
-                        hashmap_buffer,
-                        hashmap_binary,
-                        columns,
-                        indexes,
-                        columnindexmap,
-                    )
-                    .unwrap(),
-                ExpressionInput::Const(c) => InputTypes::Ref(c, &index_empty),
+                ExpressionInput::Expr(expr) => expr.eval(
+                    dict,
+                    buffer,
+                    hashmap_buffer,
+                    hashmap_binary,
+                    columns,
+                    indexes,
+                    columnindexmap,
+                ),
+                ExpressionInput::Const(c) => Ok(InputTypes::Ref(c, &index_empty)),
+                ExpressionInput::OwnedConst(c) => Ok(InputTypes::Ref(c, &index_empty)),
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         let mut inp_types: Vec<_> = inp
             .iter()
@@ -202,22 +279,21 @@ impl<'a> TableExpression<'a> {
                         Some(i) => &indexes[*i],
                         None => &index_empty,
                     };
-                    InputTypes::Ref(&columns[*col_id], col_index)
+                    Ok(InputTypes::Ref(&columns[*col_id], col_index))
                 }
-                ExpressionInput::Expr(expr) => expr
-                    .eval(
-                        dict,
-                        buffer,
-                        hashmap_buffer,
-                        hashmap_binary,
-                        columns,
-                        indexes,
-                        columnindexmap,
-                    )
-                    .unwrap(),
-                ExpressionInput::Const(c) => InputTypes::Ref(c, &index_empty),
+                ExpressionInput::Expr(expr) => expr.eval(
+                    dict,
+                    buffer,
+                    hashmap_buffer,
+                    hashmap_binary,
+                    columns,
+                    indexes,
+                    columnindexmap,
+                ),
+                ExpressionInput::Const(c) => Ok(InputTypes::Ref(c, &index_empty)),
+                ExpressionInput::OwnedConst(c) => Ok(InputTypes::Ref(c, &index_empty)),
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         if !part_by.is_empty() {
             let buffer_group_ids = buffer.pop(dict, std::any::TypeId::of::<usize>())?;
@@ -225,37 +301,25 @@ impl<'a> TableExpression<'a> {
 
             let _number_of_groups = buffer.pop(dict, std::any::TypeId::of::<usize>())?;
 
-            part_by.iter().for_each(|c| match c {
-                InputTypes::Ref(c, src_index) => c
-                    .group_in(
-                        dict,
-                        src_index,
-                        &mut buffer_group_ids,
-                        hashmap_buffer,
-                        hashmap_binary,
-                    )
-                    .unwrap(),
-                InputTypes::Owned(c, src_index) => c
-                    .group_in(
-                        dict,
-                        src_index,
-                        &mut buffer_group_ids,
-                        hashmap_buffer,
-                        hashmap_binary,
-                    )
-                    .unwrap(),
-            });
+            part_by.iter().try_for_each(|c| match c {
+                InputTypes::Ref(c, src_index) => c.group_in(
+                    dict,
+                    src_index,
+                    &mut buffer_group_ids,
+                    hashmap_buffer,
+                    hashmap_binary,
+                ),
+                InputTypes::Owned(c, src_index) => c.group_in(
+                    dict,
+                    src_index,
+                    &mut buffer_group_ids,
+                    hashmap_buffer,
+                    hashmap_binary,
+                ),
+            })?;
 
-            let mut decrease_index_by = 0;
             let mut v = buffer_group_ids;
-            (1..v.len()).into_iter().for_each(|i| {
-                decrease_index_by += (v[i] != i) as usize;
-                v[i] = v[v[i]];
-                let diff = ((v[i] != i) as usize).wrapping_sub(1); //If equal then 0 else FFFFFF
-                let diff = diff & decrease_index_by;
-                v[i] -= diff;
-            });
-            let number_of_groups = v.len() - decrease_index_by;
+            let number_of_groups = compact_group_ids(&mut v);
 
             inp.push(InputTypes::Owned(
                 ColumnWrapper::new_from_vec(dict, v),
@@ -269,12 +333,16 @@ impl<'a> TableExpression<'a> {
         }
 
         let signature = Signature::new(&self.op, inp_types);
-        let op = dict.op.get(&signature).unwrap();
+        let op = dict
+            .op
+            .get(&signature)
+            .ok_or_else(|| signature.not_found(dict))?;
 
-        let mut output = buffer.pop(dict, op.output_type_id)?;
+        let mut output = buffer.pop_for(dict, op.output_type_id, |output| {
+            dict.call_op(&signature, output, &index_empty, &inp)
+        })?;
         let mut output_index = ColumnDataIndex::None;
 
-        (op.f)(&mut output, &index_empty, &inp)?;
         if !part_by.is_empty() {
             inp.pop().unwrap();
             let v = inp.pop().unwrap();
@@ -285,6 +353,18 @@ impl<'a> TableExpression<'a> {
             let (v, _) = v.get_inner();
             let v = v.downcast_owned::<usize>()?;
             output_index = ColumnDataIndex::new(v);
+
+            //User-defined aggregates output one state per group, which is turned into the value of the group
+            if let Some(finalize) = dict.aggregate_finalize(&self.op, op.output_type_id) {
+                let finalized = buffer.pop_for(dict, finalize.output_type_id, |finalized| {
+                    (finalize.f)(
+                        finalized,
+                        &index_empty,
+                        &[InputTypes::Ref(&output, &index_empty)],
+                    )
+                })?;
+                buffer.push(dict, std::mem::replace(&mut output, finalized));
+            }
         }
         inp.into_iter().for_each(|inp| {
             if let InputTypes::Owned(c, _) = inp {
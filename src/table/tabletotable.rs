@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
 
 use rayon::prelude::*;
+
+use crate::ExecutionContext;
 #[derive(Debug)]
 pub struct TableToTableMap {
     pub target_partition_size: usize,
@@ -17,6 +19,7 @@ pub struct TableToTableBinaryMap {
 
 impl TableToTableMap {
     pub(crate) fn new(
+        ctx: &ExecutionContext,
         hash: &Vec<Vec<u64>>,
         number_of_worker_threads: usize,
         bucket_bits: usize,
@@ -34,24 +37,24 @@ impl TableToTableMap {
         //[1,1,1,1]
         //[1,1,1,1]
 
-        let mut elements_per_worker_and_bucket: Vec<_> = hash
-            .par_chunks(target_partition_size)
-            .map(|hash| {
-                let mut v: Vec<usize> = vec![0; 2 * number_of_buckets];
-                v.truncate(number_of_buckets);
-                hash.iter()
-                    .for_each(|hash| hash.iter().for_each(|h| v[(h & bucket_mask) as usize] += 1));
-                VecDeque::from(v)
-            })
-            .collect();
+        let mut elements_per_worker_and_bucket: Vec<_> = ctx.install(|| {
+            hash.par_chunks(target_partition_size)
+                .map(|hash| {
+                    let mut v: Vec<usize> = vec![0; 2 * number_of_buckets];
+                    v.truncate(number_of_buckets);
+                    hash.iter().for_each(|hash| {
+                        hash.iter().for_each(|h| v[(h & bucket_mask) as usize] += 1)
+                    });
+                    VecDeque::from(v)
+                })
+                .collect()
+        });
 
         let mut current_offset_per_worker: Vec<usize> = vec![0; number_of_buckets];
 
-        //TO-DO: Very inefficient from CPU architecture perspective
-        //Maybe switch to unsafe?
         elements_per_worker_and_bucket.iter_mut().for_each(|v| {
-            v.par_iter_mut()
-                .zip_eq(current_offset_per_worker.par_iter_mut())
+            v.iter_mut()
+                .zip(current_offset_per_worker.iter_mut())
                 .for_each(|(v, cur)| {
                     let tmp = *v;
                     *v = *cur;